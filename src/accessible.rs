@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::{Duration, SystemTime};
+
+use crate::app::{
+    DisplaySessionRow, TuiOptions, group_sessions_for_display, short_thread_id, shorten_home_path,
+};
+use crate::changes::{ChangeTracker, SnapshotChanges};
+use crate::collector::Collector;
+use crate::hooks::HookRunner;
+use crate::model::SessionId;
+use crate::model::{SessionRow, SessionStatus, Snapshot};
+use crate::timefmt::format_timestamp;
+use crate::util::{message_preview, system_time_to_unix_s};
+
+/// Screen-reader friendly mode: plain appended lines, no cursor addressing or redraws.
+///
+/// The first refresh prints a full listing; afterwards only changes are announced (new or
+/// ended sessions, status transitions, host errors appearing/clearing).
+pub fn run_accessible(
+    mut collector: Collector,
+    hosts: Vec<String>,
+    opts: TuiOptions,
+    mut hooks: HookRunner,
+) -> anyhow::Result<()> {
+    let refresh = Duration::from_millis(opts.refresh_ms.max(100));
+    let mut state = AnnounceState::default();
+    let mut last_error: Option<String> = None;
+    let mut stdout = std::io::stdout();
+
+    loop {
+        let lines = match collector.collect(&hosts) {
+            Ok(snap) => {
+                last_error = None;
+                let now = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+                let (mut lines, changes) = announce(&mut state, &snap, now, &opts);
+                hooks.on_snapshot(&snap, &changes, now);
+                match collector.enforce_policy(&snap) {
+                    Ok(records) => lines.extend(records.iter().map(|r| r.summary())),
+                    Err(e) => lines.push(format!("Policy audit log failed: {e:#}")),
+                }
+                lines
+            }
+            Err(e) => {
+                let msg = format!("Refresh failed: {e}");
+                if last_error.as_deref() == Some(msg.as_str()) {
+                    Vec::new()
+                } else {
+                    last_error = Some(msg.clone());
+                    vec![msg]
+                }
+            }
+        };
+
+        for line in lines {
+            if let Err(e) = writeln!(stdout, "{line}") {
+                if e.kind() == std::io::ErrorKind::BrokenPipe {
+                    return Ok(());
+                }
+                return Err(e.into());
+            }
+        }
+        stdout.flush().ok();
+
+        std::thread::sleep(refresh);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct AnnounceState {
+    tracker: ChangeTracker,
+}
+
+fn announce(
+    state: &mut AnnounceState,
+    snap: &Snapshot,
+    now: i64,
+    opts: &TuiOptions,
+) -> (Vec<String>, SnapshotChanges) {
+    let changes = state.tracker.update(snap);
+    let mut lines: Vec<String> = Vec::new();
+
+    if changes.initial {
+        let rows = group_sessions_for_display(&snap.sessions, opts.debug);
+        lines.push(format!(
+            "{} active Codex sessions on {}.",
+            rows.len(),
+            snap.host
+        ));
+        for (i, r) in rows.iter().enumerate() {
+            lines.push(format!(
+                "Session {} of {}: {}",
+                i + 1,
+                rows.len(),
+                describe(r, now, opts)
+            ));
+        }
+        push_host_errors(&mut lines, &changes);
+        return (lines, changes);
+    }
+
+    let rows: HashMap<SessionId, &SessionRow> = snap.sessions.iter().map(|r| (r.id(), r)).collect();
+    for key in &changes.added {
+        if let Some(r) = rows.get(key) {
+            lines.push(format!("New session: {}", describe_row(r, now, opts)));
+        }
+    }
+    for c in &changes.status_changed {
+        if let Some(r) = rows.get(&c.key) {
+            lines.push(format!(
+                "{} is now {} (was {}).",
+                spoken_label(r),
+                spoken_status(c.to),
+                spoken_status(c.from)
+            ));
+        }
+    }
+    for f in &changes.finished {
+        if let Some(r) = rows.get(&f.key) {
+            lines.push(format!(
+                "{} finished after {} minutes: {}",
+                spoken_label(r),
+                f.worked_secs / 60,
+                message_preview(&f.message, 200)
+            ));
+        }
+    }
+    for r in &changes.removed {
+        match changes.receipt_for(r).map(|rc| rc.summary()) {
+            Some(summary) if !summary.is_empty() => {
+                lines.push(format!("Session ended: {}: {summary}.", spoken_label(r)))
+            }
+            _ => lines.push(format!("Session ended: {}.", spoken_label(r))),
+        }
+    }
+
+    push_host_errors(&mut lines, &changes);
+    for host in &changes.hosts_recovered {
+        lines.push(format!("Host {host} recovered."));
+    }
+
+    (lines, changes)
+}
+
+fn push_host_errors(lines: &mut Vec<String>, changes: &SnapshotChanges) {
+    for e in &changes.host_errors {
+        lines.push(format!(
+            "Host {} error, {}: {}.",
+            e.host,
+            e.kind.as_str(),
+            e.error
+        ));
+    }
+}
+
+fn spoken_label(r: &SessionRow) -> String {
+    let name = r
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .or(r.title.as_deref())
+        .unwrap_or("untitled");
+    format!(
+        "{name} on {} ({})",
+        r.host_display(),
+        short_thread_id(&r.thread_id)
+    )
+}
+
+fn spoken_status(s: SessionStatus) -> &'static str {
+    match s {
+        SessionStatus::Working => "working",
+        SessionStatus::Waiting => "waiting for input",
+        SessionStatus::Unknown => "unknown",
+    }
+}
+
+/// A grouped row (root plus rolled-up subagents) for the initial listing.
+fn describe(r: &DisplaySessionRow, now: i64, opts: &TuiOptions) -> String {
+    let mut parts = describe_parts(&r.root, r.status, r.last_activity_unix_s, now, opts);
+    if r.subagents.total > 0 {
+        parts.insert(parts.len() - 1, format!("{} subagents", r.subagents.total));
+    }
+    format!("{}.", parts.join(", "))
+}
+
+fn describe_row(r: &SessionRow, now: i64, opts: &TuiOptions) -> String {
+    let mut parts = describe_parts(r, r.status, r.last_activity_unix_s, now, opts);
+    if let Some(parent) = r.subagent_parent_thread_id.as_deref() {
+        parts.push(format!("subagent of {}", short_thread_id(parent)));
+    }
+    format!("{}.", parts.join(", "))
+}
+
+fn describe_parts(
+    r: &SessionRow,
+    status: SessionStatus,
+    last_activity_unix_s: Option<i64>,
+    now: i64,
+    opts: &TuiOptions,
+) -> Vec<String> {
+    let mut parts = vec![spoken_label(r), spoken_status(status).to_string()];
+    if let Some(prev) = r.previous_title.as_deref() {
+        parts.push(format!("previously titled {prev}"));
+    }
+    if !r.tags.is_empty() {
+        parts.push(format!("tagged {}", r.tags.join(" and ")));
+    }
+    if let Some(cwd) = r.cwd.as_deref() {
+        parts.push(format!("in {}", shorten_home_path(cwd)));
+    }
+    if let Some(branch) = r.git_branch.as_deref() {
+        parts.push(format!("branch {branch}"));
+    }
+    if let Some(ts) = last_activity_unix_s {
+        parts.push(format!(
+            "last activity {}",
+            format_timestamp(ts, now, opts.time_display)
+        ));
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timefmt::TimeDisplay;
+
+    fn row(thread_id: &str, status: SessionStatus) -> SessionRow {
+        SessionRow {
+            host: "local".into(),
+            thread_id: thread_id.into(),
+            title: Some(format!("title-{thread_id}")),
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn announces_listing_then_only_changes() {
+        let opts = TuiOptions {
+            refresh_ms: 1000,
+            debug: false,
+            time_display: TimeDisplay::Relative,
+            set_title: false,
+            theme: Default::default(),
+            truncate: Default::default(),
+            tag_styles: Default::default(),
+            guard: Default::default(),
+            schedule: Default::default(),
+            columns: Default::default(),
+            resume_command: None,
+            group_by_host: false,
+        };
+        let first = Snapshot::of(vec![
+            row("a", SessionStatus::Working),
+            row("b", SessionStatus::Waiting),
+        ]);
+        let mut state = AnnounceState::default();
+        let (lines, _) = announce(&mut state, &first, 0, &opts);
+        assert_eq!(lines[0], "2 active Codex sessions on local.");
+        assert_eq!(lines.len(), 3);
+
+        let (lines, _) = announce(&mut state, &first, 0, &opts);
+        assert!(lines.is_empty());
+
+        let second = Snapshot::of(vec![
+            row("a", SessionStatus::Waiting),
+            row("c", SessionStatus::Working),
+        ]);
+        let (lines, _) = announce(&mut state, &second, 0, &opts);
+        assert_eq!(
+            lines,
+            vec![
+                "New session: title-c on local (c), working.".to_string(),
+                "title-a on local (a) is now waiting for input (was working).".to_string(),
+                "Session ended: title-b on local (b).".to_string(),
+            ]
+        );
+    }
+}
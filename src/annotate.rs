@@ -0,0 +1,50 @@
+use std::io::Write;
+
+use crate::collector::Collector;
+use crate::guard::{GuardProfile, GuardedAction, confirm_cli};
+use crate::selector;
+
+/// What `codex-ps annotate` should attach to the matched session.
+#[derive(Clone, Debug, Default)]
+pub struct AnnotateRequest {
+    /// See `selector::resolve` (thread id prefix, name, `host:name`, `@latest`, ...).
+    pub selector: String,
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    /// Skip the guard profile's confirmation.
+    pub yes: bool,
+}
+
+/// Resolve the selector against the currently active sessions and apply the annotations.
+/// Ambiguous or unmatched selectors are errors (non-zero exit) so hooks notice.
+pub fn run_annotate(
+    mut collector: Collector,
+    hosts: &[String],
+    req: AnnotateRequest,
+    guard: GuardProfile,
+) -> anyhow::Result<()> {
+    if req.name.is_none() && req.tags.is_empty() && req.note.is_none() {
+        anyhow::bail!("nothing to do: pass at least one of --name, --tag, --note");
+    }
+
+    let snap = collector.collect(hosts)?;
+    let row = selector::resolve(&snap.sessions, &req.selector)?;
+    let key = row.id();
+    confirm_cli(
+        guard,
+        GuardedAction::Annotate,
+        &format!("Annotate {}:{}", key.host, key.thread_id),
+        req.yes,
+    )?;
+
+    if let Some(name) = req.name {
+        collector.set_session_name(key.clone(), name)?;
+    }
+    if !req.tags.is_empty() || req.note.is_some() {
+        collector.annotate_session(key.clone(), &req.tags, req.note)?;
+    }
+
+    writeln!(std::io::stdout(), "{} {}", key.host, key.thread_id).ok();
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::model::SessionId;
+use crate::names::{config_dir, unix_ms_now};
+
+/// Free-form tags and a note attached to a session (names live in `NamesStore`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Pinned to the top of the TUI, with a hook on every status change.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub watched: bool,
+}
+
+/// `session_annotations.jsonl`: one full `Annotation` per line, latest line per session wins.
+#[derive(Clone, Debug)]
+pub struct AnnotationsStore {
+    path: PathBuf,
+    last_mtime: Option<SystemTime>,
+    entries: HashMap<SessionId, Annotation>,
+    /// When each session's latest record was written (ms), for last-writer-wins sync.
+    stamps: HashMap<SessionId, i64>,
+}
+
+impl AnnotationsStore {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self::new_at(
+            config_dir()?.join("session_annotations.jsonl"),
+        ))
+    }
+
+    pub fn new_at(path: PathBuf) -> Self {
+        Self {
+            path,
+            last_mtime: None,
+            entries: HashMap::new(),
+            stamps: HashMap::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn refresh_if_changed(&mut self) -> anyhow::Result<()> {
+        let mtime = match fs::metadata(&self.path) {
+            Ok(m) => m.modified().ok(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.last_mtime = None;
+                self.entries.clear();
+                self.stamps.clear();
+                return Ok(());
+            }
+            Err(e) => return Err(e).with_context(|| format!("stat {}", self.path.display())),
+        };
+        if mtime.is_some() && mtime == self.last_mtime {
+            return Ok(());
+        }
+        self.last_mtime = mtime;
+        self.entries.clear();
+        self.stamps.clear();
+
+        let f =
+            fs::File::open(&self.path).with_context(|| format!("open {}", self.path.display()))?;
+        for (i, line) in BufReader::new(f).lines().enumerate() {
+            let line = line.context("read line")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let rec: AnnotationRecord = serde_json::from_str(&line)
+                .with_context(|| format!("parse session_annotations.jsonl line {}", i + 1))?;
+            let key = SessionId {
+                host: rec.host,
+                thread_id: rec.thread_id,
+            };
+            self.stamps.insert(key.clone(), rec.at_unix_ms.unwrap_or(0));
+            self.entries.insert(key, rec.annotation);
+        }
+        Ok(())
+    }
+
+    pub fn get_cached(&self, key: &SessionId) -> Option<&Annotation> {
+        self.entries.get(key)
+    }
+
+    /// Add `tags` (deduplicated, trimmed) and replace the note when given; an empty note clears it.
+    pub fn update(
+        &mut self,
+        key: SessionId,
+        tags: &[String],
+        note: Option<String>,
+    ) -> anyhow::Result<Annotation> {
+        self.refresh_if_changed()?;
+        let mut a = self.entries.get(&key).cloned().unwrap_or_default();
+        a.tags.extend(tags.iter().cloned());
+        if note.is_some() {
+            a.note = note;
+        }
+        self.set(key, a)
+    }
+
+    /// Replace a session's tags, note and watch flag (as edited in the TUI), normalized like
+    /// `update`.
+    pub fn set(&mut self, key: SessionId, a: Annotation) -> anyhow::Result<Annotation> {
+        let mut tags: Vec<String> = Vec::new();
+        for t in a.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if !tags.iter().any(|x| x == t) {
+                tags.push(t.to_string());
+            }
+        }
+        let note = a
+            .note
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(str::to_string);
+        let a = Annotation {
+            tags,
+            note,
+            watched: a.watched,
+        };
+        self.write(key, a.clone(), unix_ms_now())?;
+        Ok(a)
+    }
+
+    /// The latest record per session (what `labels export` prints).
+    pub fn records(&self) -> Vec<AnnotationRecord> {
+        let mut out: Vec<AnnotationRecord> = self
+            .entries
+            .iter()
+            .map(|(key, a)| AnnotationRecord {
+                host: key.host.clone(),
+                thread_id: key.thread_id.clone(),
+                annotation: a.clone(),
+                at_unix_ms: Some(self.stamps.get(key).copied().unwrap_or(0)),
+            })
+            .collect();
+        out.sort_by(|a, b| (&a.host, &a.thread_id).cmp(&(&b.host, &b.thread_id)));
+        out
+    }
+
+    /// Last-writer-wins, like `NamesStore::merge_record`.
+    pub fn merge_record(&mut self, rec: AnnotationRecord) -> anyhow::Result<bool> {
+        self.refresh_if_changed()?;
+        let key = SessionId::new(rec.host, rec.thread_id);
+        let at = rec.at_unix_ms.unwrap_or(0);
+        if self.stamps.get(&key).is_some_and(|have| *have >= at) {
+            return Ok(false);
+        }
+        self.write(key, rec.annotation, at)?;
+        Ok(true)
+    }
+
+    fn write(&mut self, key: SessionId, a: Annotation, at: i64) -> anyhow::Result<()> {
+        self.append_record(&key, &a, at)?;
+        self.stamps.insert(key.clone(), at);
+        self.entries.insert(key, a);
+        Ok(())
+    }
+
+    fn append_record(&mut self, key: &SessionId, a: &Annotation, at: i64) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create dir {}", parent.display()))?;
+        }
+        let rec = AnnotationRecord {
+            host: key.host.clone(),
+            thread_id: key.thread_id.clone(),
+            annotation: a.clone(),
+            at_unix_ms: Some(at),
+        };
+        let line = serde_json::to_string(&rec).context("serialize session annotation record")?;
+        let mut f = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("open for append {}", self.path.display()))?;
+        writeln!(f, "{line}").context("append session annotation record")?;
+
+        self.last_mtime = fs::metadata(&self.path)
+            .ok()
+            .and_then(|m| m.modified().ok());
+        Ok(())
+    }
+}
+
+/// One `session_annotations.jsonl` line.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AnnotationRecord {
+    pub host: String,
+    pub thread_id: String,
+    #[serde(flatten)]
+    pub annotation: Annotation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at_unix_ms: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_merges_tags_and_replaces_note() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let p = dir.path().join("session_annotations.jsonl");
+        let key = SessionId::new("local", "t1");
+
+        let mut store = AnnotationsStore::new_at(p.clone());
+        store
+            .update(key.clone(), &["bug".into()], Some("first".into()))
+            .expect("update");
+        let a = store
+            .update(key.clone(), &["bug".into(), " infra ".into()], None)
+            .expect("update");
+        assert_eq!(a.tags, vec!["bug".to_string(), "infra".to_string()]);
+        assert_eq!(a.note.as_deref(), Some("first"));
+
+        let mut reloaded = AnnotationsStore::new_at(p);
+        reloaded.refresh_if_changed().expect("refresh");
+        assert_eq!(reloaded.get_cached(&key), Some(&a));
+    }
+}
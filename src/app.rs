@@ -1,37 +1,83 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::io;
+use std::path::Path;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::Context;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEventKind};
 use crossterm::execute;
 use crossterm::terminal::{
-    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+    EnterAlternateScreen, LeaveAlternateScreen, SetTitle, disable_raw_mode, enable_raw_mode,
 };
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap};
 
+use crate::annotations::Annotation;
+use crate::budget::parse_duration_secs;
+use crate::changes::{ChangeTracker, SnapshotChanges};
 use crate::collector::Collector;
-use crate::model::{SessionRow, SessionStatus, Snapshot};
-use crate::names::SessionNameKey;
-use crate::util::truncate_middle;
+use crate::error::ErrorKind;
+use crate::guard::{GuardProfile, GuardedAction};
+use crate::hooks::HookRunner;
+use crate::last_seen::LastSnapshotStore;
+use crate::logs::RolloutTail;
+use crate::model::SessionId;
+use crate::model::{HostError, SessionRow, SessionStatus, Snapshot, StatusReasonCode};
+use crate::schedule::{PauseScope, ScheduleConfig};
+use crate::show::{SessionDetail, format_detail};
+use crate::swarm::{SPAWN_RATE_WINDOW_SECS, SwarmRow, SwarmTracker};
+use crate::theme::{TagStyle, Theme, TruncateConfig, tag_style};
+use crate::timefmt::{TimeDisplay, format_duration, format_timestamp};
+use crate::util::{Truncation, truncate_end, truncate_middle, wrap_chars};
+use crate::waits::WaitTracker;
+
+/// While the terminal is unfocused, refresh this many times slower (but at least every
+/// `UNFOCUSED_MIN_REFRESH`).
+const UNFOCUSED_REFRESH_FACTOR: u32 = 5;
+const UNFOCUSED_MIN_REFRESH: Duration = Duration::from_secs(10);
+/// How long a row shows DONE after a long turn completes (until it starts working again).
+const FINISHED_DISPLAY: Duration = Duration::from_secs(120);
+/// Below this many columns (or `COMPACT_HEIGHT` rows) the table is replaced by a compact
+/// summary instead of being squeezed into an unreadable state.
+const COMPACT_WIDTH: u16 = 50;
+const COMPACT_HEIGHT: u16 = 6;
+
+/// Presentation knobs for the TUI (collection options live on `Collector`).
+#[derive(Clone, Debug)]
+pub struct TuiOptions {
+    pub refresh_ms: u64,
+    pub debug: bool,
+    pub time_display: TimeDisplay,
+    /// Keep the terminal/tmux title set to `attention_title` (OSC 0).
+    pub set_title: bool,
+    pub theme: Theme,
+    pub truncate: TruncateConfig,
+    pub tag_styles: BTreeMap<String, TagStyle>,
+    pub guard: GuardProfile,
+    pub schedule: ScheduleConfig,
+    pub columns: ColumnLayout,
+    /// `resume_command` from the config (`o`).
+    pub resume_command: Option<String>,
+    /// Start with rows grouped under per-host headers (`g` toggles).
+    pub group_by_host: bool,
+}
 
 pub fn run_tui(
     collector: Collector,
     hosts: Vec<String>,
-    refresh_ms: u64,
-    debug: bool,
+    opts: TuiOptions,
+    hooks: HookRunner,
 ) -> anyhow::Result<()> {
     enable_raw_mode().context("enable raw mode")?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen).context("enter alternate screen")?;
+    execute!(stdout, EnterAlternateScreen, EnableFocusChange).context("enter alternate screen")?;
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).context("create terminal")?;
@@ -40,19 +86,33 @@ pub fn run_tui(
     let (cmd_tx, cmd_rx) = mpsc::channel::<WorkerCmd>();
     let (msg_tx, msg_rx) = mpsc::channel::<WorkerMsg>();
 
-    let worker = thread::spawn(move || worker_loop(collector, hosts, debug, cmd_rx, msg_tx));
+    let cancel = collector.cancel_token();
+    let last_seen = LastSnapshotStore::new().ok();
+    let stale = last_seen.as_ref().and_then(|s| s.load(&hosts));
+    let mut app = App::new(&opts, cmd_tx, msg_rx);
+    app.hosts = hosts.clone();
+    let worker = thread::spawn(move || worker_loop(collector, hosts, hooks, cmd_rx, msg_tx));
 
-    let mut app = App::new(refresh_ms, debug, cmd_tx, msg_rx);
+    if let Some(snap) = stale {
+        app.show_stale(snap);
+    }
     app.request_refresh();
 
     let res = run_loop(&mut terminal, &mut app);
 
-    // Stop the worker (drop sender, then join).
+    // Stop the worker: abort any slow remote collection, drop the sender, then join.
+    cancel.cancel();
+    if let (Some(store), Some(snap), false) = (last_seen, app.last_snapshot.as_ref(), app.stale) {
+        store.save(snap).ok();
+    }
     drop(app);
     let _ = worker.join();
 
     disable_raw_mode().ok();
-    execute!(io::stdout(), LeaveAlternateScreen).ok();
+    if opts.set_title {
+        execute!(io::stdout(), SetTitle("")).ok();
+    }
+    execute!(io::stdout(), DisableFocusChange, LeaveAlternateScreen).ok();
     terminal.show_cursor().ok();
 
     res
@@ -60,98 +120,320 @@ pub fn run_tui(
 
 #[derive(Debug, Clone)]
 enum WorkerCmd {
-    Refresh,
-    SetName { key: SessionNameKey, name: String },
-    ClearName { key: SessionNameKey },
+    /// `poll_remote = false` keeps remote hosts at their last result (terminal unfocused or
+    /// quiet hours).
+    Refresh {
+        poll_remote: bool,
+    },
+    RefreshSession {
+        key: SessionId,
+    },
+    SetName {
+        key: SessionId,
+        name: String,
+    },
+    ClearName {
+        key: SessionId,
+    },
+    /// Replace a session's tags and note.
+    SetAnnotation {
+        key: SessionId,
+        annotation: Annotation,
+    },
+    /// SIGTERM, or SIGKILL with `force`.
+    Kill {
+        key: SessionId,
+        pids: Vec<i32>,
+        force: bool,
+    },
+    /// Poll `hosts` from now on, once `probe` (a newly added host) answers.
+    SetHosts {
+        hosts: Vec<String>,
+        probe: Option<String>,
+    },
+}
+
+/// Pending worker commands. Edits and kills run first (in order), then per-session refreshes
+/// (deduplicated), then at most one full refresh however many were requested (polling
+/// remotes if any of them asked to).
+#[derive(Debug, Default)]
+struct CommandQueue {
+    actions: VecDeque<WorkerCmd>,
+    sessions: VecDeque<SessionId>,
+    refresh: Option<bool>,
+}
+
+impl CommandQueue {
+    fn push(&mut self, cmd: WorkerCmd) {
+        match cmd {
+            WorkerCmd::Refresh { poll_remote } => {
+                self.refresh = Some(self.refresh.unwrap_or(false) || poll_remote);
+            }
+            WorkerCmd::RefreshSession { key } => {
+                if !self.sessions.contains(&key) {
+                    self.sessions.push_back(key);
+                }
+            }
+            WorkerCmd::SetName { .. }
+            | WorkerCmd::ClearName { .. }
+            | WorkerCmd::SetAnnotation { .. }
+            | WorkerCmd::Kill { .. }
+            | WorkerCmd::SetHosts { .. } => self.actions.push_back(cmd),
+        }
+    }
+
+    fn pop(&mut self) -> Option<WorkerCmd> {
+        if let Some(cmd) = self.actions.pop_front() {
+            return Some(cmd);
+        }
+        if let Some(key) = self.sessions.pop_front() {
+            return Some(WorkerCmd::RefreshSession { key });
+        }
+        self.refresh
+            .take()
+            .map(|poll_remote| WorkerCmd::Refresh { poll_remote })
+    }
+
+    /// The next edit or kill, which may run mid-collection. A host change waits its turn.
+    fn pop_action(&mut self) -> Option<WorkerCmd> {
+        if matches!(self.actions.front(), Some(WorkerCmd::SetHosts { .. })) {
+            return None;
+        }
+        self.actions.pop_front()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.actions.is_empty() && self.sessions.is_empty() && self.refresh.is_none()
+    }
 }
 
 #[derive(Debug)]
 enum WorkerMsg {
-    Snapshot(Snapshot),
+    /// A full snapshot plus its diff against the previous one (computed once, here).
+    Snapshot(Snapshot, Box<SnapshotChanges>),
+    /// Mid-cycle view while slower hosts are still being polled (display only).
+    Partial(Box<Snapshot>),
     Error(String),
     Status(String),
     NameUpdated {
-        key: SessionNameKey,
+        key: SessionId,
         name: Option<String>,
     },
+    AnnotationUpdated {
+        key: SessionId,
+        annotation: Annotation,
+    },
+    SessionRefreshed {
+        key: SessionId,
+        row: Option<Box<SessionRow>>,
+    },
+    /// The hosts now being polled.
+    HostsUpdated(Vec<String>),
 }
 
 fn worker_loop(
     mut collector: Collector,
-    hosts: Vec<String>,
-    debug: bool,
+    mut hosts: Vec<String>,
+    mut hooks: HookRunner,
     cmd_rx: Receiver<WorkerCmd>,
     msg_tx: mpsc::Sender<WorkerMsg>,
 ) {
-    while let Ok(cmd) = cmd_rx.recv() {
+    let mut queue = CommandQueue::default();
+    let mut tracker = ChangeTracker::default();
+    loop {
+        if queue.is_empty() {
+            match cmd_rx.recv() {
+                Ok(cmd) => queue.push(cmd),
+                Err(_) => return,
+            }
+        }
+        // Fold in everything that arrived while the last command ran before picking the next.
+        while let Ok(cmd) = cmd_rx.try_recv() {
+            queue.push(cmd);
+        }
+        let Some(cmd) = queue.pop() else {
+            continue;
+        };
+
         match cmd {
-            WorkerCmd::Refresh => match collector.collect(&hosts, debug) {
-                Ok(snap) => {
-                    let _ = msg_tx.send(WorkerMsg::Snapshot(snap));
-                }
-                Err(e) => {
-                    let _ = msg_tx.send(WorkerMsg::Error(format!("{e}")));
+            WorkerCmd::Refresh { poll_remote } => {
+                let mut send_partial = |snap: Snapshot| {
+                    let _ = msg_tx.send(WorkerMsg::Partial(Box::new(snap)));
+                };
+                // Edits and kills that arrive while slow hosts are polled run between hosts.
+                let mut run_actions = |collector: &mut Collector| {
+                    while let Ok(cmd) = cmd_rx.try_recv() {
+                        queue.push(cmd);
+                    }
+                    while let Some(action) = queue.pop_action() {
+                        run_action(collector, action, &msg_tx);
+                    }
+                };
+                match collector.collect_progressive(
+                    &hosts,
+                    poll_remote,
+                    &mut send_partial,
+                    &mut run_actions,
+                ) {
+                    Ok(snap) => {
+                        let changes = tracker.update(&snap);
+                        let now =
+                            crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+                        hooks.on_snapshot(&snap, &changes, now);
+                        match collector.enforce_policy(&snap) {
+                            Ok(records) => {
+                                for r in records {
+                                    let _ = msg_tx.send(WorkerMsg::Status(r.summary()));
+                                }
+                            }
+                            Err(e) => {
+                                let _ = msg_tx
+                                    .send(WorkerMsg::Error(format!("policy audit log: {e:#}")));
+                            }
+                        }
+                        let _ = msg_tx.send(WorkerMsg::Snapshot(snap, Box::new(changes)));
+                    }
+                    Err(e) => {
+                        let _ = msg_tx.send(WorkerMsg::Error(format!("{e}")));
+                    }
                 }
-            },
-            WorkerCmd::SetName { key, name } => match collector.set_session_name(key.clone(), name)
-            {
-                Ok(normalized) => {
-                    let _ = msg_tx.send(WorkerMsg::NameUpdated {
-                        key: key.clone(),
-                        name: normalized.clone(),
+            }
+            WorkerCmd::RefreshSession { key } => match collector.refresh_session(&key) {
+                Ok(row) => {
+                    let _ = msg_tx.send(WorkerMsg::SessionRefreshed {
+                        key,
+                        row: row.map(Box::new),
                     });
-                    let tid = short_thread_id(&key.thread_id);
-                    let _ = msg_tx.send(WorkerMsg::Status(format!(
-                        "Saved name for ({}) {tid}",
-                        key.host
-                    )));
                 }
                 Err(e) => {
-                    let _ = msg_tx.send(WorkerMsg::Error(format!("failed to save name: {e}")));
+                    let _ =
+                        msg_tx.send(WorkerMsg::Error(format!("failed to refresh session: {e}")));
                 }
             },
-            WorkerCmd::ClearName { key } => match collector.clear_session_name(key.clone()) {
-                Ok(()) => {
-                    let _ = msg_tx.send(WorkerMsg::NameUpdated {
+            WorkerCmd::SetHosts { hosts: next, probe } => {
+                if let Some(host) = probe {
+                    let _ = msg_tx.send(WorkerMsg::Status(format!("checking {host}...")));
+                    if let Err(e) = collector.probe_host(&host) {
+                        let _ = msg_tx.send(WorkerMsg::Error(format!("not adding {host}: {e:#}")));
+                        continue;
+                    }
+                }
+                tracker.retain_hosts(&next);
+                hosts = next;
+                let _ = msg_tx.send(WorkerMsg::HostsUpdated(hosts.clone()));
+                let _ = msg_tx.send(WorkerMsg::Status(format!("polling {}", hosts.join(", "))));
+                queue.push(WorkerCmd::Refresh { poll_remote: true });
+            }
+            action => run_action(&mut collector, action, &msg_tx),
+        }
+    }
+}
+
+/// Renames, tag/note edits and kills: run from the queue, or between hosts mid-collection.
+fn run_action(collector: &mut Collector, cmd: WorkerCmd, msg_tx: &mpsc::Sender<WorkerMsg>) {
+    match cmd {
+        WorkerCmd::SetName { key, name } => match collector.set_session_name(key.clone(), name) {
+            Ok(normalized) => {
+                let _ = msg_tx.send(WorkerMsg::NameUpdated {
+                    key: key.clone(),
+                    name: normalized.clone(),
+                });
+                let tid = short_thread_id(&key.thread_id);
+                let _ = msg_tx.send(WorkerMsg::Status(format!(
+                    "Saved name for ({}) {tid}",
+                    key.host
+                )));
+            }
+            Err(e) => {
+                let _ = msg_tx.send(WorkerMsg::Error(format!("failed to save name: {e}")));
+            }
+        },
+        WorkerCmd::ClearName { key } => match collector.clear_session_name(key.clone()) {
+            Ok(()) => {
+                let _ = msg_tx.send(WorkerMsg::NameUpdated {
+                    key: key.clone(),
+                    name: None,
+                });
+                let tid = short_thread_id(&key.thread_id);
+                let _ = msg_tx.send(WorkerMsg::Status(format!(
+                    "Cleared name for ({}) {tid}",
+                    key.host
+                )));
+            }
+            Err(e) => {
+                let _ = msg_tx.send(WorkerMsg::Error(format!("failed to clear name: {e}")));
+            }
+        },
+        WorkerCmd::Kill { key, pids, force } => {
+            let tid = short_thread_id(&key.thread_id);
+            let signal = if force { "SIGKILL" } else { "SIGTERM" };
+            let msg = match collector.kill_session(&key, &pids, force) {
+                Ok(()) => WorkerMsg::Status(format!("Sent {signal} to ({}) {tid}", key.host)),
+                Err(e) => WorkerMsg::Error(format!("failed to kill ({}) {tid}: {e:#}", key.host)),
+            };
+            let _ = msg_tx.send(msg);
+        }
+        WorkerCmd::SetAnnotation { key, annotation } => {
+            match collector.set_session_annotation(key.clone(), annotation) {
+                Ok(annotation) => {
+                    let _ = msg_tx.send(WorkerMsg::AnnotationUpdated {
                         key: key.clone(),
-                        name: None,
+                        annotation,
                     });
                     let tid = short_thread_id(&key.thread_id);
                     let _ = msg_tx.send(WorkerMsg::Status(format!(
-                        "Cleared name for ({}) {tid}",
+                        "Saved tags/note for ({}) {tid}",
                         key.host
                     )));
                 }
                 Err(e) => {
-                    let _ = msg_tx.send(WorkerMsg::Error(format!("failed to clear name: {e}")));
+                    let _ = msg_tx.send(WorkerMsg::Error(format!("failed to save tags/note: {e}")));
                 }
-            },
+            }
         }
+        // Handled by the worker loop itself.
+        WorkerCmd::Refresh { .. }
+        | WorkerCmd::RefreshSession { .. }
+        | WorkerCmd::SetHosts { .. } => {}
     }
 }
 
-#[derive(Clone, Debug)]
-struct SubagentSummary {
-    total: usize,
-    working: usize,
-    unknown: usize,
-    waiting: usize,
+#[derive(Clone, Debug, Default)]
+pub struct SubagentSummary {
+    pub total: usize,
+    pub working: usize,
+    pub unknown: usize,
+    pub waiting: usize,
 }
 
+/// One visible row: a root thread with its subagents rolled up.
 #[derive(Clone, Debug)]
-struct DisplaySessionRow {
-    root: SessionRow,
-    status: SessionStatus,
-    last_activity_unix_s: Option<i64>,
-    reason: Option<String>,
-    subagents: SubagentSummary,
+pub struct DisplaySessionRow {
+    pub root: SessionRow,
+    pub status: SessionStatus,
+    pub last_activity_unix_s: Option<i64>,
+    pub reason: Option<String>,
+    pub subagents: SubagentSummary,
+    /// The subagent threads themselves, oldest first, for the expanded tree.
+    pub children: Vec<SessionRow>,
 }
 
-fn group_sessions_for_display(sessions: &[SessionRow], debug: bool) -> Vec<DisplaySessionRow> {
-    let mut ids: HashSet<(String, String)> = HashSet::new();
-    for s in sessions {
-        ids.insert((s.host.clone(), s.thread_id.clone()));
+impl DisplaySessionRow {
+    /// A subagent shown on its own line of an expanded tree.
+    fn leaf(row: &SessionRow) -> Self {
+        Self {
+            root: row.clone(),
+            status: row.status,
+            last_activity_unix_s: row.last_activity_unix_s,
+            reason: row.debug.as_ref().and_then(|d| d.status_reason.clone()),
+            subagents: SubagentSummary::default(),
+            children: Vec::new(),
+        }
     }
+}
+
+pub fn group_sessions_for_display(sessions: &[SessionRow], debug: bool) -> Vec<DisplaySessionRow> {
+    let ids: HashSet<SessionId> = sessions.iter().map(SessionRow::id).collect();
 
     #[derive(Default)]
     struct Agg {
@@ -159,15 +441,17 @@ fn group_sessions_for_display(sessions: &[SessionRow], debug: bool) -> Vec<Displ
         subs: Vec<SessionRow>,
     }
 
-    let mut groups: HashMap<(String, String), Agg> = HashMap::new();
+    let mut groups: HashMap<SessionId, Agg> = HashMap::new();
     for s in sessions {
         let root_id = match s.subagent_parent_thread_id.as_ref() {
-            Some(parent) if ids.contains(&(s.host.clone(), parent.clone())) => parent.clone(),
-            _ => s.thread_id.clone(),
+            Some(parent) if ids.contains(&SessionId::new(s.host.clone(), parent.clone())) => {
+                SessionId::new(s.host.clone(), parent.clone())
+            }
+            _ => s.id(),
         };
-        let key = (s.host.clone(), root_id.clone());
-        let entry = groups.entry(key).or_default();
-        if s.thread_id == root_id {
+        let is_root = s.thread_id == root_id.thread_id;
+        let entry = groups.entry(root_id).or_default();
+        if is_root {
             entry.root = Some(s.clone());
         } else {
             entry.subs.push(s.clone());
@@ -175,7 +459,7 @@ fn group_sessions_for_display(sessions: &[SessionRow], debug: bool) -> Vec<Displ
     }
 
     let mut out: Vec<DisplaySessionRow> = Vec::new();
-    for ((_host, _root_id), agg) in groups {
+    for agg in groups.into_values() {
         let Some(root) = agg.root else {
             // Shouldn't happen with the root-id selection fallback, but fail-loud by omission.
             continue;
@@ -232,75 +516,373 @@ fn group_sessions_for_display(sessions: &[SessionRow], debug: bool) -> Vec<Displ
             None
         };
 
+        let mut children = agg.subs;
+        children.sort_by(|a, b| {
+            (a.started_at_unix_s, &a.thread_id).cmp(&(b.started_at_unix_s, &b.thread_id))
+        });
         out.push(DisplaySessionRow {
             root,
             status,
             last_activity_unix_s: last_ts,
             reason,
             subagents: sub_summary,
+            children,
         });
     }
 
-    // Stable sort:
-    // 1) named sessions first (scanability)
-    // 2) most recent activity
-    // 3) host, then thread id (deterministic tiebreakers)
-    out.sort_by(|a, b| {
-        let a_named = a.root.name.as_ref().is_some_and(|s| !s.trim().is_empty());
-        let b_named = b.root.name.as_ref().is_some_and(|s| !s.trim().is_empty());
+    sort_display_rows(&mut out, SortOrder::Activity);
+    out
+}
+
+/// Row order in the TUI (`s` cycles through them).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SortOrder {
+    /// Named sessions first, then most recent activity.
+    #[default]
+    Activity,
+    /// Waiting, then working, then unknown.
+    Status,
+    Host,
+    /// Alphabetical by name; unnamed sessions last.
+    Name,
+    /// Oldest session first.
+    Age,
+}
+
+impl SortOrder {
+    fn next(self) -> Self {
+        match self {
+            SortOrder::Activity => SortOrder::Status,
+            SortOrder::Status => SortOrder::Host,
+            SortOrder::Host => SortOrder::Name,
+            SortOrder::Name => SortOrder::Age,
+            SortOrder::Age => SortOrder::Activity,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortOrder::Activity => "activity",
+            SortOrder::Status => "status",
+            SortOrder::Host => "host",
+            SortOrder::Name => "name",
+            SortOrder::Age => "age",
+        }
+    }
+}
+
+/// Stable sort: watched sessions always first, then `order`, then most recent activity, then
+/// host and thread id (deterministic tiebreakers).
+fn sort_display_rows(rows: &mut [DisplaySessionRow], order: SortOrder) {
+    let name = |r: &DisplaySessionRow| {
+        r.root
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_lowercase)
+    };
+    let status_rank = |r: &DisplaySessionRow| match r.status {
+        SessionStatus::Waiting => 0,
+        SessionStatus::Working => 1,
+        SessionStatus::Unknown => 2,
+    };
+    rows.sort_by(|a, b| {
+        let primary = match order {
+            SortOrder::Activity => name(b).is_some().cmp(&name(a).is_some()),
+            SortOrder::Status => status_rank(a).cmp(&status_rank(b)),
+            SortOrder::Host => a.root.host_display().cmp(b.root.host_display()),
+            SortOrder::Name => match (name(a), name(b)) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (x, y) => y.is_some().cmp(&x.is_some()),
+            },
+            SortOrder::Age => a
+                .root
+                .started_at_unix_s
+                .unwrap_or(i64::MAX)
+                .cmp(&b.root.started_at_unix_s.unwrap_or(i64::MAX)),
+        };
         let a_ts = a.last_activity_unix_s.unwrap_or(i64::MIN);
         let b_ts = b.last_activity_unix_s.unwrap_or(i64::MIN);
-        b_named
-            .cmp(&a_named)
+        b.root
+            .watched
+            .cmp(&a.root.watched)
+            .then(primary)
             .then_with(|| b_ts.cmp(&a_ts))
             .then_with(|| a.root.host.cmp(&b.root.host))
             .then_with(|| a.root.thread_id.cmp(&b.root.thread_id))
     });
-
-    out
 }
 
 struct App {
     refresh: Duration,
+    /// False after a FocusLost event: refresh slowly and leave remote hosts alone.
+    focused: bool,
+    /// `--debug`, toggled with `d`: the WHY column, status reasons and store warnings.
     debug: bool,
+    time_display: TimeDisplay,
+    columns: ColumnLayout,
     refresh_in_flight: bool,
     last_refresh_sent: Instant,
     last_snapshot: Option<Snapshot>,
+    last_changes: Option<SnapshotChanges>,
     display_sessions: Vec<DisplaySessionRow>,
-    selected: Option<SessionNameKey>,
-    rename_modal: Option<RenameModal>,
+    selected: Option<SessionId>,
+    edit_modal: Option<EditModal>,
     last_error: Option<String>,
     last_status: Option<(Instant, String)>,
     last_warning_seen: Option<String>,
+    view: View,
+    swarm: SwarmTracker,
+    swarm_rows: Vec<SwarmRow>,
+    swarm_selected: usize,
+    finished: HashMap<SessionId, Instant>,
+    waits: WaitTracker,
+    set_title: bool,
+    theme: Theme,
+    truncate: TruncateConfig,
+    tag_styles: BTreeMap<String, TagStyle>,
+    /// Show the selected row in full, wrapping long cells.
+    expanded: bool,
+    /// `g`: rows grouped under a header per host.
+    by_host: bool,
+    /// `1`/`2`/`3`: only working, waiting or unknown rows (`0` shows all again).
+    status_filter: Option<SessionStatus>,
+    /// `P`: no periodic refreshes, so rows hold still (`r` still refreshes by hand).
+    paused: bool,
+    /// `h`: the host-errors panel is open, scrolled down this many lines.
+    host_errors_panel: Option<u16>,
+    /// Hosts being polled, and those taken out with `H` this run (they can be added back).
+    hosts: Vec<String>,
+    disabled_hosts: Vec<String>,
+    /// `H`: the host name being typed.
+    host_input: Option<String>,
+    /// Roots whose subagents are listed as child rows (Tab / → / ←).
+    open_trees: HashSet<SessionId>,
+    /// Show the latest transcript line of each watched session under the table.
+    watch_pane: bool,
+    /// `l`: tail the selected session's rollout under the table.
+    log_pane: Option<LogPane>,
+    /// Show everything known about the selected session under the table.
+    detail: bool,
+    /// Recent status changes per session, listed in the detail pane.
+    transitions: HashMap<SessionId, VecDeque<Transition>>,
+    /// `/` filter: only rows matching every word are listed.
+    filter: String,
+    /// Keys go to the filter while it is being typed.
+    filter_input: bool,
+    /// `y` was pressed; the next key picks what to copy.
+    yank: bool,
+    sort: SortOrder,
+    guard: GuardProfile,
+    schedule: ScheduleConfig,
+    resume_command: Option<String>,
+    /// A guarded action waiting for `y`.
+    confirm: Option<Confirm>,
+    /// Sessions sent a SIGTERM; killing one again sends SIGKILL.
+    terminated: HashSet<SessionId>,
+    last_title: Option<String>,
+    /// `last_snapshot` was loaded from the previous run and no collection has finished yet.
+    stale: bool,
     cmd_tx: Sender<WorkerCmd>,
     msg_rx: Receiver<WorkerMsg>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum View {
+    Sessions,
+    /// Per-root subagent fleet summary; `drill` shows one root's subagents.
+    Swarm {
+        drill: Option<SessionId>,
+    },
+}
+
+#[derive(Debug)]
+struct Confirm {
+    prompt: String,
+    cmd: WorkerCmd,
+}
+
+/// In-place editor for a session's name, tags and note: ↑/↓ picks a field, Enter edits it,
+/// Enter again saves just that field.
 #[derive(Clone, Debug)]
-struct RenameModal {
-    key: SessionNameKey,
-    buffer: String,
+struct EditModal {
+    key: SessionId,
+    field: EditField,
+    name: String,
+    /// Comma-separated.
+    tags: String,
+    note: String,
+    /// Text being typed into `field`; `None` while navigating between fields.
+    buffer: Option<String>,
+    /// Opened with `n`: close once the name is saved, like a plain rename prompt.
+    close_after_save: bool,
+    /// Proposed name for an unnamed session, shown dimmed; Tab takes it.
+    suggestion: Option<String>,
+    /// Carried through so saving tags or a note doesn't unwatch the session.
+    watched: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditField {
+    Name,
+    Tags,
+    Note,
+}
+
+impl EditField {
+    const ALL: [EditField; 3] = [EditField::Name, EditField::Tags, EditField::Note];
+
+    fn label(self) -> &'static str {
+        match self {
+            EditField::Name => "Name",
+            EditField::Tags => "Tags",
+            EditField::Note => "Note",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum EditOutcome {
+    None,
+    Close,
+    /// Persist `field` with the new text.
+    Save(EditField, String),
+}
+
+impl EditModal {
+    fn for_row(row: &SessionRow) -> Self {
+        Self {
+            key: row.id(),
+            field: EditField::Name,
+            name: row.name.clone().unwrap_or_default(),
+            tags: row.tags.join(", "),
+            note: row.note.clone().unwrap_or_default(),
+            buffer: None,
+            close_after_save: false,
+            suggestion: row.suggested_name.clone().filter(|_| row.name.is_none()),
+            watched: row.watched,
+        }
+    }
+
+    /// The suggestion, while the name being typed is still empty.
+    fn pending_suggestion(&self) -> Option<&str> {
+        let empty = match self.buffer.as_deref() {
+            Some(buffer) => buffer.is_empty(),
+            None => self.name.is_empty(),
+        };
+        self.suggestion
+            .as_deref()
+            .filter(|_| self.field == EditField::Name && empty)
+    }
+
+    fn value(&self, field: EditField) -> &str {
+        match field {
+            EditField::Name => &self.name,
+            EditField::Tags => &self.tags,
+            EditField::Note => &self.note,
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> EditOutcome {
+        if code == KeyCode::Tab {
+            if let Some(s) = self.pending_suggestion().map(str::to_string) {
+                self.buffer = None;
+                self.name = s.clone();
+                return EditOutcome::Save(EditField::Name, s);
+            }
+        }
+        if let Some(buffer) = self.buffer.as_mut() {
+            match code {
+                KeyCode::Esc if self.close_after_save => return EditOutcome::Close,
+                KeyCode::Esc => self.buffer = None,
+                KeyCode::Enter => {
+                    let text = self.buffer.take().unwrap_or_default().trim().to_string();
+                    match self.field {
+                        EditField::Name => self.name = text.clone(),
+                        EditField::Tags => self.tags = text.clone(),
+                        EditField::Note => self.note = text.clone(),
+                    }
+                    return EditOutcome::Save(self.field, text);
+                }
+                KeyCode::Backspace => {
+                    buffer.pop();
+                }
+                KeyCode::Char(c) if !c.is_control() => buffer.push(c),
+                _ => {}
+            }
+            return EditOutcome::None;
+        }
+
+        let idx = EditField::ALL
+            .iter()
+            .position(|f| *f == self.field)
+            .unwrap_or(0);
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => return EditOutcome::Close,
+            KeyCode::Up => self.field = EditField::ALL[idx.saturating_sub(1)],
+            KeyCode::Down | KeyCode::Tab => {
+                self.field = EditField::ALL[(idx + 1).min(EditField::ALL.len() - 1)]
+            }
+            KeyCode::Enter => self.buffer = Some(self.value(self.field).to_string()),
+            _ => {}
+        }
+        EditOutcome::None
+    }
 }
 
 impl App {
-    fn new(
-        refresh_ms: u64,
-        debug: bool,
-        cmd_tx: Sender<WorkerCmd>,
-        msg_rx: Receiver<WorkerMsg>,
-    ) -> Self {
+    fn new(opts: &TuiOptions, cmd_tx: Sender<WorkerCmd>, msg_rx: Receiver<WorkerMsg>) -> Self {
         Self {
-            refresh: Duration::from_millis(refresh_ms.max(100)),
-            debug,
+            set_title: opts.set_title,
+            theme: opts.theme,
+            truncate: opts.truncate,
+            tag_styles: opts.tag_styles.clone(),
+            expanded: false,
+            open_trees: HashSet::new(),
+            paused: false,
+            host_errors_panel: None,
+            hosts: Vec::new(),
+            disabled_hosts: Vec::new(),
+            host_input: None,
+            status_filter: None,
+            by_host: opts.group_by_host,
+            watch_pane: false,
+            log_pane: None,
+            detail: false,
+            transitions: HashMap::new(),
+            filter: String::new(),
+            filter_input: false,
+            yank: false,
+            sort: SortOrder::default(),
+            guard: opts.guard,
+            schedule: opts.schedule.clone(),
+            resume_command: opts.resume_command.clone(),
+            confirm: None,
+            terminated: HashSet::new(),
+            last_title: None,
+            stale: false,
+            refresh: Duration::from_millis(opts.refresh_ms.max(100)),
+            focused: true,
+            debug: opts.debug,
+            time_display: opts.time_display,
+            columns: opts.columns.clone(),
             refresh_in_flight: false,
             last_refresh_sent: Instant::now() - Duration::from_secs(999),
             last_snapshot: None,
+            last_changes: None,
             display_sessions: Vec::new(),
             selected: None,
-            rename_modal: None,
+            edit_modal: None,
             last_error: None,
             last_status: None,
             last_warning_seen: None,
+            view: View::Sessions,
+            swarm: SwarmTracker::default(),
+            swarm_rows: Vec::new(),
+            swarm_selected: 0,
+            finished: HashMap::new(),
+            waits: WaitTracker::default(),
             cmd_tx,
             msg_rx,
         }
@@ -312,34 +894,183 @@ impl App {
         }
         self.refresh_in_flight = true;
         self.last_refresh_sent = Instant::now();
-        let _ = self.cmd_tx.send(WorkerCmd::Refresh);
+        let _ = self.cmd_tx.send(WorkerCmd::Refresh {
+            poll_remote: self.focused && self.schedule.paused_now().is_none(),
+        });
+    }
+
+    /// Time for a periodic refresh: not paused (by `P` or the schedule), not editing, and
+    /// the interval has passed.
+    fn auto_refresh_due(&self) -> bool {
+        !self.paused
+            && self.edit_modal.is_none()
+            && self.last_refresh_sent.elapsed() >= self.effective_refresh()
+            && self.schedule.paused_now() != Some(PauseScope::All)
+    }
+
+    fn effective_refresh(&self) -> Duration {
+        if self.focused {
+            self.refresh
+        } else {
+            (self.refresh * UNFOCUSED_REFRESH_FACTOR).max(UNFOCUSED_MIN_REFRESH)
+        }
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        let regained = focused && !self.focused;
+        self.focused = focused;
+        if regained && !self.paused {
+            // Even if a local-only refresh is in flight: the worker coalesces this behind it.
+            self.refresh_in_flight = false;
+            self.request_refresh();
+        }
+    }
+
+    /// Show the previous run's final snapshot until the first collection lands. It is not
+    /// fed to the change/swarm/wait trackers, so nothing is announced from stale data.
+    fn show_stale(&mut self, snap: Snapshot) {
+        self.display_sessions = filtered_display(
+            &snap.sessions,
+            self.debug,
+            &self.filter,
+            self.status_filter,
+            self.sort,
+            self.by_host,
+        );
+        self.last_snapshot = Some(snap);
+        self.stale = true;
+        self.reconcile_selection();
+    }
+
+    /// Log this snapshot's status changes; sessions that ended take their log with them.
+    fn record_transitions(&mut self, snap: &Snapshot, changes: &SnapshotChanges, now_s: i64) {
+        self.transitions
+            .retain(|key, _| snap.sessions.iter().any(|r| r.has_id(key)));
+        for c in &changes.status_changed {
+            let reason = snap
+                .sessions
+                .iter()
+                .find(|r| r.has_id(&c.key))
+                .and_then(|r| r.debug.as_ref()?.status_reason_code);
+            let log = self.transitions.entry(c.key.clone()).or_default();
+            if log.len() == TRANSITIONS_PER_SESSION {
+                log.pop_front();
+            }
+            log.push_back(Transition {
+                at_unix_s: now_s,
+                from: c.from,
+                to: c.to,
+                reason,
+            });
+        }
+    }
+
+    /// Mark newly finished turns (transient DONE state) and flash their final message, or
+    /// the receipt of a session that just ended.
+    fn note_finished(&mut self, snap: &Snapshot, changes: &SnapshotChanges) {
+        for f in &changes.finished {
+            self.finished.insert(f.key.clone(), Instant::now());
+            if let Some(r) = snap.sessions.iter().find(|r| r.has_id(&f.key)) {
+                let label = r
+                    .name
+                    .as_deref()
+                    .or(r.title.as_deref())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| short_thread_id(&r.thread_id));
+                self.last_status = Some((
+                    Instant::now(),
+                    format!(
+                        "finished: {label}: {}",
+                        crate::util::message_preview(&f.message, 80)
+                    ),
+                ));
+            }
+        }
+        for r in &changes.removed {
+            if let Some(summary) = changes.receipt_for(r).map(|rc| rc.summary()) {
+                let label = r
+                    .name
+                    .as_deref()
+                    .or(r.title.as_deref())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| short_thread_id(&r.thread_id));
+                self.last_status = Some((Instant::now(), format!("ended: {label}: {summary}")));
+            }
+        }
+        self.finished.retain(|key, at| {
+            at.elapsed() < FINISHED_DISPLAY
+                && snap.sessions.iter().any(|r| {
+                    r.host == key.host
+                        && r.thread_id == key.thread_id
+                        && r.status != SessionStatus::Working
+                })
+        });
     }
 
     fn poll_worker(&mut self) {
         while let Ok(msg) = self.msg_rx.try_recv() {
             match msg {
-                WorkerMsg::Snapshot(snap) => {
-                    let names_warning = snap
+                WorkerMsg::Snapshot(snap, changes) => {
+                    // Store problems are debug detail; a sandbox hiding every process is not.
+                    let debug = self.debug;
+                    let warning = snap
                         .warnings
                         .as_ref()
-                        .and_then(|w| w.iter().find(|s| s.starts_with("names store")))
-                        .cloned();
+                        .and_then(|w| {
+                            w.iter().find(|w| {
+                                w.kind == ErrorKind::Discovery
+                                    || (debug && w.kind == ErrorKind::NamesStore)
+                            })
+                        })
+                        .map(|w| w.to_string());
 
-                    self.display_sessions = group_sessions_for_display(&snap.sessions, self.debug);
+                    self.display_sessions = filtered_display(
+                        &snap.sessions,
+                        self.debug,
+                        &self.filter,
+                        self.status_filter,
+                        self.sort,
+                        self.by_host,
+                    );
+                    let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+                    self.swarm.update(&snap, &changes, now);
+                    self.swarm_rows = self.swarm.rows(&snap.sessions);
+                    self.swarm_selected = self
+                        .swarm_selected
+                        .min(self.swarm_rows.len().saturating_sub(1));
+                    self.note_finished(&snap, &changes);
+                    self.record_transitions(&snap, &changes, now);
+                    self.terminated
+                        .retain(|key| snap.sessions.iter().any(|r| r.has_id(key)));
+                    self.waits.update(&snap, chrono::Local::now());
                     self.last_snapshot = Some(snap);
+                    self.stale = false;
+                    self.last_changes = Some(*changes);
                     self.last_error = None;
                     self.refresh_in_flight = false;
                     self.reconcile_selection();
 
-                    if self.debug {
-                        if let Some(w) = names_warning {
-                            if self.last_warning_seen.as_deref() != Some(&w) {
-                                self.last_warning_seen = Some(w.clone());
-                                self.last_status = Some((Instant::now(), format!("WARN: {w}")));
-                            }
+                    if let Some(w) = warning {
+                        if self.last_warning_seen.as_deref() != Some(&w) {
+                            self.last_warning_seen = Some(w.clone());
+                            self.last_status = Some((Instant::now(), format!("WARN: {w}")));
                         }
                     }
                 }
+                WorkerMsg::Partial(snap) => {
+                    // Trackers only see full snapshots, so partials never announce changes.
+                    self.display_sessions = filtered_display(
+                        &snap.sessions,
+                        self.debug,
+                        &self.filter,
+                        self.status_filter,
+                        self.sort,
+                        self.by_host,
+                    );
+                    self.last_snapshot = Some(*snap);
+                    self.stale = false;
+                    self.reconcile_selection();
+                }
                 WorkerMsg::Error(e) => {
                     self.last_error = Some(e);
                     if self.refresh_in_flight {
@@ -349,19 +1080,78 @@ impl App {
                 WorkerMsg::Status(msg) => {
                     self.last_status = Some((Instant::now(), msg));
                 }
+                WorkerMsg::HostsUpdated(hosts) => {
+                    self.disabled_hosts.append(&mut self.hosts);
+                    self.disabled_hosts.retain(|h| !hosts.contains(h));
+                    self.disabled_hosts.sort();
+                    self.disabled_hosts.dedup();
+                    self.hosts = hosts;
+                }
                 WorkerMsg::NameUpdated { key, name } => {
                     if let Some(snap) = self.last_snapshot.as_mut() {
                         for row in &mut snap.sessions {
-                            if row.host == key.host && row.thread_id == key.thread_id {
+                            if row.has_id(&key) {
                                 row.name = name.clone();
                             }
                         }
-                        self.display_sessions =
-                            group_sessions_for_display(&snap.sessions, self.debug);
+                        self.display_sessions = filtered_display(
+                            &snap.sessions,
+                            self.debug,
+                            &self.filter,
+                            self.status_filter,
+                            self.sort,
+                            self.by_host,
+                        );
                         self.reconcile_selection();
                     }
                     self.last_error = None;
                 }
+                WorkerMsg::AnnotationUpdated { key, annotation } => {
+                    if let Some(snap) = self.last_snapshot.as_mut() {
+                        for row in &mut snap.sessions {
+                            if row.has_id(&key) {
+                                row.tags = annotation.tags.clone();
+                                row.note = annotation.note.clone();
+                                row.watched = annotation.watched;
+                            }
+                        }
+                        self.display_sessions = filtered_display(
+                            &snap.sessions,
+                            self.debug,
+                            &self.filter,
+                            self.status_filter,
+                            self.sort,
+                            self.by_host,
+                        );
+                    }
+                    self.last_error = None;
+                }
+                WorkerMsg::SessionRefreshed { key, row } => {
+                    let tid = short_thread_id(&key.thread_id);
+                    let Some(row) = row else {
+                        self.last_status = Some((
+                            Instant::now(),
+                            format!("({}) {tid} is no longer running", key.host),
+                        ));
+                        continue;
+                    };
+                    if let Some(snap) = self.last_snapshot.as_mut() {
+                        if let Some(existing) = snap.sessions.iter_mut().find(|r| r.has_id(&key)) {
+                            *existing = *row;
+                        }
+                        self.display_sessions = filtered_display(
+                            &snap.sessions,
+                            self.debug,
+                            &self.filter,
+                            self.status_filter,
+                            self.sort,
+                            self.by_host,
+                        );
+                        self.reconcile_selection();
+                    }
+                    self.last_status =
+                        Some((Instant::now(), format!("Refreshed ({}) {tid}", key.host)));
+                }
             }
         }
     }
@@ -373,27 +1163,37 @@ impl App {
         }
 
         if let Some(sel) = self.selected.as_ref() {
-            if self
-                .display_sessions
-                .iter()
-                .any(|s| s.root.host == sel.host && s.root.thread_id == sel.thread_id)
-            {
+            if self.display_sessions.iter().any(|s| s.root.has_id(sel)) {
                 return;
             }
         }
 
         let first = &self.display_sessions[0].root;
-        self.selected = Some(SessionNameKey {
-            host: first.host.clone(),
-            thread_id: first.thread_id.clone(),
-        });
+        self.selected = Some(first.id());
+    }
+
+    /// Open (`Some(true)`), close or toggle the selected root's subagent tree.
+    fn toggle_tree(&mut self, open: Option<bool>) {
+        let Some(i) = self.selected_index() else {
+            return;
+        };
+        let row = &self.display_sessions[i];
+        let key = row.root.id();
+        let open = open.unwrap_or(!self.open_trees.contains(&key));
+        if !open {
+            self.open_trees.remove(&key);
+        } else if row.children.is_empty() {
+            self.last_status = Some((Instant::now(), "no subagents".into()));
+        } else {
+            self.open_trees.insert(key);
+        }
     }
 
     fn selected_index(&self) -> Option<usize> {
         let sel = self.selected.as_ref()?;
         self.display_sessions
             .iter()
-            .position(|s| s.root.host == sel.host && s.root.thread_id == sel.thread_id)
+            .position(|s| s.root.has_id(sel))
     }
 
     fn select_prev(&mut self) {
@@ -403,10 +1203,7 @@ impl App {
         };
         let next = idx.saturating_sub(1);
         let row = &self.display_sessions[next].root;
-        self.selected = Some(SessionNameKey {
-            host: row.host.clone(),
-            thread_id: row.thread_id.clone(),
-        });
+        self.selected = Some(row.id());
     }
 
     fn select_next(&mut self) {
@@ -416,135 +1213,1092 @@ impl App {
         };
         let next = (idx + 1).min(self.display_sessions.len().saturating_sub(1));
         let row = &self.display_sessions[next].root;
-        self.selected = Some(SessionNameKey {
-            host: row.host.clone(),
-            thread_id: row.thread_id.clone(),
-        });
+        self.selected = Some(row.id());
     }
 
-    fn start_rename(&mut self) {
+    /// Open the editor on the selected session; `rename` jumps straight into the name.
+    fn start_edit(&mut self, rename: bool) {
         self.reconcile_selection();
         let Some(sel) = self.selected.clone() else {
             return;
         };
-
-        let existing = self
-            .display_sessions
-            .iter()
-            .find(|s| s.root.host == sel.host && s.root.thread_id == sel.thread_id)
-            .and_then(|s| s.root.name.clone())
-            .unwrap_or_default();
-
-        self.rename_modal = Some(RenameModal {
-            key: sel,
-            buffer: existing,
-        });
-    }
-
-    fn commit_rename(&mut self) {
-        let Some(modal) = self.rename_modal.take() else {
+        let Some(row) = self.display_sessions.iter().find(|s| s.root.has_id(&sel)) else {
             return;
         };
-        let key = modal.key;
-        let trimmed = modal.buffer.trim().to_string();
-        if trimmed.is_empty() {
-            let _ = self.cmd_tx.send(WorkerCmd::ClearName { key });
-        } else {
-            let _ = self.cmd_tx.send(WorkerCmd::SetName { key, name: trimmed });
+
+        let mut modal = EditModal::for_row(&row.root);
+        if rename {
+            modal.buffer = Some(modal.name.clone());
+            modal.close_after_save = true;
         }
+        self.edit_modal = Some(modal);
     }
 
-    fn clear_name(&mut self) {
-        self.reconcile_selection();
-        let Some(key) = self.selected.clone() else {
+    fn handle_edit_key(&mut self, code: KeyCode) {
+        let Some(modal) = self.edit_modal.as_mut() else {
             return;
         };
-        let _ = self.cmd_tx.send(WorkerCmd::ClearName { key });
-    }
-
-    fn handle_key(&mut self, code: KeyCode) -> bool {
-        if self.rename_modal.is_some() {
-            match code {
-                KeyCode::Esc => self.rename_modal = None,
-                KeyCode::Enter => self.commit_rename(),
-                KeyCode::Backspace => {
-                    if let Some(modal) = self.rename_modal.as_mut() {
-                        modal.buffer.pop();
-                    }
-                }
-                KeyCode::Char(c) => {
-                    if !c.is_control() {
-                        if let Some(modal) = self.rename_modal.as_mut() {
-                            modal.buffer.push(c);
-                        }
-                    }
+        match modal.handle_key(code) {
+            EditOutcome::None => {}
+            EditOutcome::Close => self.edit_modal = None,
+            EditOutcome::Save(field, text) => {
+                let key = modal.key.clone();
+                let annotation = Annotation {
+                    tags: modal.tags.split(',').map(str::to_string).collect(),
+                    note: Some(modal.note.clone()),
+                    watched: modal.watched,
+                };
+                if modal.close_after_save {
+                    self.edit_modal = None;
                 }
-                _ => {}
+                self.commit_field(key, field, text, annotation);
             }
-            return false;
         }
+    }
 
-        match code {
-            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => return true,
-            KeyCode::Char('r') | KeyCode::Char('R') => self.request_refresh(),
-            KeyCode::Up => self.select_prev(),
-            KeyCode::Down => self.select_next(),
-            KeyCode::Char('n') | KeyCode::Char('N') => self.start_rename(),
-            KeyCode::Char('x') | KeyCode::Char('X') => self.clear_name(),
-            _ => {}
+    fn commit_field(&mut self, key: SessionId, field: EditField, text: String, a: Annotation) {
+        let tid = short_thread_id(&key.thread_id);
+        match field {
+            EditField::Name if text.is_empty() => self.guarded(
+                GuardedAction::ClearName,
+                format!("Clear name of {tid}"),
+                WorkerCmd::ClearName { key },
+            ),
+            EditField::Name => self.guarded(
+                GuardedAction::Annotate,
+                format!("Name {tid} \"{text}\""),
+                WorkerCmd::SetName { key, name: text },
+            ),
+            EditField::Tags | EditField::Note => self.guarded(
+                GuardedAction::Annotate,
+                format!("Set {} of {tid}", field.label().to_lowercase()),
+                WorkerCmd::SetAnnotation { key, annotation: a },
+            ),
         }
-        false
     }
-}
 
-fn run_loop(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    app: &mut App,
-) -> anyhow::Result<()> {
-    loop {
-        if app.rename_modal.is_none() && app.last_refresh_sent.elapsed() >= app.refresh {
-            app.request_refresh();
+    /// Send `cmd` now, or hold it for a `y` when the guard profile wants confirmation.
+    fn guarded(&mut self, action: GuardedAction, prompt: String, cmd: WorkerCmd) {
+        if self.guard.requires_confirmation(action) {
+            self.confirm = Some(Confirm { prompt, cmd });
+        } else {
+            self.dispatch(cmd);
         }
+    }
 
-        app.poll_worker();
-
-        terminal.draw(|f| draw_ui(f, app)).context("draw ui")?;
-
-        if event::poll(Duration::from_millis(50)).unwrap_or(false) {
-            match event::read().context("read event")? {
-                Event::Key(k) if k.kind == KeyEventKind::Press => {
-                    if app.handle_key(k.code) {
-                        return Ok(());
-                    }
-                }
-                _ => {}
-            }
+    /// Send a (confirmed) action to the worker, remembering which sessions got a SIGTERM.
+    fn dispatch(&mut self, cmd: WorkerCmd) {
+        if let WorkerCmd::Kill {
+            key, force: false, ..
+        } = &cmd
+        {
+            self.terminated.insert(key.clone());
         }
+        let _ = self.cmd_tx.send(cmd);
     }
-}
 
-fn draw_ui(f: &mut ratatui::Frame, app: &App) {
+    fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.regroup();
+    }
+
+    fn handle_host_key(&mut self, code: KeyCode) {
+        let Some(input) = self.host_input.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.host_input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !c.is_whitespace() && !c.is_control() => input.push(c),
+            KeyCode::Enter => {
+                let host = input.trim().to_string();
+                self.host_input = None;
+                if !host.is_empty() {
+                    self.toggle_host(host);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A polled host is disabled; any other is added once the worker has reached it.
+    fn toggle_host(&mut self, host: String) {
+        if self.hosts.contains(&host) {
+            if self.hosts.len() == 1 {
+                self.last_status = Some((Instant::now(), format!("{host} is the only host")));
+                return;
+            }
+            let hosts = self.hosts.iter().filter(|h| **h != host).cloned().collect();
+            self.dispatch(WorkerCmd::SetHosts { hosts, probe: None });
+        } else {
+            let mut hosts = self.hosts.clone();
+            hosts.push(host.clone());
+            self.dispatch(WorkerCmd::SetHosts {
+                hosts,
+                probe: Some(host),
+            });
+        }
+    }
+
+    /// Follow the selection, and read what the rollout gained since the last poll.
+    fn poll_log_pane(&mut self) {
+        let Some(pane) = self.log_pane.as_mut() else {
+            return;
+        };
+        if pane.key != self.selected {
+            *pane = LogPane {
+                key: self.selected.clone(),
+                ..LogPane::default()
+            };
+        }
+        if pane.polled.is_some_and(|t| t.elapsed() < LOG_PANE_POLL) {
+            return;
+        }
+        pane.polled = Some(Instant::now());
+        if pane.tail.is_none() {
+            let row = pane.key.as_ref().and_then(|key| {
+                let snap = self.last_snapshot.as_ref()?;
+                snap.sessions.iter().find(|r| r.has_id(key))
+            });
+            let opened = match row {
+                None => Err("no session selected".to_string()),
+                Some(r) if r.host != "local" => Err(format!(
+                    "remote session: run `codex-ps logs -f {}` on {}",
+                    short_thread_id(&r.thread_id),
+                    r.host_display()
+                )),
+                Some(r) => match r.rollout_path.as_deref() {
+                    None => Err("no rollout file yet".to_string()),
+                    Some(p) => RolloutTail::open(Path::new(p), LOG_PANE_TAIL_BYTES)
+                        .map_err(|e| format!("{e:#}")),
+                },
+            };
+            match opened {
+                Ok(tail) => pane.tail = Some(tail),
+                Err(note) => {
+                    pane.note = Some(note);
+                    return;
+                }
+            }
+        }
+        let Some(tail) = pane.tail.as_mut() else {
+            return;
+        };
+        match tail.read_new() {
+            Ok(entries) => {
+                pane.note = None;
+                pane.entries.extend(entries);
+                let excess = pane.entries.len().saturating_sub(LOG_PANE_MAX_ENTRIES);
+                pane.entries.drain(..excess);
+            }
+            Err(e) => {
+                pane.note = Some(format!("{e:#}"));
+                pane.tail = None;
+            }
+        }
+    }
+
+    fn open_host_errors(&mut self) {
+        let failing = self
+            .last_snapshot
+            .as_ref()
+            .and_then(|s| s.host_errors.as_deref())
+            .is_some_and(|e| !e.is_empty());
+        if failing {
+            self.host_errors_panel = Some(0);
+        } else {
+            self.last_status = Some((Instant::now(), "no host errors".into()));
+        }
+    }
+
+    /// Waiting sessions the `/` filter or a status hotkey leaves out of the table.
+    fn waiting_hidden_by_filter(&self) -> usize {
+        if self.filter.trim().is_empty() && self.status_filter.is_none() {
+            return 0;
+        }
+        self.last_snapshot.as_ref().map_or(0, |snap| {
+            filtered_out_waiting(&snap.sessions, self.debug, &self.display_sessions)
+        })
+    }
+
+    fn set_status_filter(&mut self, status: Option<SessionStatus>) {
+        self.status_filter = status;
+        self.regroup();
+    }
+
+    fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.regroup();
+    }
+
+    /// Rebuild the rows after the filter or sort changed; the selection stays on its session.
+    fn regroup(&mut self) {
+        if let Some(snap) = self.last_snapshot.as_ref() {
+            self.display_sessions = filtered_display(
+                &snap.sessions,
+                self.debug,
+                &self.filter,
+                self.status_filter,
+                self.sort,
+                self.by_host,
+            );
+        }
+        self.reconcile_selection();
+    }
+
+    fn handle_filter_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.filter_input = false;
+                self.set_filter(String::new());
+            }
+            KeyCode::Enter => self.filter_input = false,
+            KeyCode::Up => self.select_prev(),
+            KeyCode::Down => self.select_next(),
+            KeyCode::Backspace => {
+                let mut f = self.filter.clone();
+                f.pop();
+                self.set_filter(f);
+            }
+            KeyCode::Char(c) => self.set_filter(format!("{}{c}", self.filter)),
+            _ => {}
+        }
+    }
+
+    /// The selected root for the detail pane, with its subagents.
+    fn selected_detail(&self) -> Option<SessionDetail> {
+        let key = self.selected.as_ref()?;
+        let snap = self.last_snapshot.as_ref()?;
+        let row = snap.sessions.iter().find(|r| r.has_id(key))?;
+        Some(SessionDetail::from_sessions(&snap.sessions, row))
+    }
+
+    fn refresh_selected(&mut self) {
+        self.reconcile_selection();
+        let Some(key) = self.selected.clone() else {
+            return;
+        };
+        let _ = self.cmd_tx.send(WorkerCmd::RefreshSession { key });
+    }
+
+    /// The new terminal title when `--set-title` is on and the summary changed.
+    fn title_update(&mut self) -> Option<String> {
+        if !self.set_title || self.stale || self.last_snapshot.is_none() {
+            return None;
+        }
+        // The title counts every session, whatever the filter hides.
+        let title = match self.last_snapshot.as_ref() {
+            Some(snap) if !self.filter.is_empty() || self.status_filter.is_some() => {
+                attention_title(&group_sessions_for_display(&snap.sessions, self.debug))
+            }
+            _ => attention_title(&self.display_sessions),
+        };
+        if self.last_title.as_ref() == Some(&title) {
+            return None;
+        }
+        self.last_title = Some(title.clone());
+        Some(title)
+    }
+
+    /// Approval passthrough: copy the selected session's pending call and jump to its tmux
+    /// pane, so approving is one keypress away.
+    fn open_pending_call(&mut self) {
+        self.reconcile_selection();
+        let Some(row) = self.selected.as_ref().and_then(|key| {
+            self.last_snapshot
+                .as_ref()?
+                .sessions
+                .iter()
+                .find(|r| r.has_id(key))
+        }) else {
+            return;
+        };
+        let Some(call) = row.pending_call.clone() else {
+            self.last_status = Some((
+                Instant::now(),
+                "selected session has no pending tool call".into(),
+            ));
+            return;
+        };
+
+        let mut parts = Vec::new();
+        match crate::jump::copy_to_clipboard(&call) {
+            Ok(via) => parts.push(format!("copied {call:?} ({via})")),
+            Err(e) => parts.push(format!("copy failed: {e:#}")),
+        }
+        match crate::jump::focus_session_pane(row) {
+            Ok(pane) => parts.push(format!("jumped to tmux pane {pane}")),
+            Err(e) => parts.push(format!("no jump: {e:#}")),
+        }
+        self.last_status = Some((Instant::now(), parts.join("; ")));
+    }
+
+    /// Second key after `y`: copy the selected session's thread id, cwd or rollout path.
+    fn yank_selected(&mut self, code: KeyCode) {
+        let KeyCode::Char(c) = code else {
+            return;
+        };
+        self.reconcile_selection();
+        let Some(row) = self.selected.as_ref().and_then(|key| {
+            self.last_snapshot
+                .as_ref()?
+                .sessions
+                .iter()
+                .find(|r| r.has_id(key))
+        }) else {
+            return;
+        };
+        let msg = match yank_value(row, c.to_ascii_lowercase()) {
+            Some((what, Some(value))) => match crate::jump::copy_to_clipboard(&value) {
+                Ok(via) => format!("copied {what} {value} ({via})"),
+                Err(e) => format!("copy failed: {e:#}"),
+            },
+            Some((what, None)) => format!("selected session has no {what}"),
+            None => return,
+        };
+        self.last_status = Some((Instant::now(), msg));
+    }
+
+    /// Resume the selected idle session in a new window (`resume_command`, or tmux).
+    fn resume_selected(&mut self) {
+        self.reconcile_selection();
+        let Some(row) = self.selected.as_ref().and_then(|key| {
+            self.last_snapshot
+                .as_ref()?
+                .sessions
+                .iter()
+                .find(|r| r.has_id(key))
+        }) else {
+            return;
+        };
+        let msg = match crate::jump::launch_resume(row, self.resume_command.as_deref()) {
+            Ok(cmd) => format!("resuming: {cmd}"),
+            Err(e) => format!("resume failed: {e:#}"),
+        };
+        self.last_status = Some((Instant::now(), msg));
+    }
+
+    /// Write the waiting sessions as a Markdown checklist to `todo.md` in the config dir.
+    fn export_todo(&mut self) {
+        let Some(snap) = self.last_snapshot.as_ref() else {
+            return;
+        };
+        let text = crate::todo::render_todo(&snap.sessions);
+        let count = text.lines().count();
+        let result = crate::names::config_dir().and_then(|dir| {
+            std::fs::create_dir_all(&dir)?;
+            let path = dir.join("todo.md");
+            std::fs::write(&path, &text)?;
+            Ok(path)
+        });
+        let msg = match result {
+            Ok(path) => format!("exported {count} waiting session(s) to {}", path.display()),
+            Err(e) => format!("export failed: {e:#}"),
+        };
+        self.last_status = Some((Instant::now(), msg));
+    }
+
+    fn kill_selected(&mut self) {
+        self.reconcile_selection();
+        let Some(row) = self
+            .selected
+            .as_ref()
+            .and_then(|key| self.display_sessions.iter().find(|s| s.root.has_id(key)))
+        else {
+            return;
+        };
+        let key = row.root.id();
+        let pids = row.root.pids.clone();
+        if pids.is_empty() {
+            self.last_status = Some((Instant::now(), "selected session has no known pid".into()));
+            return;
+        }
+        // Still listed after a SIGTERM: it is wedged, so the next kill is SIGKILL.
+        let force = self.terminated.contains(&key);
+        let list: Vec<String> = pids.iter().map(|p| p.to_string()).collect();
+        let prompt = format!(
+            "{} {} on {} (pid {})",
+            if force {
+                "Force-kill (SIGKILL)"
+            } else {
+                "Kill"
+            },
+            short_thread_id(&key.thread_id),
+            row.root.host_display(),
+            list.join(", ")
+        );
+        self.guarded(
+            GuardedAction::Kill,
+            prompt,
+            WorkerCmd::Kill { key, pids, force },
+        );
+    }
+
+    fn clear_name(&mut self) {
+        self.reconcile_selection();
+        let Some(key) = self.selected.clone() else {
+            return;
+        };
+        let prompt = format!("Clear name of {}", short_thread_id(&key.thread_id));
+        self.guarded(
+            GuardedAction::ClearName,
+            prompt,
+            WorkerCmd::ClearName { key },
+        );
+    }
+
+    fn toggle_watch(&mut self) {
+        self.reconcile_selection();
+        let Some(row) = self
+            .selected
+            .as_ref()
+            .and_then(|key| self.display_sessions.iter().find(|s| s.root.has_id(key)))
+        else {
+            return;
+        };
+        let key = row.root.id();
+        let annotation = Annotation {
+            tags: row.root.tags.clone(),
+            note: row.root.note.clone(),
+            watched: !row.root.watched,
+        };
+        let verb = if annotation.watched {
+            "Watch"
+        } else {
+            "Unwatch"
+        };
+        let prompt = format!("{verb} {}", short_thread_id(&key.thread_id));
+        self.guarded(
+            GuardedAction::Annotate,
+            prompt,
+            WorkerCmd::SetAnnotation { key, annotation },
+        );
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some(confirm) = self.confirm.take() {
+            if matches!(code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.dispatch(confirm.cmd);
+            } else {
+                self.last_status = Some((Instant::now(), "cancelled".into()));
+            }
+            return false;
+        }
+        if self.edit_modal.is_some() {
+            self.handle_edit_key(code);
+            return false;
+        }
+        if self.host_input.is_some() {
+            self.handle_host_key(code);
+            return false;
+        }
+        if let Some(scroll) = self.host_errors_panel.as_mut() {
+            match code {
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down => *scroll = scroll.saturating_add(1),
+                KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('q') => {
+                    self.host_errors_panel = None
+                }
+                _ => {}
+            }
+            return false;
+        }
+        if self.filter_input && self.view == View::Sessions {
+            self.handle_filter_key(code);
+            return false;
+        }
+        if self.yank {
+            self.yank = false;
+            self.yank_selected(code);
+            return false;
+        }
+
+        if let View::Swarm { drill } = &self.view {
+            match code {
+                KeyCode::Esc if drill.is_some() => {
+                    self.view = View::Swarm { drill: None };
+                    return false;
+                }
+                KeyCode::Char('w') | KeyCode::Char('W') => {
+                    self.view = View::Sessions;
+                    return false;
+                }
+                KeyCode::Up if drill.is_none() => {
+                    self.swarm_selected = self.swarm_selected.saturating_sub(1);
+                    return false;
+                }
+                KeyCode::Down if drill.is_none() => {
+                    self.swarm_selected =
+                        (self.swarm_selected + 1).min(self.swarm_rows.len().saturating_sub(1));
+                    return false;
+                }
+                KeyCode::Enter if drill.is_none() => {
+                    if let Some(r) = self.swarm_rows.get(self.swarm_selected) {
+                        self.view = View::Swarm {
+                            drill: Some(r.root.id()),
+                        };
+                    }
+                    return false;
+                }
+                KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => return true,
+                KeyCode::Char('r') | KeyCode::Char('R') => self.request_refresh(),
+                _ => {}
+            }
+            return false;
+        }
+
+        match code {
+            KeyCode::Esc if self.detail => self.detail = false,
+            KeyCode::Esc if !self.filter.is_empty() => self.set_filter(String::new()),
+            KeyCode::Char('/') => self.filter_input = true,
+            KeyCode::Char('s') | KeyCode::Char('S') => self.cycle_sort(),
+            KeyCode::Enter => self.detail = !self.detail,
+            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => return true,
+            KeyCode::Char('w') | KeyCode::Char('W') => self.view = View::Swarm { drill: None },
+            KeyCode::Char('r') | KeyCode::Char('R') => self.request_refresh(),
+            KeyCode::Up => self.select_prev(),
+            KeyCode::Down => self.select_next(),
+            KeyCode::Char('!') => {
+                if self.waiting_hidden_by_filter() > 0 {
+                    self.filter.clear();
+                    self.status_filter = None;
+                    self.regroup();
+                    self.last_status = Some((
+                        Instant::now(),
+                        "filter cleared to show waiting sessions".into(),
+                    ));
+                }
+                if let Some(i) = next_waiting(&self.display_sessions, self.selected_index()) {
+                    self.selected = Some(self.display_sessions[i].root.id());
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                self.debug = !self.debug;
+                self.regroup();
+                let state = if self.debug { "on" } else { "off" };
+                self.last_status = Some((Instant::now(), format!("debug {state}")));
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => self.start_edit(true),
+            KeyCode::Char('i') | KeyCode::Char('I') => self.start_edit(false),
+            KeyCode::Char('z') | KeyCode::Char('Z') => self.expanded = !self.expanded,
+            KeyCode::Tab => self.toggle_tree(None),
+            KeyCode::Right => self.toggle_tree(Some(true)),
+            KeyCode::Left => self.toggle_tree(Some(false)),
+            KeyCode::Char('*') => self.toggle_watch(),
+            KeyCode::Char('p') => self.watch_pane = !self.watch_pane,
+            KeyCode::Char('l') => {
+                self.log_pane = match self.log_pane {
+                    Some(_) => None,
+                    None => Some(LogPane::default()),
+                };
+                self.poll_log_pane();
+            }
+            KeyCode::Char('P') => self.paused = !self.paused,
+            KeyCode::Char('h') => self.open_host_errors(),
+            KeyCode::Char('H') => self.host_input = Some(String::new()),
+            KeyCode::Char('1') => self.set_status_filter(Some(SessionStatus::Working)),
+            KeyCode::Char('2') => self.set_status_filter(Some(SessionStatus::Waiting)),
+            KeyCode::Char('3') => self.set_status_filter(Some(SessionStatus::Unknown)),
+            KeyCode::Char('0') => self.set_status_filter(None),
+            KeyCode::Char('g') | KeyCode::Char('G') => {
+                self.by_host = !self.by_host;
+                self.regroup();
+            }
+            KeyCode::Char('x') | KeyCode::Char('X') => self.clear_name(),
+            KeyCode::Char('k') | KeyCode::Char('K') => self.kill_selected(),
+            KeyCode::Char('u') | KeyCode::Char('U') => self.refresh_selected(),
+            KeyCode::Char('e') | KeyCode::Char('E') => self.export_todo(),
+            KeyCode::Char('a') | KeyCode::Char('A') => self.open_pending_call(),
+            KeyCode::Char('o') | KeyCode::Char('O') => self.resume_selected(),
+            KeyCode::Char('y') | KeyCode::Char('Y') => self.yank = true,
+            _ => {}
+        }
+        false
+    }
+}
+
+fn run_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> anyhow::Result<()> {
+    loop {
+        // `r` still refreshes by hand while paused.
+        if app.auto_refresh_due() {
+            app.request_refresh();
+        }
+
+        app.poll_worker();
+        app.poll_log_pane();
+
+        if let Some(title) = app.title_update() {
+            execute!(terminal.backend_mut(), SetTitle(title)).ok();
+        }
+
+        terminal.draw(|f| draw_ui(f, app)).context("draw ui")?;
+
+        if event::poll(Duration::from_millis(50)).unwrap_or(false) {
+            match event::read().context("read event")? {
+                Event::Key(k) if k.kind == KeyEventKind::Press => {
+                    if app.handle_key(k.code) {
+                        return Ok(());
+                    }
+                }
+                Event::FocusLost => app.set_focused(false),
+                Event::FocusGained => app.set_focused(true),
+                // Re-fit right away rather than waiting for the next tick, and clear so a
+                // shrinking terminal doesn't keep fragments of the wider layout.
+                Event::Resize(_, _) => {
+                    terminal.autoresize().context("resize terminal")?;
+                    terminal.clear().context("clear terminal")?;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `codex-ps ✋2 ⚒3`: sessions waiting on the user, then sessions working (subagents are
+/// folded into their root row).
+pub fn attention_title(rows: &[DisplaySessionRow]) -> String {
+    let count = |s: SessionStatus| rows.iter().filter(|r| r.status == s).count();
+    format!(
+        "codex-ps ✋{} ⚒{}",
+        count(SessionStatus::Waiting),
+        count(SessionStatus::Working)
+    )
+}
+
+fn draw_ui(f: &mut ratatui::Frame, app: &App) {
     let area = f.area();
 
+    if area.width < COMPACT_WIDTH || area.height < COMPACT_HEIGHT {
+        f.render_widget(compact_view(app, area), area);
+        if let Some(modal) = app.edit_modal.as_ref() {
+            render_edit_modal(f, modal, app.theme, area);
+        }
+        if let Some(confirm) = app.confirm.as_ref() {
+            render_confirm(f, confirm, app.theme, area);
+        }
+        render_host_errors(f, app, area);
+        render_host_input(f, app, area);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(2), Constraint::Min(3)].as_ref())
         .split(area);
 
-    let header = header_line(app, chunks[0]);
-    f.render_widget(header, chunks[0]);
+    let mut hidden_waiting = 0;
+    match &app.view {
+        View::Sessions => {
+            let watched = if app.watch_pane {
+                watch_pane_lines(&app.display_sessions, app.theme)
+            } else {
+                Vec::new()
+            };
+            let pane_height = match watched.len() {
+                0 => 0,
+                n => u16::try_from(n.min(WATCH_PANE_MAX_ROWS)).unwrap_or(0) + 1,
+            };
+            let detail = if app.detail {
+                app.selected_detail()
+            } else {
+                None
+            };
+            let [table_area, detail_area, log_area, pane_area, footer_area] = Layout::vertical([
+                Constraint::Min(2),
+                Constraint::Percentage(if detail.is_some() { 50 } else { 0 }),
+                Constraint::Percentage(if app.log_pane.is_some() { 35 } else { 0 }),
+                Constraint::Length(pane_height),
+                Constraint::Length(1),
+            ])
+            .areas(chunks[1]);
+            if let Some(pane) = app.log_pane.as_ref() {
+                let rows = usize::from(log_area.height.saturating_sub(1));
+                let width = usize::from(log_area.width);
+                f.render_widget(
+                    Paragraph::new(log_pane_lines(pane, rows, width, app.theme)).block(
+                        Block::default()
+                            .borders(Borders::TOP)
+                            .title("Log (l to hide)"),
+                    ),
+                    log_area,
+                );
+            }
+            if let Some(detail) = detail.as_ref() {
+                let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+                let mut text = format_detail(detail, now);
+                if let Some(log) = app.transitions.get(&detail.session.id()) {
+                    text.push_str(&transition_lines(log, now, app.time_display));
+                }
+                f.render_widget(
+                    Paragraph::new(text).wrap(Wrap { trim: false }).block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title("Details (Enter to close)"),
+                    ),
+                    detail_area,
+                );
+            }
+            if pane_height > 0 {
+                f.render_widget(
+                    Paragraph::new(watched.join("\n")).block(
+                        Block::default()
+                            .borders(Borders::TOP)
+                            .title("Watched (p to hide)"),
+                    ),
+                    pane_area,
+                );
+            }
+            let errored: Vec<&HostError> = match (app.by_host, app.last_snapshot.as_ref()) {
+                (true, Some(snap)) => snap.host_errors.iter().flatten().collect(),
+                _ => Vec::new(),
+            };
+            let lines = table_lines(
+                &app.display_sessions,
+                &app.open_trees,
+                app.by_host,
+                &errored,
+            );
+            let (table, heights) = sessions_table(app, table_area, &lines);
+            let mut state = TableState::default();
+            state.select(
+                app.selected_index()
+                    .and_then(|i| lines.iter().position(|l| *l == TableLine::Root(i))),
+            );
+            f.render_stateful_widget(table, table_area, &mut state);
+            // Section headers span the whole width, which table cells can't, so they are
+            // drawn over the blank rows the table left for them.
+            let mut y = table_area.y + 2;
+            for (line, height) in lines.iter().zip(&heights).skip(state.offset()) {
+                if y >= table_area.bottom() {
+                    break;
+                }
+                if let TableLine::Host(host) = line {
+                    f.render_widget(
+                        Paragraph::new(host_header(
+                            host,
+                            &app.display_sessions,
+                            &errored,
+                            app.theme,
+                        )),
+                        Rect::new(table_area.x, y, table_area.width, 1),
+                    );
+                }
+                y += height;
+            }
+
+            // Border and header take two lines; the rest holds one row each.
+            let shown =
+                state.offset()..state.offset() + usize::from(table_area.height.saturating_sub(2));
+            let mut roots = lines
+                .get(shown.start..shown.end.min(lines.len()))
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|l| match l {
+                    TableLine::Root(i) => Some(*i),
+                    _ => None,
+                });
+            let start = roots.next().unwrap_or(app.display_sessions.len());
+            let end = roots.next_back().map_or(start + 1, |i| i + 1);
+            hidden_waiting = off_screen(&app.display_sessions, start..end)
+                .filter(|r| r.status == SessionStatus::Waiting)
+                .count();
+            let footer = footer_text(&app.display_sessions, start..end);
+            f.render_widget(
+                Paragraph::new(footer).style(app.theme.fg(Color::DarkGray)),
+                footer_area,
+            );
+        }
+        View::Swarm { drill: None } => {
+            let mut state = TableState::default();
+            state.select((!app.swarm_rows.is_empty()).then_some(app.swarm_selected));
+            f.render_stateful_widget(swarm_table(app), chunks[1], &mut state);
+        }
+        View::Swarm { drill: Some(key) } => {
+            f.render_widget(swarm_drill_table(app, key), chunks[1]);
+        }
+    }
+    // After the table, which decides what scrolls out of view.
+    f.render_widget(header_line(app, chunks[0], hidden_waiting), chunks[0]);
+
+    if let Some(modal) = app.edit_modal.as_ref() {
+        render_edit_modal(f, modal, app.theme, area);
+    }
+    if let Some(confirm) = app.confirm.as_ref() {
+        render_confirm(f, confirm, app.theme, area);
+    }
+    render_host_errors(f, app, area);
+    render_host_input(f, app, area);
+}
+
+/// Most sessions listed in the watched pane.
+const WATCH_PANE_MAX_ROWS: usize = 5;
+/// The log pane starts from this much of the end of the rollout and keeps this many entries.
+const LOG_PANE_TAIL_BYTES: u64 = 256 * 1024;
+const LOG_PANE_MAX_ENTRIES: usize = 200;
+const LOG_PANE_POLL: Duration = Duration::from_millis(500);
+
+/// Status changes remembered per session for the detail pane.
+const TRANSITIONS_PER_SESSION: usize = 50;
+
+#[derive(Clone, Copy, Debug)]
+struct Transition {
+    at_unix_s: i64,
+    from: SessionStatus,
+    to: SessionStatus,
+    reason: Option<StatusReasonCode>,
+}
+
+/// The detail pane's `status changes` section, newest first:
+/// `14:02:10 working→waiting (pending_user_input)`.
+fn transition_lines(log: &VecDeque<Transition>, now_s: i64, mode: TimeDisplay) -> String {
+    if log.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("status changes ({}):\n", log.len());
+    for t in log.iter().rev() {
+        out.push_str(&format!(
+            "  {} {}→{}",
+            format_timestamp(t.at_unix_s, now_s, mode),
+            t.from.as_str(),
+            t.to.as_str()
+        ));
+        if let Some(reason) = t.reason {
+            out.push_str(&format!(" ({})", reason.as_str()));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The `l` pane's state for the selected session.
+#[derive(Debug, Default)]
+struct LogPane {
+    key: Option<SessionId>,
+    tail: Option<RolloutTail>,
+    entries: VecDeque<String>,
+    /// Why there is nothing to show: a remote session, no rollout yet, a read error.
+    note: Option<String>,
+    polled: Option<Instant>,
+}
+
+/// The newest `rows` entries, one line each (the first line of a multi-line message).
+fn log_pane_lines(pane: &LogPane, rows: usize, width: usize, theme: Theme) -> Vec<Line<'static>> {
+    if let Some(note) = pane.note.as_deref() {
+        return vec![Line::styled(note.to_string(), theme.fg(Color::DarkGray))];
+    }
+    let first = pane.entries.len().saturating_sub(rows);
+    pane.entries
+        .iter()
+        .skip(first)
+        .map(|e| {
+            let text = truncate_end(e.lines().next().unwrap_or(""), width);
+            let style = if e.contains(" -> ") {
+                theme.fg(Color::Cyan)
+            } else if e.contains(" --- turn") {
+                theme.fg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            Line::styled(text, style)
+        })
+        .collect()
+}
+
+/// One line per watched root: name, state, then what it is doing or last said.
+fn watch_pane_lines(rows: &[DisplaySessionRow], theme: Theme) -> Vec<String> {
+    let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+    rows.iter()
+        .filter(|r| r.root.watched)
+        .map(|r| {
+            let name = r
+                .root
+                .name
+                .clone()
+                .unwrap_or_else(|| short_thread_id(&r.root.thread_id));
+            let (state, _) = theme.state(r.status, false);
+            let line = match (&r.root.pending_call, &r.root.latest_line) {
+                (Some(call), _) => match r.root.pending_call_eta.as_ref() {
+                    Some(eta) => format!("⏳ {call} ({})", eta.describe(now)),
+                    None => format!("⏳ {call}"),
+                },
+                (None, Some(line)) => line.clone(),
+                (None, None) => "—".into(),
+            };
+            format!("★ {name} [{state}] {line}")
+        })
+        .collect()
+}
 
-    let table = sessions_table(app, chunks[1]);
-    let mut state = TableState::default();
-    state.select(app.selected_index());
-    f.render_stateful_widget(table, chunks[1], &mut state);
+/// Totals under the sessions table: roots per status, subagents, and rows scrolled out of
+/// `visible` (with how many of those are waiting, so urgent sessions are never hidden).
+fn footer_text(rows: &[DisplaySessionRow], visible: std::ops::Range<usize>) -> String {
+    let count = |s: SessionStatus| rows.iter().filter(|r| r.status == s).count();
+    let mut parts = vec![
+        format!("{} waiting", count(SessionStatus::Waiting)),
+        format!("{} working", count(SessionStatus::Working)),
+    ];
+    let unknown = count(SessionStatus::Unknown);
+    if unknown > 0 {
+        parts.push(format!("{unknown} unknown"));
+    }
+    let subagents: usize = rows.iter().map(|r| r.subagents.total).sum();
+    if subagents > 0 {
+        parts.push(format!("{subagents} subagents"));
+    }
+
+    let hidden = off_screen(rows, visible.clone()).count();
+    if hidden > 0 {
+        let waiting = off_screen(rows, visible)
+            .filter(|r| r.status == SessionStatus::Waiting)
+            .count();
+        parts.push(if waiting > 0 {
+            format!("{hidden} off-screen ({waiting} waiting)")
+        } else {
+            format!("{hidden} off-screen")
+        });
+    }
+    parts.join(" · ")
+}
+
+/// Rows outside `visible` (scrolled out of the table).
+fn off_screen(
+    rows: &[DisplaySessionRow],
+    visible: std::ops::Range<usize>,
+) -> impl Iterator<Item = &DisplaySessionRow> {
+    let end = visible.end.min(rows.len());
+    let start = visible.start.min(end);
+    rows[..start].iter().chain(&rows[end..])
+}
+
+/// Grouped rows whose root matches every word of `filter` (all rows when it is empty) and,
+/// if set, `status`, in `order` (within each host when `by_host`).
+fn filtered_display(
+    sessions: &[SessionRow],
+    debug: bool,
+    filter: &str,
+    status: Option<SessionStatus>,
+    order: SortOrder,
+    by_host: bool,
+) -> Vec<DisplaySessionRow> {
+    let mut rows = group_sessions_for_display(sessions, debug);
+    if let Some(status) = status {
+        rows.retain(|r| r.status == status);
+    }
+    if !filter.trim().is_empty() {
+        let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+        rows.retain(|r| matches_filter(&r.root, filter, now));
+    }
+    if order != SortOrder::Activity {
+        sort_display_rows(&mut rows, order);
+    }
+    if by_host {
+        // Stable: `order` still applies within each host.
+        rows.sort_by(|a, b| a.root.host.cmp(&b.root.host));
+    }
+    rows
+}
+
+/// Case-insensitive: each word of `filter` appears in the name, title, branch, cwd or
+/// thread id, or is a predicate on the pending tool call: `pending:exec_command` (the call's
+/// name contains it; bare `pending:` is any call), `pending_age>5m`, `pending_age<30s`.
+fn matches_filter(row: &SessionRow, filter: &str, now_unix_s: i64) -> bool {
+    let fields: Vec<String> = [
+        row.name.as_deref(),
+        row.title.as_deref(),
+        row.git_branch.as_deref(),
+        row.current_branch.as_deref(),
+        row.cwd.as_deref(),
+        Some(row.thread_id.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .map(str::to_lowercase)
+    .collect();
+    filter.to_lowercase().split_whitespace().all(|word| {
+        match pending_predicate(row, word, now_unix_s) {
+            Some(hit) => hit,
+            None => fields.iter().any(|f| f.contains(word)),
+        }
+    })
+}
+
+/// `Some(matched)` when `word` is a `pending:`/`pending_age` predicate (which a session
+/// without a pending call never matches); `None` for a plain word.
+fn pending_predicate(row: &SessionRow, word: &str, now_unix_s: i64) -> Option<bool> {
+    let call = row.pending_call.as_deref();
+    if let Some(name) = word.strip_prefix("pending:") {
+        let tool = call.map(|c| c.split_once(':').map_or(c, |(tool, _)| tool));
+        return Some(tool.is_some_and(|t| t.to_lowercase().contains(name)));
+    }
+    let rest = word.strip_prefix("pending_age")?;
+    let (longer, limit) = match rest.split_at_checked(1)? {
+        (">", d) => (true, d),
+        ("<", d) => (false, d),
+        _ => return None,
+    };
+    let limit = i64::try_from(parse_duration_secs(limit)?).ok()?;
+    let since = row.pending_call_since_unix_s.or(row.last_activity_unix_s);
+    Some(
+        call.is_some()
+            && since.is_some_and(|ts| {
+                let age = now_unix_s.saturating_sub(ts);
+                if longer { age > limit } else { age < limit }
+            }),
+    )
+}
 
-    if let Some(modal) = app.rename_modal.as_ref() {
-        render_rename_modal(f, modal, area);
+/// What `y` followed by `key` copies: a label and the value, if the row has one.
+fn yank_value(row: &SessionRow, key: char) -> Option<(&'static str, Option<String>)> {
+    match key {
+        'y' | 't' => Some(("thread id", Some(row.thread_id.clone()))),
+        'c' => Some(("cwd", row.cwd.clone())),
+        'r' => Some(("rollout path", row.rollout_path.clone())),
+        _ => None,
     }
 }
 
-fn header_line(app: &App, area: Rect) -> Paragraph {
+/// Waiting root sessions in `sessions` that aren't among the `shown` rows.
+fn filtered_out_waiting(
+    sessions: &[SessionRow],
+    debug: bool,
+    shown: &[DisplaySessionRow],
+) -> usize {
+    group_sessions_for_display(sessions, debug)
+        .iter()
+        .filter(|r| r.status == SessionStatus::Waiting)
+        .filter(|r| !shown.iter().any(|s| s.root.has_id(&r.root.id())))
+        .count()
+}
+
+/// The next waiting row after `from`, wrapping around (`!` in the TUI).
+fn next_waiting(rows: &[DisplaySessionRow], from: Option<usize>) -> Option<usize> {
+    let start = from.map_or(0, |i| i + 1);
+    (0..rows.len())
+        .map(|i| (start + i) % rows.len())
+        .find(|&i| rows[i].status == SessionStatus::Waiting)
+}
+
+/// Per-host age of the rows on screen, e.g. `local 0s, home 12s`; `…` means the host hasn't
+/// answered yet this run.
+pub fn host_freshness(snap: &Snapshot, now_s: i64) -> String {
+    snap.host
+        .split(',')
+        .map(|h| match snap.host_collected_at.get(h) {
+            Some(at) => format!("{h} {}s", now_s.saturating_sub(*at).max(0)),
+            None => format!("{h} …"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `hidden_waiting`: waiting sessions scrolled out of view, flagged with a blinking badge, as
+/// are waiting sessions an active filter hides.
+fn header_line(app: &App, area: Rect, hidden_waiting: usize) -> Paragraph {
     let now = SystemTime::now();
     let display_rows = app.display_sessions.len();
     let raw_threads = app
@@ -560,148 +2314,843 @@ fn header_line(app: &App, area: Rect) -> Paragraph {
     let host_errs = app
         .last_snapshot
         .as_ref()
-        .and_then(|s| s.host_errors.as_ref())
-        .map(|v| v.len())
-        .unwrap_or(0);
+        .and_then(|s| s.host_errors.as_deref())
+        .unwrap_or(&[]);
 
     let mut header_spans = Vec::new();
-    header_spans.push(Span::styled(
-        "codex-ps  ",
-        Style::default().add_modifier(Modifier::BOLD),
-    ));
-    header_spans.push(Span::raw(format!("hosts: {host_sel}  ")));
+    header_spans.push(Span::styled("codex-ps  ", app.theme.header()));
+    match app.last_snapshot.as_ref() {
+        Some(snap) if !app.stale && snap.host.contains(',') => {
+            let now_s = crate::util::system_time_to_unix_s(now).unwrap_or(0);
+            header_spans.push(Span::raw(format!(
+                "hosts: {}  ",
+                host_freshness(snap, now_s)
+            )));
+        }
+        _ => header_spans.push(Span::raw(format!("hosts: {host_sel}  "))),
+    }
     header_spans.push(Span::raw(format!("sessions: {display_rows}  ")));
+    header_spans.push(Span::raw(format!("sort: {}  ", app.sort.label())));
+    if let Some(status) = app.status_filter {
+        header_spans.push(Span::styled(
+            format!("only {} (0: all)  ", status.as_str()),
+            app.theme.fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if app.filter_input || !app.filter.is_empty() {
+        let cursor = if app.filter_input { "▏" } else { "" };
+        header_spans.push(Span::styled(
+            format!("/{}{cursor}  ", app.filter),
+            app.theme.fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if hidden_waiting > 0 {
+        let mut style = app.theme.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        // Pulse once a second (the TUI redraws at least every refresh).
+        if crate::util::system_time_to_unix_s(now).unwrap_or(0) % 2 == 0 {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        header_spans.push(Span::styled(
+            format!("⚠ {hidden_waiting} waiting off-screen (!)"),
+            style,
+        ));
+        header_spans.push(Span::raw("  "));
+    }
+    let filtered_waiting = app.waiting_hidden_by_filter();
+    if filtered_waiting > 0 {
+        header_spans.push(Span::styled(
+            format!("⚠ {filtered_waiting} waiting hidden by filter (!)"),
+            app.theme.fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+        header_spans.push(Span::raw("  "));
+    }
     if raw_threads != display_rows {
         header_spans.push(Span::raw(format!("threads: {raw_threads}  ")));
     }
-    if host_errs > 0 {
+    if !host_errs.is_empty() {
+        let now_s = crate::util::system_time_to_unix_s(now).unwrap_or(0);
+        let streak = failing_for(host_errs, now_s)
+            .map(|d| format!(" for {d}"))
+            .unwrap_or_default();
+        header_spans.push(Span::styled(
+            format!(
+                "errors: {} ({}){streak} (h)  ",
+                host_errs.len(),
+                summarize_error_kinds(host_errs)
+            ),
+            app.theme.fg(Color::Red),
+        ));
+    }
+    if app.paused {
+        header_spans.push(Span::styled(
+            "PAUSED (P to resume)  ",
+            app.theme.fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    } else if let Some(scope) = app.schedule.paused_now() {
+        let what = match scope {
+            PauseScope::Remote => "remote collection",
+            PauseScope::All => "collection",
+        };
+        header_spans.push(Span::styled(
+            format!("{what} paused by schedule  "),
+            app.theme.fg(Color::DarkGray),
+        ));
+    } else if app.focused {
+        header_spans.push(Span::raw(format!(
+            "refresh: {}ms  ",
+            app.refresh.as_millis()
+        )));
+    } else {
+        header_spans.push(Span::styled(
+            format!(
+                "refresh: {}ms (unfocused, remotes paused)  ",
+                app.effective_refresh().as_millis()
+            ),
+            app.theme.fg(Color::DarkGray),
+        ));
+    }
+    let waited = app.waits.total_today();
+    if waited > 0 {
+        let mut text = format!("waited on you today: {}", format_duration(waited));
+        if let Some(key) = app.selected.as_ref() {
+            text.push_str(&format!(
+                " (selected: {})",
+                format_duration(app.waits.waited_today(key))
+            ));
+        }
         header_spans.push(Span::styled(
-            format!("errors: {host_errs}  "),
-            Style::default().fg(Color::Red),
+            format!("{text}  "),
+            app.theme.fg(Color::Yellow),
+        ));
+    }
+    if app.debug {
+        if let Some(c) = app.last_changes.as_ref() {
+            header_spans.push(Span::styled(
+                format!(
+                    "gen {} (+{} -{} ~{})  ",
+                    c.generation,
+                    c.added.len(),
+                    c.removed.len(),
+                    c.status_changed.len()
+                ),
+                app.theme.fg(Color::DarkGray),
+            ));
+        }
+    }
+
+    if let Some(err) = app.last_error.as_ref() {
+        header_spans.push(Span::styled(
+            truncate_middle(err, area.width.saturating_sub(30) as usize),
+            app.theme.fg(Color::Red),
+        ));
+    } else {
+        let now_s = crate::util::system_time_to_unix_s(now).unwrap_or(0);
+        let updated_s = app
+            .last_snapshot
+            .as_ref()
+            .map(|s| s.generated_at_unix_s)
+            .unwrap_or(0);
+        let updated = match app.time_display {
+            TimeDisplay::Relative => format!("updated: {}s ago", now_s.saturating_sub(updated_s)),
+            mode => format!("updated: {}", format_timestamp(updated_s, now_s, mode)),
+        };
+        if app.stale {
+            header_spans.push(Span::styled(
+                format!("STALE (last run, {updated}), collecting…"),
+                app.theme.fg(Color::Yellow),
+            ));
+        } else {
+            header_spans.push(Span::styled(updated, app.theme.fg(Color::DarkGray)));
+        }
+    }
+
+    let mut lines = Vec::new();
+    lines.push(Line::from(header_spans));
+
+    let mut help_spans = Vec::new();
+    if let Some(modal) = app.edit_modal.as_ref() {
+        help_spans.push(Span::styled(
+            "Keys: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        help_spans.push(Span::raw(if modal.buffer.is_some() {
+            "Enter save  Esc cancel  Backspace delete"
+        } else {
+            "↑/↓ field  Enter edit  Esc close"
+        }));
+    } else if app.yank {
+        help_spans.push(Span::styled(
+            "Copy: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        help_spans.push(Span::raw(
+            "y/t thread id  c cwd  r rollout path  Esc cancel",
+        ));
+    } else if app.filter_input && app.view == View::Sessions {
+        help_spans.push(Span::styled(
+            "Keys: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        help_spans.push(Span::raw(
+            "type to filter by name/title/branch/cwd/id  Enter keep  Esc clear  ↑/↓ select",
+        ));
+    } else {
+        help_spans.push(Span::styled(
+            "Keys: ",
+            Style::default().add_modifier(Modifier::BOLD),
         ));
+        let keys = match app.view {
+            View::Sessions => {
+                "↑/↓ select  / filter  s sort  ! next waiting  Enter details  a open pending call  n name  i edit name/tags/note  * watch  p watched pane  l log pane  P pause  d debug  h host errors  H add/disable host  g group by host  1/2/3/0 working/waiting/unknown/all  z expand row  Tab subagents  x clear  k kill  o resume  y copy id/cwd/path  u update selected  e export todo  w swarm  r refresh  q quit"
+            }
+            View::Swarm { drill: None } => "↑/↓ select  Enter subagents  w sessions  q quit",
+            View::Swarm { drill: Some(_) } => "Esc back  w sessions  q quit",
+        };
+        help_spans.push(Span::raw(keys));
+    }
+
+    if let Some((at, msg)) = app.last_status.as_ref() {
+        if at.elapsed() <= Duration::from_secs(4) {
+            help_spans.push(Span::raw("   "));
+            help_spans.push(Span::styled(
+                format!("Status: {msg}"),
+                app.theme.fg(Color::Green),
+            ));
+        }
+    }
+
+    lines.push(Line::from(help_spans));
+
+    Paragraph::new(lines).block(Block::default().borders(Borders::NONE))
+}
+
+/// Narrow-pane fallback: attention counts, the selected session in a few lines, then one
+/// line per session.
+fn compact_view(app: &App, area: Rect) -> Paragraph {
+    let width = area.width.saturating_sub(2) as usize;
+    let mut lines = Vec::new();
+
+    let mut top = attention_title(&app.display_sessions);
+    if app.stale {
+        top.push_str(" STALE");
+    } else if app.last_error.is_some() {
+        top.push_str(" ERR");
+    }
+    lines.push(Line::from(Span::styled(
+        top,
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+
+    let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+    let label = |s: &DisplaySessionRow| {
+        s.root
+            .name
+            .clone()
+            .or_else(|| s.root.title.clone())
+            .unwrap_or_else(|| short_thread_id(&s.root.thread_id))
+    };
+    let selected = app.selected_index();
+    if let Some(s) = selected.map(|i| &app.display_sessions[i]) {
+        let age = s
+            .last_activity_unix_s
+            .map(|ts| format_timestamp(ts, now, app.time_display))
+            .unwrap_or_else(|| "?".into());
+        let mut detail = format!("{} {age}", status_glyph(s.status));
+        if let Some(branch) = s.root.git_branch.as_deref() {
+            detail.push_str(&format!(" {branch}"));
+        }
+        lines.push(Line::from(format!(
+            "> {}",
+            truncate_middle(&label(s), width.saturating_sub(2))
+        )));
+        lines.push(Line::from(format!(
+            "  {}",
+            truncate_middle(&detail, width.saturating_sub(2))
+        )));
+        if let Some(call) = s.root.pending_call.as_deref() {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", truncate_middle(call, width.saturating_sub(2))),
+                app.theme.fg(Color::Yellow),
+            )));
+        }
+        if let Some(eta) = s.root.pending_call_eta.as_ref() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {}",
+                    truncate_middle(&eta.describe(now), width.saturating_sub(2))
+                ),
+                app.theme.fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    let room = (area.height as usize).saturating_sub(lines.len());
+    for (i, s) in app.display_sessions.iter().enumerate().take(room) {
+        let marker = if Some(i) == selected { ">" } else { " " };
+        lines.push(Line::from(format!(
+            "{marker}{} {}",
+            status_glyph(s.status),
+            truncate_middle(&label(s), width.saturating_sub(3))
+        )));
+    }
+    Paragraph::new(lines)
+}
+
+fn status_glyph(status: SessionStatus) -> &'static str {
+    match status {
+        SessionStatus::Working => "⚒",
+        SessionStatus::Waiting => "✋",
+        SessionStatus::Unknown => "?",
+    }
+}
+
+/// A column of the sessions table. Listed in display order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SessionColumn {
+    Host,
+    Pid,
+    Tid,
+    Sub,
+    Commits,
+    State,
+    Age,
+    Rate,
+    Name,
+    Title,
+    Branch,
+    Pwd,
+    Why,
+}
+
+impl SessionColumn {
+    const ALL: [SessionColumn; 13] = [
+        SessionColumn::Host,
+        SessionColumn::Pid,
+        SessionColumn::Tid,
+        SessionColumn::Sub,
+        SessionColumn::Commits,
+        SessionColumn::State,
+        SessionColumn::Age,
+        SessionColumn::Rate,
+        SessionColumn::Name,
+        SessionColumn::Title,
+        SessionColumn::Branch,
+        SessionColumn::Pwd,
+        SessionColumn::Why,
+    ];
+    /// Dropped first to last as the terminal narrows; the rest always stay.
+    const DROP_ORDER: [SessionColumn; 9] = [
+        SessionColumn::Pid,
+        SessionColumn::Sub,
+        SessionColumn::Commits,
+        SessionColumn::Rate,
+        SessionColumn::Branch,
+        SessionColumn::Title,
+        SessionColumn::Why,
+        SessionColumn::Pwd,
+        SessionColumn::Host,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SessionColumn::Host => "HOST",
+            SessionColumn::Pid => "PID",
+            SessionColumn::Tid => "TID",
+            SessionColumn::Sub => "SUB",
+            SessionColumn::Commits => "CMT",
+            SessionColumn::State => "STATE",
+            SessionColumn::Age => "AGE",
+            SessionColumn::Rate => "RATE",
+            SessionColumn::Name => "NAME",
+            SessionColumn::Title => "TITLE",
+            SessionColumn::Branch => "BRANCH",
+            SessionColumn::Pwd => "PWD",
+            SessionColumn::Why => "WHY",
+        }
+    }
+
+    fn from_label(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.label().eq_ignore_ascii_case(s))
+    }
+
+    fn constraint(self, time_display: TimeDisplay) -> Constraint {
+        match self {
+            SessionColumn::Host => Constraint::Length(6),
+            SessionColumn::Pid => Constraint::Length(8),
+            SessionColumn::Tid => Constraint::Length(14),
+            SessionColumn::Sub => Constraint::Length(10),
+            SessionColumn::Commits => Constraint::Length(3),
+            SessionColumn::State => Constraint::Length(5),
+            SessionColumn::Age => Constraint::Length(time_display.column_width()),
+            SessionColumn::Rate => Constraint::Length(5),
+            SessionColumn::Name => Constraint::Length(22),
+            SessionColumn::Title => Constraint::Length(18),
+            SessionColumn::Branch => Constraint::Length(28),
+            SessionColumn::Pwd | SessionColumn::Why => Constraint::Min(18),
+        }
+    }
+}
+
+/// Which session columns are shown, in what order and how wide (`columns` in the config or
+/// `--columns`, e.g. `state,age,name:30,pwd`). WHY still needs `--debug`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnLayout {
+    columns: Vec<SessionColumn>,
+    widths: BTreeMap<SessionColumn, u16>,
+}
+
+impl Default for ColumnLayout {
+    fn default() -> Self {
+        Self {
+            columns: SessionColumn::ALL.to_vec(),
+            widths: BTreeMap::new(),
+        }
+    }
+}
+
+impl ColumnLayout {
+    /// Column labels (any case), each optionally followed by `:WIDTH`.
+    pub fn parse<S: AsRef<str>>(specs: &[S]) -> anyhow::Result<Self> {
+        let mut layout = Self {
+            columns: Vec::new(),
+            widths: BTreeMap::new(),
+        };
+        for spec in specs {
+            let spec = spec.as_ref().trim();
+            let (label, width) = match spec.split_once(':') {
+                Some((l, w)) => (l, Some(w)),
+                None => (spec, None),
+            };
+            let col = SessionColumn::from_label(label.trim()).with_context(|| {
+                let all: Vec<&str> = SessionColumn::ALL.iter().map(|c| c.label()).collect();
+                format!("unknown column {label:?} (one of {})", all.join(", "))
+            })?;
+            if layout.columns.contains(&col) {
+                anyhow::bail!("column {} listed twice", col.label());
+            }
+            if let Some(w) = width {
+                let w: u16 =
+                    w.trim().parse().ok().filter(|w| *w > 0).with_context(|| {
+                        format!("invalid width {w:?} for column {}", col.label())
+                    })?;
+                layout.widths.insert(col, w);
+            }
+            layout.columns.push(col);
+        }
+        if layout.columns.is_empty() {
+            anyhow::bail!("no columns given");
+        }
+        Ok(layout)
+    }
+
+    fn constraint(&self, col: SessionColumn, time_display: TimeDisplay) -> Constraint {
+        match (self.widths.get(&col), col.constraint(time_display)) {
+            (Some(w), Constraint::Min(_)) => Constraint::Min(*w),
+            (Some(w), _) => Constraint::Length(*w),
+            (None, c) => c,
+        }
+    }
+
+    fn min_width(&self, col: SessionColumn, time_display: TimeDisplay) -> u16 {
+        match self.constraint(col, time_display) {
+            Constraint::Length(n) | Constraint::Min(n) => n,
+            _ => 0,
+        }
+    }
+}
+
+/// The columns of `layout` that fit in `width`, dropping the least useful ones first.
+pub fn fit_session_columns(
+    width: u16,
+    debug: bool,
+    time_display: TimeDisplay,
+    layout: &ColumnLayout,
+) -> Vec<SessionColumn> {
+    let mut cols: Vec<SessionColumn> = layout
+        .columns
+        .iter()
+        .copied()
+        .filter(|c| debug || *c != SessionColumn::Why)
+        .collect();
+    // Highlight symbol plus one space between columns.
+    let needed = |cols: &[SessionColumn]| -> u16 {
+        2 + cols
+            .iter()
+            .map(|c| layout.min_width(*c, time_display) + 1)
+            .sum::<u16>()
+    };
+    for drop in SessionColumn::DROP_ORDER {
+        if needed(&cols) <= width {
+            break;
+        }
+        cols.retain(|c| *c != drop);
+    }
+    cols
+}
+
+/// One line of the sessions table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TableLine {
+    /// Section header for a host (`g`).
+    Host(String),
+    /// A root row, by index into the display rows.
+    Root(usize),
+    /// A subagent of an open tree: root index, then index into its `children`.
+    Sub(usize, usize),
+}
+
+/// Each root, followed by its subagents when its tree is open. With `by_host` (rows already
+/// sorted by host) every host starts with a header; hosts that failed with no rows get one
+/// at the end, so their error still shows.
+fn table_lines(
+    rows: &[DisplaySessionRow],
+    open: &HashSet<SessionId>,
+    by_host: bool,
+    errors: &[&HostError],
+) -> Vec<TableLine> {
+    let mut out = Vec::with_capacity(rows.len());
+    for (i, r) in rows.iter().enumerate() {
+        if by_host
+            && rows
+                .get(i.wrapping_sub(1))
+                .is_none_or(|prev| prev.root.host != r.root.host)
+        {
+            out.push(TableLine::Host(r.root.host.clone()));
+        }
+        out.push(TableLine::Root(i));
+        if open.contains(&r.root.id()) {
+            out.extend((0..r.children.len()).map(|c| TableLine::Sub(i, c)));
+        }
+    }
+    if by_host {
+        for e in errors {
+            if !rows.iter().any(|r| r.root.host == e.host) {
+                out.push(TableLine::Host(e.host.clone()));
+            }
+        }
     }
-    header_spans.push(Span::raw(format!(
-        "refresh: {}ms  ",
-        app.refresh.as_millis()
-    )));
+    out
+}
 
-    if let Some(err) = app.last_error.as_ref() {
-        header_spans.push(Span::styled(
-            truncate_middle(err, area.width.saturating_sub(30) as usize),
-            Style::default().fg(Color::Red),
-        ));
-    } else {
-        let now_s = crate::util::system_time_to_unix_s(now).unwrap_or(0);
-        let updated_s = app
-            .last_snapshot
-            .as_ref()
-            .map(|s| s.generated_at_unix_s)
-            .unwrap_or(0);
-        let age = now_s.saturating_sub(updated_s);
-        header_spans.push(Span::styled(
-            format!("updated: {age}s ago"),
-            Style::default().fg(Color::DarkGray),
+/// `home  3 sessions: 1 waiting, 2 working  ✗ timeout: ssh: connect timed out`.
+fn host_header(
+    host: &str,
+    rows: &[DisplaySessionRow],
+    errors: &[&HostError],
+    theme: Theme,
+) -> Line<'static> {
+    let mine: Vec<&DisplaySessionRow> = rows.iter().filter(|r| r.root.host == host).collect();
+    let label = mine
+        .first()
+        .map_or(host, |r| r.root.host_display())
+        .to_string();
+    let count = |s: SessionStatus| mine.iter().filter(|r| r.status == s).count();
+    let mut counts: Vec<String> = [
+        SessionStatus::Waiting,
+        SessionStatus::Working,
+        SessionStatus::Unknown,
+    ]
+    .into_iter()
+    .filter_map(|s| match count(s) {
+        0 => None,
+        n => Some(format!("{n} {}", s.as_str())),
+    })
+    .collect();
+    if counts.is_empty() {
+        counts.push("none".into());
+    }
+    let mut spans = vec![
+        Span::styled(format!("── {label}"), theme.header()),
+        Span::styled(
+            format!(
+                "  {} session{}: {}",
+                mine.len(),
+                if mine.len() == 1 { "" } else { "s" },
+                counts.join(", ")
+            ),
+            theme.fg(Color::DarkGray),
+        ),
+    ];
+    if let Some(e) = errors.iter().find(|e| e.host == host) {
+        spans.push(Span::styled(
+            format!("  ✗ {}: {}", e.kind.as_str(), e.error),
+            theme.fg(Color::Red),
         ));
     }
+    Line::from(spans)
+}
 
-    let mut lines = Vec::new();
-    lines.push(Line::from(header_spans));
+/// The table and each line's height (expanded rows take several).
+fn sessions_table<'a>(app: &'a App, area: Rect, lines: &[TableLine]) -> (Table<'a>, Vec<u16>) {
+    let sessions = app.display_sessions.as_slice();
+    let columns = fit_session_columns(area.width, app.debug, app.time_display, &app.columns);
 
-    let mut help_spans = Vec::new();
-    if app.rename_modal.is_some() {
-        help_spans.push(Span::styled(
-            "Keys: ",
-            Style::default().add_modifier(Modifier::BOLD),
-        ));
-        help_spans.push(Span::raw("Enter save  Esc cancel  Backspace delete"));
+    let header_cells: Vec<Cell> = columns.iter().map(|c| Cell::from(c.label())).collect();
+
+    let header = Row::new(header_cells)
+        .style(app.theme.header())
+        .bottom_margin(0);
+
+    let constraints: Vec<Constraint> = columns
+        .iter()
+        .map(|c| app.columns.constraint(*c, app.time_display))
+        .collect();
+    // Same split the table does: highlight symbol, then one space between columns.
+    let widths: Vec<u16> = Layout::horizontal(constraints.iter().copied())
+        .spacing(1)
+        .split(Rect::new(0, 0, area.width.saturating_sub(2), 1))
+        .iter()
+        .map(|r| r.width)
+        .collect();
+    let expanded = app.expanded.then(|| app.selected_index()).flatten();
+
+    let fmt = RowFormat {
+        theme: app.theme,
+        debug: app.debug,
+        time_display: app.time_display,
+        truncate: app.truncate,
+        tag_styles: &app.tag_styles,
+    };
+    let name_col = columns.iter().position(|c| *c == SessionColumn::Name);
+    let (rows, heights): (Vec<Row>, Vec<u16>) = lines
+        .iter()
+        .map(|line| {
+            let (i, c) = match *line {
+                TableLine::Host(_) => return (Row::new(Vec::<Cell>::new()), 1),
+                TableLine::Root(i) => {
+                    let s = &sessions[i];
+                    let finished = app.finished.contains_key(&s.root.id());
+                    let expand = (expanded == Some(i)).then_some(widths.as_slice());
+                    return row_for_session(s, &columns, fmt, finished, expand);
+                }
+                TableLine::Sub(i, c) => (i, c),
+            };
+            let s = &sessions[i];
+            let sub = &s.children[c];
+            let mut cells =
+                session_cells(&DisplaySessionRow::leaf(sub), &columns, fmt, false, true);
+            if let Some(n) = name_col {
+                let last = c + 1 == s.children.len();
+                let indent = "  ".repeat(
+                    usize::try_from(sub.subagent_depth.unwrap_or(1).max(1) - 1).unwrap_or(0),
+                );
+                let label = sub
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("sub {}", short_thread_id(&sub.thread_id)));
+                cells[n].0 = format!("{indent}{} {label}", if last { "└" } else { "├" });
+            }
+            let row = Row::new(
+                cells
+                    .into_iter()
+                    .map(|(text, style)| Cell::from(Span::styled(text, style))),
+            )
+            .style(app.theme.fg(Color::DarkGray));
+            (row, 1)
+        })
+        .unzip();
+
+    let title = if app.stale {
+        "Active Codex Sessions (last seen, refreshing…)"
     } else {
-        help_spans.push(Span::styled(
-            "Keys: ",
-            Style::default().add_modifier(Modifier::BOLD),
-        ));
-        help_spans.push(Span::raw("↑/↓ select  n name  x clear  r refresh  q quit"));
-    }
+        "Active Codex Sessions"
+    };
+    let table = Table::new(rows, constraints)
+        .header(header)
+        .block(Block::default().borders(Borders::TOP).title(title))
+        .column_spacing(1)
+        .highlight_symbol("> ")
+        .highlight_style(app.theme.highlight());
+    (table, heights)
+}
 
-    if let Some((at, msg)) = app.last_status.as_ref() {
-        if at.elapsed() <= Duration::from_secs(4) {
-            help_spans.push(Span::raw("   "));
-            help_spans.push(Span::styled(
-                format!("Status: {msg}"),
-                Style::default().fg(Color::Green),
-            ));
-        }
-    }
+fn swarm_table(app: &App) -> Table {
+    let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+    let header = Row::new(vec![
+        Cell::from("HOST"),
+        Cell::from("ROOT"),
+        Cell::from("TID"),
+        Cell::from("SUBS"),
+        Cell::from("W/WT/U"),
+        Cell::from(format!("NEW/{}m", SPAWN_RATE_WINDOW_SECS / 60)),
+        Cell::from("DONE"),
+        Cell::from("DEPTH"),
+        Cell::from("OLDEST PENDING"),
+    ])
+    .style(app.theme.header());
 
-    lines.push(Line::from(help_spans));
+    let rows = app.swarm_rows.iter().map(|r| {
+        let label = r
+            .root
+            .name
+            .as_deref()
+            .or(r.root.title.as_deref())
+            .unwrap_or("unknown");
+        let oldest = r
+            .oldest_pending
+            .as_ref()
+            .map(|(tid, since)| {
+                format!(
+                    "{} {}",
+                    short_thread_id(tid),
+                    format_timestamp(*since, now, TimeDisplay::Relative)
+                )
+            })
+            .unwrap_or_else(|| "-".into());
+        Row::new(vec![
+            Cell::from(truncate_middle(r.root.host_display(), 6)),
+            Cell::from(truncate_middle(label, 24)),
+            Cell::from(short_thread_id(&r.root.thread_id)),
+            Cell::from(r.subagents.len().to_string()),
+            Cell::from(format!("{}/{}/{}", r.working, r.waiting, r.unknown)),
+            Cell::from(r.recent_spawns.to_string()),
+            Cell::from(r.completed.to_string()),
+            Cell::from(
+                r.avg_depth
+                    .map(|d| format!("{d:.1}"))
+                    .unwrap_or_else(|| "-".into()),
+            ),
+            Cell::from(oldest),
+        ])
+    });
 
-    Paragraph::new(lines).block(Block::default().borders(Borders::NONE))
+    Table::new(
+        rows,
+        [
+            Constraint::Length(6),
+            Constraint::Length(24),
+            Constraint::Length(14),
+            Constraint::Length(5),
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Length(5),
+            Constraint::Length(6),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::TOP)
+            .title("Subagent Swarms"),
+    )
+    .column_spacing(1)
+    .highlight_symbol("> ")
+    .highlight_style(app.theme.highlight())
 }
 
-fn sessions_table(app: &App, _area: Rect) -> Table {
-    let sessions = app.display_sessions.as_slice();
+fn swarm_drill_table<'a>(app: &'a App, key: &SessionId) -> Table<'a> {
+    let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+    let fleet = app.swarm_rows.iter().find(|r| r.root.has_id(key));
+    let title = format!(
+        "Subagents of {} ({})",
+        fleet
+            .and_then(|r| r.root.name.as_deref().or(r.root.title.as_deref()))
+            .unwrap_or("unknown"),
+        short_thread_id(&key.thread_id)
+    );
 
-    let mut header_cells = vec![
-        Cell::from("HOST"),
-        Cell::from("PID"),
+    let header = Row::new(vec![
         Cell::from("TID"),
-        Cell::from("SUB"),
         Cell::from("STATE"),
+        Cell::from("DEPTH"),
         Cell::from("AGE"),
-        Cell::from("NAME"),
+        Cell::from("PARENT"),
         Cell::from("TITLE"),
-        Cell::from("BRANCH"),
-        Cell::from("PWD"),
-    ];
-    if app.debug {
-        header_cells.push(Cell::from("WHY"));
-    }
+    ])
+    .style(app.theme.header());
 
-    let header = Row::new(header_cells)
-        .style(Style::default().add_modifier(Modifier::BOLD))
-        .bottom_margin(0);
+    let rows = fleet
+        .map(|r| r.subagents.as_slice())
+        .unwrap_or(&[])
+        .iter()
+        .map(|s| {
+            let (state, style) = app.theme.state(s.status, false);
+            Row::new(vec![
+                Cell::from(short_thread_id(&s.thread_id)),
+                Cell::from(Span::styled(state, style)),
+                Cell::from(
+                    s.subagent_depth
+                        .map(|d| d.to_string())
+                        .unwrap_or_else(|| "?".into()),
+                ),
+                Cell::from(
+                    s.last_activity_unix_s
+                        .map(|ts| format_timestamp(ts, now, app.time_display))
+                        .unwrap_or_else(|| "?".into()),
+                ),
+                Cell::from(
+                    s.subagent_parent_thread_id
+                        .as_deref()
+                        .map(short_thread_id)
+                        .unwrap_or_default(),
+                ),
+                Cell::from(truncate_middle(s.title.as_deref().unwrap_or("unknown"), 40)),
+            ])
+        });
 
-    let rows = sessions.iter().map(|s| row_for_session(s, app.debug));
-
-    // Rough width budget (60–120 cols). Keep it stable and let long cells truncate.
-    let mut constraints = vec![
-        Constraint::Length(6),  // HOST
-        Constraint::Length(8),  // PID
-        Constraint::Length(14), // TID
-        Constraint::Length(10), // SUB
-        Constraint::Length(5),  // STATE
-        Constraint::Length(6),  // AGE
-        Constraint::Length(22), // NAME
-        Constraint::Length(18), // TITLE
-        Constraint::Length(28), // BRANCH
-        Constraint::Min(18),    // PWD
-    ];
-    if app.debug {
-        constraints.push(Constraint::Min(18)); // WHY
+    Table::new(
+        rows,
+        [
+            Constraint::Length(14),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(app.time_display.column_width()),
+            Constraint::Length(14),
+            Constraint::Min(18),
+        ],
+    )
+    .header(header)
+    .block(Block::default().borders(Borders::TOP).title(title))
+    .column_spacing(1)
+}
+
+/// "timeout×2, remote_exit" — grouped by kind, in first-seen order.
+fn summarize_error_kinds(errs: &[HostError]) -> String {
+    let mut counts: Vec<(ErrorKind, usize)> = Vec::new();
+    for e in errs {
+        match counts.iter_mut().find(|(k, _)| *k == e.kind) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((e.kind, 1)),
+        }
     }
+    counts
+        .into_iter()
+        .map(|(k, n)| {
+            if n == 1 {
+                k.as_str().to_string()
+            } else {
+                format!("{}×{n}", k.as_str())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    Table::new(rows, constraints)
-        .header(header)
-        .block(
-            Block::default()
-                .borders(Borders::TOP)
-                .title("Active Codex Sessions"),
-        )
-        .column_spacing(1)
-        .highlight_symbol("> ")
-        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+/// How long the oldest repeated error has been failing, e.g. `5m`; `None` when every
+/// error is new this refresh.
+fn failing_for(errs: &[HostError], now_s: i64) -> Option<String> {
+    errs.iter()
+        .filter(|e| e.count > 1)
+        .filter_map(|e| e.first_seen_unix_s)
+        .min()
+        .map(|since| crate::timefmt::format_duration((now_s - since).max(0)))
 }
 
-fn short_thread_id(thread_id: &str) -> String {
+pub fn short_thread_id(thread_id: &str) -> String {
     let tid = thread_id.trim();
-    if tid.len() <= 14 {
+    let chars: Vec<char> = tid.chars().collect();
+    if chars.len() <= 14 {
         return tid.to_string();
     }
-    let left = &tid[..8.min(tid.len())];
-    let right = &tid[tid.len().saturating_sub(5)..];
+    let left: String = chars[..8].iter().collect();
+    let right: String = chars[chars.len() - 5..].iter().collect();
     format!("{left}…{right}")
 }
 
-fn shorten_home_path(path: &str) -> String {
+pub fn shorten_home_path(path: &str) -> String {
     let p = path.trim();
     let Some(home_os) = std::env::var_os("HOME") else {
         return p.to_string();
@@ -745,7 +3194,83 @@ fn format_subagents(s: &SubagentSummary, debug: bool) -> String {
     format!("{} ({})", s.total, parts.join("/"))
 }
 
-fn row_for_session(s: &DisplaySessionRow, debug: bool) -> Row {
+/// Display settings shared by every session row.
+#[derive(Clone, Copy, Debug)]
+pub struct RowFormat<'a> {
+    pub theme: Theme,
+    pub debug: bool,
+    pub time_display: TimeDisplay,
+    pub truncate: TruncateConfig,
+    pub tag_styles: &'a BTreeMap<String, TagStyle>,
+}
+
+/// One table row; with `expand` (the columns' rendered widths) long cells wrap onto extra
+/// lines instead of being truncated.
+fn row_for_session(
+    s: &DisplaySessionRow,
+    columns: &[SessionColumn],
+    fmt: RowFormat<'_>,
+    finished: bool,
+    expand: Option<&[u16]>,
+) -> (Row<'static>, u16) {
+    let contents = session_cells(s, columns, fmt, finished, expand.is_none());
+
+    let mut height = 1;
+    let cells: Vec<Cell> = contents
+        .into_iter()
+        .enumerate()
+        .map(|(i, (text, style))| match expand.and_then(|w| w.get(i)) {
+            Some(&width) => {
+                let lines: Vec<Line> = wrap_chars(&text, width as usize)
+                    .into_iter()
+                    .map(|l| Line::styled(l, style))
+                    .collect();
+                height = height.max(lines.len() as u16);
+                Cell::from(Text::from(lines))
+            }
+            None => Cell::from(Span::styled(text, style)),
+        })
+        .collect();
+
+    let mut row = Row::new(cells).height(height);
+
+    let mut style = Style::default();
+    if fmt.debug {
+        style = style.patch(fmt.theme.fg(Color::White));
+    }
+    if let Some(tagged) = tag_style(fmt.tag_styles, &s.root.tags) {
+        style = style.patch(tagged);
+    }
+    if style != Style::default() {
+        row = row.style(style);
+    }
+
+    (row, height)
+}
+
+/// Text and style of each of `columns` for one session, shortened to the TUI's widths
+/// unless `truncate` is off (expanded rows, `--format csv`).
+pub fn session_cells(
+    s: &DisplaySessionRow,
+    columns: &[SessionColumn],
+    fmt: RowFormat<'_>,
+    finished: bool,
+    truncate_cells: bool,
+) -> Vec<(String, Style)> {
+    let RowFormat {
+        theme,
+        debug,
+        time_display,
+        truncate,
+        ..
+    } = fmt;
+    let fit = |how: Truncation, text: &str, max: usize| {
+        if truncate_cells {
+            how.apply(text, max)
+        } else {
+            text.to_string()
+        }
+    };
     let pid = if s.root.pids.is_empty() {
         "unknown".to_string()
     } else if s.root.pids.len() == 1 {
@@ -754,11 +3279,7 @@ fn row_for_session(s: &DisplaySessionRow, debug: bool) -> Row {
         format!("{}+", s.root.pids[0])
     };
 
-    let (state_text, state_style) = match s.status {
-        SessionStatus::Working => ("WORK", Style::default().fg(Color::Green)),
-        SessionStatus::Waiting => ("IDLE", Style::default().fg(Color::Yellow)),
-        SessionStatus::Unknown => ("UNK", Style::default().fg(Color::Red)),
-    };
+    let (state_text, state_style) = theme.state(s.status, finished);
 
     let tid = short_thread_id(&s.root.thread_id);
     let sub = format_subagents(&s.subagents, debug);
@@ -767,14 +3288,7 @@ fn row_for_session(s: &DisplaySessionRow, debug: bool) -> Row {
         .last_activity_unix_s
         .map(|ts| {
             let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(ts);
-            let delta = now.saturating_sub(ts);
-            if delta < 60 {
-                format!("{delta}s")
-            } else if delta < 3600 {
-                format!("{}m", delta / 60)
-            } else {
-                format!("{}h", delta / 3600)
-            }
+            format_timestamp(ts, now, time_display)
         })
         .unwrap_or_else(|| "?".into());
 
@@ -787,74 +3301,283 @@ fn row_for_session(s: &DisplaySessionRow, debug: bool) -> Row {
         .filter(|s| !s.is_empty())
         .unwrap_or("(unset)");
     let branch = s.root.git_branch.as_deref().unwrap_or("unknown");
-    let why = s.reason.as_deref().unwrap_or("");
+    let mut why = s
+        .root
+        .rollout_unreadable
+        .as_deref()
+        .or(s.reason.as_deref())
+        .unwrap_or("")
+        .to_string();
+    if let Some(prev) = s.root.previous_title.as_deref() {
+        if !why.is_empty() {
+            why.push_str("; ");
+        }
+        why.push_str(&format!("retitled, was \"{prev}\""));
+    }
+    if let Some(eta) = s.root.pending_call_eta.as_ref() {
+        if !why.is_empty() {
+            why.push_str("; ");
+        }
+        let now = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+        why.push_str(&eta.describe(now));
+    }
 
-    let name = truncate_middle(name, 22);
-    let title = truncate_middle(title, 18);
-    let branch = branch.to_string();
+    let name = if s.root.tags.is_empty() {
+        name.to_string()
+    } else {
+        let tags: Vec<String> = s.root.tags.iter().map(|t| format!("#{t}")).collect();
+        format!("{name} {}", tags.join(" "))
+    };
+    let name = if s.root.watched {
+        format!("★ {}", fit(truncate.name, &name, 20))
+    } else {
+        fit(truncate.name, &name, 22)
+    };
+    let title = if s.root.rollout_unreadable.is_some() {
+        format!("🔒 {}", fit(truncate.title, title, 15))
+    } else if s.root.previous_title.is_some() {
+        format!("↺ {}", fit(truncate.title, title, 16))
+    } else {
+        fit(truncate.title, title, 18)
+    };
+    let branch = match s.root.current_branch.as_deref() {
+        Some(cur) if cur != branch => format!("{cur} (started {branch})"),
+        _ => branch.to_string(),
+    };
+    let (branch, branch_style) = match s.root.unpushed_commits {
+        Some(n) if n > 0 => (format!("↑{n} {branch}"), theme.fg(Color::Yellow)),
+        _ => (branch, Style::default()),
+    };
+    let branch = fit(truncate.branch, &branch, 28);
     let pwd = s
         .root
         .cwd
         .as_deref()
         .map(shorten_home_path)
         .unwrap_or_else(|| "unknown".into());
-    let pwd = truncate_middle(&pwd, 44);
-    let host = truncate_middle(&s.root.host, 6);
-    let why = truncate_middle(why, 60);
-
-    let mut cells = vec![
-        Cell::from(host),
-        Cell::from(pid),
-        Cell::from(tid),
-        Cell::from(sub),
-        Cell::from(Span::styled(state_text, state_style)),
-        Cell::from(age),
-        Cell::from(name),
-        Cell::from(title),
-        Cell::from(branch),
-        Cell::from(pwd),
-    ];
-    if debug {
-        cells.push(Cell::from(why));
-    }
-
-    let mut row = Row::new(cells);
-
-    if debug {
-        row = row.style(Style::default().fg(Color::White));
-    }
+    let repo_root = s.root.repo_root.as_deref().map(shorten_home_path);
+    let fit_path = |text: &str, max: usize| {
+        if truncate_cells {
+            truncate.pwd.apply_path(text, repo_root.as_deref(), max)
+        } else {
+            text.to_string()
+        }
+    };
+    let commits = s
+        .root
+        .commits_since_start
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "-".into());
+    let rate = match s.root.tokens_per_min {
+        Some(n) if n >= 100_000 => format!("{}k", n / 1000),
+        Some(n) if n >= 1000 => format!("{:.1}k", n as f64 / 1000.0),
+        Some(n) => n.to_string(),
+        None => "-".into(),
+    };
+    let (pwd, pwd_style) = if s.root.cwd_missing {
+        (
+            format!("✗ cwd missing: {}", fit_path(&pwd, 30)),
+            theme.fg(Color::Red),
+        )
+    } else {
+        (fit_path(&pwd, 44), Style::default())
+    };
+    let host = fit(Truncation::Middle, s.root.host_display(), 6);
+    let (why, why_style) = match s.root.over_budget.as_deref() {
+        Some(over) => (
+            fit(
+                truncate.why,
+                format!("OVERBUDGET {over}; {why}").trim_end_matches("; "),
+                60,
+            ),
+            theme.fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        None => (fit(truncate.why, &why, 60), Style::default()),
+    };
 
-    row
+    let plain = Style::default();
+    columns
+        .iter()
+        .map(|c| match c {
+            SessionColumn::Host => (host.clone(), plain),
+            SessionColumn::Pid => (pid.clone(), plain),
+            SessionColumn::Tid => (tid.clone(), plain),
+            SessionColumn::Sub => (sub.clone(), plain),
+            SessionColumn::Commits => (commits.clone(), plain),
+            SessionColumn::State => (state_text.to_string(), state_style),
+            SessionColumn::Age => (age.clone(), plain),
+            SessionColumn::Rate => (rate.clone(), plain),
+            SessionColumn::Name => (name.clone(), plain),
+            SessionColumn::Title => (title.clone(), plain),
+            SessionColumn::Branch => (branch.clone(), branch_style),
+            SessionColumn::Pwd => (pwd.clone(), pwd_style),
+            SessionColumn::Why => (why.clone(), why_style),
+        })
+        .collect()
 }
 
-fn render_rename_modal(f: &mut ratatui::Frame, modal: &RenameModal, area: Rect) {
-    let width = area.width.min(80).max(40);
-    let height = area.height.min(9).max(7);
+fn render_edit_modal(f: &mut ratatui::Frame, modal: &EditModal, theme: Theme, area: Rect) {
+    let width = area.width.clamp(40, 80);
+    let height = area.height.clamp(7, 9);
     let rect = centered_rect(width, height, area);
 
     f.render_widget(Clear, rect);
 
     let tid = short_thread_id(&modal.key.thread_id);
-    let title = format!("Name session ({}) {tid}", modal.key.host);
+    let title = format!("Edit session ({}) {tid}", modal.key.host);
 
     let input_max = rect.width.saturating_sub(4) as usize;
-    let input = format!("> {}_", modal.buffer);
-    let input = truncate_middle(&input, input_max);
+    let mut lines = vec![Line::raw("")];
+    for field in EditField::ALL {
+        let selected = field == modal.field;
+        let text = match modal.buffer.as_deref() {
+            Some(buffer) if selected => format!("{}: > {buffer}_", field.label()),
+            _ => format!("{}: {}", field.label(), modal.value(field)),
+        };
+        let style = if selected {
+            theme.highlight()
+        } else {
+            Style::default()
+        };
+        let text = truncate_middle(&text, input_max);
+        let suggestion = modal
+            .pending_suggestion()
+            .filter(|_| selected)
+            .map(|s| truncate_end(s, input_max.saturating_sub(text.chars().count() + 1)));
+        lines.push(match suggestion {
+            Some(s) => Line::from(vec![
+                Span::styled(format!("{text} "), style),
+                Span::styled(s, style.patch(theme.fg(Color::DarkGray))),
+            ]),
+            None => Line::styled(text, style),
+        });
+    }
+    lines.push(Line::raw(""));
+    let hint = match (modal.buffer.is_some(), modal.pending_suggestion().is_some()) {
+        (true, true) => "Tab = Use suggestion    Enter = Save    Esc = Cancel",
+        (true, false) => "Enter = Save    Esc = Cancel",
+        (false, true) => "Tab = Use suggestion    Enter = Edit    Esc = Close",
+        (false, false) => "↑/↓ = Field    Enter = Edit    Esc = Close",
+    };
+    lines.push(Line::styled(hint, theme.fg(Color::DarkGray)));
+
+    let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(widget, rect);
+}
 
+fn render_confirm(f: &mut ratatui::Frame, confirm: &Confirm, theme: Theme, area: Rect) {
+    let width = area.width.clamp(30, 60);
+    let rect = centered_rect(width, 5, area);
+    f.render_widget(Clear, rect);
     let lines = vec![
+        Line::raw(truncate_middle(
+            &format!("{}?", confirm.prompt),
+            rect.width.saturating_sub(4) as usize,
+        )),
         Line::raw(""),
-        Line::raw(input),
+        Line::styled(
+            "y = Confirm    any other key = Cancel",
+            theme.fg(Color::DarkGray),
+        ),
+    ];
+    let widget =
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Confirm"));
+    f.render_widget(widget, rect);
+}
+
+/// The `H` prompt: a host to add, or one of the polled hosts to disable.
+fn render_host_input(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let Some(input) = app.host_input.as_deref() else {
+        return;
+    };
+    let width = area.width.clamp(30, 70);
+    let rect = centered_rect(width, 7, area);
+    f.render_widget(Clear, rect);
+    let max = rect.width.saturating_sub(4) as usize;
+    let mut known = format!("polling: {}", app.hosts.join(", "));
+    if !app.disabled_hosts.is_empty() {
+        known.push_str(&format!("  disabled: {}", app.disabled_hosts.join(", ")));
+    }
+    let lines = vec![
+        Line::raw(truncate_middle(&format!("Host: > {input}_"), max)),
+        Line::styled(truncate_end(&known, max), app.theme.fg(Color::DarkGray)),
         Line::raw(""),
         Line::styled(
-            "Enter = Save    Esc = Cancel",
-            Style::default().fg(Color::DarkGray),
+            "Enter = Add (or disable a polled host)    Esc = Cancel",
+            app.theme.fg(Color::DarkGray),
         ),
     ];
+    let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Hosts"));
+    f.render_widget(widget, rect);
+}
 
-    let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+/// The `h` panel: every failing host with its full error, context and how long it has failed.
+fn render_host_errors(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let Some(scroll) = app.host_errors_panel else {
+        return;
+    };
+    let errs = app
+        .last_snapshot
+        .as_ref()
+        .and_then(|s| s.host_errors.as_deref())
+        .unwrap_or(&[]);
+    let rect = centered_rect(
+        area.width.saturating_sub(4).min(100),
+        area.height.saturating_sub(2),
+        area,
+    );
+    f.render_widget(Clear, rect);
+    let now_s = crate::util::system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+    let mut lines = host_error_lines(errs, now_s, app.theme);
+    lines.push(Line::styled(
+        "↑/↓ = Scroll    Esc = Close",
+        app.theme.fg(Color::DarkGray),
+    ));
+    let title = format!("Host errors ({})", errs.len());
+    let widget = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
     f.render_widget(widget, rect);
 }
 
+/// `devbox  timeout  failing for 12m (24 refreshes)`, then the context frames and the
+/// message, for each host.
+fn host_error_lines(errs: &[HostError], now_s: i64, theme: Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for e in errs {
+        let mut head = vec![
+            Span::styled(e.host.clone(), theme.header()),
+            Span::styled(format!("  {}", e.kind.as_str()), theme.fg(Color::Red)),
+        ];
+        if e.count > 1 {
+            let since = e
+                .first_seen_unix_s
+                .map(|t| {
+                    format!(
+                        "failing for {} ",
+                        crate::timefmt::format_duration((now_s - t).max(0))
+                    )
+                })
+                .unwrap_or_default();
+            head.push(Span::styled(
+                format!("  {since}({} refreshes)", e.count),
+                theme.fg(Color::DarkGray),
+            ));
+        }
+        lines.push(Line::from(head));
+        for c in &e.context {
+            lines.push(Line::styled(
+                format!("  while: {c}"),
+                theme.fg(Color::DarkGray),
+            ));
+        }
+        lines.extend(e.error.lines().map(|l| Line::raw(format!("  {l}"))));
+        lines.push(Line::raw(""));
+    }
+    lines
+}
+
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let width = width.min(area.width);
     let height = height.min(area.height);
@@ -876,22 +3599,11 @@ mod tests {
         SessionRow {
             host: "local".into(),
             thread_id: thread_id.into(),
-            pids: Vec::new(),
-            tty: None,
             title: Some("t".into()),
             name: name.map(|s| s.to_string()),
-            cwd: None,
-            repo_root: None,
-            git_branch: None,
-            git_commit: None,
-            session_source: None,
-            forked_from_id: None,
-            subagent_parent_thread_id: None,
-            subagent_depth: None,
             status: SessionStatus::Waiting,
             last_activity_unix_s,
-            rollout_path: None,
-            debug: None,
+            ..Default::default()
         }
     }
 
@@ -905,4 +3617,504 @@ mod tests {
         assert_eq!(out[0].root.thread_id, "a");
         assert_eq!(out[1].root.thread_id, "b");
     }
+
+    #[test]
+    fn attention_title_counts_waiting_and_working_roots() {
+        let mut a = row("a", None, Some(100));
+        a.status = SessionStatus::Waiting;
+        let mut b = row("b", None, Some(100));
+        b.status = SessionStatus::Working;
+        let mut c = row("c", None, Some(100));
+        c.status = SessionStatus::Waiting;
+        let out = group_sessions_for_display(&[a, b, c], false);
+        assert_eq!(attention_title(&out), "codex-ps ✋2 ⚒1");
+        assert_eq!(footer_text(&out, 0..10), "2 waiting · 1 working");
+        let first = next_waiting(&out, None).expect("waiting row");
+        let second = next_waiting(&out, Some(first)).expect("waiting row");
+        assert_ne!(first, second);
+        assert_eq!(next_waiting(&out, Some(second)), Some(first));
+        assert_eq!(
+            footer_text(&out, 0..0),
+            "2 waiting · 1 working · 3 off-screen (2 waiting)"
+        );
+    }
+
+    #[test]
+    fn narrow_terminals_drop_low_priority_columns() {
+        let all = ColumnLayout::default();
+        let wide = fit_session_columns(200, true, TimeDisplay::Relative, &all);
+        assert_eq!(wide.len(), SessionColumn::ALL.len());
+        let narrow = fit_session_columns(80, false, TimeDisplay::Relative, &all);
+        assert!(!narrow.contains(&SessionColumn::Pid));
+        assert!(!narrow.contains(&SessionColumn::Branch));
+        assert!(narrow.contains(&SessionColumn::Name));
+        let tiny = fit_session_columns(COMPACT_WIDTH, false, TimeDisplay::Relative, &all);
+        assert_eq!(
+            tiny,
+            vec![
+                SessionColumn::Tid,
+                SessionColumn::State,
+                SessionColumn::Age,
+                SessionColumn::Name
+            ]
+        );
+    }
+
+    #[test]
+    fn column_layouts_parse_order_and_widths() {
+        let layout =
+            ColumnLayout::parse(&["state", "AGE", "name:40", "pwd:30", "why"]).expect("parse");
+        let cols = fit_session_columns(200, false, TimeDisplay::Relative, &layout);
+        assert_eq!(
+            cols,
+            vec![
+                SessionColumn::State,
+                SessionColumn::Age,
+                SessionColumn::Name,
+                SessionColumn::Pwd,
+            ]
+        );
+        assert_eq!(
+            layout.constraint(SessionColumn::Name, TimeDisplay::Relative),
+            Constraint::Length(40)
+        );
+        assert_eq!(
+            layout.constraint(SessionColumn::Pwd, TimeDisplay::Relative),
+            Constraint::Min(30)
+        );
+        assert!(ColumnLayout::parse(&["nope"]).is_err());
+        assert!(ColumnLayout::parse(&["name", "NAME"]).is_err());
+        assert!(ColumnLayout::parse(&["name:0"]).is_err());
+        assert!(ColumnLayout::parse::<&str>(&[]).is_err());
+    }
+
+    #[test]
+    fn host_freshness_marks_pending_hosts() {
+        let snap = Snapshot {
+            generation: 1,
+            generated_at_unix_s: 1_000,
+            host: "local,home".into(),
+            host_collected_at: [("local".to_string(), 995)].into_iter().collect(),
+            ..Default::default()
+        };
+        assert_eq!(host_freshness(&snap, 1_000), "local 5s, home …");
+    }
+
+    #[test]
+    fn edit_modal_navigates_fields_and_saves_one_at_a_time() {
+        let mut row = row("a", Some("triage"), None);
+        row.tags = vec!["bug".into()];
+        let mut modal = EditModal::for_row(&row);
+
+        assert_eq!(modal.handle_key(KeyCode::Down), EditOutcome::None);
+        assert_eq!(modal.field, EditField::Tags);
+        modal.handle_key(KeyCode::Enter);
+        assert_eq!(modal.buffer.as_deref(), Some("bug"));
+        for c in ", infra".chars() {
+            modal.handle_key(KeyCode::Char(c));
+        }
+        assert_eq!(
+            modal.handle_key(KeyCode::Enter),
+            EditOutcome::Save(EditField::Tags, "bug, infra".into())
+        );
+        assert_eq!(modal.tags, "bug, infra");
+        assert_eq!(modal.handle_key(KeyCode::Esc), EditOutcome::Close);
+
+        let mut unnamed = self::row("b", None, None);
+        unnamed.suggested_name = Some("codex-ps: flaky login test".into());
+        let mut modal = EditModal::for_row(&unnamed);
+        modal.buffer = Some(String::new());
+        assert_eq!(
+            modal.handle_key(KeyCode::Tab),
+            EditOutcome::Save(EditField::Name, "codex-ps: flaky login test".into())
+        );
+        assert_eq!(modal.pending_suggestion(), None);
+    }
+
+    #[test]
+    fn command_queue_prioritizes_names_and_coalesces_refreshes() {
+        let key = |t: &str| SessionId::new("local", t);
+        let mut q = CommandQueue::default();
+        q.push(WorkerCmd::Refresh { poll_remote: false });
+        q.push(WorkerCmd::RefreshSession { key: key("a") });
+        q.push(WorkerCmd::Refresh { poll_remote: true });
+        q.push(WorkerCmd::Refresh { poll_remote: false });
+        q.push(WorkerCmd::SetName {
+            key: key("a"),
+            name: "n".into(),
+        });
+        q.push(WorkerCmd::RefreshSession { key: key("a") });
+        q.push(WorkerCmd::ClearName { key: key("b") });
+
+        assert!(matches!(q.pop_action(), Some(WorkerCmd::SetName { .. })));
+        q.push(WorkerCmd::SetHosts {
+            hosts: vec!["local".into()],
+            probe: None,
+        });
+        assert!(matches!(q.pop_action(), Some(WorkerCmd::ClearName { .. })));
+        assert!(q.pop_action().is_none());
+        assert!(matches!(q.pop(), Some(WorkerCmd::SetHosts { .. })));
+        assert!(matches!(q.pop(), Some(WorkerCmd::RefreshSession { .. })));
+        assert!(matches!(
+            q.pop(),
+            Some(WorkerCmd::Refresh { poll_remote: true })
+        ));
+        assert!(q.pop().is_none());
+        assert!(q.is_empty());
+    }
+
+    #[test]
+    fn filter_matches_every_word_across_fields() {
+        let row: SessionRow = serde_json::from_value(serde_json::json!({
+            "host": "devbox",
+            "thread_id": "019c2590-aaaa",
+            "pids": [],
+            "status": "waiting",
+            "name": "Release triage",
+            "git_branch": "fix/auth",
+            "cwd": "/src/payments",
+        }))
+        .expect("row");
+        assert!(matches_filter(&row, "triage", 0));
+        assert!(matches_filter(&row, "AUTH pay", 0));
+        assert!(matches_filter(&row, "019c", 0));
+        assert!(!matches_filter(&row, "auth billing", 0));
+        assert!(!matches_filter(&row, "devbox", 0));
+        assert_eq!(
+            filtered_display(
+                std::slice::from_ref(&row),
+                false,
+                " ",
+                None,
+                SortOrder::Activity,
+                false,
+            )
+            .len(),
+            1
+        );
+        assert_eq!(
+            filtered_display(&[row], false, "nope", None, SortOrder::Activity, false).len(),
+            0
+        );
+    }
+
+    #[test]
+    fn filter_predicates_on_the_pending_call() {
+        let mut exec = row("a", Some("deploy"), Some(100));
+        exec.pending_call = Some("exec_command: cargo publish".into());
+        exec.pending_call_since_unix_s = Some(1000);
+        let mut ask = row("b", None, Some(1200));
+        ask.pending_call = Some("request_user_input: which env?".into());
+        let idle = row("c", None, Some(0));
+
+        let hits = |filter: &str| -> Vec<String> {
+            [&exec, &ask, &idle]
+                .into_iter()
+                .filter(|r| matches_filter(r, filter, 1400))
+                .map(|r| r.thread_id.clone())
+                .collect()
+        };
+        assert_eq!(hits("pending:exec_command"), vec!["a"]);
+        assert_eq!(hits("pending:request_user"), vec!["b"]);
+        assert_eq!(hits("pending:"), vec!["a", "b"]);
+        // Age from the call's timestamp, else the session's last activity.
+        assert_eq!(hits("pending_age>5m"), vec!["a"]);
+        assert_eq!(hits("pending_age<5m"), vec!["b"]);
+        assert_eq!(hits("pending:exec deploy"), vec!["a"]);
+        assert!(hits("pending:exec publish").is_empty());
+    }
+
+    #[test]
+    fn sort_orders_keep_watched_rows_first() {
+        let mut a = row("a", Some("zeta"), Some(300));
+        a.status = SessionStatus::Working;
+        a.started_at_unix_s = Some(200);
+        let mut b = row("b", None, Some(200));
+        b.status = SessionStatus::Waiting;
+        b.started_at_unix_s = Some(100);
+        let mut c = row("c", Some("Alpha"), Some(100));
+        c.status = SessionStatus::Working;
+        let mut w = row("w", None, Some(0));
+        w.watched = true;
+        let sessions = [a, b, c, w];
+        let order = |sort| -> Vec<String> {
+            filtered_display(&sessions, false, "", None, sort, false)
+                .into_iter()
+                .map(|r| r.root.thread_id)
+                .collect()
+        };
+        assert_eq!(order(SortOrder::Activity), ["w", "a", "c", "b"]);
+        assert_eq!(order(SortOrder::Status), ["w", "b", "a", "c"]);
+        assert_eq!(order(SortOrder::Name), ["w", "c", "a", "b"]);
+        assert_eq!(order(SortOrder::Age), ["w", "b", "a", "c"]);
+    }
+
+    #[test]
+    fn yank_keys_pick_the_copied_value() {
+        let mut r = row("019c2590-aaaa", None, None);
+        r.rollout_path = Some("/home/u/.codex/sessions/rollout-019c2590-aaaa.jsonl".into());
+        assert_eq!(
+            yank_value(&r, 'y'),
+            Some(("thread id", Some("019c2590-aaaa".into())))
+        );
+        assert_eq!(yank_value(&r, 't'), yank_value(&r, 'y'));
+        assert_eq!(yank_value(&r, 'c'), Some(("cwd", None)));
+        assert_eq!(
+            yank_value(&r, 'r').and_then(|(_, v)| v).as_deref(),
+            r.rollout_path.as_deref()
+        );
+        assert_eq!(yank_value(&r, 'q'), None);
+    }
+
+    #[test]
+    fn open_trees_list_subagents_under_their_root() {
+        let mut late = row("s2", None, Some(300));
+        late.subagent_parent_thread_id = Some("root".into());
+        late.started_at_unix_s = Some(20);
+        late.status = SessionStatus::Working;
+        let mut early = row("s1", None, Some(200));
+        early.subagent_parent_thread_id = Some("root".into());
+        early.started_at_unix_s = Some(10);
+        let rows = group_sessions_for_display(
+            &[
+                row("root", Some("a"), Some(100)),
+                late,
+                early,
+                row("other", None, Some(50)),
+            ],
+            false,
+        );
+        let children: Vec<&str> = rows[0]
+            .children
+            .iter()
+            .map(|c| c.thread_id.as_str())
+            .collect();
+        assert_eq!(children, vec!["s1", "s2"]);
+        assert_eq!(rows[0].subagents.working, 1);
+
+        let mut open = HashSet::new();
+        assert_eq!(
+            table_lines(&rows, &open, false, &[]),
+            vec![TableLine::Root(0), TableLine::Root(1)]
+        );
+        open.insert(rows[0].root.id());
+        assert_eq!(
+            table_lines(&rows, &open, false, &[]),
+            vec![
+                TableLine::Root(0),
+                TableLine::Sub(0, 0),
+                TableLine::Sub(0, 1),
+                TableLine::Root(1)
+            ]
+        );
+        let leaf = DisplaySessionRow::leaf(&rows[0].children[1]);
+        assert_eq!(leaf.status, SessionStatus::Working);
+        assert_eq!(leaf.last_activity_unix_s, Some(300));
+    }
+
+    #[test]
+    fn by_host_lines_start_each_host_with_a_header() {
+        let mut remote = row("r", None, Some(300));
+        remote.host = "devbox".into();
+        let rows = filtered_display(
+            &[remote, row("a", None, Some(200)), row("b", None, Some(100))],
+            false,
+            "",
+            None,
+            SortOrder::Activity,
+            true,
+        );
+        let ids: Vec<&str> = rows.iter().map(|r| r.root.thread_id.as_str()).collect();
+        assert_eq!(ids, vec!["r", "a", "b"]);
+        let down = HostError::new(
+            "build",
+            crate::error::ErrorInfo::new(crate::error::ErrorKind::Timeout, "connect timed out"),
+        );
+        assert_eq!(
+            table_lines(&rows, &HashSet::new(), true, &[&down]),
+            vec![
+                TableLine::Host("devbox".into()),
+                TableLine::Root(0),
+                TableLine::Host("local".into()),
+                TableLine::Root(1),
+                TableLine::Root(2),
+                TableLine::Host("build".into()),
+            ]
+        );
+        let header = host_header("local", &rows, &[&down], Theme::default());
+        let text: String = header.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "── local  2 sessions: 2 waiting");
+    }
+
+    fn test_app() -> (App, Receiver<WorkerCmd>, mpsc::Sender<WorkerMsg>) {
+        let opts = TuiOptions {
+            refresh_ms: 1000,
+            debug: false,
+            time_display: TimeDisplay::Relative,
+            set_title: false,
+            theme: Default::default(),
+            truncate: Default::default(),
+            tag_styles: Default::default(),
+            guard: Default::default(),
+            schedule: Default::default(),
+            columns: Default::default(),
+            resume_command: None,
+            group_by_host: false,
+        };
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let (msg_tx, msg_rx) = mpsc::channel();
+        (App::new(&opts, cmd_tx, msg_rx), cmd_rx, msg_tx)
+    }
+
+    #[test]
+    fn pause_stops_periodic_refresh_but_not_r() {
+        let (mut app, cmd_rx, _msg_tx) = test_app();
+        assert!(app.auto_refresh_due());
+
+        app.handle_key(KeyCode::Char('P'));
+        assert!(app.paused);
+        assert!(!app.auto_refresh_due());
+        app.handle_key(KeyCode::Char('r'));
+        assert!(matches!(cmd_rx.try_recv(), Ok(WorkerCmd::Refresh { .. })));
+
+        app.handle_key(KeyCode::Char('P'));
+        app.refresh_in_flight = false;
+        app.last_refresh_sent = Instant::now() - Duration::from_secs(5);
+        assert!(app.auto_refresh_due());
+    }
+
+    #[test]
+    fn host_prompt_adds_with_probe_and_disables() {
+        let (mut app, cmd_rx, msg_tx) = test_app();
+        app.hosts = vec!["local".into(), "devbox".into()];
+        for code in [KeyCode::Char('H'), KeyCode::Char('b'), KeyCode::Enter] {
+            app.handle_key(code);
+        }
+        let Ok(WorkerCmd::SetHosts { hosts, probe }) = cmd_rx.try_recv() else {
+            panic!("expected SetHosts");
+        };
+        assert_eq!(hosts, vec!["local", "devbox", "b"]);
+        assert_eq!(probe.as_deref(), Some("b"));
+        app.handle_key(KeyCode::Char('H'));
+        for c in "devbox".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter);
+        let Ok(WorkerCmd::SetHosts { hosts, probe }) = cmd_rx.try_recv() else {
+            panic!("expected SetHosts");
+        };
+        assert_eq!((hosts.clone(), probe), (vec!["local".to_string()], None));
+        msg_tx.send(WorkerMsg::HostsUpdated(hosts)).expect("send");
+        app.poll_worker();
+        assert_eq!(app.disabled_hosts, vec!["devbox"]);
+    }
+
+    #[test]
+    fn bang_clears_a_filter_hiding_waiting_sessions() {
+        let (mut app, _cmd_rx, _msg_tx) = test_app();
+        let mut fix = row("a", Some("fix tests"), Some(300));
+        fix.status = SessionStatus::Working;
+        app.last_snapshot = Some(Snapshot::of(vec![
+            fix,
+            row("b", Some("blocked"), Some(200)),
+        ]));
+        app.set_filter("fix".into());
+        assert_eq!(app.display_sessions.len(), 1);
+        assert_eq!(app.waiting_hidden_by_filter(), 1);
+
+        app.handle_key(KeyCode::Char('!'));
+        assert!(app.filter.is_empty());
+        assert_eq!(app.waiting_hidden_by_filter(), 0);
+        assert_eq!(
+            app.selected.as_ref().map(|k| k.thread_id.as_str()),
+            Some("b")
+        );
+    }
+
+    #[test]
+    fn status_filter_keeps_one_state() {
+        let mut working = row("a", None, Some(300));
+        working.status = SessionStatus::Working;
+        let sessions = vec![
+            working,
+            row("b", None, Some(200)),
+            row("c", None, Some(100)),
+        ];
+        let ids = |status| -> Vec<String> {
+            filtered_display(&sessions, false, "", status, SortOrder::Activity, false)
+                .into_iter()
+                .map(|r| r.root.thread_id)
+                .collect()
+        };
+        assert_eq!(ids(Some(SessionStatus::Working)), vec!["a"]);
+        assert_eq!(ids(Some(SessionStatus::Waiting)), vec!["b", "c"]);
+        assert!(ids(Some(SessionStatus::Unknown)).is_empty());
+        assert_eq!(ids(None).len(), 3);
+    }
+
+    #[test]
+    fn host_error_panel_lists_full_errors() {
+        let mut info = crate::error::ErrorInfo::new(
+            crate::error::ErrorKind::Transport,
+            "ssh: connect to host devbox port 22: Connection refused",
+        );
+        info.context = vec!["collect devbox".into()];
+        let mut err = HostError::new("devbox", info);
+        err.count = 3;
+        err.first_seen_unix_s = Some(1000);
+        let text: Vec<String> = host_error_lines(&[err], 1300, Theme::default())
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                "devbox  transport  failing for 5m (3 refreshes)",
+                "  while: collect devbox",
+                "  ssh: connect to host devbox port 22: Connection refused",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn transitions_keep_the_newest_per_session() {
+        let (mut app, _cmd_rx, _msg_tx) = test_app();
+        let mut a = row("a", None, None);
+        a.debug = serde_json::from_value(serde_json::json!({
+            "status_reason_code": "pending_user_input"
+        }))
+        .ok();
+        let mut snap = Snapshot {
+            generation: 1,
+            host: "local".into(),
+            sessions: vec![a],
+            ..Default::default()
+        };
+        let changes = SnapshotChanges {
+            status_changed: vec![crate::changes::StatusChange {
+                key: SessionId::new("local", "a"),
+                from: SessionStatus::Working,
+                to: SessionStatus::Waiting,
+            }],
+            ..Default::default()
+        };
+        for t in 0..TRANSITIONS_PER_SESSION as i64 + 5 {
+            app.record_transitions(&snap, &changes, 60 * t);
+        }
+        let log = &app.transitions[&SessionId::new("local", "a")];
+        assert_eq!(log.len(), TRANSITIONS_PER_SESSION);
+        assert_eq!(log[0].at_unix_s, 5 * 60);
+        let text = transition_lines(log, 60 * 54, TimeDisplay::Relative);
+        assert!(
+            text.starts_with(
+                "status changes (50):\n  0s working→waiting (pending_user_input)\n  1m "
+            ),
+            "{text}"
+        );
+
+        snap.sessions.clear();
+        app.record_transitions(&snap, &SnapshotChanges::default(), 0);
+        assert!(app.transitions.is_empty());
+    }
 }
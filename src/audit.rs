@@ -0,0 +1,59 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::names::config_dir;
+
+/// One line of `audit.jsonl`: something codex-ps did to a session on its own.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditRecord {
+    pub at_unix_s: i64,
+    pub host: String,
+    pub thread_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// `stop`, `terminate` or `kill`.
+    pub action: String,
+    /// The `[[policy.rules]]` entry that matched, and why.
+    pub rule: String,
+    pub reason: String,
+    /// Matched while `enforce` was off: logged, not done.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Append-only log of automatic actions (`audit.jsonl`).
+#[derive(Clone, Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self::new_at(config_dir()?.join("audit.jsonl")))
+    }
+
+    pub fn new_at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn append(&self, rec: &AuditRecord) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create dir {}", parent.display()))?;
+        }
+        let line = serde_json::to_string(rec).context("serialize audit record")?;
+        let mut f = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("open for append {}", self.path.display()))?;
+        writeln!(f, "{line}").context("append audit record")?;
+        Ok(())
+    }
+}
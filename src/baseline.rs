@@ -0,0 +1,253 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::app::short_thread_id;
+use crate::collector::Collector;
+use crate::model::Snapshot;
+use crate::names::config_dir;
+use crate::timefmt::{TimeDisplay, format_duration, format_timestamp};
+use crate::util::system_time_to_unix_s;
+
+/// The sessions expected to stay up, recorded by `codex-ps baseline save`. Only root
+/// sessions: subagents finishing is normal.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Baseline {
+    pub saved_at_unix_s: i64,
+    /// The hosts `check` polls.
+    pub hosts: Vec<String>,
+    pub sessions: Vec<BaselineSession>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BaselineSession {
+    pub host: String,
+    pub thread_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+}
+
+/// How the current snapshot differs from the baseline.
+#[derive(Debug, Default, Serialize)]
+pub struct Deviations {
+    /// Baseline sessions still running.
+    pub running: usize,
+    /// Baseline sessions gone from a host that answered.
+    pub missing: Vec<BaselineSession>,
+    /// Baseline sessions on hosts that failed this time, so not known either way.
+    pub unchecked: Vec<BaselineSession>,
+    /// Root sessions running now that the baseline doesn't have.
+    pub new: Vec<BaselineSession>,
+}
+
+impl Baseline {
+    pub fn from_snapshot(snap: &Snapshot, hosts: &[String], now_unix_s: i64) -> Self {
+        Self {
+            saved_at_unix_s: now_unix_s,
+            hosts: hosts.to_vec(),
+            sessions: roots(snap),
+        }
+    }
+
+    pub fn compare(&self, snap: &Snapshot) -> Deviations {
+        let failed = |host: &str| snap.host_errors.iter().flatten().any(|e| e.host == host);
+        let mut out = Deviations::default();
+        for s in &self.sessions {
+            if snap
+                .sessions
+                .iter()
+                .any(|r| r.host == s.host && r.thread_id == s.thread_id)
+            {
+                out.running += 1;
+            } else if failed(&s.host) {
+                out.unchecked.push(s.clone());
+            } else {
+                out.missing.push(s.clone());
+            }
+        }
+        out.new = roots(snap)
+            .into_iter()
+            .filter(|r| {
+                !self
+                    .sessions
+                    .iter()
+                    .any(|s| s.host == r.host && s.thread_id == r.thread_id)
+            })
+            .collect();
+        out
+    }
+}
+
+fn roots(snap: &Snapshot) -> Vec<BaselineSession> {
+    snap.sessions
+        .iter()
+        .filter(|r| r.subagent_parent_thread_id.is_none())
+        .map(|r| BaselineSession {
+            host: r.host.clone(),
+            thread_id: r.thread_id.clone(),
+            name: r.name.clone(),
+            cwd: r.cwd.clone(),
+        })
+        .collect()
+}
+
+fn baseline_path(file: Option<&Path>) -> anyhow::Result<PathBuf> {
+    match file {
+        Some(p) => Ok(p.to_path_buf()),
+        None => Ok(config_dir()?.join("baseline.json")),
+    }
+}
+
+/// `codex-ps baseline save`: record the root sessions running on `hosts` now.
+pub fn run_save(
+    mut collector: Collector,
+    hosts: &[String],
+    file: Option<&Path>,
+) -> anyhow::Result<()> {
+    let snap = collector.collect(hosts)?;
+    for e in snap.host_errors.iter().flatten() {
+        eprintln!(
+            "warning: {}: {} (its sessions are not recorded)",
+            e.host, e.error
+        );
+    }
+    let now = system_time_to_unix_s(std::time::SystemTime::now()).unwrap_or(0);
+    let baseline = Baseline::from_snapshot(&snap, hosts, now);
+    let path = baseline_path(file)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("create dir {}", parent.display()))?;
+    }
+    let text = serde_json::to_string_pretty(&baseline).context("serialize baseline")?;
+    std::fs::write(&path, text).with_context(|| format!("write {}", path.display()))?;
+    println!(
+        "Saved {} session(s) to {}",
+        baseline.sessions.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// `codex-ps baseline check`: poll the baseline's hosts and report what changed. Fails when
+/// a baseline session is missing, so cron and batch scripts can alert on the exit status.
+pub fn run_check(mut collector: Collector, file: Option<&Path>, json: bool) -> anyhow::Result<()> {
+    let path = baseline_path(file)?;
+    let text = std::fs::read_to_string(&path).with_context(|| {
+        format!(
+            "read {} (record one with `codex-ps baseline save`)",
+            path.display()
+        )
+    })?;
+    let baseline: Baseline =
+        serde_json::from_str(&text).with_context(|| format!("parse {}", path.display()))?;
+    let snap = collector.collect(&baseline.hosts)?;
+    let dev = baseline.compare(&snap);
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&dev).context("serialize deviations")?
+        );
+    } else {
+        let now = system_time_to_unix_s(std::time::SystemTime::now()).unwrap_or(0);
+        print!("{}", format_report(&baseline, &dev, now));
+    }
+    if !dev.missing.is_empty() {
+        anyhow::bail!(
+            "{} of {} baseline session(s) no longer running",
+            dev.missing.len(),
+            baseline.sessions.len()
+        );
+    }
+    Ok(())
+}
+
+fn format_report(baseline: &Baseline, dev: &Deviations, now_unix_s: i64) -> String {
+    let mut out = format!(
+        "Baseline from {} ({} ago): {} of {} session(s) running\n",
+        format_timestamp(baseline.saved_at_unix_s, now_unix_s, TimeDisplay::Local),
+        format_duration((now_unix_s - baseline.saved_at_unix_s).max(0)),
+        dev.running,
+        baseline.sessions.len()
+    );
+    for (title, list) in [
+        ("Missing", &dev.missing),
+        ("Not checked (host unreachable)", &dev.unchecked),
+        ("New since baseline", &dev.new),
+    ] {
+        if list.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("{title} ({}):\n", list.len()));
+        for s in list {
+            let who = s
+                .name
+                .clone()
+                .unwrap_or_else(|| short_thread_id(&s.thread_id));
+            out.push_str(&format!("  {}  {who}", s.host));
+            if let Some(cwd) = s.cwd.as_deref() {
+                out.push_str(&format!("  {cwd}"));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(rows: serde_json::Value, failed: &[&str]) -> Snapshot {
+        let errors: Vec<serde_json::Value> = failed
+            .iter()
+            .map(|h| serde_json::json!({"host": h, "error": "connect timed out"}))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "host": "all",
+            "generated_at_unix_s": 0,
+            "sessions": rows,
+            "host_errors": errors,
+        }))
+        .expect("snapshot")
+    }
+
+    fn row(host: &str, thread_id: &str) -> serde_json::Value {
+        serde_json::json!({"host": host, "thread_id": thread_id, "pids": [], "status": "working"})
+    }
+
+    #[test]
+    fn reports_missing_unchecked_and_new_sessions() {
+        let mut sub = row("local", "sub");
+        sub["subagent_parent_thread_id"] = "a".into();
+        let before = snap(
+            serde_json::json!([
+                row("local", "a"),
+                row("local", "b"),
+                row("devbox", "c"),
+                sub
+            ]),
+            &[],
+        );
+        let baseline = Baseline::from_snapshot(&before, &["all".into()], 100);
+        assert_eq!(baseline.sessions.len(), 3);
+
+        let after = snap(
+            serde_json::json!([row("local", "a"), row("local", "d")]),
+            &["devbox"],
+        );
+        let dev = baseline.compare(&after);
+        let ids = |l: &[BaselineSession]| -> Vec<String> {
+            l.iter().map(|s| s.thread_id.clone()).collect()
+        };
+        assert_eq!(dev.running, 1);
+        assert_eq!(ids(&dev.missing), vec!["b"]);
+        assert_eq!(ids(&dev.unchecked), vec!["c"]);
+        assert_eq!(ids(&dev.new), vec!["d"]);
+        let report = format_report(&baseline, &dev, 100 + 3600);
+        assert!(report.contains("1 of 3 session(s) running"), "{report}");
+        assert!(report.contains("Missing (1):\n  local  b\n"), "{report}");
+    }
+}
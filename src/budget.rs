@@ -0,0 +1,136 @@
+use serde::{Deserialize, Deserializer};
+
+use crate::model::SessionRow;
+use crate::timefmt::format_duration;
+
+/// `[budget]`: advisory per-session limits. A session past one is flagged OVERBUDGET and
+/// announced once (`over_budget` event and hook); nothing is killed.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BudgetConfig {
+    /// Cumulative tokens, from the rollout's latest `token_count`.
+    pub max_tokens_per_session: Option<u64>,
+    /// Time since the session started: seconds, or `90m`, `6h`, `1h30m`.
+    #[serde(default, deserialize_with = "de_duration")]
+    pub max_session_duration: Option<u64>,
+}
+
+impl BudgetConfig {
+    /// Why `row` is over budget at `now_unix_s`, or `None` while it is within every limit.
+    pub fn exceeded(&self, row: &SessionRow, now_unix_s: i64) -> Option<String> {
+        let mut over = Vec::new();
+        if let (Some(max), Some(n)) = (self.max_tokens_per_session, row.total_tokens) {
+            if n > max {
+                over.push(format!(
+                    "{} tokens > {}",
+                    format_tokens(n),
+                    format_tokens(max)
+                ));
+            }
+        }
+        if let (Some(max), Some(start)) = (self.max_session_duration, row.started_at_unix_s) {
+            let ran = now_unix_s.saturating_sub(start);
+            let max = i64::try_from(max).unwrap_or(i64::MAX);
+            if ran > max {
+                over.push(format!(
+                    "ran {} > {}",
+                    format_duration(ran),
+                    format_duration(max)
+                ));
+            }
+        }
+        (!over.is_empty()).then(|| over.join(", "))
+    }
+}
+
+fn format_tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1e6)
+    } else if n >= 1000 {
+        format!("{:.1}k", n as f64 / 1000.0)
+    } else {
+        n.to_string()
+    }
+}
+
+/// `21600`, `"6h"`, `"1h30m"`, `"45s"` → seconds.
+pub fn parse_duration_secs(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if let Ok(n) = text.parse() {
+        return Some(n);
+    }
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        let n: u64 = std::mem::take(&mut digits).parse().ok()?;
+        total = total.checked_add(n.checked_mul(unit)?)?;
+    }
+    digits.is_empty().then_some(total)
+}
+
+/// Serde helper for optional durations written as seconds or `"6h"`-style text.
+pub fn de_duration<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Secs(u64),
+        Text(String),
+    }
+    match Option::<Raw>::deserialize(d)? {
+        None => Ok(None),
+        Some(Raw::Secs(n)) => Ok(Some(n)),
+        Some(Raw::Text(t)) => parse_duration_secs(&t).map(Some).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "invalid duration {t:?} (expected seconds or e.g. \"6h\", \"1h30m\")"
+            ))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_flag_tokens_and_duration() {
+        let cfg: BudgetConfig =
+            toml::from_str("max_tokens_per_session = 1000000\nmax_session_duration = \"6h\"")
+                .expect("parse");
+        assert_eq!(cfg.max_session_duration, Some(6 * 3600));
+        let mut row: SessionRow = serde_json::from_value(serde_json::json!({
+            "host": "local",
+            "thread_id": "t",
+            "pids": [],
+            "status": "working",
+            "started_at_unix_s": 0,
+            "total_tokens": 900_000,
+        }))
+        .expect("row");
+        assert_eq!(cfg.exceeded(&row, 3600), None);
+        assert_eq!(
+            cfg.exceeded(&row, 6 * 3600 + 120).as_deref(),
+            Some("ran 6h02m > 6h00m")
+        );
+        row.total_tokens = Some(1_300_000);
+        assert_eq!(
+            cfg.exceeded(&row, 60).as_deref(),
+            Some("1.3M tokens > 1.0M")
+        );
+
+        assert_eq!(parse_duration_secs("1h30m"), Some(5400));
+        assert_eq!(parse_duration_secs("45"), Some(45));
+        assert_eq!(parse_duration_secs("6d"), None);
+        assert_eq!(parse_duration_secs("1h30"), None);
+        assert!(toml::from_str::<BudgetConfig>("max_session_duration = \"soon\"").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+/// What this build offers to a machine that runs it remotely (`codex-ps capabilities`). Add
+/// a name here with every new subcommand or flag other hosts rely on.
+pub const FEATURES: &[&str] = &[
+    "json",
+    "debug",
+    "annotate",
+    "labels",
+    "logs",
+    "show",
+    "capabilities",
+    "relay",
+    "kill",
+];
+
+/// Assumed for hosts whose codex-ps predates `capabilities`.
+const BASELINE: &[&str] = &["json", "debug", "annotate"];
+
+/// A host's `codex-ps capabilities` answer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// `None` for hosts too old to say.
+    pub version: Option<String>,
+    pub features: Vec<String>,
+}
+
+impl Capabilities {
+    pub fn current() -> Self {
+        Self {
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            features: FEATURES.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    pub fn baseline() -> Self {
+        Self {
+            version: None,
+            features: BASELINE.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    /// A host's answer; anything that isn't one (an older codex-ps printing something else)
+    /// means the baseline.
+    pub fn parse(out: &str) -> Self {
+        serde_json::from_str(out.trim()).unwrap_or_else(|_| Self::baseline())
+    }
+
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+}
+
+/// `codex-ps capabilities`: this build's version and features as JSON.
+pub fn run_capabilities() -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string(&Capabilities::current())?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_round_trip_and_anything_else_is_the_baseline() {
+        let current = Capabilities::current();
+        let out = serde_json::to_string(&current).expect("serialize");
+        assert_eq!(Capabilities::parse(&format!("{out}\n")), current);
+        assert!(current.supports("logs"));
+
+        let old = Capabilities::parse(r#"{"generated_at_unix_s":1,"sessions":[]}"#);
+        assert_eq!(old, Capabilities::baseline());
+        assert!(old.supports("annotate"));
+        assert!(!old.supports("logs"));
+        assert!(BASELINE.iter().all(|f| FEATURES.contains(f)));
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+
+use crate::model::SessionId;
+use crate::model::{HostError, SessionRow, SessionStatus, Snapshot};
+use crate::receipt::SessionReceipt;
+
+/// A session whose status differs from the previous snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatusChange {
+    pub key: SessionId,
+    pub from: SessionStatus,
+    pub to: SessionStatus,
+}
+
+/// A session went quiet with a completed turn after working for at least `LONG_TURN_SECS`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurnFinished {
+    pub key: SessionId,
+    /// How long codex-ps saw it working (lower bound: sessions found mid-turn count from
+    /// the first snapshot).
+    pub worked_secs: i64,
+    pub message: String,
+}
+
+/// Turns shorter than this aren't worth announcing as "finished".
+pub const LONG_TURN_SECS: i64 = 120;
+
+/// What changed between two consecutive snapshots, keyed by `(host, thread_id)`.
+///
+/// `added`/`status_changed` refer to rows in the new snapshot; `removed` carries the last
+/// known row since it is no longer in the snapshot. Only hosts that answered can remove
+/// sessions: rows of a host that failed or wasn't polled are carried over until it does.
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotChanges {
+    pub generation: u64,
+    /// First snapshot seen by this tracker: everything is "added".
+    pub initial: bool,
+    pub added: Vec<SessionId>,
+    pub removed: Vec<SessionRow>,
+    pub status_changed: Vec<StatusChange>,
+    /// Host errors that are new or whose kind/message changed.
+    pub host_errors: Vec<HostError>,
+    /// Hosts that had an error last time and are clean now.
+    pub hosts_recovered: Vec<String>,
+    /// Long turns that just completed (see `TurnFinished`).
+    pub finished: Vec<TurnFinished>,
+    /// Sessions that just went over a `[budget]` limit (or showed up already past one).
+    pub over_budget: Vec<SessionId>,
+    /// One receipt per `removed` session, in the same order; like `removed`, only for hosts
+    /// that answered.
+    pub receipts: Vec<SessionReceipt>,
+}
+
+impl SnapshotChanges {
+    pub fn receipt_for(&self, row: &SessionRow) -> Option<&SessionReceipt> {
+        self.receipts
+            .iter()
+            .find(|r| r.host == row.host && r.thread_id == row.thread_id)
+    }
+}
+
+/// Remembers the previous snapshot's sessions so every consumer sees the same diff.
+#[derive(Clone, Debug, Default)]
+pub struct ChangeTracker {
+    seen_any: bool,
+    prev: HashMap<SessionId, SessionRow>,
+    prev_host_errors: HashMap<String, HostError>,
+    working_since: HashMap<SessionId, i64>,
+    /// Long turns that stopped working but whose final message isn't in the tail yet.
+    awaiting_final: HashMap<SessionId, i64>,
+}
+
+impl ChangeTracker {
+    pub fn update(&mut self, snap: &Snapshot) -> SnapshotChanges {
+        let mut changes = SnapshotChanges {
+            generation: snap.generation,
+            initial: !self.seen_any,
+            ..Default::default()
+        };
+
+        let mut next: HashMap<SessionId, SessionRow> = HashMap::new();
+        for row in &snap.sessions {
+            let key = row.id();
+            let was_over = self
+                .prev
+                .get(&key)
+                .is_some_and(|old| old.over_budget.is_some());
+            if row.over_budget.is_some() && !was_over {
+                changes.over_budget.push(key.clone());
+            }
+            match self.prev.remove(&key) {
+                None => changes.added.push(key.clone()),
+                Some(old) if old.status != row.status => {
+                    changes.status_changed.push(StatusChange {
+                        key: key.clone(),
+                        from: old.status,
+                        to: row.status,
+                    })
+                }
+                Some(_) => {}
+            }
+            self.track_turn(&key, row, snap.generated_at_unix_s, &mut changes);
+            next.insert(key, row.clone());
+        }
+        for (key, old) in std::mem::take(&mut self.prev) {
+            if snap.answered_for(&old) {
+                changes.removed.push(old);
+            } else {
+                next.insert(key, old);
+            }
+        }
+        self.working_since.retain(|k, _| next.contains_key(k));
+        self.awaiting_final.retain(|k, _| next.contains_key(k));
+
+        changes
+            .removed
+            .sort_by(|a, b| (&a.host, &a.thread_id).cmp(&(&b.host, &b.thread_id)));
+        changes.receipts = changes
+            .removed
+            .iter()
+            .map(SessionReceipt::for_row)
+            .collect();
+
+        self.prev = next;
+
+        let mut errors: HashMap<String, HostError> = HashMap::new();
+        for e in snap.host_errors.as_deref().unwrap_or(&[]) {
+            let unchanged = self
+                .prev_host_errors
+                .get(&e.host)
+                .is_some_and(|old| old.kind == e.kind && old.error == e.error);
+            if !unchanged {
+                changes.host_errors.push(e.clone());
+            }
+            errors.insert(e.host.clone(), e.clone());
+        }
+        // A host left out of this cycle hasn't recovered, it just wasn't asked.
+        for (host, e) in std::mem::take(&mut self.prev_host_errors) {
+            if errors.contains_key(&host) {
+                continue;
+            }
+            if snap.host_collected_at.contains_key(&host) {
+                changes.hosts_recovered.push(host);
+            } else {
+                errors.insert(host, e);
+            }
+        }
+        changes.hosts_recovered.sort();
+        self.prev_host_errors = errors;
+
+        self.seen_any = true;
+        changes
+    }
+
+    /// Forget everything about hosts that are no longer polled, so disabling one doesn't report
+    /// its sessions as removed (or its errors as recovered) on the next update.
+    pub fn retain_hosts(&mut self, hosts: &[String]) {
+        let polled = |hop: &String| {
+            hosts
+                .iter()
+                .any(|h| h == hop || h.strip_prefix("via:") == Some(hop.as_str()))
+        };
+        self.prev.retain(|_, row| {
+            std::iter::once(&row.host)
+                .chain(&row.collected_via)
+                .any(polled)
+        });
+        let prev = &self.prev;
+        self.working_since.retain(|k, _| prev.contains_key(k));
+        self.awaiting_final.retain(|k, _| prev.contains_key(k));
+        self.prev_host_errors.retain(|host, _| polled(host));
+    }
+
+    /// The tail is only re-read once a rollout goes quiet, so the final message can show up a
+    /// tick after the status flips; a long turn waits in `awaiting_final` until it does.
+    fn track_turn(
+        &mut self,
+        key: &SessionId,
+        row: &SessionRow,
+        now: i64,
+        changes: &mut SnapshotChanges,
+    ) {
+        if row.status == SessionStatus::Working {
+            self.working_since.entry(key.clone()).or_insert(now);
+            self.awaiting_final.remove(key);
+            return;
+        }
+        if let Some(start) = self.working_since.remove(key) {
+            let worked = now.saturating_sub(start);
+            if worked >= LONG_TURN_SECS {
+                self.awaiting_final.insert(key.clone(), worked);
+            }
+        }
+        if let Some(message) = row.final_message.as_ref() {
+            if let Some(worked_secs) = self.awaiting_final.remove(key) {
+                changes.finished.push(TurnFinished {
+                    key: key.clone(),
+                    worked_secs,
+                    message: message.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(thread_id: &str, status: SessionStatus) -> SessionRow {
+        SessionRow {
+            host: "local".into(),
+            thread_id: thread_id.into(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    fn host_error(host: &str, message: &str) -> HostError {
+        HostError::new(
+            host,
+            crate::error::ErrorInfo::new(crate::error::ErrorKind::Timeout, message),
+        )
+    }
+
+    #[test]
+    fn tracks_host_errors_and_recoveries() {
+        let mut tracker = ChangeTracker::default();
+        let mut s = snap(1, Vec::new());
+        s.host_collected_at.insert("home".into(), 0);
+        s.host_errors = Some(vec![host_error("home", "timed out")]);
+        assert_eq!(tracker.update(&s).host_errors.len(), 1);
+        assert!(tracker.update(&s).host_errors.is_empty());
+
+        s.host_errors = Some(Vec::new());
+        assert_eq!(tracker.update(&s).hosts_recovered, vec!["home".to_string()]);
+    }
+
+    fn snap(generation: u64, sessions: Vec<SessionRow>) -> Snapshot {
+        Snapshot {
+            generation,
+            ..Snapshot::of(sessions)
+        }
+    }
+
+    #[test]
+    fn diffs_added_removed_and_status_changes() {
+        let mut tracker = ChangeTracker::default();
+        let first = tracker.update(&snap(
+            1,
+            vec![
+                row("a", SessionStatus::Working),
+                row("b", SessionStatus::Waiting),
+            ],
+        ));
+        assert!(first.initial);
+        assert_eq!(first.added.len(), 2);
+
+        let second = tracker.update(&snap(
+            2,
+            vec![
+                row("a", SessionStatus::Waiting),
+                row("c", SessionStatus::Working),
+            ],
+        ));
+        assert!(!second.initial);
+        assert_eq!(second.generation, 2);
+        assert_eq!(second.added[0].thread_id, "c");
+        assert_eq!(second.removed[0].thread_id, "b");
+        assert_eq!(
+            second.status_changed,
+            vec![StatusChange {
+                key: SessionId::new("local", "a"),
+                from: SessionStatus::Working,
+                to: SessionStatus::Waiting,
+            }]
+        );
+
+        let third = tracker.update(&snap(
+            3,
+            vec![
+                row("a", SessionStatus::Waiting),
+                row("c", SessionStatus::Working),
+            ],
+        ));
+        assert!(third.added.is_empty() && third.removed.is_empty());
+        assert!(third.status_changed.is_empty());
+    }
+
+    #[test]
+    fn announces_long_turn_once_final_message_arrives() {
+        let mut tracker = ChangeTracker::default();
+        let at = |t: i64, status: SessionStatus, msg: Option<&str>| {
+            let mut r = row("a", status);
+            r.final_message = msg.map(str::to_string);
+            let mut s = snap(0, vec![r]);
+            s.generated_at_unix_s = t;
+            s
+        };
+
+        tracker.update(&at(1000, SessionStatus::Working, None));
+        assert!(
+            tracker
+                .update(&at(1200, SessionStatus::Waiting, None))
+                .finished
+                .is_empty()
+        );
+        let done = tracker.update(&at(1201, SessionStatus::Waiting, Some("Done.")));
+        assert_eq!(done.finished.len(), 1);
+        assert_eq!(done.finished[0].worked_secs, 200);
+        assert_eq!(done.finished[0].message, "Done.");
+        assert!(
+            tracker
+                .update(&at(1202, SessionStatus::Waiting, Some("Done.")))
+                .finished
+                .is_empty()
+        );
+
+        // Short turns stay quiet.
+        tracker.update(&at(1300, SessionStatus::Working, None));
+        assert!(
+            tracker
+                .update(&at(1310, SessionStatus::Waiting, Some("ok")))
+                .finished
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn unreachable_host_keeps_its_sessions() {
+        let mut tracker = ChangeTracker::default();
+        let mut remote = row("r", SessionStatus::Working);
+        remote.host = "home".into();
+        let both = snap(1, vec![row("a", SessionStatus::Working), remote.clone()]);
+        tracker.update(&both);
+
+        let mut down = snap(2, vec![row("a", SessionStatus::Working)]);
+        down.host_errors = Some(vec![host_error("home", "timed out")]);
+        let changes = tracker.update(&down);
+        assert!(changes.removed.is_empty() && changes.receipts.is_empty());
+
+        // Not polled at all (e.g. a cycle that skipped remotes).
+        let skipped = snap(3, vec![row("a", SessionStatus::Working)]);
+        assert!(tracker.update(&skipped).removed.is_empty());
+
+        let back = tracker.update(&both);
+        assert!(back.added.is_empty() && back.removed.is_empty());
+        assert_eq!(back.hosts_recovered, vec!["home".to_string()]);
+
+        let mut home_only = snap(4, vec![row("a", SessionStatus::Working)]);
+        home_only.host_collected_at.insert("home".into(), 0);
+        assert_eq!(tracker.update(&home_only).removed[0].thread_id, "r");
+    }
+
+    #[test]
+    fn disabled_host_is_forgotten_quietly() {
+        let mut tracker = ChangeTracker::default();
+        let mut remote = row("r", SessionStatus::Working);
+        remote.host = "home".into();
+        let mut both = snap(1, vec![row("a", SessionStatus::Working), remote]);
+        both.host_errors = Some(vec![host_error("home", "timed out")]);
+        tracker.update(&both);
+
+        tracker.retain_hosts(&["local".to_string()]);
+        let mut local = snap(2, vec![row("a", SessionStatus::Working)]);
+        local.host_collected_at.insert("home".into(), 0);
+        let changes = tracker.update(&local);
+        assert!(changes.removed.is_empty() && changes.receipts.is_empty());
+        assert!(changes.hosts_recovered.is_empty());
+    }
+}
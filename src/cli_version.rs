@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::util::run_cmd_with_timeout;
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+
+static VERSION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\d+\.\d+\.\d+(?:-[0-9A-Za-z.]+)?").expect("version regex"));
+
+/// Codex CLI version per executable: read from the path when it carries one (Homebrew's
+/// `Cellar/codex/0.46.0/`, a Nix store path), else `<exe> --version`, run once per
+/// binary (path and mtime, so an upgrade in place is noticed).
+#[derive(Debug, Default)]
+pub struct CliVersions {
+    by_exe: HashMap<PathBuf, (Option<SystemTime>, Option<String>)>,
+}
+
+impl CliVersions {
+    /// `None` for executables that aren't obviously codex (a `node` wrapper) and for
+    /// binaries replaced since the process started, whose `--version` would say the new one.
+    pub fn version_for(&mut self, exe: &Path) -> Option<String> {
+        let text = exe.to_string_lossy();
+        if !exe.is_absolute() || !text.contains("codex") || text.ends_with(" (deleted)") {
+            return None;
+        }
+        if let Some(v) = version_in_path(exe) {
+            return Some(v);
+        }
+        let mtime = std::fs::metadata(exe).and_then(|m| m.modified()).ok();
+        if let Some((seen, v)) = self.by_exe.get(exe) {
+            if *seen == mtime {
+                return v.clone();
+            }
+        }
+        let mut cmd = Command::new(exe);
+        cmd.arg("--version");
+        let v = run_cmd_with_timeout(cmd, VERSION_TIMEOUT)
+            .ok()
+            .filter(|out| out.status.success())
+            .and_then(|out| parse_version(&String::from_utf8_lossy(&out.stdout)));
+        self.by_exe.insert(exe.to_path_buf(), (mtime, v.clone()));
+        v
+    }
+}
+
+/// The version in a directory name of `exe`'s path (`codex-0.46.0`, `codex/0.46.0`).
+fn version_in_path(exe: &Path) -> Option<String> {
+    exe.parent()?
+        .components()
+        .rev()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .find_map(|c| {
+            let v = parse_version(&c)?;
+            (c == v || c.ends_with(&format!("codex-{v}"))).then_some(v)
+        })
+}
+
+/// Where a codex executable was installed from, by its path: `homebrew`, `npm-global`,
+/// `cargo-install`, `nix`, `custom build` (a cargo `target/` dir), `system` or `other`.
+pub fn install_source(exe: &str) -> &'static str {
+    let has = |s: &str| exe.contains(s);
+    if has("/Cellar/") || exe.starts_with("/opt/homebrew/") || has("/.linuxbrew/") {
+        "homebrew"
+    } else if has("/node_modules/") {
+        "npm-global"
+    } else if has("/.cargo/bin/") {
+        "cargo-install"
+    } else if exe.starts_with("/nix/store/") {
+        "nix"
+    } else if has("/target/release/") || has("/target/debug/") {
+        "custom build"
+    } else if exe.starts_with("/usr/bin/") || exe.starts_with("/bin/") {
+        "system"
+    } else {
+        "other"
+    }
+}
+
+/// `codex-cli 0.46.0` → `0.46.0`.
+pub fn parse_version(text: &str) -> Option<String> {
+    VERSION.find(text).map(|m| m.as_str().to_string())
+}
+
+/// Orders versions by their numeric parts (`0.9.1` < `0.10.0`); pre-releases sort with
+/// their release.
+pub fn version_key(v: &str) -> Vec<u64> {
+    v.split(['.', '-']).map_while(|p| p.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_from_paths_and_output() {
+        assert_eq!(
+            version_in_path(Path::new("/opt/homebrew/Cellar/codex/0.46.0/bin/codex")).as_deref(),
+            Some("0.46.0")
+        );
+        assert_eq!(
+            version_in_path(Path::new("/nix/store/abc123-codex-0.47.1/bin/codex")).as_deref(),
+            Some("0.47.1")
+        );
+        assert_eq!(version_in_path(Path::new("/usr/local/bin/codex")), None);
+        assert_eq!(
+            parse_version("codex-cli 0.46.0-alpha.2\n").as_deref(),
+            Some("0.46.0-alpha.2")
+        );
+        assert!(version_key("0.9.1") < version_key("0.10.0"));
+
+        for (exe, source) in [
+            ("/opt/homebrew/bin/codex", "homebrew"),
+            (
+                "/usr/lib/node_modules/@openai/codex/vendor/x86_64-unknown-linux-musl/codex/codex",
+                "npm-global",
+            ),
+            ("/home/me/.cargo/bin/codex", "cargo-install"),
+            (
+                "/home/me/src/codex/codex-rs/target/release/codex",
+                "custom build",
+            ),
+            ("/usr/local/bin/codex", "other"),
+        ] {
+            assert_eq!(install_source(exe), source, "{exe}");
+        }
+
+        let mut versions = CliVersions::default();
+        assert_eq!(versions.version_for(Path::new("/usr/bin/node")), None);
+        assert_eq!(
+            versions.version_for(Path::new("/usr/bin/codex (deleted)")),
+            None
+        );
+        assert!(versions.by_exe.is_empty());
+    }
+}
@@ -1,27 +1,74 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::Context;
 
 #[derive(Clone, Debug)]
 pub struct CodexHome {
-    pub root: std::path::PathBuf,
+    pub root: PathBuf,
+    /// `root` with symlinks resolved. Process tables (lsof, /proc) report canonical paths, so
+    /// a symlinked CODEX_HOME (dotfile managers) would otherwise match no rollouts at all.
+    pub canonical: PathBuf,
 }
 
 impl CodexHome {
-    pub fn resolve(override_path: Option<std::path::PathBuf>) -> anyhow::Result<Self> {
+    pub fn resolve(override_path: Option<PathBuf>) -> anyhow::Result<Self> {
         if let Some(p) = override_path {
-            return Ok(Self { root: p });
+            return Ok(Self::at(p));
         }
 
         if let Ok(env) = std::env::var("CODEX_HOME") {
             if !env.trim().is_empty() {
-                return Ok(Self {
-                    root: std::path::PathBuf::from(env),
-                });
+                return Ok(Self::at(PathBuf::from(env)));
             }
         }
 
         let home = dirs::home_dir().context("resolve home dir (needed for ~/.codex)")?;
-        Ok(Self {
-            root: home.join(".codex"),
-        })
+        Ok(Self::at(home.join(".codex")))
+    }
+
+    pub fn at(root: PathBuf) -> Self {
+        // A missing CODEX_HOME is not fatal here (zero sessions); keep the literal path.
+        let canonical = std::fs::canonicalize(&root).unwrap_or_else(|_| root.clone());
+        Self { root, canonical }
+    }
+
+    /// True when `root` and `canonical` differ (symlink or relative path).
+    pub fn is_relocated(&self) -> bool {
+        self.root != self.canonical
+    }
+
+    /// Whether `path` lives under this CODEX_HOME, via either spelling. Falls back to
+    /// canonicalizing `path` itself, so only call this for rollout-shaped candidates.
+    pub fn contains(&self, path: &Path) -> bool {
+        if path.starts_with(&self.canonical) || path.starts_with(&self.root) {
+            return true;
+        }
+        std::fs::canonicalize(path).is_ok_and(|p| p.starts_with(&self.canonical))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_home_matches_canonical_rollout_paths() {
+        let dir = TempDir::new().expect("tempdir");
+        let real = dir.path().join("real-codex");
+        std::fs::create_dir_all(real.join("sessions")).expect("mkdir");
+        let link = dir.path().join("codex-link");
+        std::os::unix::fs::symlink(&real, &link).expect("symlink");
+
+        let home = CodexHome::at(link.clone());
+        assert!(home.is_relocated());
+
+        let canonical_rollout = std::fs::canonicalize(&real)
+            .expect("canonicalize")
+            .join("sessions/rollout-x.jsonl");
+        assert!(home.contains(&canonical_rollout));
+        assert!(home.contains(&link.join("sessions/rollout-x.jsonl")));
+        assert!(!home.contains(Path::new("/elsewhere/rollout-x.jsonl")));
     }
 }
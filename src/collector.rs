@@ -1,90 +1,462 @@
-use std::collections::HashMap;
-use std::time::{Duration, SystemTime};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::Context;
 
+use crate::annotations::{Annotation, AnnotationsStore};
+use crate::audit::AuditRecord;
+use crate::budget::BudgetConfig;
+use crate::capabilities::Capabilities;
+use crate::cli_version::CliVersions;
 use crate::codex_home::CodexHome;
-use crate::discovery::{extract_thread_id_from_rollout_path, lsof_codex_processes};
+use crate::discovery::{CodexProcess, ProcessDiscovery, ThreadIdPatterns};
+use crate::error::{ErrorInfo, ErrorKind};
+use crate::eta::CallDurations;
 use crate::git::GitCache;
-use crate::model::{HostError, SessionBuilder, SessionDebug, SessionRow, SessionStatus, Snapshot};
-use crate::names::{NamesStore, SessionNameKey};
+use crate::health::{CacheSizes, Health, HealthTracker};
+use crate::history::TitleHistory;
+use crate::lineage::ThreadAliases;
+use crate::model::{
+    CallEta, HostError, SessionBuilder, SessionDebug, SessionId, SessionRow, SessionStatus,
+    Snapshot, StatusReasonCode, dedupe_sessions, sort_sessions,
+};
+use crate::mounts::detect_network_fs;
+use crate::names::{NamesStore, normalize_name_opt};
+use crate::policy::PolicyEngine;
 use crate::rollout::{
-    PendingFunctionCall, read_pending_function_call_from_tail, read_session_meta,
+    PendingFunctionCall, TailHints, read_first_user_message, read_session_meta, read_tail_hints,
 };
+use crate::suggest::suggest_name;
+use crate::sync::LabelSet;
+use crate::timefmt::to_iso8601_utc;
 use crate::titles::TitleResolver;
-use crate::util::{system_time_to_unix_s, truncate_middle};
+use crate::transport::{HostTransport, RemoteCommandFailed};
+use crate::usage::UsageLog;
+use crate::util::{CancelToken, system_time_to_unix_s, truncate_middle};
 
 const STATUS_WORKING_MAX_AGE_SECS: u64 = 15;
 const STATUS_UNCERTAIN_MAX_AGE_SECS: u64 = 60;
 const STATUS_MAX_FUTURE_MTIME_SKEW_SECS: u64 = 2;
 const ROLLOUT_TAIL_MAX_BYTES: u64 = 512 * 1024;
+/// How far back the RATE column looks.
+const TOKEN_RATE_WINDOW_SECS: i64 = 300;
+/// How far into a rollout to look for the first prompt (name suggestions).
+const FIRST_PROMPT_MAX_BYTES: u64 = 256 * 1024;
+/// On network filesystems mtimes can be stale for several seconds (attribute caching), so
+/// the tail is re-read on this cadence instead of only when mtime changes.
+const NETWORK_FS_TAIL_REFRESH_SECS: u64 = 5;
+/// Permission errors rarely resolve by themselves; don't hammer unreadable rollouts every tick.
+const UNREADABLE_ROLLOUT_RETRY_SECS: u64 = 60;
 
 pub struct Collector {
     codex_home: CodexHome,
+    discovery: Box<dyn ProcessDiscovery>,
     titles: TitleResolver,
     names: NamesStore,
+    annotations: AnnotationsStore,
+    title_history: TitleHistory,
+    /// Forks → the thread they came from, so names and tags carry over.
+    thread_aliases: ThreadAliases,
+    usage: UsageLog,
+    call_durations: CallDurations,
     git_cache: GitCache,
-    ssh_bin: String,
+    transport: Box<dyn HostTransport>,
     remote_bin: String,
-    ssh_timeout: Duration,
     rollout_tail_cache: HashMap<std::path::PathBuf, TailCacheEntry>,
+    /// Local sessions from the last discovery pass, so one row can be rebuilt on demand.
+    local_builders: HashMap<String, SessionBuilder>,
+    unreadable_rollouts: HashMap<std::path::PathBuf, (Instant, ErrorInfo)>,
+    /// Filesystem type when CODEX_HOME is on a network mount (NFS/SMB/...).
+    network_fs: Option<String>,
+    thresholds: StatusThresholds,
+    cancel: CancelToken,
+    /// When the last `collect_progressive` started (unix seconds); hosts collected before
+    /// that were reused from an earlier cycle.
+    cycle_started_unix_s: i64,
+    generation: u64,
+    /// Last result per remote host and when it was fetched (unix seconds), reused when
+    /// remote polling is suspended.
+    remote_cache: HashMap<String, (i64, Result<Snapshot, ErrorInfo>)>,
+    health: HealthTracker,
+    /// Last cycle's host errors, so a host that keeps failing is reported once with a count.
+    last_host_errors: Vec<HostError>,
+    /// Probe each local session's current branch (it can change mid-session).
+    probe_branch: bool,
+    /// `[host_labels]` from the config.
+    host_labels: BTreeMap<String, String>,
+    /// Propose names for unnamed local sessions (`suggest_names` in the config).
+    suggest_names: bool,
+    /// Suggested name per local thread id, once its first prompt has been read.
+    suggestions: HashMap<String, String>,
+    /// Latest `token_count` readings per local thread id, kept while tail reads are skipped.
+    token_readings: HashMap<String, Vec<(i64, u64)>>,
+    /// How thread ids are read from rollout file names (`rollout_id_patterns` in the config).
+    thread_id_patterns: ThreadIdPatterns,
+    /// `session_meta.id` per rollout path, which wins over the file name's id.
+    meta_ids: HashMap<std::path::PathBuf, Option<String>>,
+    /// What each remote host's codex-ps supports, probed the first time it matters.
+    capabilities: HashMap<String, Capabilities>,
+    /// `via:` hosts already relaying this request (`--relay-chain`), to refuse loops.
+    relay_chain: Vec<String>,
+    /// The `via:` host each relayed origin was last seen through.
+    relay_routes: HashMap<String, String>,
+    /// Set when running sandboxed without `--proc-root`, to explain an empty process list.
+    sandbox: Option<&'static str>,
+    /// `[budget]` limits each row is checked against.
+    budget: BudgetConfig,
+    /// `[policy]` rules, when any are configured.
+    policy: Option<PolicyEngine>,
+    /// Codex CLI version per executable, probed once per binary.
+    cli_versions: CliVersions,
 }
 
 #[derive(Clone, Debug)]
 struct TailCacheEntry {
     mtime: Option<SystemTime>,
-    parsed_for_mtime: bool,
-    pending_call: Option<PendingFunctionCall>,
+    parsed_at: Option<Instant>,
+    hints: TailHints,
+}
+
+/// Age windows for the mtime/timestamp fallback in `classify_status`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatusThresholds {
+    pub working_max_age: Duration,
+    pub uncertain_max_age: Duration,
+    pub max_future_skew: Duration,
+}
+
+impl Default for StatusThresholds {
+    fn default() -> Self {
+        Self {
+            working_max_age: Duration::from_secs(STATUS_WORKING_MAX_AGE_SECS),
+            uncertain_max_age: Duration::from_secs(STATUS_UNCERTAIN_MAX_AGE_SECS),
+            max_future_skew: Duration::from_secs(STATUS_MAX_FUTURE_MTIME_SKEW_SECS),
+        }
+    }
+}
+
+impl StatusThresholds {
+    /// Network mounts: coarse/cached mtimes and server clock skew.
+    fn network() -> Self {
+        Self {
+            working_max_age: Duration::from_secs(STATUS_WORKING_MAX_AGE_SECS * 3),
+            uncertain_max_age: Duration::from_secs(STATUS_UNCERTAIN_MAX_AGE_SECS * 3),
+            max_future_skew: Duration::from_secs(30),
+        }
+    }
+
+    /// The user's windows in place of the built-in (or network-mount) ones. Raising only the
+    /// working window raises the uncertain one with it.
+    pub fn with_overrides(
+        mut self,
+        working: Option<Duration>,
+        uncertain: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        if let Some(w) = working {
+            self.working_max_age = w;
+            self.uncertain_max_age = self.uncertain_max_age.max(w);
+        }
+        if let Some(u) = uncertain {
+            if u < self.working_max_age {
+                anyhow::bail!(
+                    "uncertain window ({}s) is shorter than the working window ({}s)",
+                    u.as_secs(),
+                    self.working_max_age.as_secs()
+                );
+            }
+            self.uncertain_max_age = u;
+        }
+        Ok(self)
+    }
 }
 
 impl Collector {
     pub fn new(
         codex_home: CodexHome,
-        ssh_bin: String,
+        discovery: Box<dyn ProcessDiscovery>,
+        transport: Box<dyn HostTransport>,
         remote_bin: String,
-        ssh_timeout: Duration,
     ) -> anyhow::Result<Self> {
+        let network_fs = detect_network_fs(&codex_home.canonical);
+        let thresholds = if network_fs.is_some() {
+            StatusThresholds::network()
+        } else {
+            StatusThresholds::default()
+        };
         Ok(Self {
             titles: TitleResolver::new(&codex_home.root),
             names: NamesStore::new()?,
+            annotations: AnnotationsStore::new()?,
+            title_history: TitleHistory::new()?,
+            thread_aliases: ThreadAliases::new()?,
+            usage: UsageLog::new()?,
+            call_durations: CallDurations::new()?,
             git_cache: GitCache::new(Duration::from_secs(5)),
             codex_home,
-            ssh_bin,
+            discovery,
+            transport,
             remote_bin,
-            ssh_timeout,
             rollout_tail_cache: HashMap::new(),
+            local_builders: HashMap::new(),
+            unreadable_rollouts: HashMap::new(),
+            network_fs,
+            thresholds,
+            cancel: CancelToken::default(),
+            cycle_started_unix_s: 0,
+            generation: 0,
+            remote_cache: HashMap::new(),
+            health: HealthTracker::default(),
+            last_host_errors: Vec::new(),
+            host_labels: BTreeMap::new(),
+            suggest_names: false,
+            suggestions: HashMap::new(),
+            token_readings: HashMap::new(),
+            thread_id_patterns: ThreadIdPatterns::default(),
+            meta_ids: HashMap::new(),
+            capabilities: HashMap::new(),
+            relay_chain: Vec::new(),
+            relay_routes: HashMap::new(),
+            sandbox: None,
+            budget: BudgetConfig::default(),
+            policy: None,
+            cli_versions: CliVersions::default(),
+            probe_branch: false,
         })
     }
 
-    pub fn collect(&mut self, hosts: &[String], debug: bool) -> anyhow::Result<Snapshot> {
+    /// Self-health: cycle timings, per-host error counts, cache sizes, memory.
+    pub fn health(&self) -> Health {
+        self.health.report(CacheSizes {
+            rollout_tails: self.rollout_tail_cache.len(),
+            local_sessions: self.local_builders.len(),
+            remote_hosts: self.remote_cache.len(),
+            git_repos: self.git_cache.len(),
+        })
+    }
+
+    /// See `StatusThresholds::with_overrides`.
+    pub fn set_status_thresholds(
+        &mut self,
+        working: Option<Duration>,
+        uncertain: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        self.thresholds = self.thresholds.with_overrides(working, uncertain)?;
+        Ok(())
+    }
+
+    pub fn set_probe_branch(&mut self, on: bool) {
+        self.probe_branch = on;
+    }
+
+    pub fn set_host_labels(&mut self, labels: BTreeMap<String, String>) {
+        self.host_labels = labels;
+    }
+
+    pub fn set_suggest_names(&mut self, on: bool) {
+        self.suggest_names = on;
+    }
+
+    pub fn set_relay_chain(&mut self, chain: Vec<String>) {
+        self.relay_chain = chain;
+    }
+
+    pub fn set_sandbox(&mut self, sandbox: Option<&'static str>) {
+        self.sandbox = sandbox;
+    }
+
+    pub fn set_budget(&mut self, budget: BudgetConfig) {
+        self.budget = budget;
+    }
+
+    pub fn set_policy(&mut self, policy: PolicyEngine) {
+        self.policy = Some(policy);
+    }
+
+    /// Run `[policy]` over a snapshot from `collect`, signalling the sessions it acts on.
+    /// Rows reused from an earlier cycle (remote polling suspended) are left alone: their
+    /// activity times are as old as the poll. Fails only when the audit log can't be written.
+    pub fn enforce_policy(&mut self, snap: &Snapshot) -> anyhow::Result<Vec<AuditRecord>> {
+        let Some(mut policy) = self.policy.take() else {
+            return Ok(Vec::new());
+        };
+        let now = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+        let since = self.cycle_started_unix_s;
+        let records = policy.apply(
+            &snap.sessions,
+            now,
+            |row| snap.collected_at_for(row).is_some_and(|at| at >= since),
+            |row, signal| self.signal_session(&row.id(), &row.pids, signal),
+        );
+        self.policy = Some(policy);
+        records
+    }
+
+    pub fn set_thread_id_patterns(&mut self, patterns: ThreadIdPatterns) {
+        self.thread_id_patterns = patterns;
+    }
+
+    /// The thread id of a local rollout: its `session_meta.id`, or else the one in its name.
+    pub fn thread_id_for_rollout(&mut self, path: &std::path::Path) -> Option<String> {
+        let meta_id = self.meta_id(path);
+        self.thread_id_patterns
+            .resolve(path, meta_id.as_deref())
+            .map(|ids| ids.thread_id)
+    }
+
+    /// A rollout's `session_meta.id`. The first line never changes, so it is read once (a file
+    /// whose first line isn't written yet is tried again next time).
+    fn meta_id(&mut self, path: &std::path::Path) -> Option<String> {
+        if let Some(id) = self.meta_ids.get(path) {
+            return id.clone();
+        }
+        let id = read_session_meta(path).ok()?.id;
+        self.meta_ids.insert(path.to_path_buf(), id.clone());
+        id
+    }
+
+    pub fn codex_home(&self) -> &CodexHome {
+        &self.codex_home
+    }
+
+    /// The discovery backend's view of running Codex processes, without building rows.
+    pub fn discover_processes(&self) -> anyhow::Result<Vec<CodexProcess>> {
+        self.discovery
+            .discover(&self.codex_home)
+            .with_context(|| format!("discovery backend: {}", self.discovery.name()))
+    }
+
+    /// Firing this aborts in-flight remote commands and skips hosts not yet queried.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Rows always carry their `debug` detail and warnings include verbose ones; callers not
+    /// in debug mode drop both with `Snapshot::strip_debug`.
+    pub fn collect(&mut self, hosts: &[String]) -> anyhow::Result<Snapshot> {
+        self.collect_with_remotes(hosts, true)
+    }
+
+    /// `poll_remote = false` refreshes local sessions only and reuses each remote host's last
+    /// result, leaving out hosts never polled (e.g. while the terminal is unfocused).
+    pub fn collect_with_remotes(
+        &mut self,
+        hosts: &[String],
+        poll_remote: bool,
+    ) -> anyhow::Result<Snapshot> {
+        self.collect_progressive(hosts, poll_remote, &mut |_| {}, &mut |_| {})
+    }
+
+    /// Like `collect_with_remotes`, but hands `on_partial` a provisional snapshot each time a
+    /// host finishes while others are still being polled (local first). Hosts not done yet
+    /// keep their previous rows. Partials don't bump the generation or touch the history/usage
+    /// logs; only the returned snapshot is authoritative. `between_hosts` runs before each
+    /// remote host is polled, so the caller can act on the collector (renames, kills) without
+    /// waiting for the whole cycle.
+    pub fn collect_progressive(
+        &mut self,
+        hosts: &[String],
+        poll_remote: bool,
+        on_partial: &mut dyn FnMut(Snapshot),
+        between_hosts: &mut dyn FnMut(&mut Collector),
+    ) -> anyhow::Result<Snapshot> {
+        let cycle_started = Instant::now();
+        self.cycle_started_unix_s = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
         // Always include at least local.
         let mut host_list = hosts.to_vec();
         if host_list.is_empty() {
             host_list.push("local".into());
         }
 
-        let mut warnings: Vec<String> = Vec::new();
+        let mut warnings: Vec<ErrorInfo> = Vec::new();
         let mut host_errors: Vec<HostError> = Vec::new();
         let mut sessions: Vec<SessionRow> = Vec::new();
+        let mut collected_at: BTreeMap<String, i64> = BTreeMap::new();
+
+        if let Err(e) = self.names.refresh_if_changed() {
+            // Keep the kind stable so the UI can find this warning; the root cause
+            // (permission, parse, ...) stays in the message.
+            let mut info = ErrorInfo::from_anyhow(ErrorKind::NamesStore, &e);
+            info.kind = ErrorKind::NamesStore;
+            info.context
+                .insert(0, format!("names store ({})", self.names.path().display()));
+            warnings.push(info.verbose());
+        }
+        if let Err(e) = self.annotations.refresh_if_changed() {
+            let mut info = ErrorInfo::from_anyhow(ErrorKind::NamesStore, &e);
+            info.kind = ErrorKind::NamesStore;
+            info.context.insert(
+                0,
+                format!("annotations store ({})", self.annotations.path().display()),
+            );
+            warnings.push(info.verbose());
+        }
+
+        let remotes: Vec<String> = host_list
+            .iter()
+            .filter(|h| *h != "local")
+            .cloned()
+            .collect();
+        // Partials are only worth sending while some host is actually being polled.
+        let emit_partials = poll_remote && !remotes.is_empty();
 
         if host_list.iter().any(|h| h == "local") {
-            match self.collect_local_rows(debug) {
+            match self.collect_local_rows() {
                 Ok((mut rows, mut local_warnings)) => {
                     sessions.append(&mut rows);
                     warnings.append(&mut local_warnings);
                 }
-                Err(e) => host_errors.push(HostError {
-                    host: "local".into(),
-                    error: format!("{e}"),
-                }),
+                Err(e) => host_errors.push(HostError::new(
+                    "local",
+                    ErrorInfo::from_anyhow(ErrorKind::Discovery, &e),
+                )),
             }
+            collected_at.insert(
+                "local".into(),
+                system_time_to_unix_s(SystemTime::now()).unwrap_or(0),
+            );
         }
 
-        for host in host_list.iter().filter(|h| *h != "local") {
-            match self.collect_remote_host(host, debug) {
+        for (i, host) in remotes.iter().enumerate() {
+            if emit_partials {
+                on_partial(self.partial_snapshot(
+                    &host_list,
+                    &sessions,
+                    &host_errors,
+                    &collected_at,
+                    &remotes[i..],
+                ));
+            }
+            if self.cancel.is_cancelled() {
+                anyhow::bail!("collection cancelled");
+            }
+            between_hosts(self);
+            let cached = self.remote_cache.get(host).filter(|_| !poll_remote);
+            let (fetched_at, result) = match cached {
+                Some(r) => r.clone(),
+                // Never polled and not allowed to: leave the host out rather than reach it.
+                None if !poll_remote => continue,
+                None => {
+                    let r = match relay_target(host) {
+                        Some(target) => self
+                            .require_feature(target, "relay")
+                            .and_then(|()| self.collect_remote_host(host)),
+                        None => self.collect_remote_host(host),
+                    }
+                    .map_err(|e| ErrorInfo::from_anyhow(ErrorKind::Transport, &e));
+                    let at = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+                    self.remote_cache.insert(host.clone(), (at, r.clone()));
+                    (at, r)
+                }
+            };
+            collected_at.insert(host.clone(), fetched_at);
+            match result {
                 Ok(mut snap) => {
-                    for row in &mut snap.sessions {
-                        row.host = host.clone();
+                    let name = relay_target(host).unwrap_or(host);
+                    snap.relabel_host(name);
+                    if name != host {
+                        for row in snap.sessions.iter().filter(|r| r.host != name) {
+                            self.relay_routes.insert(row.host.clone(), host.clone());
+                        }
                     }
                     sessions.extend(snap.sessions);
                     if let Some(mut w) = snap.warnings.take() {
@@ -94,83 +466,482 @@ impl Collector {
                         host_errors.append(&mut he);
                     }
                 }
-                Err(e) => host_errors.push(HostError {
-                    host: host.clone(),
-                    error: format!("{e}"),
-                }),
+                Err(info) => host_errors.push(HostError::new(host.clone(), info)),
             }
         }
 
-        if let Err(e) = self.names.refresh_if_changed() {
-            if debug {
-                warnings.push(format!(
-                    "names store ({}): {e}",
-                    self.names.path().display()
-                ));
-            }
+        // A remote that aggregates other hosts (or a jump collector) may relay rows that were
+        // also collected directly.
+        let mut sessions = dedupe_sessions(sessions);
+        let now_s = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+        if let Err(e) = self.thread_aliases.observe(&sessions) {
+            let mut info = ErrorInfo::from_anyhow(ErrorKind::Io, &e);
+            info.context.insert(
+                0,
+                format!("thread aliases ({})", self.thread_aliases.path().display()),
+            );
+            warnings.push(info.verbose());
         }
+        let mut history_error: Option<anyhow::Error> = None;
         for row in &mut sessions {
-            let key = SessionNameKey {
-                host: row.host.clone(),
-                thread_id: row.thread_id.clone(),
-            };
-            row.name = self.names.get_cached(&key).map(|s| s.to_string());
+            if let Some(title) = row.title.as_deref() {
+                let key = row.id();
+                if let Err(e) = self.title_history.observe(&key, title, now_s) {
+                    history_error.get_or_insert(e);
+                }
+            }
+            self.label_row(row);
+            row.over_budget = self.budget.exceeded(row, now_s);
         }
 
-        let now = SystemTime::now();
-        sessions.sort_by(|a, b| {
-            let a_ts = a.last_activity_unix_s.unwrap_or(i64::MIN);
-            let b_ts = b.last_activity_unix_s.unwrap_or(i64::MIN);
-            b_ts.cmp(&a_ts)
-                .then_with(|| a.host.cmp(&b.host))
-                .then_with(|| a.thread_id.cmp(&b.thread_id))
-        });
+        if let Some(e) = history_error {
+            let mut info = ErrorInfo::from_anyhow(ErrorKind::Io, &e);
+            info.context.insert(
+                0,
+                format!("title history ({})", self.title_history.path().display()),
+            );
+            warnings.push(info.verbose());
+        }
+        if let Err(e) = self
+            .usage
+            .record(&host_list, &sessions, &host_errors, now_s)
+        {
+            let mut info = ErrorInfo::from_anyhow(ErrorKind::Io, &e);
+            info.context
+                .insert(0, format!("usage log ({})", self.usage.path().display()));
+            warnings.push(info.verbose());
+        }
 
+        sort_sessions(&mut sessions);
+        self.health
+            .record_cycle(&host_list, &host_errors, cycle_started.elapsed());
+        let generated_at_unix_s = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+        let host_errors =
+            fold_repeated_host_errors(&self.last_host_errors, host_errors, generated_at_unix_s);
+        self.last_host_errors = host_errors.clone();
+        self.generation += 1;
         Ok(Snapshot {
-            generated_at_unix_s: system_time_to_unix_s(now).unwrap_or(0),
+            generation: self.generation,
+            generated_at_unix_s,
+            generated_at: to_iso8601_utc(generated_at_unix_s),
             host: host_list.join(","),
+            host_collected_at: collected_at,
             sessions,
             host_errors: Some(host_errors),
             warnings: Some(warnings),
         })
     }
 
+    /// What is known mid-cycle: fresh rows for finished hosts, last cycle's rows for `pending`.
+    fn partial_snapshot(
+        &self,
+        host_list: &[String],
+        done: &[SessionRow],
+        host_errors: &[HostError],
+        collected_at: &BTreeMap<String, i64>,
+        pending: &[String],
+    ) -> Snapshot {
+        let mut sessions = done.to_vec();
+        let mut collected_at = collected_at.clone();
+        for host in pending {
+            if let Some((at, Ok(snap))) = self.remote_cache.get(host) {
+                sessions.extend(snap.sessions.iter().cloned().map(|mut r| {
+                    r.relay_through(relay_target(host).unwrap_or(host));
+                    r
+                }));
+                collected_at.insert(host.clone(), *at);
+            }
+        }
+        for row in &mut sessions {
+            self.label_row(row);
+        }
+        sort_sessions(&mut sessions);
+
+        let generated_at_unix_s = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+        Snapshot {
+            generation: self.generation,
+            generated_at_unix_s,
+            generated_at: to_iso8601_utc(generated_at_unix_s),
+            host: host_list.join(","),
+            host_collected_at: collected_at,
+            sessions,
+            host_errors: Some(fold_repeated_host_errors(
+                &self.last_host_errors,
+                host_errors.to_vec(),
+                generated_at_unix_s,
+            )),
+            warnings: Some(Vec::new()),
+        }
+    }
+
+    /// Attach local-only state (name, tags/note, previous title) to a collected row.
+    fn label_row(&self, row: &mut SessionRow) {
+        // State saved under an alias (the id a renamed rollout used to have) still applies, then
+        // whatever the session was forked from, nearest first.
+        let key = row.id();
+        let keys: Vec<SessionId> = std::iter::once(key.clone())
+            .chain(row.alias_ids())
+            .chain(self.thread_aliases.ancestors(&key))
+            .collect();
+        // A local entry wins; otherwise keep the name a remote host reported for its own session.
+        row.name = keys
+            .iter()
+            .find_map(|k| self.names.get_cached(k))
+            .map(|s| s.to_string())
+            .or(row.name.take());
+        self.apply_annotation(row, &keys);
+        row.previous_title = keys
+            .iter()
+            .find_map(|k| self.title_history.previous_title(k))
+            .map(str::to_string);
+        row.host_label = self.host_labels.get(&row.host).cloned();
+        if row.name.is_some() {
+            row.suggested_name = None;
+        }
+        // Recompute locally so remote rows from older versions get it too.
+        row.last_activity_at = row.last_activity_unix_s.and_then(to_iso8601_utc);
+    }
+
+    /// Rebuild one session immediately (re-stat, re-tail, re-probe git) without a full
+    /// collection cycle. Remote sessions re-query only their own host.
+    ///
+    /// `Ok(None)` means the session is no longer known; the next full refresh reconciles it.
+    pub fn refresh_session(&mut self, key: &SessionId) -> anyhow::Result<Option<SessionRow>> {
+        let mut row = if key.host == "local" {
+            let Some(b) = self.local_builders.get(&key.thread_id).cloned() else {
+                return Ok(None);
+            };
+            if let Some(p) = b.rollout_path.as_ref() {
+                self.unreadable_rollouts.remove(p);
+                // Pre-seed with the current mtime so the tail is read now rather than
+                // after the usual quiet tick.
+                let mtime = std::fs::metadata(p).and_then(|m| m.modified()).ok();
+                self.rollout_tail_cache.insert(
+                    p.clone(),
+                    TailCacheEntry {
+                        mtime,
+                        parsed_at: None,
+                        hints: TailHints::default(),
+                    },
+                );
+            }
+            if let Some(cwd) = b.proc_cwd.as_ref() {
+                self.git_cache.invalidate(cwd);
+            }
+            self.build_row(b, SystemTime::now())
+        } else {
+            // Hosts behind an aggregator are only reachable through it.
+            let source = self
+                .relay_routes
+                .get(&key.host)
+                .cloned()
+                .unwrap_or_else(|| key.host.clone());
+            let mut snap = self.collect_remote_host(&source)?;
+            snap.relabel_host(relay_target(&source).unwrap_or(&source));
+            let Some(row) = snap.sessions.into_iter().find(|r| r.id() == *key) else {
+                return Ok(None);
+            };
+            row
+        };
+
+        self.label_row(&mut row);
+        Ok(Some(row))
+    }
+
+    /// Name a session where it runs: remote sessions are named in that host's own store
+    /// (`codex-ps annotate` over the transport) so every viewer sees the same name.
     pub fn set_session_name(
         &mut self,
-        key: SessionNameKey,
+        key: SessionId,
         name: String,
     ) -> anyhow::Result<Option<String>> {
-        self.names.set(key, name)
+        if key.host == "local" {
+            return self.names.set(key, name);
+        }
+        let normalized = normalize_name_opt(Some(name));
+        let argv = [
+            "--yes",
+            "annotate",
+            "--thread",
+            &key.thread_id,
+            "--name",
+            normalized.as_deref().unwrap_or(""),
+        ];
+        self.run_for_session(&key, &argv)?;
+        // An older local override would otherwise keep hiding the remote name.
+        if self.names.get_cached(&key).is_some() {
+            self.names.clear(key.clone())?;
+        }
+        let cached = self.route_for(&key.host).to_string();
+        if let Some((_, Ok(snap))) = self.remote_cache.get_mut(&cached) {
+            for row in &mut snap.sessions {
+                if row.thread_id == key.thread_id {
+                    row.name = normalized.clone();
+                }
+            }
+        }
+        Ok(normalized)
+    }
+
+    pub fn clear_session_name(&mut self, key: SessionId) -> anyhow::Result<()> {
+        if key.host == "local" {
+            return self.names.clear(key);
+        }
+        self.set_session_name(key, String::new()).map(|_| ())
+    }
+
+    /// SIGTERM the session's processes, or SIGKILL with `force`.
+    pub fn kill_session(
+        &mut self,
+        key: &SessionId,
+        pids: &[i32],
+        force: bool,
+    ) -> anyhow::Result<()> {
+        self.signal_session(key, pids, if force { "KILL" } else { "TERM" })
+    }
+
+    /// Send `signal` (`TERM`, `KILL`, `STOP`, ...) to a session's processes on whichever
+    /// host runs them.
+    pub fn signal_session(
+        &mut self,
+        key: &SessionId,
+        pids: &[i32],
+        signal: &str,
+    ) -> anyhow::Result<()> {
+        if pids.is_empty() {
+            anyhow::bail!("no known pids for {key}");
+        }
+        if self.relay_routes.contains_key(&key.host) {
+            let route = self.route_for(&key.host).to_string();
+            self.require_feature(relay_target(&route).unwrap_or(&route), "kill")?;
+            self.run_for_session(key, &["--yes", "kill", &key.thread_id, "--signal", signal])?;
+            return Ok(());
+        }
+        let mut args = vec![format!("-{signal}")];
+        args.extend(pids.iter().map(|p| p.to_string()));
+        if key.host != "local" {
+            let mut argv = vec!["kill".to_string()];
+            argv.extend(args);
+            self.transport
+                .run(&key.host, &argv, &self.cancel)
+                .with_context(|| format!("{} {} kill", self.transport.name(), key.host))?;
+            return Ok(());
+        }
+        let out = std::process::Command::new("kill")
+            .args(&args)
+            .output()
+            .context("run kill")?;
+        if !out.status.success() {
+            anyhow::bail!(
+                "kill {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Name suggestion from the first prompt; read once, retried until the prompt exists.
+    fn suggestion(&mut self, b: &SessionBuilder, repo_root: Option<&str>) -> Option<String> {
+        if let Some(s) = self.suggestions.get(&b.thread_id) {
+            return Some(s.clone());
+        }
+        let path = b.rollout_path.as_ref()?;
+        let prompt = read_first_user_message(path, FIRST_PROMPT_MAX_BYTES).ok()??;
+        let s = suggest_name(&prompt, repo_root)?;
+        self.suggestions.insert(b.thread_id.clone(), s.clone());
+        Some(s)
+    }
+
+    /// Everything in the local names and annotations stores (`labels export`).
+    pub fn export_labels(&mut self) -> anyhow::Result<LabelSet> {
+        self.names.refresh_if_changed()?;
+        self.annotations.refresh_if_changed()?;
+        Ok(LabelSet {
+            names: self.names.records(),
+            annotations: self.annotations.records(),
+        })
     }
 
-    pub fn clear_session_name(&mut self, key: SessionNameKey) -> anyhow::Result<()> {
-        self.names.clear(key)
+    /// Merge records from another machine, newest per session winning; returns how many
+    /// were applied.
+    pub fn import_labels(&mut self, set: LabelSet) -> anyhow::Result<usize> {
+        let mut applied = 0;
+        for rec in set.names {
+            applied += usize::from(self.names.merge_record(rec)?);
+        }
+        for rec in set.annotations {
+            applied += usize::from(self.annotations.merge_record(rec)?);
+        }
+        Ok(applied)
+    }
+
+    /// The host `host`'s rows were collected through: its `via:` aggregator, or itself.
+    fn route_for<'a>(&'a self, host: &'a str) -> &'a str {
+        self.relay_routes.get(host).map_or(host, String::as_str)
+    }
+
+    /// Run `codex-ps --host <session's host> <args>` where that host is reachable: on the host
+    /// itself (as `--host local`), or on the aggregator a `via:` host is only visible through.
+    fn run_for_session(&self, key: &SessionId, args: &[&str]) -> anyhow::Result<String> {
+        let route = self.route_for(&key.host);
+        let (target, host) = match relay_target(route) {
+            Some(agg) => (agg, key.host.as_str()),
+            None => (route, "local"),
+        };
+        let mut argv = vec!["--host", host];
+        argv.extend_from_slice(args);
+        self.run_on_host(target, &argv)
+    }
+
+    /// Run `codex-ps <args>` on `host` through the transport; returns its stdout.
+    pub fn run_on_host(&self, host: &str, args: &[&str]) -> anyhow::Result<String> {
+        let mut argv = vec![self.remote_bin.clone()];
+        argv.extend(args.iter().map(|a| a.to_string()));
+        let out = self
+            .transport
+            .run(host, &argv, &self.cancel)
+            .with_context(|| {
+                format!(
+                    "{} {host} {} {}",
+                    self.transport.name(),
+                    self.remote_bin,
+                    args.join(" ")
+                )
+            })?;
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    /// What `host`'s codex-ps supports. Hosts too old to answer get the baseline; a host that
+    /// can't be reached is an error (and is asked again next time).
+    pub fn host_capabilities(&mut self, host: &str) -> anyhow::Result<&Capabilities> {
+        if !self.capabilities.contains_key(host) {
+            let caps = match self.run_on_host(host, &["capabilities"]) {
+                Ok(out) => Capabilities::parse(&out),
+                Err(e) if rejected_usage(&e) => Capabilities::baseline(),
+                Err(e) => return Err(e),
+            };
+            self.capabilities.insert(host.to_string(), caps);
+        }
+        Ok(&self.capabilities[host])
+    }
+
+    /// Check that `host` answers before it is polled by asking its codex-ps (an aggregator's,
+    /// for `via:` hosts) for its capabilities; unlike a collection this records nothing.
+    pub fn probe_host(&mut self, host: &str) -> anyhow::Result<()> {
+        if host == "local" {
+            return Ok(());
+        }
+        let target = relay_target(host).unwrap_or(host).to_string();
+        self.host_capabilities(&target).map(|_| ())
+    }
+
+    /// Fail with an upgrade hint unless `host`'s codex-ps has `feature`.
+    pub fn require_feature(&mut self, host: &str, feature: &str) -> anyhow::Result<()> {
+        let caps = self.host_capabilities(host)?;
+        if !caps.supports(feature) {
+            let version = caps.version.as_deref().unwrap_or("an older version");
+            anyhow::bail!(
+                "{host} runs codex-ps {version} without `{feature}`; upgrade codex-ps there"
+            );
+        }
+        Ok(())
+    }
+
+    pub fn annotate_session(
+        &mut self,
+        key: SessionId,
+        tags: &[String],
+        note: Option<String>,
+    ) -> anyhow::Result<Annotation> {
+        self.annotations.update(key, tags, note)
     }
 
-    fn collect_local_rows(
+    pub fn set_session_annotation(
         &mut self,
-        debug: bool,
-    ) -> anyhow::Result<(Vec<SessionRow>, Vec<String>)> {
-        // Single `lsof` call for all `codex` processes. This is the most reliable and
-        // least error-prone SSOT for "what is actively running right now?"
-        let lsof_procs = lsof_codex_processes(&self.codex_home.root, Duration::from_secs(10))?;
+        key: SessionId,
+        annotation: Annotation,
+    ) -> anyhow::Result<Annotation> {
+        self.annotations.set(key, annotation)
+    }
+
+    fn apply_annotation(&self, row: &mut SessionRow, keys: &[SessionId]) {
+        let a = keys
+            .iter()
+            .find_map(|k| self.annotations.get_cached(k))
+            .cloned()
+            .unwrap_or_default();
+        row.tags = a.tags;
+        row.note = a.note;
+        row.watched = a.watched;
+    }
+
+    fn collect_local_rows(&mut self) -> anyhow::Result<(Vec<SessionRow>, Vec<ErrorInfo>)> {
+        // The discovery backend (default: a single `lsof` call) is the SSOT for
+        // "what is actively running right now?"
+        let procs = self
+            .discovery
+            .discover(&self.codex_home)
+            .with_context(|| format!("discovery backend: {}", self.discovery.name()))?;
         let now = SystemTime::now();
 
-        let mut warnings: Vec<String> = Vec::new();
+        let mut warnings: Vec<ErrorInfo> = Vec::new();
+        if let (Some(sandbox), true) = (self.sandbox, procs.is_empty()) {
+            warnings.push(ErrorInfo::new(
+                ErrorKind::Discovery,
+                format!(
+                    "running inside {sandbox}: {} may not see Codex processes on the host; mount the host's /proc and pass --proc-root (e.g. --proc-root /run/host/proc)",
+                    self.discovery.name()
+                ),
+            ));
+        }
+        if self.codex_home.is_relocated() {
+            warnings.push(
+                ErrorInfo::new(
+                    ErrorKind::Config,
+                    format!(
+                        "CODEX_HOME {} resolves to {}; matching rollouts against both",
+                        self.codex_home.root.display(),
+                        self.codex_home.canonical.display()
+                    ),
+                )
+                .verbose(),
+            );
+        }
+        if let Some(fs) = self.network_fs.as_ref() {
+            warnings.push(
+                ErrorInfo::new(
+                    ErrorKind::Config,
+                    format!(
+                        "CODEX_HOME is on a network filesystem ({fs}); using rollout timestamps for activity and widened status thresholds"
+                    ),
+                )
+                .verbose(),
+            );
+        }
         let mut by_thread: HashMap<String, SessionBuilder> = HashMap::new();
 
-        for p in lsof_procs {
+        for p in procs {
             for rollout_path in p.rollout_paths {
-                let Some(thread_id) = extract_thread_id_from_rollout_path(&rollout_path) else {
-                    if debug {
-                        warnings.push(format!(
-                            "unparseable rollout filename: {}",
-                            rollout_path.display()
-                        ));
-                    }
+                let meta_id = self.meta_id(&rollout_path);
+                let Some(ids) = self
+                    .thread_id_patterns
+                    .resolve(&rollout_path, meta_id.as_deref())
+                else {
+                    warnings.push(
+                        ErrorInfo::new(
+                            ErrorKind::Rollout,
+                            format!(
+                                "no thread id in rollout filename or session_meta: {}",
+                                rollout_path.display()
+                            ),
+                        )
+                        .verbose(),
+                    );
                     continue;
                 };
 
+                let thread_id = ids.thread_id;
                 let entry = by_thread
                     .entry(thread_id.clone())
                     .or_insert_with(|| SessionBuilder {
@@ -184,10 +955,18 @@ impl Collector {
                             .as_ref()
                             .map(|x| x.to_string_lossy().to_string())
                             .or_else(|| Some("codex".into())),
+                        aliases: Vec::new(),
                     });
+                if let Some(alias) = ids.alias {
+                    if !entry.aliases.contains(&alias) {
+                        entry.aliases.push(alias);
+                    }
+                }
 
-                if !entry.pids.contains(&p.pid) {
-                    entry.pids.push(p.pid);
+                if let Some(pid) = p.pid {
+                    if !entry.pids.contains(&pid) {
+                        entry.pids.push(pid);
+                    }
                 }
 
                 // Prefer the newest rollout path (in case something moved between dirs).
@@ -209,9 +988,18 @@ impl Collector {
             }
         }
 
+        let live: HashSet<&std::path::PathBuf> = by_thread
+            .values()
+            .filter_map(|b| b.rollout_path.as_ref())
+            .collect();
+        self.meta_ids.retain(|p, _| live.contains(p));
+        self.local_builders = by_thread.clone();
+        self.suggestions.retain(|id, _| by_thread.contains_key(id));
+        self.token_readings
+            .retain(|id, _| by_thread.contains_key(id));
         let mut sessions: Vec<SessionRow> = by_thread
             .into_values()
-            .map(|b| self.build_row(b, now, debug))
+            .map(|b| self.build_row(b, now))
             .collect();
 
         sessions.sort_by(|a, b| {
@@ -223,14 +1011,17 @@ impl Collector {
         Ok((sessions, warnings))
     }
 
-    fn build_row(&mut self, b: SessionBuilder, now: SystemTime, debug: bool) -> SessionRow {
+    fn build_row(&mut self, b: SessionBuilder, now: SystemTime) -> SessionRow {
         let mut row = SessionRow {
             host: "local".into(),
             thread_id: b.thread_id.clone(),
             pids: b.pids.clone(),
             tty: b.tty.clone(),
             title: None,
+            previous_title: None,
             name: None,
+            tags: Vec::new(),
+            note: None,
             cwd: None,
             repo_root: None,
             git_branch: None,
@@ -241,15 +1032,38 @@ impl Collector {
             subagent_depth: None,
             status: SessionStatus::Unknown,
             last_activity_unix_s: None,
+            last_activity_at: None,
             rollout_path: b
                 .rollout_path
                 .as_ref()
                 .map(|p| p.to_string_lossy().to_string()),
+            rollout_unreadable: None,
+            final_message: None,
+            pending_call: None,
+            pending_call_since_unix_s: None,
+            cwd_missing: false,
+            current_branch: None,
+            commits_since_start: None,
+            unpushed_commits: None,
+            host_label: None,
+            suggested_name: None,
+            watched: false,
+            latest_line: None,
+            pending_call_eta: None,
+            tokens_per_min: None,
+            total_tokens: None,
+            thread_id_aliases: b.aliases.clone(),
+            started_at_unix_s: None,
+            collected_via: Vec::new(),
+            exe: None,
+            cli_version: None,
+            over_budget: None,
             debug: None,
         };
 
         let mut dbg = SessionDebug {
             status_reason: None,
+            status_reason_code: None,
             process_command_sample: b
                 .proc_command_sample
                 .as_ref()
@@ -258,23 +1072,31 @@ impl Collector {
             meta_parse_error: None,
             meta_id_mismatch: None,
             repo_probe_error: None,
+            tail_parse_error: None,
+            activity_source: None,
             title_source: None,
         };
 
         // CWD preference:
-        // 1) OS truth: lsof cwd
+        // 1) OS truth: process cwd from the discovery backend
         // 2) session_meta.cwd (if parseable)
         if let Some(cwd) = b.proc_cwd.as_ref() {
             row.cwd = Some(cwd.to_string_lossy().to_string());
-            dbg.proc_cwd_source = Some("lsof".into());
+            dbg.proc_cwd_source = Some(self.discovery.name().into());
         }
 
-        // Rollout metadata (best-effort).
-        let meta = match b.rollout_path.as_ref() {
+        // Rollout metadata (best-effort). Skip rollouts we recently failed to open.
+        let readable_rollout = b
+            .rollout_path
+            .as_ref()
+            .filter(|p| !self.is_known_unreadable(p));
+        let meta = match readable_rollout {
             Some(p) => match read_session_meta(p) {
                 Ok(m) => Some(m),
                 Err(e) => {
-                    dbg.meta_parse_error = Some(format!("{e}"));
+                    let info = ErrorInfo::from_anyhow(ErrorKind::Rollout, &e);
+                    self.note_unreadable(p, &info);
+                    dbg.meta_parse_error = Some(info);
                     None
                 }
             },
@@ -288,13 +1110,26 @@ impl Collector {
             }
         }
 
+        let started_at = meta.as_ref().and_then(|m| m.started_at_unix_s);
+        row.started_at_unix_s = started_at;
+        // Without a resolved exe the sample is just "codex".
+        row.exe = b
+            .proc_command_sample
+            .clone()
+            .filter(|exe| std::path::Path::new(exe).is_absolute());
+        row.cli_version = row
+            .exe
+            .as_deref()
+            .and_then(|exe| self.cli_versions.version_for(std::path::Path::new(exe)))
+            .or_else(|| meta.as_ref().and_then(|m| m.cli_version.clone()));
+        if !b.aliases.is_empty() {
+            dbg.meta_id_mismatch = Some(format!(
+                "meta.id={} != filename.id={}",
+                row.thread_id,
+                b.aliases.join(",")
+            ));
+        }
         if let Some(meta) = meta {
-            if let Some(id) = meta.id.as_ref() {
-                if id != &row.thread_id {
-                    dbg.meta_id_mismatch =
-                        Some(format!("meta.id={id} != filename.id={}", row.thread_id));
-                }
-            }
             row.git_branch = meta.git_branch;
             row.git_commit = meta.git_commit;
             row.session_source = meta.session_source;
@@ -304,7 +1139,10 @@ impl Collector {
         }
 
         // Title (best-effort): global state titles → fallback to last path segment of cwd.
-        if let Ok(Some((t, src))) = self.titles.get_title(&row.thread_id) {
+        let title = std::iter::once(&row.thread_id)
+            .chain(&row.thread_id_aliases)
+            .find_map(|id| self.titles.get_title(id).ok().flatten());
+        if let Some((t, src)) = title {
             row.title = Some(t);
             dbg.title_source = Some(src.into());
         } else if let Some(cwd) = row.cwd.as_ref() {
@@ -318,123 +1156,314 @@ impl Collector {
             }
         }
 
-        // Repo root (best-effort, cached).
+        // Repo root (best-effort, cached). Skip git entirely for a cwd that's gone.
         if let Some(cwd_s) = row.cwd.as_ref() {
+            let cwd = std::path::Path::new(cwd_s);
+            row.cwd_missing = self.git_cache.cwd_missing(cwd);
+        }
+        if let Some(cwd_s) = row.cwd.as_ref().filter(|_| !row.cwd_missing) {
             let cwd = std::path::Path::new(cwd_s);
             let (root, err) = self
                 .git_cache
                 .repo_root(cwd, Duration::from_millis(250))
-                .unwrap_or((None, Some("git probe error".into())));
+                .unwrap_or_else(|e| (None, Some(ErrorInfo::from_anyhow(ErrorKind::Git, &e))));
             row.repo_root = root.map(|p| p.to_string_lossy().to_string());
             dbg.repo_probe_error = err;
+            if let (Some(repo), Some(since)) = (row.repo_root.as_ref(), started_at) {
+                row.commits_since_start = self.git_cache.commits_since(
+                    std::path::Path::new(repo),
+                    since,
+                    Duration::from_millis(500),
+                );
+            }
+            if row.repo_root.is_some() {
+                row.unpushed_commits = self
+                    .git_cache
+                    .unpushed_commits(cwd, Duration::from_millis(500));
+            }
+            if self.probe_branch && row.repo_root.is_some() {
+                row.current_branch = self
+                    .git_cache
+                    .current_branch(cwd, Duration::from_millis(250));
+            }
+        }
+
+        if self.suggest_names {
+            row.suggested_name = self.suggestion(&b, row.repo_root.as_deref());
         }
 
         // Last activity: rollout mtime when available.
-        let mut last_activity: Option<SystemTime> = None;
+        let mut mtime: Option<SystemTime> = None;
         if let Some(p) = b.rollout_path.as_ref() {
             if let Ok(m) = std::fs::metadata(p) {
-                last_activity = m.modified().ok();
+                mtime = m.modified().ok();
             }
         }
-        row.last_activity_unix_s = last_activity.and_then(system_time_to_unix_s);
 
-        let pending_call = b
+        let hints = b
             .rollout_path
             .as_ref()
-            .and_then(|p| self.pending_function_call_hint(p.as_path(), last_activity, &mut dbg));
+            .filter(|p| !self.is_known_unreadable(p))
+            .map(|p| self.tail_hints(p.as_path(), mtime, &mut dbg))
+            .unwrap_or_default();
+
+        // On network mounts mtime lags (attribute caching); the newest line timestamp is
+        // written by Codex itself and is the better activity signal.
+        let mut last_activity = mtime;
+        dbg.activity_source = mtime.map(|_| "mtime".into());
+        if self.network_fs.is_some() {
+            if let Some(ts) = hints
+                .last_timestamp_unix_s
+                .and_then(|ts| u64::try_from(ts).ok())
+            {
+                last_activity = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(ts));
+                dbg.activity_source = Some("tail_timestamp".into());
+            }
+        }
+        row.last_activity_unix_s = last_activity.and_then(system_time_to_unix_s);
 
-        row.status = classify_status(now, last_activity, pending_call.as_ref(), &mut dbg);
+        let heartbeat = std::fs::metadata(
+            self.codex_home
+                .root
+                .join("heartbeats")
+                .join(format!("{}.hb", b.thread_id)),
+        )
+        .and_then(|m| m.modified())
+        .ok();
+        row.status = classify_status(
+            now,
+            last_activity,
+            hints.pending_call.as_ref(),
+            heartbeat,
+            &self.thresholds,
+            &mut dbg,
+        );
+        row.final_message = hints.final_message;
+        row.latest_line = hints.latest_line;
+        row.pending_call = hints.pending_call.as_ref().map(|c| c.describe());
+        row.pending_call_since_unix_s = hints.pending_call.as_ref().and_then(|c| c.started_unix_s);
+        if !hints.token_readings.is_empty() {
+            self.token_readings
+                .insert(b.thread_id.clone(), hints.token_readings.clone());
+        }
+        row.tokens_per_min = self
+            .token_readings
+            .get(&b.thread_id)
+            .and_then(|r| tokens_per_min(r, system_time_to_unix_s(now).unwrap_or(0)));
+        row.total_tokens = self
+            .token_readings
+            .get(&b.thread_id)
+            .and_then(|r| r.last())
+            .map(|&(_, n)| n);
+        // Best-effort, like the tail itself: without the log there is just no ETA.
+        if self.call_durations.observe(&hints.completed_calls).is_ok() {
+            row.pending_call_eta = hints.pending_call.as_ref().and_then(|c| {
+                let (prefix, typical_secs) =
+                    self.call_durations.typical_secs(c.summary.as_deref()?)?;
+                Some(CallEta {
+                    prefix,
+                    typical_secs,
+                    started_unix_s: c.started_unix_s?,
+                })
+            });
+        }
 
-        if debug {
-            row.debug = Some(dbg);
+        if let Some((_, info)) = b
+            .rollout_path
+            .as_ref()
+            .and_then(|p| self.unreadable_rollouts.get(p))
+        {
+            row.rollout_unreadable = Some(format!(
+                "permission denied reading rollout ({}); status is mtime-only",
+                info.message
+            ));
+            if let Some(reason) = dbg.status_reason.as_mut() {
+                reason.insert_str(0, "rollout unreadable; ");
+            }
         }
 
+        row.debug = Some(dbg);
         row
     }
 
-    fn collect_remote_host(&self, host: &str, debug: bool) -> anyhow::Result<Snapshot> {
+    fn collect_remote_host(&self, host: &str) -> anyhow::Result<Snapshot> {
         // Phase 2 strategy: ask the remote machine to run `codex-ps --json` and aggregate.
         // This keeps parsing/state logic identical on every host.
-        let mut cmd = std::process::Command::new(&self.ssh_bin);
-        cmd.args(["-o", "BatchMode=yes"]);
-        cmd.args(["-o", "ConnectTimeout=3"]);
-        cmd.arg(host);
-        cmd.arg(&self.remote_bin);
-        cmd.arg("--json");
-        cmd.arg("--host");
-        cmd.arg("local");
-        if debug {
-            cmd.arg("--debug");
-        }
-
-        let out = crate::util::run_cmd_with_timeout(cmd, self.ssh_timeout)
-            .with_context(|| format!("ssh {host} {} --json", self.remote_bin))?;
-
-        if !out.status.success() {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            anyhow::bail!(
-                "ssh {host} failed (status {}): {}",
-                out.status,
-                truncate_middle(stderr.trim(), 200)
-            );
+        // `via:agg` asks `agg` for everything it aggregates (its `--host all`).
+        let (target, hosts) = match relay_target(host) {
+            Some(target) => (target, "all"),
+            None => (host, "local"),
+        };
+        // `--debug` so remote rows carry their status reasons too, like local ones.
+        let mut argv: Vec<String> = vec![
+            self.remote_bin.clone(),
+            "--json".into(),
+            "--debug".into(),
+            "--host".into(),
+            hosts.into(),
+        ];
+        if target != host {
+            if self.relay_chain.iter().any(|h| h == target) {
+                anyhow::bail!(
+                    "relay loop: {target} is already relaying this request ({})",
+                    self.relay_chain.join(" → ")
+                );
+            }
+            let chain: Vec<&str> = self
+                .relay_chain
+                .iter()
+                .map(String::as_str)
+                .chain([target])
+                .collect();
+            argv.push("--relay-chain".into());
+            argv.push(chain.join(","));
         }
+        let out = self
+            .transport
+            .run(target, &argv, &self.cancel)
+            .with_context(|| {
+                format!(
+                    "{} {target} {} --json",
+                    self.transport.name(),
+                    self.remote_bin
+                )
+            })?;
 
         let snap: Snapshot = serde_json::from_slice(&out.stdout)
             .with_context(|| format!("parse remote JSON snapshot from host={host}"))?;
         Ok(snap)
     }
 
-    fn pending_function_call_hint(
+    fn tail_hints(
         &mut self,
         rollout_path: &std::path::Path,
         mtime: Option<SystemTime>,
         dbg: &mut SessionDebug,
-    ) -> Option<PendingFunctionCall> {
+    ) -> TailHints {
+        let network = self.network_fs.is_some();
         let entry = self
             .rollout_tail_cache
             .entry(rollout_path.to_path_buf())
             .or_insert_with(|| TailCacheEntry {
                 mtime: None,
-                parsed_for_mtime: false,
-                pending_call: None,
+                parsed_at: None,
+                hints: TailHints::default(),
             });
 
         if entry.mtime != mtime {
             entry.mtime = mtime;
-            entry.parsed_for_mtime = false;
-            entry.pending_call = None;
-            return None;
-        }
-
-        if !entry.parsed_for_mtime {
-            entry.parsed_for_mtime = true;
-            entry.pending_call =
-                match read_pending_function_call_from_tail(rollout_path, ROLLOUT_TAIL_MAX_BYTES) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        // Tail parsing is best-effort; fall back to mtime heuristics.
-                        dbg.status_reason = Some(format!("tail parse failed: {e}"));
-                        None
+            entry.parsed_at = None;
+            entry.hints = TailHints::default();
+            // Local disks: wait for one quiet tick before paying for a tail read.
+            if !network {
+                return TailHints::default();
+            }
+        }
+
+        let stale = match entry.parsed_at {
+            None => true,
+            Some(at) => {
+                network && at.elapsed() >= Duration::from_secs(NETWORK_FS_TAIL_REFRESH_SECS)
+            }
+        };
+        if stale {
+            entry.parsed_at = Some(Instant::now());
+            entry.hints = match read_tail_hints(rollout_path, ROLLOUT_TAIL_MAX_BYTES) {
+                Ok(v) => v,
+                Err(e) => {
+                    // Tail parsing is best-effort; fall back to mtime heuristics.
+                    let info = ErrorInfo::from_anyhow(ErrorKind::Rollout, &e);
+                    if info.kind == ErrorKind::PermissionDenied {
+                        self.unreadable_rollouts
+                            .insert(rollout_path.to_path_buf(), (Instant::now(), info.clone()));
                     }
-                };
+                    dbg.tail_parse_error = Some(info);
+                    TailHints::default()
+                }
+            };
         }
 
-        entry.pending_call.clone()
+        entry.hints.clone()
+    }
+
+    fn is_known_unreadable(&mut self, rollout_path: &std::path::Path) -> bool {
+        match self.unreadable_rollouts.get(rollout_path) {
+            Some((at, _)) if at.elapsed() < Duration::from_secs(UNREADABLE_ROLLOUT_RETRY_SECS) => {
+                true
+            }
+            Some(_) => {
+                // Retry window elapsed: forget and try reading again this tick.
+                self.unreadable_rollouts.remove(rollout_path);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn note_unreadable(&mut self, rollout_path: &std::path::Path, info: &ErrorInfo) {
+        if info.kind == ErrorKind::PermissionDenied {
+            self.unreadable_rollouts
+                .insert(rollout_path.to_path_buf(), (Instant::now(), info.clone()));
+        }
+    }
+}
+
+/// Collapse identical errors within a cycle and carry the count and first-seen time of
+/// errors that were already present last cycle.
+pub fn fold_repeated_host_errors(
+    previous: &[HostError],
+    current: Vec<HostError>,
+    now_s: i64,
+) -> Vec<HostError> {
+    let mut out: Vec<HostError> = Vec::new();
+    for mut e in current {
+        if let Some(seen) = out.iter_mut().find(|o| o.same_as(&e)) {
+            seen.count += 1;
+            continue;
+        }
+        match previous.iter().find(|p| p.same_as(&e)) {
+            Some(p) => {
+                e.count = p.count + 1;
+                e.first_seen_unix_s = p.first_seen_unix_s;
+            }
+            None => e.first_seen_unix_s = e.first_seen_unix_s.or(Some(now_s)),
+        }
+        e.last_seen_unix_s = Some(now_s);
+        out.push(e);
     }
+    out
+}
+
+/// Tokens per minute since the last reading at least `TOKEN_RATE_WINDOW_SECS` old (or the
+/// oldest one), measured up to now so a session that stopped spending decays to 0.
+fn tokens_per_min(readings: &[(i64, u64)], now_unix_s: i64) -> Option<u64> {
+    let &(_, latest) = readings.last()?;
+    let window_start = now_unix_s - TOKEN_RATE_WINDOW_SECS;
+    let (base_ts, base) = readings
+        .iter()
+        .rev()
+        .find(|(ts, _)| *ts <= window_start)
+        .unwrap_or(&readings[0]);
+    let secs = u64::try_from(now_unix_s - base_ts).unwrap_or(0).max(60);
+    Some(latest.saturating_sub(*base) * 60 / secs)
 }
 
 fn classify_status(
     now: SystemTime,
     last_activity: Option<SystemTime>,
     pending_call: Option<&PendingFunctionCall>,
+    heartbeat: Option<SystemTime>,
+    thresholds: &StatusThresholds,
     dbg: &mut SessionDebug,
 ) -> SessionStatus {
     if let Some(call) = pending_call {
         if call.name == "request_user_input" {
+            dbg.status_reason_code = Some(StatusReasonCode::PendingUserInput);
             dbg.status_reason = Some(format!("waiting for user input (call_id={})", call.call_id));
             return SessionStatus::Waiting;
         }
 
+        dbg.status_reason_code = Some(StatusReasonCode::PendingToolCall);
         dbg.status_reason = Some(format!(
             "pending tool call: {} (call_id={})",
             call.name, call.call_id
@@ -442,8 +1471,20 @@ fn classify_status(
         return SessionStatus::Working;
     }
 
+    // Opt-in `$CODEX_HOME/heartbeats/<thread>.hb`: a fresh touch means busy even when a long
+    // tool run writes nothing to the rollout. A stale one falls back to the rollout mtime.
+    if let Some(hb) = heartbeat {
+        let age = now.duration_since(hb).unwrap_or_default();
+        if age <= thresholds.working_max_age {
+            dbg.status_reason_code = Some(StatusReasonCode::RecentHeartbeat);
+            dbg.status_reason = Some(format!("recent heartbeat: {}s", age.as_secs()));
+            return SessionStatus::Working;
+        }
+    }
+
     // If we can't even get last activity, stay unknown (fail-loud).
     let Some(ts) = last_activity else {
+        dbg.status_reason_code = Some(StatusReasonCode::NoMtime);
         dbg.status_reason = Some("no rollout mtime".into());
         return SessionStatus::Unknown;
     };
@@ -453,10 +1494,9 @@ fn classify_status(
     let age = match now.duration_since(ts) {
         Ok(d) => d,
         Err(_) => match ts.duration_since(now) {
-            Ok(skew) if skew <= Duration::from_secs(STATUS_MAX_FUTURE_MTIME_SKEW_SECS) => {
-                Duration::from_secs(0)
-            }
+            Ok(skew) if skew <= thresholds.max_future_skew => Duration::from_secs(0),
             _ => {
+                dbg.status_reason_code = Some(StatusReasonCode::MtimeFuture);
                 dbg.status_reason = Some("rollout mtime is in the future".into());
                 return SessionStatus::Unknown;
             }
@@ -464,14 +1504,16 @@ fn classify_status(
     };
 
     // Very recent writes are a strong (but not perfect) signal of "working".
-    if age <= Duration::from_secs(STATUS_WORKING_MAX_AGE_SECS) {
+    if age <= thresholds.working_max_age {
+        dbg.status_reason_code = Some(StatusReasonCode::RecentWrite);
         dbg.status_reason = Some(format!("recent rollout write: {}s", age.as_secs()));
         return SessionStatus::Working;
     }
 
     // Rollouts do not persist all lifecycle events (e.g. TurnStarted/TurnComplete, RequestUserInput),
     // so even with tail hints we keep an mtime-based fallback that biases toward Unknown.
-    if age <= Duration::from_secs(STATUS_UNCERTAIN_MAX_AGE_SECS) {
+    if age <= thresholds.uncertain_max_age {
+        dbg.status_reason_code = Some(StatusReasonCode::Uncertain);
         dbg.status_reason = Some(format!(
             "uncertain (no rollout writes for {}s)",
             age.as_secs()
@@ -479,22 +1521,93 @@ fn classify_status(
         return SessionStatus::Unknown;
     }
 
+    dbg.status_reason_code = Some(StatusReasonCode::IdleTimeout);
     dbg.status_reason = Some(format!("idle (no rollout writes for {}s)", age.as_secs()));
     SessionStatus::Waiting
 }
 
+/// The remote codex-ps ran but rejected its arguments: clap exits 2 on usage errors, which is
+/// how a build without a subcommand answers it. ssh's own failures exit 255.
+fn rejected_usage(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|c| c.downcast_ref::<RemoteCommandFailed>())
+        .any(|f| f.status.code() == Some(2))
+}
+
+/// The aggregator named by a `via:` host (`via:home-server` → `home-server`).
+pub fn relay_target(host: &str) -> Option<&str> {
+    host.strip_prefix("via:")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::discovery::{CodexProcess, MockDiscovery};
+    use crate::transport::MockTransport;
+
+    #[test]
+    fn repeated_host_errors_fold_into_one_entry() {
+        let err = |msg: &str| HostError::new("home", ErrorInfo::new(ErrorKind::Transport, msg));
+        let first = fold_repeated_host_errors(&[], vec![err("timeout"), err("timeout")], 100);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].count, 2);
+
+        let second = fold_repeated_host_errors(&first, vec![err("timeout")], 130);
+        assert_eq!(second[0].count, 3);
+        assert_eq!(
+            (second[0].first_seen_unix_s, second[0].last_seen_unix_s),
+            (Some(100), Some(130))
+        );
+
+        let changed = fold_repeated_host_errors(&second, vec![err("refused")], 160);
+        assert_eq!(changed[0].count, 1);
+        assert_eq!(changed[0].first_seen_unix_s, Some(160));
+    }
+
+    fn test_collector(
+        root: &std::path::Path,
+        procs: Vec<CodexProcess>,
+        responses: HashMap<String, Result<String, String>>,
+    ) -> Collector {
+        recording_collector(root, procs, responses).0
+    }
+
+    /// Also returns every remote command run, as `host: argv`.
+    fn recording_collector(
+        root: &std::path::Path,
+        procs: Vec<CodexProcess>,
+        responses: HashMap<String, Result<String, String>>,
+    ) -> (Collector, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        let calls = std::sync::Arc::default();
+        let mut c = Collector::new(
+            CodexHome::at(root.to_path_buf()),
+            Box::new(MockDiscovery { procs }),
+            Box::new(MockTransport {
+                responses,
+                calls: std::sync::Arc::clone(&calls),
+            }),
+            "codex-ps".into(),
+        )
+        .expect("collector");
+        c.title_history = TitleHistory::new_at(root.join("title_history.jsonl"));
+        c.thread_aliases = ThreadAliases::new_at(root.join("thread_aliases.jsonl"));
+        c.annotations = AnnotationsStore::new_at(root.join("session_annotations.jsonl"));
+        c.usage = UsageLog::new_at(root.join("usage.jsonl"));
+        c.call_durations = CallDurations::new_at(root.join("call_durations.jsonl"));
+        (c, calls)
+    }
 
     fn blank_dbg() -> SessionDebug {
         SessionDebug {
             status_reason: None,
+            status_reason_code: None,
             process_command_sample: None,
             proc_cwd_source: None,
             meta_parse_error: None,
             meta_id_mismatch: None,
             repo_probe_error: None,
+            tail_parse_error: None,
+            activity_source: None,
             title_source: None,
         }
     }
@@ -503,7 +1616,14 @@ mod tests {
     fn classify_status_unknown_when_no_activity_time() {
         let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
         let mut dbg = blank_dbg();
-        let status = classify_status(now, None, None, &mut dbg);
+        let status = classify_status(
+            now,
+            None,
+            None,
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
         assert!(matches!(status, SessionStatus::Unknown));
         assert_eq!(dbg.status_reason.as_deref(), Some("no rollout mtime"));
     }
@@ -513,7 +1633,14 @@ mod tests {
         let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
         let last = now + Duration::from_secs(1);
         let mut dbg = blank_dbg();
-        let status = classify_status(now, Some(last), None, &mut dbg);
+        let status = classify_status(
+            now,
+            Some(last),
+            None,
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
         assert!(matches!(status, SessionStatus::Working));
     }
 
@@ -522,12 +1649,20 @@ mod tests {
         let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
         let last = now + Duration::from_secs(STATUS_MAX_FUTURE_MTIME_SKEW_SECS + 5);
         let mut dbg = blank_dbg();
-        let status = classify_status(now, Some(last), None, &mut dbg);
+        let status = classify_status(
+            now,
+            Some(last),
+            None,
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
         assert!(matches!(status, SessionStatus::Unknown));
         assert_eq!(
             dbg.status_reason.as_deref(),
             Some("rollout mtime is in the future")
         );
+        assert_eq!(dbg.status_reason_code, Some(StatusReasonCode::MtimeFuture));
     }
 
     #[test]
@@ -535,7 +1670,14 @@ mod tests {
         let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
         let last = now - Duration::from_secs(10);
         let mut dbg = blank_dbg();
-        let status = classify_status(now, Some(last), None, &mut dbg);
+        let status = classify_status(
+            now,
+            Some(last),
+            None,
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
         assert!(matches!(status, SessionStatus::Working));
     }
 
@@ -544,7 +1686,14 @@ mod tests {
         let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
         let last = now - Duration::from_secs(30);
         let mut dbg = blank_dbg();
-        let status = classify_status(now, Some(last), None, &mut dbg);
+        let status = classify_status(
+            now,
+            Some(last),
+            None,
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
         assert!(matches!(status, SessionStatus::Unknown));
     }
 
@@ -553,7 +1702,14 @@ mod tests {
         let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
         let last = now - Duration::from_secs(STATUS_UNCERTAIN_MAX_AGE_SECS + 1);
         let mut dbg = blank_dbg();
-        let status = classify_status(now, Some(last), None, &mut dbg);
+        let status = classify_status(
+            now,
+            Some(last),
+            None,
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
         assert!(matches!(status, SessionStatus::Waiting));
     }
 
@@ -565,13 +1721,38 @@ mod tests {
         let pending = PendingFunctionCall {
             call_id: "call_ui".into(),
             name: "request_user_input".into(),
+            summary: None,
+            started_unix_s: None,
         };
-        let status = classify_status(now, Some(last), Some(&pending), &mut dbg);
+        let status = classify_status(
+            now,
+            Some(last),
+            Some(&pending),
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
         assert!(matches!(status, SessionStatus::Waiting));
         assert_eq!(
             dbg.status_reason.as_deref(),
             Some("waiting for user input (call_id=call_ui)")
         );
+        assert_eq!(
+            dbg.status_reason_code,
+            Some(StatusReasonCode::PendingUserInput)
+        );
+    }
+
+    #[test]
+    fn token_rate_uses_recent_window_and_decays() {
+        let readings = [(0, 1_000), (400, 5_000), (600, 11_000), (700, 14_000)];
+        // Since the reading at 400 (the last one at least 5m old): 9k tokens over 5m.
+        assert_eq!(tokens_per_min(&readings, 700), Some(1_800));
+        // Nothing new for ten minutes: measured from 700 onwards.
+        assert_eq!(tokens_per_min(&readings, 1_300), Some(0));
+        // A young session: the oldest reading, over at least a minute.
+        assert_eq!(tokens_per_min(&[(10, 100), (40, 700)], 40), Some(600));
+        assert_eq!(tokens_per_min(&[], 40), None);
     }
 
     #[test]
@@ -582,12 +1763,482 @@ mod tests {
         let pending = PendingFunctionCall {
             call_id: "call_exec".into(),
             name: "exec_command".into(),
+            summary: None,
+            started_unix_s: None,
         };
-        let status = classify_status(now, Some(last), Some(&pending), &mut dbg);
+        let status = classify_status(
+            now,
+            Some(last),
+            Some(&pending),
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
         assert!(matches!(status, SessionStatus::Working));
         assert_eq!(
             dbg.status_reason.as_deref(),
             Some("pending tool call: exec_command (call_id=call_exec)")
         );
     }
+
+    #[test]
+    fn collect_local_rows_uses_injected_discovery_backend() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let rollout = dir
+            .path()
+            .join("rollout-2026-02-03T16-12-22-019c2590-5605-7cd1-81b8-8a488af219a3.jsonl");
+        std::fs::write(
+            &rollout,
+            r#"{"type":"session_meta","payload":{"id":"019c2590-5605-7cd1-81b8-8a488af219a3","cwd":"/tmp/example","git":{"branch":"main"}}}
+"#,
+        )
+        .expect("write rollout");
+
+        let procs = vec![CodexProcess {
+            pid: Some(42),
+            exe: None,
+            cwd: None,
+            tty: Some("ttys001".into()),
+            rollout_paths: vec![rollout.clone()],
+        }];
+        let mut collector = test_collector(dir.path(), procs, HashMap::new());
+
+        let (rows, _warnings) = collector.collect_local_rows().expect("collect");
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(row.thread_id, "019c2590-5605-7cd1-81b8-8a488af219a3");
+        assert_eq!(row.pids, vec![42]);
+        assert_eq!(row.cwd.as_deref(), Some("/tmp/example"));
+        assert_eq!(row.git_branch.as_deref(), Some("main"));
+        assert_eq!(
+            row.debug
+                .as_ref()
+                .and_then(|d| d.proc_cwd_source.as_deref()),
+            Some("session_meta")
+        );
+    }
+
+    #[test]
+    fn refresh_session_reads_tail_immediately() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let thread_id = "019c2590-5605-7cd1-81b8-8a488af219a3";
+        let rollout = dir
+            .path()
+            .join(format!("rollout-2026-02-03T16-12-22-{thread_id}.jsonl"));
+        std::fs::write(
+            &rollout,
+            r#"{"type":"session_meta","payload":{"id":"019c2590-5605-7cd1-81b8-8a488af219a3","cwd":"/tmp/example"}}
+{"type":"response_item","payload":{"type":"function_call","name":"request_user_input","call_id":"c1"}}
+"#,
+        )
+        .expect("write rollout");
+
+        let procs = vec![CodexProcess {
+            pid: Some(42),
+            exe: None,
+            cwd: None,
+            tty: None,
+            rollout_paths: vec![rollout],
+        }];
+        let mut collector = test_collector(dir.path(), procs, HashMap::new());
+
+        // A fresh mtime skips the tail on the regular tick...
+        let (rows, _) = collector.collect_local_rows().expect("collect");
+        assert!(matches!(rows[0].status, SessionStatus::Working));
+
+        // ...but an explicit per-session refresh reads it right away.
+        let key = SessionId::new("local", thread_id);
+        let row = collector
+            .refresh_session(&key)
+            .expect("refresh")
+            .expect("known session");
+        assert!(matches!(row.status, SessionStatus::Waiting));
+
+        let unknown = SessionId::new("local", "nope");
+        assert!(
+            collector
+                .refresh_session(&unknown)
+                .expect("refresh")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn collect_remote_host_relabels_rows_and_reports_failures() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mut responses = HashMap::new();
+        // Version skew: an older remote that omits host_errors/warnings and per-row host.
+        responses.insert(
+            "old".to_string(),
+            Ok(r#"{"generated_at_unix_s":1,"host":"local","sessions":[{"thread_id":"t1","pids":[1],"tty":null,"title":null,"name":null,"cwd":null,"repo_root":null,"git_branch":null,"git_commit":null,"session_source":null,"forked_from_id":null,"subagent_parent_thread_id":null,"subagent_depth":null,"status":"working","last_activity_unix_s":null,"rollout_path":null}]}"#.to_string()),
+        );
+        responses.insert(
+            "down".to_string(),
+            Err("command timed out after 6s".to_string()),
+        );
+        let mut collector = test_collector(dir.path(), Vec::new(), responses);
+
+        let snap = collector
+            .collect(&["old".to_string(), "down".to_string()])
+            .expect("collect");
+        assert_eq!(snap.sessions.len(), 1);
+        assert_eq!(snap.sessions[0].host, "old");
+        let errs = snap.host_errors.expect("host_errors");
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].host, "down");
+        assert_eq!(errs[0].kind, ErrorKind::Transport);
+        assert_eq!(errs[0].error, "command timed out after 6s");
+        assert!(errs[0].context[0].starts_with("mock down"));
+    }
+
+    #[test]
+    fn relayed_hosts_keep_their_origin_and_loops_are_refused() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mut responses = HashMap::new();
+        responses.insert(
+            "agg".to_string(),
+            Ok(
+                r#"{"generated_at_unix_s":1,"host":"local,inner","sessions":[
+                {"host":"local","thread_id":"t1","pids":[1],"status":"working"},
+                {"host":"inner","thread_id":"t2","pids":[2],"status":"waiting"}]}"#
+                    .to_string(),
+            ),
+        );
+        let (mut collector, calls) = recording_collector(dir.path(), Vec::new(), responses);
+        collector
+            .capabilities
+            .insert("agg".into(), Capabilities::current());
+
+        let snap = collector
+            .collect(&["via:agg".to_string()])
+            .expect("collect");
+        let mut rows: Vec<(String, Vec<String>)> = snap
+            .sessions
+            .iter()
+            .map(|r| (r.id().to_string(), r.collected_via.clone()))
+            .collect();
+        rows.sort();
+        assert_eq!(
+            rows,
+            vec![
+                ("agg:t1".to_string(), Vec::new()),
+                ("inner:t2".to_string(), vec!["agg".to_string()]),
+            ]
+        );
+        assert!(snap.host_errors.expect("host_errors").is_empty());
+        let inner = SessionId::new("inner", "t2");
+        let row = collector
+            .refresh_session(&inner)
+            .expect("refresh")
+            .expect("row");
+        assert_eq!(row.collected_via, vec!["agg"]);
+
+        // Actions on hosts behind the aggregator go through it too.
+        calls.lock().expect("calls").clear();
+        collector
+            .signal_session(&inner, &[2], "TERM")
+            .expect("kill");
+        collector
+            .set_session_name(inner.clone(), "relayed".into())
+            .expect("rename");
+        assert_eq!(
+            *calls.lock().expect("calls"),
+            vec![
+                "agg: codex-ps --host inner --yes kill t2 --signal TERM",
+                "agg: codex-ps --host inner --yes annotate --thread t2 --name relayed",
+            ]
+        );
+        let snap = collector
+            .collect_with_remotes(&["via:agg".to_string()], false)
+            .expect("collect");
+        let row = snap
+            .sessions
+            .iter()
+            .find(|r| r.has_id(&inner))
+            .expect("row");
+        assert_eq!(row.name.as_deref(), Some("relayed"));
+
+        collector.set_relay_chain(vec!["agg".into()]);
+        let snap = collector
+            .collect(&["via:agg".to_string()])
+            .expect("collect");
+        let errs = snap.host_errors.expect("host_errors");
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].host, "via:agg");
+        assert!(errs[0].error.contains("relay loop"), "{}", errs[0].error);
+    }
+
+    #[test]
+    fn old_remotes_get_the_baseline_capabilities() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mut responses = HashMap::new();
+        // Predates `capabilities`: prints its snapshot whatever it is asked.
+        responses.insert(
+            "old".to_string(),
+            Ok(r#"{"generated_at_unix_s":1,"host":"local","sessions":[]}"#.to_string()),
+        );
+        responses.insert(
+            "down".to_string(),
+            Err("command timed out after 6s".to_string()),
+        );
+        let mut collector = test_collector(dir.path(), Vec::new(), responses);
+
+        collector
+            .require_feature("old", "annotate")
+            .expect("baseline feature");
+        let err = collector.require_feature("old", "logs").unwrap_err();
+        assert!(err.to_string().contains("without `logs`"), "{err}");
+        assert!(collector.require_feature("down", "logs").is_err());
+        assert!(!collector.capabilities.contains_key("down"));
+    }
+
+    #[test]
+    fn remote_names_are_set_on_the_remote_host() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mut responses = HashMap::new();
+        responses.insert(
+            "home".to_string(),
+            Ok(r#"{"generated_at_unix_s":1,"host":"local","sessions":[{"thread_id":"t1","pids":[7],"tty":null,"title":null,"name":"old","cwd":null,"repo_root":null,"git_branch":null,"git_commit":null,"session_source":null,"forked_from_id":null,"subagent_parent_thread_id":null,"subagent_depth":null,"status":"working","last_activity_unix_s":null,"rollout_path":null}]}"#.to_string()),
+        );
+        let mut collector = test_collector(dir.path(), Vec::new(), responses);
+        let hosts = ["home".to_string()];
+        let snap = collector.collect(&hosts).expect("collect");
+        assert_eq!(snap.sessions[0].name.as_deref(), Some("old"));
+
+        let key = SessionId::new("home", "t1");
+        let name = collector
+            .set_session_name(key.clone(), " release ".into())
+            .expect("rename");
+        assert_eq!(name.as_deref(), Some("release"));
+        // Nothing was written locally; the cached remote row shows the new name right away.
+        assert!(collector.names.get_cached(&key).is_none());
+        let snap = collector
+            .collect_with_remotes(&hosts, false)
+            .expect("collect");
+        assert_eq!(snap.sessions[0].name.as_deref(), Some("release"));
+    }
+
+    #[test]
+    fn unreadable_rollout_is_flagged_and_not_reread_within_retry_window() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let rollout = dir
+            .path()
+            .join("rollout-2026-02-03T16-12-22-019c2590-5605-7cd1-81b8-8a488af219a3.jsonl");
+        std::fs::write(
+            &rollout,
+            r#"{"type":"session_meta","payload":{"id":"019c2590-5605-7cd1-81b8-8a488af219a3","git":{"branch":"main"}}}
+"#,
+        )
+        .expect("write rollout");
+
+        let procs = vec![CodexProcess {
+            pid: Some(7),
+            exe: None,
+            cwd: Some(dir.path().to_path_buf()),
+            tty: None,
+            rollout_paths: vec![rollout.clone()],
+        }];
+        let mut collector = test_collector(dir.path(), procs, HashMap::new());
+        // Simulate an earlier EACCES (tests usually run with permissions that can read anything).
+        collector.note_unreadable(
+            &rollout,
+            &ErrorInfo::new(
+                ErrorKind::PermissionDenied,
+                "Permission denied (os error 13)",
+            ),
+        );
+
+        let (rows, _) = collector.collect_local_rows().expect("collect");
+        let row = &rows[0];
+        assert!(
+            row.rollout_unreadable
+                .as_deref()
+                .is_some_and(|r| r.contains("permission denied"))
+        );
+        // Metadata was not re-read while the rollout is marked unreadable.
+        assert_eq!(row.git_branch, None);
+        assert!(
+            row.debug
+                .as_ref()
+                .and_then(|d| d.status_reason.as_deref())
+                .is_some_and(|r| r.starts_with("rollout unreadable; "))
+        );
+    }
+
+    #[test]
+    fn fresh_heartbeat_overrides_quiet_rollout() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let last = now - Duration::from_secs(STATUS_UNCERTAIN_MAX_AGE_SECS + 10);
+        let mut dbg = blank_dbg();
+        let status = classify_status(
+            now,
+            Some(last),
+            None,
+            Some(now - Duration::from_secs(3)),
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
+        assert!(matches!(status, SessionStatus::Working));
+        assert_eq!(dbg.status_reason.as_deref(), Some("recent heartbeat: 3s"));
+
+        let stale = classify_status(
+            now,
+            Some(last),
+            None,
+            Some(last),
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
+        assert!(matches!(stale, SessionStatus::Waiting));
+    }
+
+    #[test]
+    fn network_thresholds_keep_older_writes_working() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let last = now - Duration::from_secs(STATUS_WORKING_MAX_AGE_SECS + 10);
+        let mut dbg = blank_dbg();
+        let local = classify_status(
+            now,
+            Some(last),
+            None,
+            None,
+            &StatusThresholds::default(),
+            &mut dbg,
+        );
+        assert!(matches!(local, SessionStatus::Unknown));
+        let network = classify_status(
+            now,
+            Some(last),
+            None,
+            None,
+            &StatusThresholds::network(),
+            &mut dbg,
+        );
+        assert!(matches!(network, SessionStatus::Working));
+    }
+
+    #[test]
+    fn status_threshold_overrides() {
+        let t = StatusThresholds::default()
+            .with_overrides(Some(Duration::from_secs(120)), None)
+            .expect("working only");
+        assert_eq!(t.working_max_age, Duration::from_secs(120));
+        assert_eq!(t.uncertain_max_age, Duration::from_secs(120));
+
+        let t = StatusThresholds::network()
+            .with_overrides(None, Some(Duration::from_secs(300)))
+            .expect("uncertain only");
+        assert_eq!(t.working_max_age, Duration::from_secs(45));
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let last = now - Duration::from_secs(200);
+        let status = classify_status(now, Some(last), None, None, &t, &mut blank_dbg());
+        assert!(matches!(status, SessionStatus::Unknown));
+
+        assert!(
+            StatusThresholds::default()
+                .with_overrides(Some(Duration::from_secs(60)), Some(Duration::from_secs(30)))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn policy_skips_rows_reused_from_an_earlier_poll() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mut responses = HashMap::new();
+        responses.insert(
+            "home".to_string(),
+            Ok(r#"{"generated_at_unix_s":1,"host":"local","sessions":[
+                {"thread_id":"t1","pids":[7],"status":"waiting","last_activity_unix_s":1}]}"#
+                .to_string()),
+        );
+        let (mut collector, calls) = recording_collector(dir.path(), Vec::new(), responses);
+        let cfg: crate::policy::PolicyConfig = toml::from_str(
+            "enforce = true\n[[rules]]\nname = \"stale\"\naction = \"stop\"\nidle_over = \"24h\"",
+        )
+        .expect("parse");
+        let audit = crate::audit::AuditLog::new_at(dir.path().join("audit.jsonl"));
+        collector.set_policy(PolicyEngine::new_at(cfg, audit).expect("policy"));
+        let hosts = ["home".to_string()];
+        collector.collect(&hosts).expect("collect");
+
+        // An unfocused TUI an hour later: home's row is the cached one, as old as that poll.
+        collector.remote_cache.get_mut("home").expect("cached").0 -= 3600;
+        let cached = collector
+            .collect_with_remotes(&hosts, false)
+            .expect("collect");
+        assert!(
+            collector
+                .enforce_policy(&cached)
+                .expect("policy")
+                .is_empty()
+        );
+
+        let fresh = collector.collect(&hosts).expect("collect");
+        calls.lock().expect("calls").clear();
+        let acted = collector.enforce_policy(&fresh).expect("policy");
+        assert_eq!(acted.len(), 1);
+        assert_eq!(*calls.lock().expect("calls"), vec!["home: kill -STOP 7"]);
+    }
+
+    #[test]
+    fn probing_a_host_only_asks_for_capabilities() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mut responses = HashMap::new();
+        responses.insert(
+            "agg".to_string(),
+            Ok(r#"{"version":"9.9.9","features":["kill"]}"#.to_string()),
+        );
+        responses.insert("down".to_string(), Err("connection refused".to_string()));
+        let (mut collector, calls) = recording_collector(dir.path(), Vec::new(), responses);
+
+        collector.probe_host("local").expect("local");
+        collector.probe_host("via:agg").expect("probe");
+        assert!(collector.probe_host("down").is_err());
+        assert_eq!(
+            *calls.lock().expect("calls"),
+            vec!["agg: codex-ps capabilities", "down: codex-ps capabilities"]
+        );
+        assert_eq!(collector.generation, 0);
+        assert!(collector.health().hosts.is_empty());
+    }
+
+    #[test]
+    fn between_hosts_runs_before_each_remote_is_polled() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let mut responses = HashMap::new();
+        for host in ["a", "b"] {
+            responses.insert(
+                host.to_string(),
+                Ok(r#"{"generated_at_unix_s":1,"host":"local","sessions":[]}"#.to_string()),
+            );
+        }
+        let (mut collector, calls) = recording_collector(dir.path(), Vec::new(), responses);
+        let mut seen = Vec::new();
+        collector
+            .collect_progressive(
+                &["a".to_string(), "b".to_string()],
+                true,
+                &mut |_| {},
+                &mut |_| seen.push(calls.lock().expect("calls").len()),
+            )
+            .expect("collect");
+        assert_eq!(seen, vec![0, 1]);
+    }
+
+    #[test]
+    fn old_remotes_are_recognized_by_their_usage_exit() {
+        let failed = |code| {
+            anyhow::Error::from(RemoteCommandFailed {
+                label: "ssh old".into(),
+                status: crate::transport::exit_status(code),
+                stderr: "error: unrecognized subcommand 'capabilities'".into(),
+            })
+            .context("ssh old codex-ps capabilities")
+        };
+        assert!(rejected_usage(&failed(2)));
+        assert!(!rejected_usage(&failed(255)));
+        assert!(!rejected_usage(&anyhow::anyhow!(
+            "proxy: unrecognized subcommand"
+        )));
+    }
 }
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::budget::BudgetConfig;
+use crate::guard::GuardProfile;
+use crate::hooks::HooksConfig;
+use crate::names::config_dir;
+use crate::policy::PolicyConfig;
+use crate::schedule::ScheduleConfig;
+use crate::serve::ServeConfig;
+use crate::theme::ThemeConfig;
+
+/// `config.toml` next to the names store. Every section is optional.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Which actions need confirmation (`paranoid`, `normal`, `yolo`).
+    #[serde(default)]
+    pub guard: GuardProfile,
+    /// Remote hosts `--host all` covers (replaces the built-in list; `codex-ps init` writes it).
+    #[serde(default)]
+    pub hosts: Option<Vec<String>>,
+    /// Hosts left out of `--host all` (they can still be named explicitly).
+    #[serde(default)]
+    pub disabled_hosts: Vec<String>,
+    /// Short display names per host (`amirs-work-studio = "work"`).
+    #[serde(default)]
+    pub host_labels: BTreeMap<String, String>,
+    /// Propose names for unnamed sessions from their first prompt and repo.
+    #[serde(default)]
+    pub suggest_names: bool,
+    /// Quiet hours/days when the TUI stops collecting.
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    /// Extra regexes for thread ids in rollout file names, tried before the built-in ones.
+    #[serde(default)]
+    pub rollout_id_patterns: Vec<String>,
+    /// `codex-ps serve`: listen address, bearer token and CORS origins.
+    #[serde(default)]
+    pub serve: ServeConfig,
+    /// Sessions table columns in order, optionally with widths (`["state", "name:30", "pwd"]`).
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    /// Command `o` runs to resume a session in a new window (`{resume}`, `{thread_id}`,
+    /// `{cwd}`, `{host}`); defaults to a new tmux window.
+    #[serde(default)]
+    pub resume_command: Option<String>,
+    /// Advisory per-session limits (`max_tokens_per_session`, `max_session_duration`).
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    /// Opt-in rules that stop or kill neglected sessions (`enforce = true` to act).
+    #[serde(default)]
+    pub policy: PolicyConfig,
+    /// Start the TUI with rows grouped under a header per host.
+    #[serde(default)]
+    pub group_by_host: bool,
+    /// Defaults for the flags of the same name; a flag on the command line wins.
+    #[serde(default)]
+    pub refresh_ms: Option<u64>,
+    #[serde(default)]
+    pub ssh_bin: Option<String>,
+    #[serde(default)]
+    pub ssh_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub remote_bin: Option<String>,
+    /// Start in debug mode (`--debug`; `d` toggles it in the TUI).
+    #[serde(default)]
+    pub debug: bool,
+    /// Status classification windows (`--working-max-age-secs`, `--uncertain-max-age-secs`).
+    #[serde(default)]
+    pub working_max_age_secs: Option<u64>,
+    #[serde(default)]
+    pub uncertain_max_age_secs: Option<u64>,
+}
+
+impl Config {
+    /// Load `path` (or the default location). A missing default file is an empty config; a
+    /// missing explicit `--config` is an error.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let (path, explicit) = match path {
+            Some(p) => (p.to_path_buf(), true),
+            None => (default_config_path()?, false),
+        };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(t) => t,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !explicit => {
+                return Ok(Self::default());
+            }
+            Err(e) => return Err(e).with_context(|| format!("read {}", path.display())),
+        };
+        toml::from_str(&text).with_context(|| format!("parse {}", path.display()))
+    }
+}
+
+pub fn default_config_path() -> anyhow::Result<PathBuf> {
+    Ok(config_dir()?.join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hooks_section() {
+        let cfg: Config = toml::from_str(
+            r#"
+[hooks]
+on_needs_input = "notify-send 'codex needs you' \"$CODEX_PS_TITLE\""
+stuck_after_secs = 300
+"#,
+        )
+        .expect("parse");
+        assert!(cfg.hooks.on_needs_input.is_some());
+        assert!(cfg.hooks.on_session_start.is_none());
+        assert_eq!(cfg.hooks.stuck_after_secs, 300);
+
+        let empty: Config = toml::from_str("").expect("parse empty");
+        assert_eq!(empty.hooks.stuck_after_secs, 600);
+        assert_eq!(empty.hosts, None);
+
+        let flags: Config =
+            toml::from_str("refresh_ms = 2000\nssh_bin = \"/opt/ssh\"\ndebug = true")
+                .expect("parse");
+        assert_eq!(flags.refresh_ms, Some(2000));
+        assert_eq!(flags.ssh_bin.as_deref(), Some("/opt/ssh"));
+        assert!(flags.debug);
+        assert!(toml::from_str::<Config>("refresh = 2000").is_err());
+    }
+}
@@ -1,11 +1,12 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use anyhow::Context;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::codex_home::CodexHome;
 use crate::util::run_cmd_with_timeout;
 
 static UUID_LIKE: Lazy<Regex> = Lazy::new(|| {
@@ -13,40 +14,127 @@ static UUID_LIKE: Lazy<Regex> = Lazy::new(|| {
         .expect("uuid regex must compile")
 });
 
+/// Thread ids in rollout file names as Codex has written them, or might: a trailing UUID
+/// (whatever precedes it), or a trailing ULID after a separator.
+const BUILTIN_THREAD_ID_PATTERNS: [&str; 2] = [
+    r"(?i)(?P<id>[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12})$",
+    r"(?i)(?:^|[-_.])(?P<id>[0-9A-HJKMNP-TV-Z]{26})$",
+];
+
+/// A running (or, for `fs-scan`, presumed running) Codex process and the rollouts it holds.
 #[derive(Clone, Debug)]
-pub struct CodexLsofProcess {
-    pub pid: i32,
+pub struct CodexProcess {
+    /// `None` when the backend cannot observe processes (e.g. `fs-scan`).
+    pub pid: Option<i32>,
     pub exe: Option<PathBuf>,
     pub cwd: Option<PathBuf>,
     pub tty: Option<String>,
     pub rollout_paths: Vec<PathBuf>,
 }
 
+/// Source of truth for "which Codex sessions are active right now?".
+///
+/// Backends only report processes + rollout paths; everything else (metadata, status,
+/// titles, names) is derived by the collector so every backend yields identical rows.
+pub trait ProcessDiscovery: Send {
+    /// Short, stable label (matches the `--discovery` value).
+    fn name(&self) -> &'static str;
+
+    fn discover(&self, codex_home: &CodexHome) -> anyhow::Result<Vec<CodexProcess>>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiscoveryBackend {
+    /// `procfs` when `/proc` is available (Linux), `sysinfo` on Windows, otherwise `lsof`.
+    Auto,
+    /// One `lsof -c codex` call (macOS default).
+    Lsof,
+    /// Walk `/proc/<pid>/fd` (Linux).
+    Procfs,
+    /// Cross-platform process table via the `sysinfo` crate (built in on Windows; elsewhere
+    /// requires the `sysinfo` feature).
+    Sysinfo,
+    /// No process info: treat recently written rollouts under CODEX_HOME as active.
+    FsScan,
+}
+
+/// `proc_root` (`--proc-root`) points the procfs backend at another procfs mount, such as
+/// the host's `/proc` bind-mounted into a sandbox.
+pub fn discovery_backend(
+    kind: DiscoveryBackend,
+    timeout: Duration,
+    proc_root: Option<PathBuf>,
+) -> anyhow::Result<Box<dyn ProcessDiscovery>> {
+    if let Some(root) = proc_root {
+        if !matches!(kind, DiscoveryBackend::Auto | DiscoveryBackend::Procfs) {
+            anyhow::bail!("--proc-root only applies to --discovery procfs");
+        }
+        if !root.is_dir() {
+            anyhow::bail!("--proc-root {}: not a directory", root.display());
+        }
+        return Ok(Box::new(ProcfsDiscovery { root }));
+    }
+    Ok(match kind {
+        DiscoveryBackend::Auto if cfg!(windows) => sysinfo_backend()?,
+        DiscoveryBackend::Auto if Path::new("/proc/self/fd").is_dir() => {
+            Box::new(ProcfsDiscovery::default())
+        }
+        DiscoveryBackend::Auto | DiscoveryBackend::Lsof => Box::new(LsofDiscovery { timeout }),
+        DiscoveryBackend::Procfs => Box::new(ProcfsDiscovery::default()),
+        DiscoveryBackend::Sysinfo => sysinfo_backend()?,
+        DiscoveryBackend::FsScan => Box::new(FsScanDiscovery {
+            max_age: FS_SCAN_DEFAULT_MAX_AGE,
+        }),
+    })
+}
+
+#[cfg(any(feature = "sysinfo", windows))]
+fn sysinfo_backend() -> anyhow::Result<Box<dyn ProcessDiscovery>> {
+    Ok(Box::new(SysinfoDiscovery {
+        max_age: FS_SCAN_DEFAULT_MAX_AGE,
+    }))
+}
+
+#[cfg(not(any(feature = "sysinfo", windows)))]
+fn sysinfo_backend() -> anyhow::Result<Box<dyn ProcessDiscovery>> {
+    anyhow::bail!("--discovery sysinfo requires building codex-ps with `--features sysinfo`")
+}
+
 /// Fastest robust SSOT we have on macOS: "active session" == a running `codex` process
 /// that holds one or more rollout files open under `CODEX_HOME`.
 ///
 /// Uses a single `lsof` call (instead of per-PID) to keep work bounded.
-pub fn lsof_codex_processes(
-    codex_home: &Path,
-    timeout: Duration,
-) -> anyhow::Result<Vec<CodexLsofProcess>> {
-    let mut cmd = Command::new("lsof");
-    cmd.args(["-n", "-P", "-c", "codex", "-F", "pfn"]);
-    let output = run_cmd_with_timeout(cmd, timeout).context("lsof -c codex")?;
-
-    if !output.status.success() {
-        // On macOS, `lsof -c <name>` commonly returns exit code 1 when there are no matches.
-        // That should mean "zero sessions", not "the collector is broken."
-        if output.status.code() == Some(1) {
-            return Ok(Vec::new());
-        }
-        anyhow::bail!("lsof failed with status {}", output.status);
+pub struct LsofDiscovery {
+    pub timeout: Duration,
+}
+
+impl ProcessDiscovery for LsofDiscovery {
+    fn name(&self) -> &'static str {
+        "lsof"
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    fn discover(&self, codex_home: &CodexHome) -> anyhow::Result<Vec<CodexProcess>> {
+        let mut cmd = Command::new("lsof");
+        cmd.args(["-n", "-P", "-c", "codex", "-F", "pfn"]);
+        let output = run_cmd_with_timeout(cmd, self.timeout).context("lsof -c codex")?;
 
-    let mut procs: Vec<CodexLsofProcess> = Vec::new();
-    let mut current: Option<CodexLsofProcess> = None;
+        if !output.status.success() {
+            // On macOS, `lsof -c <name>` commonly returns exit code 1 when there are no matches.
+            // That should mean "zero sessions", not "the collector is broken."
+            if output.status.code() == Some(1) {
+                return Ok(Vec::new());
+            }
+            anyhow::bail!("lsof failed with status {}", output.status);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(retain_cli_sessions(parse_lsof_output(&stdout, codex_home)))
+    }
+}
+
+fn parse_lsof_output(stdout: &str, codex_home: &CodexHome) -> Vec<CodexProcess> {
+    let mut procs: Vec<CodexProcess> = Vec::new();
+    let mut current: Option<CodexProcess> = None;
     let mut current_fd: Option<String> = None;
 
     for line in stdout.lines() {
@@ -61,8 +149,8 @@ pub fn lsof_codex_processes(
                 Err(_) => continue,
             };
 
-            current = Some(CodexLsofProcess {
-                pid,
+            current = Some(CodexProcess {
+                pid: Some(pid),
                 exe: None,
                 cwd: None,
                 tty: None,
@@ -99,8 +187,7 @@ pub fn lsof_codex_processes(
                 _ => {}
             }
 
-            if name.contains("rollout-") && name.ends_with(".jsonl") && path.starts_with(codex_home)
-            {
+            if is_rollout_path(&path, codex_home) {
                 p.rollout_paths.push(path);
             }
         }
@@ -110,7 +197,282 @@ pub fn lsof_codex_processes(
         procs.push(p);
     }
 
-    Ok(procs
+    procs
+}
+
+/// The sandbox codex-ps runs in, if any: there the process table (and `lsof`) usually
+/// shows only the sandbox's own processes, not the host's Codex sessions.
+pub fn detect_sandbox() -> Option<&'static str> {
+    if Path::new("/.flatpak-info").exists() || std::env::var_os("FLATPAK_ID").is_some() {
+        Some("a Flatpak sandbox")
+    } else if std::env::var_os("SNAP_NAME").is_some() {
+        Some("a Snap sandbox")
+    } else if Path::new("/.dockerenv").exists() || Path::new("/run/.containerenv").exists() {
+        Some("a container")
+    } else {
+        None
+    }
+}
+
+/// Linux: read `<root>/<pid>/{comm,cmdline,exe,cwd,fd}` directly (no external tools).
+pub struct ProcfsDiscovery {
+    /// `/proc`, or a host procfs mounted elsewhere (`--proc-root`).
+    pub root: PathBuf,
+}
+
+impl Default for ProcfsDiscovery {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("/proc"),
+        }
+    }
+}
+
+impl ProcessDiscovery for ProcfsDiscovery {
+    fn name(&self) -> &'static str {
+        "procfs"
+    }
+
+    fn discover(&self, codex_home: &CodexHome) -> anyhow::Result<Vec<CodexProcess>> {
+        let entries = std::fs::read_dir(&self.root)
+            .with_context(|| format!("read {}", self.root.display()))?;
+
+        let mut procs: Vec<CodexProcess> = Vec::new();
+        for entry in entries.flatten() {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<i32>().ok())
+            else {
+                continue;
+            };
+            let dir = entry.path();
+
+            // Processes exit (or are owned by other users) all the time; skip quietly.
+            let Ok(comm) = std::fs::read_to_string(dir.join("comm")) else {
+                continue;
+            };
+            let cmdline = std::fs::read(dir.join("cmdline")).unwrap_or_default();
+            if !is_codex_process(&comm, &cmdline) {
+                continue;
+            }
+
+            let mut p = CodexProcess {
+                pid: Some(pid),
+                exe: std::fs::read_link(dir.join("exe")).ok(),
+                cwd: std::fs::read_link(dir.join("cwd")).ok(),
+                tty: None,
+                rollout_paths: Vec::new(),
+            };
+
+            let Ok(fds) = std::fs::read_dir(dir.join("fd")) else {
+                continue;
+            };
+            for fd in fds.flatten() {
+                let Ok(target) = std::fs::read_link(fd.path()) else {
+                    continue;
+                };
+                let fd_name = fd.file_name();
+                if p.tty.is_none() && matches!(fd_name.to_str(), Some("0" | "1" | "2")) {
+                    p.tty = tty_name(&target);
+                }
+                if is_rollout_path(&target, codex_home) && !p.rollout_paths.contains(&target) {
+                    p.rollout_paths.push(target);
+                }
+            }
+
+            procs.push(p);
+        }
+
+        Ok(retain_cli_sessions(procs))
+    }
+}
+
+/// `lsof -c codex` semantics (command name prefix), plus `argv[0]`: `comm` is cut to 15
+/// bytes and a process can rename itself, but the command line keeps the binary's name.
+fn is_codex_process(comm: &str, cmdline: &[u8]) -> bool {
+    if comm.trim().starts_with("codex") {
+        return true;
+    }
+    let argv0 = cmdline.split(|b| *b == 0).next().unwrap_or_default();
+    Path::new(&*String::from_utf8_lossy(argv0))
+        .file_name()
+        .is_some_and(|n| n.to_string_lossy().starts_with("codex"))
+}
+
+fn tty_name(path: &Path) -> Option<String> {
+    let s = path.to_str()?;
+    let rest = s.strip_prefix("/dev/")?;
+    if rest.starts_with("tty") || rest.starts_with("pts/") {
+        Some(rest.to_string())
+    } else {
+        None
+    }
+}
+
+const FS_SCAN_DEFAULT_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Last-resort backend: no process table at all. Any rollout written within `max_age`
+/// counts as an active session (no PID/TTY/cwd; the collector falls back to `session_meta`).
+pub struct FsScanDiscovery {
+    pub max_age: Duration,
+}
+
+impl ProcessDiscovery for FsScanDiscovery {
+    fn name(&self) -> &'static str {
+        "fs-scan"
+    }
+
+    fn discover(&self, codex_home: &CodexHome) -> anyhow::Result<Vec<CodexProcess>> {
+        Ok(
+            recent_rollouts(codex_home, self.max_age, SystemTime::now())?
+                .into_iter()
+                .map(|path| CodexProcess {
+                    pid: None,
+                    exe: None,
+                    cwd: None,
+                    tty: None,
+                    rollout_paths: vec![path],
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Rollouts under `<codex_home>/sessions` modified within `max_age` of `now`.
+pub fn recent_rollouts(
+    codex_home: &CodexHome,
+    max_age: Duration,
+    now: SystemTime,
+) -> anyhow::Result<Vec<PathBuf>> {
+    rollouts_where(codex_home, |mtime| {
+        mtime
+            .and_then(|m| now.duration_since(m).ok())
+            .is_some_and(|age| age <= max_age)
+    })
+}
+
+/// Every rollout under CODEX_HOME, however old.
+pub fn all_rollouts(codex_home: &CodexHome) -> anyhow::Result<Vec<PathBuf>> {
+    rollouts_where(codex_home, |_| true)
+}
+
+fn rollouts_where(
+    codex_home: &CodexHome,
+    keep: impl Fn(Option<SystemTime>) -> bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let sessions = codex_home.root.join("sessions");
+    if !sessions.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut out: Vec<PathBuf> = Vec::new();
+    let mut stack: Vec<PathBuf> = vec![sessions];
+    while let Some(dir) = stack.pop() {
+        let entries = std::fs::read_dir(&dir).with_context(|| format!("read {}", dir.display()))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(ft) = entry.file_type() else { continue };
+            if ft.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            if !is_rollout_path(&path, codex_home) {
+                continue;
+            }
+            if keep(entry.metadata().and_then(|m| m.modified()).ok()) {
+                out.push(path);
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// Cross-platform process table (pid/exe/cwd) from `sysinfo`. Open files aren't portable,
+/// so rollouts are attributed by matching recent rollouts' `session_meta.cwd` to the
+/// process cwd.
+#[cfg(any(feature = "sysinfo", windows))]
+pub struct SysinfoDiscovery {
+    pub max_age: Duration,
+}
+
+#[cfg(any(feature = "sysinfo", windows))]
+impl ProcessDiscovery for SysinfoDiscovery {
+    fn name(&self) -> &'static str {
+        "sysinfo"
+    }
+
+    fn discover(&self, codex_home: &CodexHome) -> anyhow::Result<Vec<CodexProcess>> {
+        use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cwd(UpdateKind::OnlyIfNotSet),
+        );
+
+        let mut procs: Vec<CodexProcess> = sys
+            .processes()
+            .iter()
+            .filter(|(_, p)| p.name().to_string_lossy().starts_with("codex"))
+            .filter_map(|(pid, p)| {
+                Some(CodexProcess {
+                    pid: Some(i32::try_from(pid.as_u32()).ok()?),
+                    exe: p.exe().map(Path::to_path_buf),
+                    cwd: p.cwd().map(Path::to_path_buf),
+                    tty: None,
+                    rollout_paths: Vec::new(),
+                })
+            })
+            .collect();
+
+        for rollout in recent_rollouts(codex_home, self.max_age, SystemTime::now())? {
+            let Some(cwd) = crate::rollout::read_session_meta(&rollout)
+                .ok()
+                .and_then(|m| m.cwd)
+            else {
+                continue;
+            };
+            if let Some(p) = procs
+                .iter_mut()
+                .find(|p| p.cwd.as_deref() == Some(Path::new(&cwd)))
+            {
+                p.rollout_paths.push(rollout);
+            }
+        }
+
+        Ok(retain_cli_sessions(procs))
+    }
+}
+
+/// Injectable backend for tests: returns a fixed process list.
+#[cfg(test)]
+pub struct MockDiscovery {
+    pub procs: Vec<CodexProcess>,
+}
+
+#[cfg(test)]
+impl ProcessDiscovery for MockDiscovery {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn discover(&self, _codex_home: &CodexHome) -> anyhow::Result<Vec<CodexProcess>> {
+        Ok(self.procs.clone())
+    }
+}
+
+fn is_rollout_path(path: &Path, codex_home: &CodexHome) -> bool {
+    let name = path.to_string_lossy();
+    name.contains("rollout-") && name.ends_with(".jsonl") && codex_home.contains(path)
+}
+
+fn retain_cli_sessions(procs: Vec<CodexProcess>) -> Vec<CodexProcess> {
+    procs
         .into_iter()
         .filter(|p| !p.rollout_paths.is_empty())
         // Keep this tool scoped to CLI sessions; the Electron desktop app can hold
@@ -120,24 +482,77 @@ pub fn lsof_codex_processes(
                 .as_ref()
                 .is_none_or(|exe| !exe.to_string_lossy().contains("/Applications/Codex.app/"))
         })
-        .collect())
+        .collect()
 }
 
-pub fn extract_thread_id_from_rollout_path(path: &Path) -> Option<String> {
-    let name = path.file_name()?.to_string_lossy();
-    let stem = name.strip_suffix(".jsonl")?;
-    if stem.len() < 36 {
-        return None;
+/// Regexes that find a thread id in a rollout's file name (`.jsonl` stripped), tried in
+/// order. The `id` group is the id (the whole match if there is none); UUIDs are lowercased.
+#[derive(Clone, Debug)]
+pub struct ThreadIdPatterns(Vec<Regex>);
+
+impl Default for ThreadIdPatterns {
+    fn default() -> Self {
+        Self(
+            BUILTIN_THREAD_ID_PATTERNS
+                .iter()
+                .map(|p| Regex::new(p).expect("thread id regex must compile"))
+                .collect(),
+        )
     }
-    let candidate = &stem[stem.len() - 36..];
-    let candidate = candidate.to_ascii_lowercase();
-    if UUID_LIKE.is_match(&candidate) {
-        Some(candidate)
-    } else {
-        None
+}
+
+impl ThreadIdPatterns {
+    /// `extra` (`rollout_id_patterns` in the config) are tried before the built-in ones.
+    pub fn new(extra: &[String]) -> anyhow::Result<Self> {
+        let mut patterns = extra
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("invalid rollout_id_pattern {p:?}")))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        patterns.extend(Self::default().0);
+        Ok(Self(patterns))
+    }
+
+    pub fn extract(&self, path: &Path) -> Option<String> {
+        let name = path.file_name()?.to_string_lossy();
+        let stem = name.strip_suffix(".jsonl")?;
+        self.0.iter().find_map(|re| {
+            let caps = re.captures(stem)?;
+            let id = caps.name("id").or_else(|| caps.get(0))?.as_str();
+            let lower = id.to_ascii_lowercase();
+            match () {
+                _ if id.is_empty() => None,
+                _ if UUID_LIKE.is_match(&lower) => Some(lower),
+                _ => Some(id.to_string()),
+            }
+        })
+    }
+
+    /// `meta_id` (the file's `session_meta.id`) when there is one, so a renamed or copied
+    /// rollout stays one session and an unrecognized name doesn't drop it; otherwise the id
+    /// in the file name. A differing file name id is returned as an alias.
+    pub fn resolve(&self, path: &Path, meta_id: Option<&str>) -> Option<RolloutIds> {
+        let from_name = self.extract(path);
+        match meta_id.filter(|id| !id.is_empty()) {
+            Some(id) => Some(RolloutIds {
+                thread_id: id.to_string(),
+                alias: from_name.filter(|n| n != id),
+            }),
+            None => from_name.map(|thread_id| RolloutIds {
+                thread_id,
+                alias: None,
+            }),
+        }
     }
 }
 
+/// Which thread a rollout belongs to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RolloutIds {
+    pub thread_id: String,
+    /// The file name's id when it disagrees with `session_meta.id`.
+    pub alias: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,7 +563,7 @@ mod tests {
             "/Users/aelaguiz/.codex/sessions/2026/02/03/rollout-2026-02-03T16-12-22-019c2590-5605-7cd1-81b8-8a488af219a3.jsonl",
         );
         assert_eq!(
-            extract_thread_id_from_rollout_path(&p).as_deref(),
+            ThreadIdPatterns::default().extract(&p).as_deref(),
             Some("019c2590-5605-7cd1-81b8-8a488af219a3")
         );
     }
@@ -156,6 +571,144 @@ mod tests {
     #[test]
     fn extract_thread_id_rejects_non_uuid_suffix() {
         let p = PathBuf::from("/tmp/rollout-2026-02-03T00-00-00-not-a-uuid.jsonl");
-        assert!(extract_thread_id_from_rollout_path(&p).is_none());
+        assert!(ThreadIdPatterns::default().extract(&p).is_none());
+    }
+
+    #[test]
+    fn thread_id_patterns_cover_new_shapes_and_defer_to_session_meta() {
+        let default = ThreadIdPatterns::default();
+        let id = |p: &ThreadIdPatterns, name: &str| p.extract(Path::new(name));
+        assert_eq!(
+            id(
+                &default,
+                "/s/session_20260203-161222_019C2590-5605-7CD1-81B8-8A488AF219A3.jsonl"
+            )
+            .as_deref(),
+            Some("019c2590-5605-7cd1-81b8-8a488af219a3")
+        );
+        assert_eq!(
+            id(
+                &default,
+                "/s/rollout-1770135142-01KGHS2Q8V3J5W6X7Y8Z9ABCDE.jsonl"
+            )
+            .as_deref(),
+            Some("01KGHS2Q8V3J5W6X7Y8Z9ABCDE")
+        );
+
+        let custom =
+            ThreadIdPatterns::new(&[r"^thread\.(?P<id>\w+)\.".to_string()]).expect("patterns");
+        assert_eq!(
+            id(&custom, "/s/thread.abc123.2026-02-03.jsonl").as_deref(),
+            Some("abc123")
+        );
+        assert!(ThreadIdPatterns::new(&["(".to_string()]).is_err());
+
+        let p = Path::new("/s/rollout-odd-name.jsonl");
+        let meta_id = Some("019c2590-5605-7cd1-81b8-8a488af219a3");
+        assert_eq!(default.extract(p), None);
+        assert_eq!(
+            default.resolve(p, meta_id),
+            Some(RolloutIds {
+                thread_id: "019c2590-5605-7cd1-81b8-8a488af219a3".into(),
+                alias: None,
+            })
+        );
+
+        // A copied rollout keeps its original session's id; the file name's is an alias.
+        let copy =
+            Path::new("/s/rollout-2026-02-04T09-00-00-019c2590-0000-7000-8000-000000000001.jsonl");
+        assert_eq!(
+            default.resolve(copy, meta_id),
+            Some(RolloutIds {
+                thread_id: "019c2590-5605-7cd1-81b8-8a488af219a3".into(),
+                alias: Some("019c2590-0000-7000-8000-000000000001".into()),
+            })
+        );
+        assert_eq!(
+            default
+                .resolve(copy, None)
+                .map(|ids| ids.thread_id)
+                .as_deref(),
+            Some("019c2590-0000-7000-8000-000000000001")
+        );
+    }
+
+    #[test]
+    fn procfs_matches_comm_or_argv0() {
+        assert!(is_codex_process("codex\n", b""));
+        assert!(is_codex_process(
+            "MainThread\n",
+            b"/usr/local/bin/codex\0resume\0abc\0"
+        ));
+        assert!(!is_codex_process("node\n", b"node\0/opt/codex/cli.js\0"));
+        assert!(!is_codex_process("bash\n", b""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn procfs_reads_an_alternate_proc_root() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let home = CodexHome::at(dir.path().join("codex"));
+        let rollout = dir.path().join("codex/sessions/rollout-x.jsonl");
+        let pid = dir.path().join("hostproc/4242");
+        std::fs::create_dir_all(pid.join("fd")).expect("mkdir");
+        std::fs::write(pid.join("comm"), "codex\n").expect("comm");
+        std::os::unix::fs::symlink(&rollout, pid.join("fd/7")).expect("symlink");
+        std::fs::create_dir_all(dir.path().join("hostproc/self")).expect("mkdir");
+
+        let backend = discovery_backend(
+            DiscoveryBackend::Auto,
+            Duration::from_secs(1),
+            Some(dir.path().join("hostproc")),
+        )
+        .expect("backend");
+        let procs = backend.discover(&home).expect("discover");
+        assert_eq!(procs.len(), 1);
+        assert_eq!(procs[0].pid, Some(4242));
+        assert_eq!(procs[0].rollout_paths, vec![rollout]);
+        assert!(
+            discovery_backend(
+                DiscoveryBackend::Lsof,
+                Duration::from_secs(1),
+                Some(dir.path().join("hostproc")),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_lsof_output_collects_rollouts_under_codex_home() {
+        let out = "p123\nfcwd\nn/work/repo\nftxt\nn/usr/local/bin/codex\nf1\nn/dev/ttys004\nf20\nn/home/u/.codex/sessions/2026/02/03/rollout-x.jsonl\nf21\nn/tmp/rollout-y.jsonl\n";
+        let procs = parse_lsof_output(out, &CodexHome::at(PathBuf::from("/home/u/.codex")));
+        assert_eq!(procs.len(), 1);
+        let p = &procs[0];
+        assert_eq!(p.pid, Some(123));
+        assert_eq!(p.cwd.as_deref(), Some(Path::new("/work/repo")));
+        assert_eq!(p.tty.as_deref(), Some("ttys004"));
+        assert_eq!(
+            p.rollout_paths,
+            vec![PathBuf::from(
+                "/home/u/.codex/sessions/2026/02/03/rollout-x.jsonl"
+            )]
+        );
+    }
+
+    #[test]
+    fn fs_scan_only_returns_recent_rollouts() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let day = dir.path().join("sessions/2026/02/03");
+        std::fs::create_dir_all(&day).expect("mkdir");
+        let p = day.join("rollout-2026-02-03T00-00-00-019c2590-5605-7cd1-81b8-8a488af219a3.jsonl");
+        std::fs::write(&p, "{}\n").expect("write");
+        std::fs::write(day.join("notes.txt"), "x").expect("write");
+
+        let home = CodexHome::at(dir.path().to_path_buf());
+        let now = SystemTime::now();
+        let found = recent_rollouts(&home, Duration::from_secs(60), now).expect("scan");
+        assert_eq!(found, vec![p]);
+
+        let later = now + Duration::from_secs(3600);
+        let found = recent_rollouts(&home, Duration::from_secs(60), later).expect("scan");
+        assert!(found.is_empty());
     }
 }
@@ -0,0 +1,194 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::transport::RemoteCommandFailed;
+
+/// Coarse failure category, stable in JSON so consumers can branch on it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Local process discovery failed (lsof/procfs/...).
+    Discovery,
+    /// Could not reach a remote host (spawn/connect).
+    Transport,
+    /// A command or connection exceeded its deadline.
+    Timeout,
+    /// The remote command ran but exited non-zero.
+    RemoteExit,
+    /// Malformed JSON/JSONL (remote snapshot, rollout, state files).
+    Parse,
+    PermissionDenied,
+    NotFound,
+    /// Other filesystem / OS errors.
+    Io,
+    /// `git` probing failed.
+    Git,
+    /// The session names store could not be read or written.
+    NamesStore,
+    /// Rollout file is present but not in the expected shape.
+    Rollout,
+    /// Environment/configuration worth surfacing (e.g. a relocated CODEX_HOME).
+    Config,
+    #[default]
+    Other,
+}
+
+impl ErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Discovery => "discovery",
+            ErrorKind::Transport => "transport",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::RemoteExit => "remote_exit",
+            ErrorKind::Parse => "parse",
+            ErrorKind::PermissionDenied => "permission_denied",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Io => "io",
+            ErrorKind::Git => "git",
+            ErrorKind::NamesStore => "names_store",
+            ErrorKind::Rollout => "rollout",
+            ErrorKind::Config => "config",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
+/// Structured error/warning payload: `kind` for machines, `message` (root cause) for humans,
+/// `context` (outermost first) for "what were we doing?".
+///
+/// Deserializes from a bare string too, so snapshots from older `codex-ps` versions
+/// (stringly-typed warnings/debug fields) still aggregate.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ErrorInfoRepr")]
+pub struct ErrorInfo {
+    pub kind: ErrorKind,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
+    /// Diagnostic detail, shown only in debug mode (`--debug`, `d` in the TUI).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub verbose: bool,
+}
+
+impl ErrorInfo {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            context: Vec::new(),
+            verbose: false,
+        }
+    }
+
+    pub fn verbose(mut self) -> Self {
+        self.verbose = true;
+        self
+    }
+
+    /// Classify an `anyhow` chain; `fallback` is used when no cause has a recognizable type.
+    pub fn from_anyhow(fallback: ErrorKind, err: &anyhow::Error) -> Self {
+        let kind = classify(err).unwrap_or(fallback);
+        let mut chain: Vec<String> = err.chain().map(|c| c.to_string()).collect();
+        let message = chain.pop().unwrap_or_default();
+        Self {
+            kind,
+            message,
+            context: chain,
+            verbose: false,
+        }
+    }
+}
+
+impl fmt::Display for ErrorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in &self.context {
+            write!(f, "{c}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ErrorInfoRepr {
+    Structured {
+        #[serde(default)]
+        kind: ErrorKind,
+        message: String,
+        #[serde(default)]
+        context: Vec<String>,
+        #[serde(default)]
+        verbose: bool,
+    },
+    Legacy(String),
+}
+
+impl From<ErrorInfoRepr> for ErrorInfo {
+    fn from(r: ErrorInfoRepr) -> Self {
+        match r {
+            ErrorInfoRepr::Structured {
+                kind,
+                message,
+                context,
+                verbose,
+            } => Self {
+                kind,
+                message,
+                context,
+                verbose,
+            },
+            ErrorInfoRepr::Legacy(message) => Self::new(ErrorKind::Other, message),
+        }
+    }
+}
+
+fn classify(err: &anyhow::Error) -> Option<ErrorKind> {
+    for cause in err.chain() {
+        if cause.downcast_ref::<RemoteCommandFailed>().is_some() {
+            return Some(ErrorKind::RemoteExit);
+        }
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            return Some(match io.kind() {
+                std::io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+                std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+                std::io::ErrorKind::TimedOut => ErrorKind::Timeout,
+                _ => ErrorKind::Io,
+            });
+        }
+        if cause.downcast_ref::<serde_json::Error>().is_some() {
+            return Some(ErrorKind::Parse);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn from_anyhow_classifies_io_and_splits_context() {
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "nope");
+        let err = Err::<(), _>(io).context("open rollout: /x").unwrap_err();
+        let info = ErrorInfo::from_anyhow(ErrorKind::Other, &err);
+        assert_eq!(info.kind, ErrorKind::PermissionDenied);
+        assert_eq!(info.message, "nope");
+        assert_eq!(info.context, vec!["open rollout: /x".to_string()]);
+        assert_eq!(info.to_string(), "open rollout: /x: nope");
+    }
+
+    #[test]
+    fn deserializes_legacy_string_and_structured_forms() {
+        let legacy: ErrorInfo = serde_json::from_str(r#""ssh home failed""#).expect("legacy");
+        assert_eq!(legacy.kind, ErrorKind::Other);
+        assert_eq!(legacy.message, "ssh home failed");
+
+        let structured: ErrorInfo =
+            serde_json::from_str(r#"{"kind":"timeout","message":"timed out"}"#)
+                .expect("structured");
+        assert_eq!(structured.kind, ErrorKind::Timeout);
+        assert!(structured.context.is_empty());
+    }
+}
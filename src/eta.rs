@@ -0,0 +1,202 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::names::config_dir;
+use crate::rollout::CompletedCall;
+
+/// Durations kept per command prefix; older ones stop counting.
+const MAX_SAMPLES: usize = 20;
+
+/// Runs of a prefix needed before an ETA is shown.
+const MIN_SAMPLES: usize = 2;
+
+/// Commands that usually finish faster than this get no ETA.
+const MIN_TYPICAL_SECS: i64 = 10;
+
+/// Append-only log of how long tool calls took (`call_durations.jsonl`), keyed by command
+/// prefix, so a running call can be compared with its usual duration.
+#[derive(Clone, Debug)]
+pub struct CallDurations {
+    path: PathBuf,
+    loaded: bool,
+    /// Call ids already recorded (the same tail is read many times).
+    seen: HashSet<String>,
+    samples: HashMap<String, VecDeque<i64>>,
+}
+
+impl CallDurations {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self::new_at(config_dir()?.join("call_durations.jsonl")))
+    }
+
+    pub fn new_at(path: PathBuf) -> Self {
+        Self {
+            path,
+            loaded: false,
+            seen: HashSet::new(),
+            samples: HashMap::new(),
+        }
+    }
+
+    /// Record the calls not seen before.
+    pub fn observe(&mut self, calls: &[CompletedCall]) -> anyhow::Result<()> {
+        if !self.loaded {
+            self.load()?;
+        }
+        for call in calls {
+            if self.seen.contains(&call.call_id) {
+                continue;
+            }
+            let Some(prefix) = command_prefix(&call.summary) else {
+                continue;
+            };
+            let rec = DurationRecord {
+                prefix,
+                call_id: call.call_id.clone(),
+                secs: call.secs,
+            };
+            self.append_record(&rec)?;
+            self.insert(rec);
+        }
+        Ok(())
+    }
+
+    /// Median duration of `summary`'s command prefix, once it has run `MIN_SAMPLES` times
+    /// and when that is long enough to be worth an ETA.
+    pub fn typical_secs(&self, summary: &str) -> Option<(String, i64)> {
+        let prefix = command_prefix(summary)?;
+        let samples = self.samples.get(&prefix)?;
+        if samples.len() < MIN_SAMPLES {
+            return None;
+        }
+        let mut sorted: Vec<i64> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let median = sorted[sorted.len() / 2];
+        (median >= MIN_TYPICAL_SECS).then_some((prefix, median))
+    }
+
+    fn insert(&mut self, rec: DurationRecord) {
+        self.seen.insert(rec.call_id);
+        let samples = self.samples.entry(rec.prefix).or_default();
+        samples.push_back(rec.secs);
+        if samples.len() > MAX_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    fn load(&mut self) -> anyhow::Result<()> {
+        self.loaded = true;
+        let f = match fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("open {}", self.path.display())),
+        };
+
+        for (i, line) in BufReader::new(f).lines().enumerate() {
+            let line = line.context("read line")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let rec: DurationRecord = serde_json::from_str(&line)
+                .with_context(|| format!("parse call_durations.jsonl line {}", i + 1))?;
+            self.insert(rec);
+        }
+        Ok(())
+    }
+
+    fn append_record(&self, rec: &DurationRecord) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create dir {}", parent.display()))?;
+        }
+        let line = serde_json::to_string(rec).context("serialize duration record")?;
+        let mut f = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("open for append {}", self.path.display()))?;
+        writeln!(f, "{line}").context("append duration record")?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct DurationRecord {
+    prefix: String,
+    call_id: String,
+    secs: i64,
+}
+
+/// What makes runs comparable: the program and its subcommand, e.g. `cargo test` for
+/// `cargo test -p core --lib`. Leading `VAR=value` assignments are skipped.
+pub fn command_prefix(summary: &str) -> Option<String> {
+    let mut words = summary
+        .split_whitespace()
+        .skip_while(|w| w.contains('=') && !w.starts_with('-'));
+    let program = words.next()?;
+    Some(match words.next() {
+        Some(sub)
+            if !sub.starts_with('-')
+                && sub
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == ':') =>
+        {
+            format!("{program} {sub}")
+        }
+        _ => program.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(id: &str, summary: &str, secs: i64) -> CompletedCall {
+        CompletedCall {
+            call_id: id.into(),
+            summary: summary.into(),
+            secs,
+        }
+    }
+
+    #[test]
+    fn median_by_prefix_survives_reload_without_double_counting() {
+        assert_eq!(
+            command_prefix("RUST_LOG=debug cargo test -p core").as_deref(),
+            Some("cargo test")
+        );
+        assert_eq!(command_prefix("ls -la src/").as_deref(), Some("ls"));
+
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let p = dir.path().join("call_durations.jsonl");
+        let mut d = CallDurations::new_at(p.clone());
+        d.observe(&[call("c1", "cargo test", 200)])
+            .expect("observe");
+        assert_eq!(d.typical_secs("cargo test --lib"), None);
+        d.observe(&[
+            call("c1", "cargo test", 200),
+            call("c2", "cargo test -q", 240),
+        ])
+        .expect("observe");
+        d.observe(&[call("c3", "cargo test", 260)])
+            .expect("observe");
+        assert_eq!(
+            d.typical_secs("cargo test --lib"),
+            Some(("cargo test".to_string(), 240))
+        );
+
+        let mut reloaded = CallDurations::new_at(p);
+        reloaded
+            .observe(&[call("c3", "cargo test", 260)])
+            .expect("observe");
+        assert_eq!(
+            reloaded.typical_secs("cargo test"),
+            Some(("cargo test".to_string(), 240))
+        );
+    }
+}
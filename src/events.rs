@@ -0,0 +1,290 @@
+use std::io::Write;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::audit::AuditRecord;
+use crate::changes::{ChangeTracker, SnapshotChanges};
+use crate::collector::Collector;
+use crate::error::ErrorKind;
+use crate::health::Health;
+use crate::hooks::HookRunner;
+use crate::model::{SessionRow, SessionStatus, Snapshot};
+use crate::receipt::SessionReceipt;
+use crate::timefmt::to_iso8601_utc;
+use crate::util::system_time_to_unix_s;
+
+/// One NDJSON line of `--events` output.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    SessionStarted {
+        #[serde(flatten)]
+        meta: EventMeta,
+        /// True for sessions already running when codex-ps started.
+        initial: bool,
+        session: SessionRow,
+    },
+    StatusChanged {
+        #[serde(flatten)]
+        meta: EventMeta,
+        from: SessionStatus,
+        to: SessionStatus,
+        session: SessionRow,
+    },
+    /// A turn that ran for at least `LONG_TURN_SECS` completed.
+    TurnCompleted {
+        #[serde(flatten)]
+        meta: EventMeta,
+        worked_secs: i64,
+        message: String,
+        session: SessionRow,
+    },
+    SessionEnded {
+        #[serde(flatten)]
+        meta: EventMeta,
+        /// Last row seen before the session disappeared.
+        session: SessionRow,
+        /// Duration, turns, tokens and commit/push state of the finished run.
+        receipt: Option<SessionReceipt>,
+        /// One-line rendering of `receipt`.
+        summary: Option<String>,
+    },
+    /// A session went past a `[budget]` limit; `reason` says which.
+    OverBudget {
+        #[serde(flatten)]
+        meta: EventMeta,
+        reason: String,
+        session: SessionRow,
+    },
+    /// `[policy]` acted on a session (or, with `dry_run`, would have).
+    PolicyAction {
+        #[serde(flatten)]
+        meta: EventMeta,
+        #[serde(flatten)]
+        record: AuditRecord,
+    },
+    HostError {
+        #[serde(flatten)]
+        meta: EventMeta,
+        host: String,
+        kind: ErrorKind,
+        error: String,
+        context: Vec<String>,
+    },
+    HostRecovered {
+        #[serde(flatten)]
+        meta: EventMeta,
+        host: String,
+    },
+    /// codex-ps's own health, every `HEALTH_EVERY`; a missing heartbeat means it's wedged.
+    Health {
+        #[serde(flatten)]
+        meta: EventMeta,
+        #[serde(flatten)]
+        health: Health,
+    },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EventMeta {
+    pub generation: u64,
+    pub at: Option<String>,
+}
+
+/// Flatten a snapshot diff into events (started, status changes, finished turns, ended,
+/// over budget, host errors).
+pub fn events_from_changes(snap: &Snapshot, changes: &SnapshotChanges) -> Vec<SessionEvent> {
+    let meta = EventMeta {
+        generation: changes.generation,
+        at: to_iso8601_utc(snap.generated_at_unix_s),
+    };
+    let find = |host: &str, thread_id: &str| {
+        snap.sessions
+            .iter()
+            .find(|r| r.host == host && r.thread_id == thread_id)
+            .cloned()
+    };
+
+    let mut out = Vec::new();
+    for key in &changes.added {
+        if let Some(session) = find(&key.host, &key.thread_id) {
+            out.push(SessionEvent::SessionStarted {
+                meta: meta.clone(),
+                initial: changes.initial,
+                session,
+            });
+        }
+    }
+    for c in &changes.status_changed {
+        if let Some(session) = find(&c.key.host, &c.key.thread_id) {
+            out.push(SessionEvent::StatusChanged {
+                meta: meta.clone(),
+                from: c.from,
+                to: c.to,
+                session,
+            });
+        }
+    }
+    for f in &changes.finished {
+        if let Some(session) = find(&f.key.host, &f.key.thread_id) {
+            out.push(SessionEvent::TurnCompleted {
+                meta: meta.clone(),
+                worked_secs: f.worked_secs,
+                message: f.message.clone(),
+                session,
+            });
+        }
+    }
+    for session in &changes.removed {
+        let receipt = changes.receipt_for(session).cloned();
+        out.push(SessionEvent::SessionEnded {
+            meta: meta.clone(),
+            session: session.clone(),
+            summary: receipt.as_ref().map(SessionReceipt::summary),
+            receipt,
+        });
+    }
+    for key in &changes.over_budget {
+        if let Some(session) = find(&key.host, &key.thread_id) {
+            out.push(SessionEvent::OverBudget {
+                meta: meta.clone(),
+                reason: session.over_budget.clone().unwrap_or_default(),
+                session,
+            });
+        }
+    }
+    for e in &changes.host_errors {
+        out.push(SessionEvent::HostError {
+            meta: meta.clone(),
+            host: e.host.clone(),
+            kind: e.kind,
+            error: e.error.clone(),
+            context: e.context.clone(),
+        });
+    }
+    for host in &changes.hosts_recovered {
+        out.push(SessionEvent::HostRecovered {
+            meta: meta.clone(),
+            host: host.clone(),
+        });
+    }
+    out
+}
+
+/// What `[policy]` did with this snapshot. An enforcing monitor that can't write its audit
+/// log stops rather than act unrecorded.
+pub fn policy_events(
+    collector: &mut Collector,
+    snap: &Snapshot,
+    changes: &SnapshotChanges,
+) -> anyhow::Result<Vec<SessionEvent>> {
+    let meta = EventMeta {
+        generation: changes.generation,
+        at: to_iso8601_utc(snap.generated_at_unix_s),
+    };
+    Ok(collector
+        .enforce_policy(snap)
+        .context("policy audit log")?
+        .into_iter()
+        .map(|record| SessionEvent::PolicyAction {
+            meta: meta.clone(),
+            record,
+        })
+        .collect())
+}
+
+/// How often `--events` reports its own health.
+const HEALTH_EVERY: Duration = Duration::from_secs(60);
+
+/// `--events`: poll like the TUI and write one JSON object per event to stdout.
+/// Exits quietly when the reader goes away (broken pipe).
+pub fn run_events(
+    mut collector: Collector,
+    hosts: Vec<String>,
+    refresh_ms: u64,
+    debug: bool,
+    mut hooks: HookRunner,
+) -> anyhow::Result<()> {
+    let refresh = Duration::from_millis(refresh_ms.max(100));
+    let mut tracker = ChangeTracker::default();
+    let mut stdout = std::io::stdout();
+    let mut last_health: Option<Instant> = None;
+
+    loop {
+        // A failed cycle is not an event of its own; per-host failures arrive as host_error.
+        if let Ok(mut snap) = collector.collect(&hosts) {
+            if !debug {
+                snap.strip_debug();
+            }
+            let changes = tracker.update(&snap);
+            let now = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+            hooks.on_snapshot(&snap, &changes, now);
+
+            let mut events = events_from_changes(&snap, &changes);
+            events.extend(policy_events(&mut collector, &snap, &changes)?);
+            if last_health.is_none_or(|t| t.elapsed() >= HEALTH_EVERY) {
+                last_health = Some(Instant::now());
+                events.push(SessionEvent::Health {
+                    meta: EventMeta {
+                        generation: changes.generation,
+                        at: to_iso8601_utc(now),
+                    },
+                    health: collector.health(),
+                });
+            }
+            for ev in events {
+                let line = serde_json::to_string(&ev).context("serialize event")?;
+                if let Err(e) = writeln!(stdout, "{line}") {
+                    if e.kind() == std::io::ErrorKind::BrokenPipe {
+                        return Ok(());
+                    }
+                    return Err(e.into());
+                }
+            }
+            stdout.flush().ok();
+        }
+
+        std::thread::sleep(refresh);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(thread_id: &str, status: SessionStatus) -> SessionRow {
+        SessionRow {
+            host: "local".into(),
+            thread_id: thread_id.into(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    fn snap(generation: u64, sessions: Vec<SessionRow>) -> Snapshot {
+        Snapshot {
+            generation,
+            generated_at_unix_s: 1_770_135_142,
+            ..Snapshot::of(sessions)
+        }
+    }
+
+    #[test]
+    fn serializes_events_as_tagged_ndjson() {
+        let mut tracker = ChangeTracker::default();
+        let s1 = snap(1, vec![row("a", SessionStatus::Working)]);
+        let evs = events_from_changes(&s1, &tracker.update(&s1));
+        let line = serde_json::to_string(&evs[0]).expect("json");
+        assert!(line.starts_with(r#"{"event":"session_started","generation":1,"at":"2026-02-03T16:12:22Z","initial":true"#));
+
+        let s2 = snap(2, vec![row("a", SessionStatus::Waiting)]);
+        let evs = events_from_changes(&s2, &tracker.update(&s2));
+        let v = serde_json::to_value(&evs[0]).expect("json");
+        assert_eq!(v["event"], "status_changed");
+        assert_eq!(v["from"], "working");
+        assert_eq!(v["to"], "waiting");
+        assert_eq!(v["session"]["thread_id"], "a");
+    }
+}
@@ -1,29 +1,171 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::error::{ErrorInfo, ErrorKind};
 use crate::util::run_cmd_with_timeout;
 
 #[derive(Clone, Debug)]
 pub struct GitCache {
     ttl: Duration,
     entries: HashMap<PathBuf, (Instant, Option<PathBuf>)>,
+    /// cwds found missing, with the mtime of their nearest existing ancestor at the time.
+    missing: HashMap<PathBuf, Option<SystemTime>>,
+    branches: HashMap<PathBuf, (Instant, Option<String>)>,
+    /// (repo root, since) → commit count; probed less often than the rest. Expired entries
+    /// are dropped whenever one is added, so session start times don't pile up.
+    commits: HashMap<(PathBuf, i64), (Instant, Option<u32>)>,
+    unpushed: HashMap<PathBuf, (Instant, Option<u32>)>,
 }
 
+/// How long a commit count is reused.
+const COMMIT_COUNT_TTL: Duration = Duration::from_secs(60);
+
 impl GitCache {
     pub fn new(ttl: Duration) -> Self {
         Self {
             ttl,
             entries: HashMap::new(),
+            missing: HashMap::new(),
+            branches: HashMap::new(),
+            commits: HashMap::new(),
+            unpushed: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.branches.len() + self.commits.len() + self.unpushed.len()
+    }
+
+    /// Forget the cached answer for `cwd` so the next lookup re-probes.
+    pub fn invalidate(&mut self, cwd: &Path) {
+        self.entries.remove(cwd);
+        self.missing.remove(cwd);
+        self.branches.remove(cwd);
+        self.unpushed.remove(cwd);
+        // Counts are keyed by repo root, which `cwd` is inside.
+        self.commits.retain(|(repo, _), _| !cwd.starts_with(repo));
+    }
+
+    /// Whether `cwd` no longer exists. A missing answer is kept until the nearest existing
+    /// ancestor's mtime changes (the directory being recreated or renamed back shows up
+    /// there), so gone worktrees aren't re-probed every refresh.
+    pub fn cwd_missing(&mut self, cwd: &Path) -> bool {
+        if let Some(seen) = self.missing.get(cwd) {
+            if ancestor_mtime(cwd) == *seen {
+                return true;
+            }
+            self.missing.remove(cwd);
         }
+        if cwd.is_dir() {
+            return false;
+        }
+        self.entries.remove(cwd);
+        self.missing.insert(cwd.to_path_buf(), ancestor_mtime(cwd));
+        true
+    }
+
+    /// `git branch --show-current` in `cwd`, cached like `repo_root`. `None` for a detached
+    /// HEAD or when git fails.
+    pub fn current_branch(&mut self, cwd: &Path, timeout: Duration) -> Option<String> {
+        let now = Instant::now();
+        if let Some((ts, cached)) = self.branches.get(cwd) {
+            if now.duration_since(*ts) <= self.ttl {
+                return cached.clone();
+            }
+        }
+        let mut cmd = Command::new("git");
+        cmd.args([
+            "-C",
+            cwd.to_string_lossy().as_ref(),
+            "branch",
+            "--show-current",
+        ]);
+        let branch = run_cmd_with_timeout(cmd, timeout)
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|b| !b.is_empty());
+        self.branches
+            .insert(cwd.to_path_buf(), (now, branch.clone()));
+        branch
+    }
+
+    /// Commits on local branches of `repo` since `since_unix_s` by the repo's configured
+    /// `user.email` (any author when unset), cached for `COMMIT_COUNT_TTL`.
+    pub fn commits_since(
+        &mut self,
+        repo: &Path,
+        since_unix_s: i64,
+        timeout: Duration,
+    ) -> Option<u32> {
+        let now = Instant::now();
+        let key = (repo.to_path_buf(), since_unix_s);
+        if let Some((ts, cached)) = self.commits.get(&key) {
+            if now.duration_since(*ts) <= COMMIT_COUNT_TTL {
+                return *cached;
+            }
+        }
+
+        let repo_s = repo.to_string_lossy().to_string();
+        let mut email = Command::new("git");
+        email.args(["-C", &repo_s, "config", "user.email"]);
+        let author = run_cmd_with_timeout(email, timeout)
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|e| !e.is_empty());
+
+        let mut cmd = Command::new("git");
+        cmd.args(["-C", &repo_s, "rev-list", "--count", "--branches"]);
+        cmd.arg(format!("--since=@{since_unix_s}"));
+        if let Some(author) = author {
+            // `--author` is a regex otherwise, and emails are full of `.` and `+`.
+            cmd.args(["--fixed-strings", &format!("--author={author}")]);
+        }
+        let count = run_cmd_with_timeout(cmd, timeout)
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok());
+        self.commits
+            .retain(|_, (ts, _)| now.duration_since(*ts) <= COMMIT_COUNT_TTL);
+        self.commits.insert(key, (now, count));
+        count
+    }
+
+    /// Commits reachable from HEAD in `cwd` but from no remote-tracking branch, i.e. work
+    /// that only exists on this machine. Cached for `COMMIT_COUNT_TTL`.
+    pub fn unpushed_commits(&mut self, cwd: &Path, timeout: Duration) -> Option<u32> {
+        let now = Instant::now();
+        if let Some((ts, cached)) = self.unpushed.get(cwd) {
+            if now.duration_since(*ts) <= COMMIT_COUNT_TTL {
+                return *cached;
+            }
+        }
+        let mut cmd = Command::new("git");
+        cmd.args([
+            "-C",
+            cwd.to_string_lossy().as_ref(),
+            "rev-list",
+            "--count",
+            "HEAD",
+            "--not",
+            "--remotes",
+        ]);
+        let count = run_cmd_with_timeout(cmd, timeout)
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok());
+        self.unpushed.insert(cwd.to_path_buf(), (now, count));
+        count
     }
 
     pub fn repo_root(
         &mut self,
         cwd: &Path,
         timeout: Duration,
-    ) -> anyhow::Result<(Option<PathBuf>, Option<String>)> {
+    ) -> anyhow::Result<(Option<PathBuf>, Option<ErrorInfo>)> {
         let now = Instant::now();
         if let Some((ts, cached)) = self.entries.get(cwd) {
             if now.duration_since(*ts) <= self.ttl {
@@ -42,18 +184,27 @@ impl GitCache {
             Ok(o) if o.status.success() => o,
             Ok(_) => {
                 self.entries.insert(cwd.to_path_buf(), (now, None));
-                return Ok((None, Some("git rev-parse failed".into())));
+                return Ok((
+                    None,
+                    Some(ErrorInfo::new(ErrorKind::Git, "git rev-parse failed")),
+                ));
             }
             Err(e) => {
                 self.entries.insert(cwd.to_path_buf(), (now, None));
-                return Ok((None, Some(format!("{e}"))));
+                return Ok((None, Some(ErrorInfo::from_anyhow(ErrorKind::Git, &e))));
             }
         };
 
         let root = String::from_utf8_lossy(&out.stdout).trim().to_string();
         if root.is_empty() {
             self.entries.insert(cwd.to_path_buf(), (now, None));
-            return Ok((None, Some("git rev-parse returned empty".into())));
+            return Ok((
+                None,
+                Some(ErrorInfo::new(
+                    ErrorKind::Git,
+                    "git rev-parse returned empty",
+                )),
+            ));
         }
 
         let pb = PathBuf::from(root);
@@ -62,3 +213,60 @@ impl GitCache {
         Ok((Some(pb), None))
     }
 }
+
+/// mtime of the closest existing ancestor of `path`.
+fn ancestor_mtime(path: &Path) -> Option<SystemTime> {
+    path.ancestors()
+        .skip(1)
+        .find_map(|p| std::fs::metadata(p).ok())
+        .and_then(|m| m.modified().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_cwd_is_cached_until_parent_changes() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let wt = dir.path().join("worktree");
+        let mut cache = GitCache::new(Duration::from_secs(5));
+        assert!(cache.cwd_missing(&wt));
+        assert!(cache.cwd_missing(&wt));
+
+        std::fs::create_dir(&wt).expect("mkdir");
+        // Force a visible parent mtime change even on coarse-mtime filesystems.
+        let later = SystemTime::now() + Duration::from_secs(10);
+        std::fs::File::open(dir.path())
+            .and_then(|f| f.set_modified(later))
+            .expect("touch parent");
+        assert!(!cache.cwd_missing(&wt));
+    }
+
+    #[test]
+    fn commit_counts_match_the_email_literally_and_are_forgotten() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let ok = Command::new("git")
+                .arg("-C")
+                .arg(repo)
+                .args(args)
+                .output()
+                .is_ok_and(|o| o.status.success());
+            assert!(ok, "git {args:?}");
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "Dev"]);
+        git(&["config", "user.email", "dev+ci@example.com"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "one"]);
+
+        let mut cache = GitCache::new(Duration::from_secs(5));
+        let timeout = Duration::from_secs(10);
+        assert_eq!(cache.commits_since(repo, 0, timeout), Some(1));
+        cache.commits_since(repo, 1, timeout);
+        assert_eq!(cache.commits.len(), 2);
+        cache.invalidate(&repo.join("src"));
+        assert!(cache.commits.is_empty());
+    }
+}
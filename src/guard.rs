@@ -0,0 +1,81 @@
+use std::io::{BufRead, IsTerminal, Write};
+
+use serde::Deserialize;
+
+/// `guard = "..."` in `config.toml`: which actions ask for confirmation first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GuardProfile {
+    /// Confirm every change to session metadata (shared dashboards).
+    Paranoid,
+    /// Confirm actions that throw information away.
+    #[default]
+    Normal,
+    /// Never ask.
+    Yolo,
+}
+
+/// Actions that change state outside the current view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuardedAction {
+    /// Remove a session's name.
+    ClearName,
+    /// Set or replace a session's name, tags or note.
+    Annotate,
+    /// Terminate a session's processes.
+    Kill,
+}
+
+impl GuardProfile {
+    pub fn requires_confirmation(self, action: GuardedAction) -> bool {
+        match self {
+            GuardProfile::Paranoid => true,
+            GuardProfile::Normal => {
+                matches!(action, GuardedAction::ClearName | GuardedAction::Kill)
+            }
+            GuardProfile::Yolo => false,
+        }
+    }
+}
+
+/// CLI side of the guard: pass when the profile allows it or `--yes` was given, otherwise
+/// ask on the terminal. Non-interactive callers must pass `--yes`.
+pub fn confirm_cli(
+    profile: GuardProfile,
+    action: GuardedAction,
+    prompt: &str,
+    yes: bool,
+) -> anyhow::Result<()> {
+    if yes || !profile.requires_confirmation(action) {
+        return Ok(());
+    }
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        anyhow::bail!("{prompt}: confirmation required by guard profile (pass --yes)");
+    }
+    eprint!("{prompt}? [y/N] ");
+    std::io::stderr().flush().ok();
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer)?;
+    if matches!(answer.trim(), "y" | "Y" | "yes") {
+        Ok(())
+    } else {
+        anyhow::bail!("aborted")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_gate_actions() {
+        use GuardedAction::*;
+        assert!(GuardProfile::Paranoid.requires_confirmation(Annotate));
+        assert!(GuardProfile::Normal.requires_confirmation(ClearName));
+        assert!(!GuardProfile::Normal.requires_confirmation(Annotate));
+        assert!(GuardProfile::Normal.requires_confirmation(Kill));
+        assert!(!GuardProfile::Yolo.requires_confirmation(ClearName));
+        assert!(confirm_cli(GuardProfile::Paranoid, Annotate, "annotate", true).is_ok());
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::model::HostError;
+
+/// Counters the collector keeps about itself, so a wedged or struggling watcher is
+/// detectable from the outside.
+#[derive(Debug)]
+pub struct HealthTracker {
+    started: Instant,
+    cycles: u64,
+    last_cycle: Option<Duration>,
+    max_cycle: Duration,
+    last_finished: Option<Instant>,
+    hosts: BTreeMap<String, HostHealth>,
+}
+
+impl Default for HealthTracker {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            cycles: 0,
+            last_cycle: None,
+            max_cycle: Duration::ZERO,
+            last_finished: None,
+            hosts: BTreeMap::new(),
+        }
+    }
+}
+
+/// Collection cycles and failed cycles for one host.
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+pub struct HostHealth {
+    pub cycles: u64,
+    pub errors: u64,
+}
+
+/// Sizes of the collector's in-memory caches.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CacheSizes {
+    pub rollout_tails: usize,
+    pub local_sessions: usize,
+    pub remote_hosts: usize,
+    pub git_repos: usize,
+}
+
+/// Point-in-time health report (the `health` event in `--events`).
+#[derive(Clone, Debug, Serialize)]
+pub struct Health {
+    pub uptime_secs: u64,
+    pub cycles: u64,
+    pub last_cycle_ms: Option<u64>,
+    pub max_cycle_ms: u64,
+    /// Time since the last completed collection; grows without bound if the loop is wedged.
+    pub since_last_cycle_ms: Option<u64>,
+    pub hosts: BTreeMap<String, HostHealth>,
+    pub caches: CacheSizes,
+    /// Resident memory of this process (Linux only).
+    pub rss_bytes: Option<u64>,
+}
+
+impl HealthTracker {
+    /// Record one finished collection over `hosts` that took `elapsed`.
+    pub fn record_cycle(&mut self, hosts: &[String], host_errors: &[HostError], elapsed: Duration) {
+        self.cycles += 1;
+        self.last_cycle = Some(elapsed);
+        self.max_cycle = self.max_cycle.max(elapsed);
+        self.last_finished = Some(Instant::now());
+        for host in hosts {
+            let h = self.hosts.entry(host.clone()).or_default();
+            h.cycles += 1;
+            if host_errors.iter().any(|e| &e.host == host) {
+                h.errors += 1;
+            }
+        }
+    }
+
+    pub fn report(&self, caches: CacheSizes) -> Health {
+        let ms = |d: Duration| d.as_millis().try_into().unwrap_or(u64::MAX);
+        Health {
+            uptime_secs: self.started.elapsed().as_secs(),
+            cycles: self.cycles,
+            last_cycle_ms: self.last_cycle.map(ms),
+            max_cycle_ms: ms(self.max_cycle),
+            since_last_cycle_ms: self.last_finished.map(|t| ms(t.elapsed())),
+            hosts: self.hosts.clone(),
+            caches,
+            rss_bytes: rss_bytes(),
+        }
+    }
+}
+
+fn rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ErrorInfo, ErrorKind};
+
+    #[test]
+    fn counts_cycles_and_errors_per_host() {
+        let mut t = HealthTracker::default();
+        let hosts = vec!["local".to_string(), "home".to_string()];
+        let err = HostError::new("home", ErrorInfo::new(ErrorKind::Transport, "timeout"));
+        t.record_cycle(&hosts, &[], Duration::from_millis(40));
+        t.record_cycle(&hosts, &[err], Duration::from_millis(900));
+
+        let h = t.report(CacheSizes::default());
+        assert_eq!(h.cycles, 2);
+        assert_eq!((h.last_cycle_ms, h.max_cycle_ms), (Some(900), 900));
+        assert_eq!(
+            h.hosts["home"],
+            HostHealth {
+                cycles: 2,
+                errors: 1
+            }
+        );
+        assert_eq!(h.hosts["local"].errors, 0);
+    }
+}
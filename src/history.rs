@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::model::SessionId;
+use crate::names::config_dir;
+
+/// Append-only log of thread title changes (`title_history.jsonl`), so a session Codex
+/// retitled stays recognizable by its previous title. First sightings are only kept in memory;
+/// the file is compacted to one record per session when it is loaded.
+#[derive(Clone, Debug)]
+pub struct TitleHistory {
+    path: PathBuf,
+    loaded: bool,
+    /// Latest known `(title, previous_title)` per session.
+    current: HashMap<SessionId, (String, Option<String>)>,
+}
+
+impl TitleHistory {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self::new_at(config_dir()?.join("title_history.jsonl")))
+    }
+
+    pub fn new_at(path: PathBuf) -> Self {
+        Self {
+            path,
+            loaded: false,
+            current: HashMap::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record `title` for `key`, appending a change record when it differs from a known one.
+    pub fn observe(&mut self, key: &SessionId, title: &str, now_unix_s: i64) -> anyhow::Result<()> {
+        if !self.loaded {
+            self.load()?;
+        }
+        let previous_title = match self.current.get(key) {
+            Some((t, _)) if t == title => return Ok(()),
+            Some((t, _)) => t.clone(),
+            None => {
+                self.current.insert(key.clone(), (title.to_string(), None));
+                return Ok(());
+            }
+        };
+
+        let rec = TitleRecord {
+            host: key.host.clone(),
+            thread_id: key.thread_id.clone(),
+            title: title.to_string(),
+            previous_title: Some(previous_title.clone()),
+            changed_at_unix_s: now_unix_s,
+        };
+        self.append_record(&rec)?;
+        self.current
+            .insert(key.clone(), (title.to_string(), Some(previous_title)));
+        Ok(())
+    }
+
+    pub fn previous_title(&self, key: &SessionId) -> Option<&str> {
+        self.current.get(key).and_then(|(_, prev)| prev.as_deref())
+    }
+
+    fn load(&mut self) -> anyhow::Result<()> {
+        self.loaded = true;
+        let f = match fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("open {}", self.path.display())),
+        };
+
+        let mut lines = 0;
+        let mut latest: HashMap<SessionId, TitleRecord> = HashMap::new();
+        for (i, line) in BufReader::new(f).lines().enumerate() {
+            let line = line.context("read line")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            lines += 1;
+            let rec: TitleRecord = serde_json::from_str(&line)
+                .with_context(|| format!("parse title_history.jsonl line {}", i + 1))?;
+            let key = SessionId::new(&rec.host, &rec.thread_id);
+            latest.insert(key, rec);
+        }
+
+        // Older versions logged every first sighting; only the last change per session matters.
+        let mut keep: Vec<&TitleRecord> = latest
+            .values()
+            .filter(|r| r.previous_title.is_some())
+            .collect();
+        if keep.len() < lines {
+            keep.sort_by(|a, b| {
+                (a.changed_at_unix_s, &a.host, &a.thread_id).cmp(&(
+                    b.changed_at_unix_s,
+                    &b.host,
+                    &b.thread_id,
+                ))
+            });
+            self.rewrite(&keep)?;
+        }
+        for (key, rec) in latest {
+            self.current.insert(key, (rec.title, rec.previous_title));
+        }
+        Ok(())
+    }
+
+    fn rewrite(&self, records: &[&TitleRecord]) -> anyhow::Result<()> {
+        let mut text = String::new();
+        for rec in records {
+            text.push_str(&serde_json::to_string(rec).context("serialize title record")?);
+            text.push('\n');
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp, text).with_context(|| format!("write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("rename {} -> {}", tmp.display(), self.path.display()))?;
+        Ok(())
+    }
+
+    fn append_record(&self, rec: &TitleRecord) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create dir {}", parent.display()))?;
+        }
+        let line = serde_json::to_string(rec).context("serialize title record")?;
+        let mut f = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("open for append {}", self.path.display()))?;
+        writeln!(f, "{line}").context("append title record")?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TitleRecord {
+    host: String,
+    thread_id: String,
+    title: String,
+    /// `null` only in records written by older versions, which logged first sightings.
+    previous_title: Option<String>,
+    changed_at_unix_s: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_changes_and_survives_reload() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let p = dir.path().join("title_history.jsonl");
+        let key = SessionId::new("local", "t1");
+
+        let mut h = TitleHistory::new_at(p.clone());
+        h.observe(&key, "Fix login", 100).expect("observe");
+        h.observe(&key, "Fix login", 101).expect("observe");
+        assert_eq!(h.previous_title(&key), None);
+        h.observe(&key, "Auth refactor", 200).expect("observe");
+        assert_eq!(h.previous_title(&key), Some("Fix login"));
+
+        let text = fs::read_to_string(&p).expect("read");
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.contains(r#""previous_title":"Fix login","changed_at_unix_s":200"#));
+
+        let mut reloaded = TitleHistory::new_at(p);
+        reloaded
+            .observe(&key, "Auth refactor", 300)
+            .expect("observe");
+        assert_eq!(reloaded.previous_title(&key), Some("Fix login"));
+    }
+
+    #[test]
+    fn compacts_first_sightings_and_superseded_changes_on_load() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let p = dir.path().join("title_history.jsonl");
+        let line = |id: &str, title: &str, prev: Option<&str>, at: i64| {
+            let rec = TitleRecord {
+                host: "local".into(),
+                thread_id: id.into(),
+                title: title.into(),
+                previous_title: prev.map(str::to_string),
+                changed_at_unix_s: at,
+            };
+            serde_json::to_string(&rec).expect("serialize") + "\n"
+        };
+        let old = [
+            line("t1", "A", None, 1),
+            line("t2", "Quiet", None, 2),
+            line("t1", "B", Some("A"), 3),
+            line("t1", "C", Some("B"), 4),
+        ]
+        .concat();
+        fs::write(&p, old).expect("write");
+
+        let mut h = TitleHistory::new_at(p.clone());
+        h.observe(&SessionId::new("local", "t2"), "Retitled", 5)
+            .expect("observe");
+        assert_eq!(h.previous_title(&SessionId::new("local", "t1")), Some("B"));
+
+        let text = fs::read_to_string(&p).expect("read");
+        let kept: Vec<&str> = text.lines().collect();
+        assert_eq!(kept.len(), 2);
+        assert!(kept[0].contains(r#""title":"C""#));
+        assert!(kept[1].contains(r#""previous_title":"Quiet""#));
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::HashSet;
+use std::process::Command;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::changes::SnapshotChanges;
+use crate::model::SessionId;
+use crate::model::{SessionRow, SessionStatus, Snapshot};
+use crate::util::run_cmd_with_timeout;
+
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `[hooks]`: shell commands (`sh -c`) run on session lifecycle events, with the session
+/// described in `CODEX_PS_*` environment variables.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    pub on_session_start: Option<String>,
+    /// A session switched to waiting-for-input.
+    pub on_needs_input: Option<String>,
+    /// A long turn finished; the final message is in `CODEX_PS_FINAL_MESSAGE`.
+    pub on_turn_complete: Option<String>,
+    /// A session's process exited; a one-line receipt is in `CODEX_PS_RECEIPT`.
+    pub on_session_end: Option<String>,
+    /// A session has been "working" with no rollout activity for `stuck_after_secs`.
+    pub on_stuck: Option<String>,
+    /// Any status change of a session flagged with `*` in the TUI.
+    pub on_watched_transition: Option<String>,
+    /// A session went past a `[budget]` limit; which one is in `CODEX_PS_OVER_BUDGET`.
+    pub on_over_budget: Option<String>,
+    #[serde(default = "default_stuck_after_secs")]
+    pub stuck_after_secs: u64,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_session_start: None,
+            on_needs_input: None,
+            on_turn_complete: None,
+            on_session_end: None,
+            on_stuck: None,
+            on_watched_transition: None,
+            on_over_budget: None,
+            stuck_after_secs: default_stuck_after_secs(),
+        }
+    }
+}
+
+fn default_stuck_after_secs() -> u64 {
+    600
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    SessionStart,
+    NeedsInput,
+    TurnComplete,
+    SessionEnd,
+    Stuck,
+    WatchedTransition,
+    OverBudget,
+}
+
+impl HookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::SessionStart => "session_start",
+            HookEvent::NeedsInput => "needs_input",
+            HookEvent::TurnComplete => "turn_complete",
+            HookEvent::SessionEnd => "session_end",
+            HookEvent::Stuck => "stuck",
+            HookEvent::WatchedTransition => "watched_transition",
+            HookEvent::OverBudget => "over_budget",
+        }
+    }
+}
+
+/// Turns snapshot diffs into hook invocations. The first snapshot only seeds state, so
+/// restarting codex-ps doesn't replay `on_session_start` for everything already running.
+#[derive(Debug, Default)]
+pub struct HookRunner {
+    cfg: HooksConfig,
+    stuck: HashSet<SessionId>,
+}
+
+impl HookRunner {
+    pub fn new(cfg: HooksConfig) -> Self {
+        Self {
+            cfg,
+            stuck: HashSet::new(),
+        }
+    }
+
+    pub fn on_snapshot(&mut self, snap: &Snapshot, changes: &SnapshotChanges, now_unix_s: i64) {
+        for (event, row) in self.events(snap, changes, now_unix_s) {
+            if let Some(cmd) = self.command_for(event) {
+                let receipt = match event {
+                    HookEvent::SessionEnd => changes.receipt_for(&row).map(|r| r.summary()),
+                    _ => None,
+                };
+                spawn_hook(cmd, event, &row, receipt);
+            }
+        }
+    }
+
+    /// The `(event, session)` pairs this snapshot triggers, in a stable order.
+    pub fn events(
+        &mut self,
+        snap: &Snapshot,
+        changes: &SnapshotChanges,
+        now_unix_s: i64,
+    ) -> Vec<(HookEvent, SessionRow)> {
+        let mut out = Vec::new();
+        let find = |key: &SessionId| snap.sessions.iter().find(|r| r.has_id(key));
+
+        if !changes.initial {
+            for key in &changes.added {
+                if let Some(r) = find(key) {
+                    out.push((HookEvent::SessionStart, r.clone()));
+                }
+            }
+            for c in &changes.status_changed {
+                let Some(r) = find(&c.key) else {
+                    continue;
+                };
+                if c.to == SessionStatus::Waiting {
+                    out.push((HookEvent::NeedsInput, r.clone()));
+                }
+                if r.watched {
+                    out.push((HookEvent::WatchedTransition, r.clone()));
+                }
+            }
+            for f in &changes.finished {
+                if let Some(r) = find(&f.key) {
+                    out.push((HookEvent::TurnComplete, r.clone()));
+                }
+            }
+            for r in &changes.removed {
+                out.push((HookEvent::SessionEnd, r.clone()));
+            }
+            for key in &changes.over_budget {
+                if let Some(r) = find(key) {
+                    out.push((HookEvent::OverBudget, r.clone()));
+                }
+            }
+        }
+
+        let stuck_after = i64::try_from(self.cfg.stuck_after_secs).unwrap_or(i64::MAX);
+        let mut still_stuck = HashSet::new();
+        for r in &snap.sessions {
+            let is_stuck = r.status == SessionStatus::Working
+                && r.last_activity_unix_s
+                    .is_some_and(|ts| now_unix_s.saturating_sub(ts) >= stuck_after);
+            if !is_stuck {
+                continue;
+            }
+            let key = r.id();
+            if !self.stuck.contains(&key) && !changes.initial {
+                out.push((HookEvent::Stuck, r.clone()));
+            }
+            still_stuck.insert(key);
+        }
+        self.stuck = still_stuck;
+
+        out
+    }
+
+    fn command_for(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::SessionStart => self.cfg.on_session_start.as_deref(),
+            HookEvent::NeedsInput => self.cfg.on_needs_input.as_deref(),
+            HookEvent::TurnComplete => self.cfg.on_turn_complete.as_deref(),
+            HookEvent::SessionEnd => self.cfg.on_session_end.as_deref(),
+            HookEvent::Stuck => self.cfg.on_stuck.as_deref(),
+            HookEvent::WatchedTransition => self.cfg.on_watched_transition.as_deref(),
+            HookEvent::OverBudget => self.cfg.on_over_budget.as_deref(),
+        }
+    }
+}
+
+/// Fire and forget: hooks run on their own thread (bounded by `HOOK_TIMEOUT`) so a slow
+/// script never stalls collection.
+fn spawn_hook(cmd: &str, event: HookEvent, row: &SessionRow, receipt: Option<String>) {
+    let mut c = Command::new("sh");
+    c.arg("-c").arg(cmd);
+    for (k, v) in hook_env(event, row) {
+        c.env(k, v);
+    }
+    c.env("CODEX_PS_RECEIPT", receipt.unwrap_or_default());
+    std::thread::spawn(move || {
+        let _ = run_cmd_with_timeout(c, HOOK_TIMEOUT);
+    });
+}
+
+pub fn hook_env(event: HookEvent, row: &SessionRow) -> Vec<(&'static str, String)> {
+    let status = match row.status {
+        SessionStatus::Working => "working",
+        SessionStatus::Waiting => "waiting",
+        SessionStatus::Unknown => "unknown",
+    };
+    let opt = |v: &Option<String>| v.clone().unwrap_or_default();
+    vec![
+        ("CODEX_PS_EVENT", event.as_str().to_string()),
+        ("CODEX_PS_HOST", row.host.clone()),
+        ("CODEX_PS_HOST_LABEL", row.host_display().to_string()),
+        ("CODEX_PS_THREAD_ID", row.thread_id.clone()),
+        ("CODEX_PS_STATUS", status.to_string()),
+        ("CODEX_PS_NAME", opt(&row.name)),
+        ("CODEX_PS_TITLE", opt(&row.title)),
+        ("CODEX_PS_CWD", opt(&row.cwd)),
+        ("CODEX_PS_BRANCH", opt(&row.git_branch)),
+        ("CODEX_PS_TAGS", row.tags.join(",")),
+        ("CODEX_PS_FINAL_MESSAGE", opt(&row.final_message)),
+        ("CODEX_PS_OVER_BUDGET", opt(&row.over_budget)),
+        (
+            "CODEX_PS_PIDS",
+            row.pids
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::changes::ChangeTracker;
+
+    fn row(thread_id: &str, status: SessionStatus, last: i64) -> SessionRow {
+        SessionRow {
+            host: "local".into(),
+            thread_id: thread_id.into(),
+            pids: vec![7],
+            title: Some("t".into()),
+            status,
+            last_activity_unix_s: Some(last),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn emits_lifecycle_events_and_stuck_once() {
+        let mut tracker = ChangeTracker::default();
+        let mut hooks = HookRunner::new(HooksConfig {
+            stuck_after_secs: 100,
+            ..Default::default()
+        });
+        let kinds = |evs: Vec<(HookEvent, SessionRow)>| {
+            evs.into_iter()
+                .map(|(e, r)| (e, r.thread_id))
+                .collect::<Vec<_>>()
+        };
+
+        let s1 = Snapshot::of(vec![row("a", SessionStatus::Working, 1000)]);
+        let c1 = tracker.update(&s1);
+        assert!(hooks.events(&s1, &c1, 1000).is_empty());
+
+        let mut watched = row("a", SessionStatus::Waiting, 1000);
+        watched.watched = true;
+        let s2 = Snapshot::of(vec![watched, row("b", SessionStatus::Working, 1000)]);
+        let c2 = tracker.update(&s2);
+        assert_eq!(
+            kinds(hooks.events(&s2, &c2, 1010)),
+            vec![
+                (HookEvent::SessionStart, "b".to_string()),
+                (HookEvent::NeedsInput, "a".to_string()),
+                (HookEvent::WatchedTransition, "a".to_string()),
+            ]
+        );
+
+        let s3 = Snapshot::of(vec![row("b", SessionStatus::Working, 1000)]);
+        let c3 = tracker.update(&s3);
+        assert_eq!(
+            kinds(hooks.events(&s3, &c3, 1200)),
+            vec![
+                (HookEvent::SessionEnd, "a".to_string()),
+                (HookEvent::Stuck, "b".to_string()),
+            ]
+        );
+        let c4 = tracker.update(&s3);
+        assert!(hooks.events(&s3, &c4, 1300).is_empty());
+
+        let mut over = row("b", SessionStatus::Working, 1000);
+        over.over_budget = Some("ran 6h02m > 6h00m".into());
+        let s5 = Snapshot::of(vec![over]);
+        let c5 = tracker.update(&s5);
+        assert_eq!(
+            kinds(hooks.events(&s5, &c5, 1300)),
+            vec![(HookEvent::OverBudget, "b".to_string())]
+        );
+        let c6 = tracker.update(&s5);
+        assert!(hooks.events(&s5, &c6, 1300).is_empty());
+    }
+}
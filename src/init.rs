@@ -0,0 +1,223 @@
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::collector::Collector;
+use crate::config::{Config, default_config_path};
+use crate::error::ErrorKind;
+
+/// `codex-ps init`: check that local sessions can be seen, offer the hosts from
+/// `~/.ssh/config` as remotes, verify the chosen ones and record them as `hosts` in the
+/// config. With `yes`, nothing is asked: every ssh host that answers is added.
+pub fn run_init(
+    mut collector: Collector,
+    config_path: Option<&Path>,
+    config: &Config,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let home = collector.codex_home().clone();
+    println!("CODEX_HOME: {}", home.root.display());
+    if home.is_relocated() {
+        println!("  resolves to {}", home.canonical.display());
+    }
+    if !home.root.join("sessions").is_dir() {
+        println!("  no sessions/ directory yet; start codex once, or pass --codex-home");
+    }
+
+    match collector.collect(&["local".to_string()]) {
+        Ok(snap) => {
+            match snap.host_errors.as_deref().unwrap_or(&[]).first() {
+                Some(e) => println!("Local discovery: failed: {}", e.error),
+                None => println!(
+                    "Local discovery: ok, {} running session(s)",
+                    snap.sessions.len()
+                ),
+            }
+            for w in snap.warnings.iter().flatten() {
+                if w.kind == ErrorKind::Discovery {
+                    println!("  {}", w.message);
+                }
+            }
+        }
+        Err(e) => println!("Local discovery: failed: {e:#}"),
+    }
+
+    let candidates = match dirs::home_dir().map(|h| h.join(".ssh").join("config")) {
+        Some(p) => match std::fs::read_to_string(&p) {
+            Ok(text) => ssh_config_hosts(&text),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("read {}", p.display())),
+        },
+        None => Vec::new(),
+    };
+    if candidates.is_empty() {
+        println!("No hosts in ~/.ssh/config; remote hosts can be added to `hosts` later.");
+    }
+
+    let mut prompt = Prompt::new(yes);
+    let mut hosts: Vec<String> = Vec::new();
+    for host in candidates {
+        if !prompt.ask(&format!("Add remote host {host}?"), yes)? {
+            continue;
+        }
+        print!("  checking {host}... ");
+        std::io::stdout().flush().ok();
+        let failure = match collector.collect(std::slice::from_ref(&host)) {
+            Ok(snap) => snap
+                .host_errors
+                .unwrap_or_default()
+                .into_iter()
+                .next()
+                .map(|e| e.error),
+            Err(e) => Some(format!("{e:#}")),
+        };
+        match failure {
+            None => {
+                println!("ok");
+                hosts.push(host);
+            }
+            Some(e) => {
+                println!("failed: {e}");
+                if !yes && prompt.ask(&format!("  Add {host} anyway?"), false)? {
+                    hosts.push(host);
+                }
+            }
+        }
+    }
+
+    let path = match config_path {
+        Some(p) => p.to_path_buf(),
+        None => default_config_path()?,
+    };
+    if hosts.is_empty() {
+        println!("No remote hosts chosen; {} left as is.", path.display());
+        return Ok(());
+    }
+    let line = hosts_line(&hosts);
+    if config.hosts.is_some() {
+        println!(
+            "{} already sets `hosts`; leaving it. To use this selection instead:\n{line}",
+            path.display()
+        );
+        return Ok(());
+    }
+    if !prompt.ask(&format!("Write `{line}` to {}?", path.display()), true)? {
+        println!("Nothing written.");
+        return Ok(());
+    }
+    // Prepended: a top-level key after the file's first table would belong to that table.
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("read {}", path.display())),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("create dir {}", parent.display()))?;
+    }
+    let text = format!("# Remote hosts for `--host all` (written by codex-ps init).\n{line}\n");
+    let text = if existing.is_empty() {
+        text
+    } else {
+        format!("{text}\n{existing}")
+    };
+    std::fs::write(&path, text).with_context(|| format!("write {}", path.display()))?;
+    println!(
+        "Wrote {}. Run `codex-ps --host all` to see every host.",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Concrete `Host` names from an ssh config (patterns like `*` or `!x` can't be dialed).
+fn ssh_config_hosts(text: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        let Some((key, rest)) = line.split_once(|c: char| c.is_whitespace() || c == '=') else {
+            continue;
+        };
+        if !key.eq_ignore_ascii_case("host") {
+            continue;
+        }
+        for name in rest.split_whitespace() {
+            let name = name.trim_matches('"');
+            if name.starts_with('#') {
+                break;
+            }
+            if name.is_empty() || name.contains(['*', '?', '!']) || out.iter().any(|h| h == name) {
+                continue;
+            }
+            out.push(name.to_string());
+        }
+    }
+    out
+}
+
+fn hosts_line(hosts: &[String]) -> String {
+    let quoted: Vec<String> = hosts.iter().map(|h| format!("{h:?}")).collect();
+    format!("hosts = [{}]", quoted.join(", "))
+}
+
+/// y/n questions on stdin. Without a terminal (or with `--yes`) every answer is the default.
+struct Prompt {
+    interactive: bool,
+}
+
+impl Prompt {
+    fn new(yes: bool) -> Self {
+        Self {
+            interactive: !yes && std::io::stdin().is_terminal(),
+        }
+    }
+
+    fn ask(&mut self, question: &str, default: bool) -> anyhow::Result<bool> {
+        let hint = if default { "[Y/n]" } else { "[y/N]" };
+        if !self.interactive {
+            println!("{question} {hint} {}", if default { "y" } else { "n" });
+            return Ok(default);
+        }
+        print!("{question} {hint} ");
+        std::io::stdout().flush().context("flush stdout")?;
+        let mut answer = String::new();
+        if std::io::stdin()
+            .lock()
+            .read_line(&mut answer)
+            .context("read answer")?
+            == 0
+        {
+            self.interactive = false;
+            println!();
+            return Ok(default);
+        }
+        Ok(match answer.trim().to_ascii_lowercase().as_str() {
+            "y" | "yes" => true,
+            "n" | "no" => false,
+            _ => default,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssh_hosts_skip_patterns_and_duplicates() {
+        let text = r#"
+Host *
+    ServerAliveInterval 30
+Host home devbox # laptop + vm
+    HostName 10.0.0.2
+host=build-1
+Host *.corp !bastion home
+Match host foo
+"#;
+        assert_eq!(ssh_config_hosts(text), vec!["home", "devbox", "build-1"]);
+        assert_eq!(
+            hosts_line(&["home".into(), "devbox".into()]),
+            r#"hosts = ["home", "devbox"]"#
+        );
+    }
+}
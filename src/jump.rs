@@ -0,0 +1,218 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::model::{SessionRow, SessionStatus};
+use crate::util::{run_cmd_with_timeout, shell_quote};
+
+const TMUX_TIMEOUT: Duration = Duration::from_secs(2);
+/// Long enough for a terminal emulator to open its window and detach.
+const RESUME_TIMEOUT: Duration = Duration::from_secs(30);
+/// Used without a `resume_command` in the config.
+const DEFAULT_RESUME_TEMPLATE: &str = "tmux new-window {resume}";
+
+/// Copy `text` with the first clipboard tool found, falling back to an OSC 52 escape (which
+/// most terminals and tmux with `set-clipboard on` honor). Returns what was used.
+pub fn copy_to_clipboard(text: &str) -> anyhow::Result<&'static str> {
+    let tools: &[(&'static str, &[&str])] = &[
+        ("pbcopy", &[]),
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ];
+    for (bin, args) in tools {
+        let Ok(mut child) = Command::new(bin)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).ok();
+        }
+        if child.wait().is_ok_and(|s| s.success()) {
+            return Ok(bin);
+        }
+    }
+
+    let mut out = std::io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes())).context("write OSC 52")?;
+    out.flush().ok();
+    Ok("OSC 52")
+}
+
+/// Standard padded base64 (a dozen lines don't justify a dependency).
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Switch the surrounding tmux client to the pane whose tty is the session's.
+pub fn focus_session_pane(row: &SessionRow) -> anyhow::Result<String> {
+    if row.host != "local" {
+        anyhow::bail!("{} is a remote session", row.host);
+    }
+    let tty = row
+        .tty
+        .as_deref()
+        .context("session has no controlling tty")?;
+    if std::env::var_os("TMUX").is_none() {
+        anyhow::bail!("not running inside tmux");
+    }
+
+    let mut list = Command::new("tmux");
+    list.args(["list-panes", "-a", "-F", "#{pane_tty} #{pane_id}"]);
+    let out = run_cmd_with_timeout(list, TMUX_TIMEOUT).context("tmux list-panes")?;
+    let listing = String::from_utf8_lossy(&out.stdout);
+    let pane = pane_for_tty(&listing, tty)
+        .with_context(|| format!("no tmux pane owns {tty} (Codex runs outside tmux?)"))?;
+
+    for args in [
+        ["select-window", "-t", pane.as_str()],
+        ["select-pane", "-t", pane.as_str()],
+        ["switch-client", "-t", pane.as_str()],
+    ] {
+        let mut c = Command::new("tmux");
+        c.args(args);
+        let out =
+            run_cmd_with_timeout(c, TMUX_TIMEOUT).with_context(|| format!("tmux {}", args[0]))?;
+        // `switch-client` fails harmlessly when the pane is already in this client's session.
+        if !out.status.success() && args[0] != "switch-client" {
+            anyhow::bail!(
+                "tmux {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+    }
+    Ok(pane)
+}
+
+/// The shell command that resumes `row` where it ran: `cd <cwd> && codex resume <id>`,
+/// wrapped in `ssh -t` for remote hosts.
+pub fn resume_shell_command(row: &SessionRow) -> String {
+    let resume = match row.cwd.as_deref() {
+        Some(cwd) => format!(
+            "cd {} && codex resume {}",
+            shell_quote(cwd),
+            shell_quote(&row.thread_id)
+        ),
+        None => format!("codex resume {}", shell_quote(&row.thread_id)),
+    };
+    on_host(&row.host, &resume)
+}
+
+/// Wrap `cmd` in `ssh -t` for remote hosts.
+pub fn on_host(host: &str, cmd: &str) -> String {
+    if host == "local" {
+        cmd.to_string()
+    } else {
+        format!("ssh -t {} {}", shell_quote(host), shell_quote(cmd))
+    }
+}
+
+/// Fill a `resume_command` template. `{resume}` is `resume_shell_command`; `{thread_id}`,
+/// `{cwd}` and `{host}` are the raw values. All are shell-quoted.
+pub fn expand_resume_template(template: &str, row: &SessionRow) -> String {
+    template
+        .replace("{resume}", &shell_quote(&resume_shell_command(row)))
+        .replace("{thread_id}", &shell_quote(&row.thread_id))
+        .replace("{cwd}", &shell_quote(row.cwd.as_deref().unwrap_or(".")))
+        .replace("{host}", &shell_quote(&row.host))
+}
+
+/// Open a new window running `codex resume` for an idle session, through `template` (or a new
+/// tmux window). Returns the command that was started.
+pub fn launch_resume(row: &SessionRow, template: Option<&str>) -> anyhow::Result<String> {
+    if row.status == SessionStatus::Working {
+        anyhow::bail!("session is working; resume it once it is idle");
+    }
+    if template.is_none() && std::env::var_os("TMUX").is_none() {
+        anyhow::bail!("not running inside tmux; set resume_command in config.toml");
+    }
+    let cmd = expand_resume_template(template.unwrap_or(DEFAULT_RESUME_TEMPLATE), row);
+    let mut c = Command::new("sh");
+    c.arg("-c")
+        .arg(&cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if row.host == "local" {
+        if let Some(cwd) = row
+            .cwd
+            .as_deref()
+            .filter(|d| std::path::Path::new(d).is_dir())
+        {
+            c.current_dir(cwd);
+        }
+    }
+    std::thread::spawn(move || {
+        let _ = run_cmd_with_timeout(c, RESUME_TIMEOUT);
+    });
+    Ok(cmd)
+}
+
+/// Find the pane id for `tty` (`pts/3` or `ttys004`) in `#{pane_tty} #{pane_id}` lines.
+pub fn pane_for_tty(listing: &str, tty: &str) -> Option<String> {
+    listing.lines().find_map(|line| {
+        let (pane_tty, pane_id) = line.trim().split_once(' ')?;
+        (pane_tty.strip_prefix("/dev/").unwrap_or(pane_tty) == tty).then(|| pane_id.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_pane_by_tty() {
+        let listing = "/dev/pts/1 %0\n/dev/pts/3 %4\n/dev/ttys004 %7\n";
+        assert_eq!(pane_for_tty(listing, "pts/3").as_deref(), Some("%4"));
+        assert_eq!(pane_for_tty(listing, "ttys004").as_deref(), Some("%7"));
+        assert_eq!(pane_for_tty(listing, "pts/9"), None);
+        assert_eq!(base64_encode(b"cargo publish"), "Y2FyZ28gcHVibGlzaA==");
+    }
+
+    #[test]
+    fn resume_templates_quote_each_placeholder() {
+        let mut row: SessionRow = serde_json::from_value(serde_json::json!({
+            "host": "home",
+            "thread_id": "019c2590-aaaa",
+            "pids": [],
+            "status": "waiting",
+            "cwd": "/src/app",
+        }))
+        .expect("row");
+        assert_eq!(
+            expand_resume_template("tmux new-window -n {host} {resume}", &row),
+            r#"tmux new-window -n home 'ssh -t home '\''cd /src/app && codex resume 019c2590-aaaa'\'''"#
+        );
+        row.host = "local".into();
+        row.cwd = Some("/src/my app".into());
+        assert_eq!(
+            expand_resume_template("kitty --directory {cwd} codex resume {thread_id}", &row),
+            "kitty --directory '/src/my app' codex resume 019c2590-aaaa"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use std::io::Write;
+
+use crate::collector::Collector;
+use crate::guard::{GuardProfile, GuardedAction, confirm_cli};
+use crate::selector;
+
+/// `codex-ps kill THREAD`: signal a session's processes on whichever host runs them. An
+/// upstream collector uses it to reach hosts only this machine can see (`via:`).
+pub fn run_kill(
+    mut collector: Collector,
+    hosts: &[String],
+    thread: &str,
+    signal: &str,
+    yes: bool,
+    guard: GuardProfile,
+) -> anyhow::Result<()> {
+    let signal = signal.trim_start_matches('-').to_ascii_uppercase();
+    let signal = signal.strip_prefix("SIG").unwrap_or(&signal);
+    if signal.is_empty() || !signal.chars().all(|c| c.is_ascii_alphanumeric()) {
+        anyhow::bail!("invalid signal {signal:?} (expected e.g. TERM, KILL, STOP)");
+    }
+    let snap = collector.collect(hosts)?;
+    let row = selector::resolve(&snap.sessions, thread)?;
+    let key = row.id();
+    confirm_cli(
+        guard,
+        GuardedAction::Kill,
+        &format!("Send SIG{signal} to {}:{}", key.host, key.thread_id),
+        yes,
+    )?;
+    collector.signal_session(&key, &row.pids, signal)?;
+    writeln!(std::io::stdout(), "{} {}", key.host, key.thread_id).ok();
+    Ok(())
+}
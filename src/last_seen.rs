@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use crate::model::Snapshot;
+use crate::names::config_dir;
+
+/// The TUI's final snapshot (`last_snapshot.json`), shown as stale on the next start while
+/// the first collection runs.
+#[derive(Clone, Debug)]
+pub struct LastSnapshotStore {
+    path: PathBuf,
+}
+
+impl LastSnapshotStore {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self::new_at(config_dir()?.join("last_snapshot.json")))
+    }
+
+    pub fn new_at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The saved snapshot if it was taken for the same host selection. Missing or unreadable
+    /// files are just a cold start.
+    pub fn load(&self, hosts: &[String]) -> Option<Snapshot> {
+        let text = fs::read_to_string(&self.path).ok()?;
+        let snap: Snapshot = serde_json::from_str(&text).ok()?;
+        (snap.host == hosts.join(",")).then_some(snap)
+    }
+
+    pub fn save(&self, snap: &Snapshot) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create dir {}", parent.display()))?;
+        }
+        let json = serde_json::to_string(snap).context("serialize snapshot")?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, json).with_context(|| format!("write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("rename {} -> {}", tmp.display(), self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_only_for_same_hosts() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = LastSnapshotStore::new_at(dir.path().join("last_snapshot.json"));
+        let hosts = vec!["local".to_string(), "home".to_string()];
+        assert!(store.load(&hosts).is_none());
+
+        let snap = Snapshot {
+            generation: 7,
+            generated_at_unix_s: 1_770_135_142,
+            host: "local,home".into(),
+            ..Default::default()
+        };
+        store.save(&snap).expect("save");
+        assert_eq!(store.load(&hosts).map(|s| s.generation), Some(7));
+        assert!(store.load(&["local".to_string()]).is_none());
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+
+use crate::app::short_thread_id;
+use crate::collector::Collector;
+use crate::discovery::{CodexProcess, all_rollouts, recent_rollouts};
+use crate::model::SessionId;
+use crate::util::run_cmd_with_timeout;
+
+const DETECT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const PS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// `codex-ps run -- codex ...`: what to attach once the child's thread id shows up.
+#[derive(Clone, Debug, Default)]
+pub struct LaunchRequest {
+    pub argv: Vec<String>,
+    /// May contain `{cwd}`, `{date}`, `{time}`, `{thread}` placeholders.
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+/// Run the child on this terminal (stdio inherited, so it owns the TTY), name its session as
+/// soon as a new rollout appears, and return the child's exit code.
+///
+/// Detection prefers the rollout the child (or a process it started) holds open, so
+/// `codex resume <id>` is found too. Without process info it falls back to "the one rollout
+/// file created since launch"; if several appear at once nothing is named. Outcome is
+/// reported on stderr after the child exits so it doesn't scribble over Codex's UI.
+pub fn run_launch(mut collector: Collector, req: LaunchRequest) -> anyhow::Result<i32> {
+    let Some((program, args)) = req.argv.split_first() else {
+        anyhow::bail!("usage: codex-ps run [--name ...] -- codex [args...]");
+    };
+
+    let started = SystemTime::now();
+    let before: HashSet<PathBuf> = all_rollouts(collector.codex_home())
+        .context("list existing rollouts")?
+        .into_iter()
+        .collect();
+
+    // Ctrl-C reaches the child via the terminal; don't let it kill the wrapper first.
+    #[cfg(unix)]
+    let interrupted = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGINT, interrupted.clone())
+        .context("install SIGINT handler")?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .spawn()
+        .with_context(|| format!("spawn {program}"))?;
+
+    let child_pid = i32::try_from(child.id()).unwrap_or(-1);
+    let done = Arc::new(AtomicBool::new(false));
+    let watcher = {
+        let done = done.clone();
+        std::thread::spawn(move || -> anyhow::Result<String> {
+            let cwd = std::env::current_dir().ok();
+            loop {
+                // One last look after the child exits (short sessions still get named).
+                let last_pass = done.load(Ordering::SeqCst);
+                let procs = collector.discover_processes().unwrap_or_default();
+                let ours = descendants(child_pid, &process_table());
+                let since = started.elapsed().unwrap_or_default() + Duration::from_secs(1);
+                let created: Vec<PathBuf> =
+                    recent_rollouts(collector.codex_home(), since, SystemTime::now())?
+                        .into_iter()
+                        .filter(|p| !before.contains(p))
+                        .collect();
+                match launched_rollout(&procs, &ours, &created)? {
+                    Some(one) => return apply(&mut collector, &one, cwd.as_deref(), &req),
+                    None if last_pass => anyhow::bail!("no new Codex session appeared"),
+                    None => {}
+                }
+                std::thread::sleep(DETECT_POLL_INTERVAL);
+            }
+        })
+    };
+
+    let status = child.wait().context("wait for child")?;
+    done.store(true, Ordering::SeqCst);
+    match watcher.join() {
+        Ok(Ok(msg)) => eprintln!("codex-ps: {msg}"),
+        Ok(Err(e)) => eprintln!("codex-ps: could not name session: {e:#}"),
+        Err(_) => eprintln!("codex-ps: session watcher panicked"),
+    }
+
+    Ok(exit_code(status))
+}
+
+/// The launched session's rollout: the first one a process in `ours` holds open, else the
+/// only rollout `created` since launch. Several created at once is an error.
+fn launched_rollout(
+    procs: &[CodexProcess],
+    ours: &HashSet<i32>,
+    created: &[PathBuf],
+) -> anyhow::Result<Option<PathBuf>> {
+    let held = procs
+        .iter()
+        .filter(|p| p.pid.is_some_and(|pid| ours.contains(&pid)))
+        .flat_map(|p| &p.rollout_paths)
+        .min_by_key(|p| p.file_name().map(|n| n.to_os_string()));
+    if let Some(path) = held {
+        return Ok(Some(path.clone()));
+    }
+    match created {
+        [] => Ok(None),
+        [one] => Ok(Some(one.clone())),
+        many => anyhow::bail!(
+            "{} new sessions appeared at once; not naming any",
+            many.len()
+        ),
+    }
+}
+
+/// `root` and every process below it in a `(pid, ppid)` table (`codex` may be a wrapper
+/// that starts the real binary).
+fn descendants(root: i32, table: &[(i32, i32)]) -> HashSet<i32> {
+    let mut out = HashSet::from([root]);
+    loop {
+        let before = out.len();
+        for (pid, ppid) in table {
+            if out.contains(ppid) {
+                out.insert(*pid);
+            }
+        }
+        if out.len() == before {
+            return out;
+        }
+    }
+}
+
+/// `(pid, ppid)` for every process, from `ps`; empty if it can't be run.
+fn process_table() -> Vec<(i32, i32)> {
+    let mut cmd = Command::new("ps");
+    cmd.args(["-A", "-o", "pid=,ppid="]);
+    let Ok(out) = run_cmd_with_timeout(cmd, PS_TIMEOUT) else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|l| {
+            let mut it = l.split_whitespace().map(|n| n.parse().ok());
+            Some((it.next()??, it.next()??))
+        })
+        .collect()
+}
+
+fn apply(
+    collector: &mut Collector,
+    rollout: &Path,
+    cwd: Option<&Path>,
+    req: &LaunchRequest,
+) -> anyhow::Result<String> {
+    let thread_id = collector
+        .thread_id_for_rollout(rollout)
+        .with_context(|| format!("no thread id in rollout: {}", rollout.display()))?;
+    let key = SessionId::new("local", thread_id.clone());
+
+    let mut applied = Vec::new();
+    if let Some(t) = req.name.as_deref() {
+        let name = expand_template(t, &thread_id, cwd, chrono::Local::now());
+        if let Some(name) = collector.set_session_name(key.clone(), name)? {
+            applied.push(format!("named {name:?}"));
+        }
+    }
+    if !req.tags.is_empty() || req.note.is_some() {
+        let a = collector.annotate_session(key, &req.tags, req.note.clone())?;
+        if !a.tags.is_empty() {
+            applied.push(format!("tagged {}", a.tags.join(", ")));
+        }
+    }
+    Ok(format!(
+        "session {} {}",
+        short_thread_id(&thread_id),
+        if applied.is_empty() {
+            "detected".to_string()
+        } else {
+            applied.join("; ")
+        }
+    ))
+}
+
+/// `{cwd}` = basename of the launch directory, `{date}` = `YYYY-MM-DD`, `{time}` = `HH:MM`,
+/// `{thread}` = short thread id.
+pub fn expand_template(
+    template: &str,
+    thread_id: &str,
+    cwd: Option<&Path>,
+    now: chrono::DateTime<chrono::Local>,
+) -> String {
+    let cwd_name = cwd
+        .and_then(|p| p.file_name())
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    template
+        .replace("{cwd}", &cwd_name)
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H:%M").to_string())
+        .replace("{thread}", &short_thread_id(thread_id))
+}
+
+fn exit_code(status: std::process::ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(sig) = status.signal() {
+            return 128 + sig;
+        }
+    }
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn expands_name_templates() {
+        let now = chrono::Local
+            .with_ymd_and_hms(2026, 2, 3, 16, 12, 22)
+            .single()
+            .expect("time");
+        let name = expand_template(
+            "{cwd} {date} {time} ({thread})",
+            "019c2590-5605-7cd1-81b8-8a488af219a3",
+            Some(Path::new("/home/u/src/codex-ps")),
+            now,
+        );
+        assert_eq!(
+            name,
+            format!(
+                "codex-ps 2026-02-03 16:12 ({})",
+                short_thread_id("019c2590-5605-7cd1-81b8-8a488af219a3")
+            )
+        );
+    }
+
+    #[test]
+    fn prefers_the_rollout_the_child_holds_open() {
+        let proc_ = |pid: i32, rollout: &str| CodexProcess {
+            pid: Some(pid),
+            exe: None,
+            cwd: None,
+            tty: None,
+            rollout_paths: vec![PathBuf::from(rollout)],
+        };
+        // Our wrapper (10) started the real binary (11); 20 is an unrelated session.
+        let ours = descendants(10, &[(11, 10), (20, 1), (12, 11)]);
+        assert_eq!(ours, HashSet::from([10, 11, 12]));
+        let procs = [proc_(20, "/s/other.jsonl"), proc_(11, "/s/resumed.jsonl")];
+        let other_new = [PathBuf::from("/s/new.jsonl")];
+        assert_eq!(
+            launched_rollout(&procs, &ours, &other_new).expect("detect"),
+            Some(PathBuf::from("/s/resumed.jsonl"))
+        );
+
+        // No process info (fs-scan, or the child already exited): the one new file.
+        assert_eq!(
+            launched_rollout(&[], &ours, &other_new).expect("detect"),
+            Some(PathBuf::from("/s/new.jsonl"))
+        );
+        assert_eq!(launched_rollout(&[], &ours, &[]).expect("detect"), None);
+        let two = [PathBuf::from("/s/a.jsonl"), PathBuf::from("/s/b.jsonl")];
+        assert!(launched_rollout(&[], &ours, &two).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{SessionId, SessionRow};
+use crate::names::config_dir;
+
+/// Longest chain of forks followed back (guards against cycles in a hand-edited file).
+const MAX_ANCESTORS: usize = 16;
+
+/// Append-only record of which thread each forked session came from
+/// (`thread_aliases.jsonl`). The parent may be long gone by the time its fork is forked
+/// again, so the chain is kept here rather than re-read from rollouts.
+#[derive(Clone, Debug)]
+pub struct ThreadAliases {
+    path: PathBuf,
+    loaded: bool,
+    parents: HashMap<SessionId, String>,
+}
+
+impl ThreadAliases {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self::new_at(config_dir()?.join("thread_aliases.jsonl")))
+    }
+
+    pub fn new_at(path: PathBuf) -> Self {
+        Self {
+            path,
+            loaded: false,
+            parents: HashMap::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record the forks among `rows` not seen before.
+    pub fn observe(&mut self, rows: &[SessionRow]) -> anyhow::Result<()> {
+        if !self.loaded {
+            self.load()?;
+        }
+        for row in rows {
+            let Some(parent) = row.forked_from_id.as_ref() else {
+                continue;
+            };
+            let key = row.id();
+            if parent == &row.thread_id || self.parents.get(&key) == Some(parent) {
+                continue;
+            }
+            let rec = AliasRecord {
+                host: key.host.clone(),
+                thread_id: key.thread_id.clone(),
+                forked_from_id: parent.clone(),
+            };
+            self.append_record(&rec)?;
+            self.parents.insert(key, rec.forked_from_id);
+        }
+        Ok(())
+    }
+
+    /// The threads `key` was forked from, nearest first.
+    pub fn ancestors(&self, key: &SessionId) -> Vec<SessionId> {
+        let mut out: Vec<SessionId> = Vec::new();
+        let mut cur = key.clone();
+        while let Some(parent) = self.parents.get(&cur) {
+            let next = SessionId::new(key.host.clone(), parent.clone());
+            if next == *key || out.contains(&next) || out.len() >= MAX_ANCESTORS {
+                break;
+            }
+            out.push(next.clone());
+            cur = next;
+        }
+        out
+    }
+
+    fn load(&mut self) -> anyhow::Result<()> {
+        self.loaded = true;
+        let f = match fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("open {}", self.path.display())),
+        };
+
+        for (i, line) in BufReader::new(f).lines().enumerate() {
+            let line = line.context("read line")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let rec: AliasRecord = serde_json::from_str(&line)
+                .with_context(|| format!("parse thread_aliases.jsonl line {}", i + 1))?;
+            self.parents
+                .insert(SessionId::new(rec.host, rec.thread_id), rec.forked_from_id);
+        }
+        Ok(())
+    }
+
+    fn append_record(&self, rec: &AliasRecord) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create dir {}", parent.display()))?;
+        }
+        let line = serde_json::to_string(rec).context("serialize thread alias")?;
+        let mut f = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("open for append {}", self.path.display()))?;
+        writeln!(f, "{line}").context("append thread alias")?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AliasRecord {
+    host: String,
+    thread_id: String,
+    forked_from_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fork(thread_id: &str, parent: &str) -> SessionRow {
+        serde_json::from_value(serde_json::json!({
+            "host": "local",
+            "thread_id": thread_id,
+            "pids": [],
+            "status": "waiting",
+            "forked_from_id": parent,
+        }))
+        .expect("row")
+    }
+
+    #[test]
+    fn ancestors_survive_the_parent_exiting_and_a_reload() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let p = dir.path().join("thread_aliases.jsonl");
+        let mut a = ThreadAliases::new_at(p.clone());
+        a.observe(&[fork("b", "a")]).expect("observe");
+        // Later `b` is gone and its own fork is running.
+        a.observe(&[fork("c", "b"), fork("c", "b")])
+            .expect("observe");
+        let key = |t: &str| SessionId::new("local", t);
+        assert_eq!(a.ancestors(&key("c")), vec![key("b"), key("a")]);
+
+        let mut reloaded = ThreadAliases::new_at(p.clone());
+        reloaded.observe(&[]).expect("load");
+        assert_eq!(reloaded.ancestors(&key("c")), vec![key("b"), key("a")]);
+        assert!(reloaded.ancestors(&key("a")).is_empty());
+        assert_eq!(
+            std::fs::read_to_string(&p).expect("read").lines().count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::collector::Collector;
+use crate::rollout::render_rollout_line;
+use crate::selector;
+use crate::timefmt::{TimeDisplay, format_timestamp};
+use crate::util::system_time_to_unix_s;
+
+/// How much of the end of the rollout the first page is taken from.
+pub const LOGS_TAIL_MAX_BYTES: u64 = 1024 * 1024;
+
+/// How often `-f` checks the rollout for new lines.
+const FOLLOW_POLL: Duration = Duration::from_millis(500);
+
+/// `codex-ps logs <selector>`: the last `lines` readable events of a session's rollout, then
+/// with `follow` every new one as it is written. Remote sessions are read by running
+/// `codex-ps logs` on their host.
+pub fn run_logs(
+    mut collector: Collector,
+    hosts: &[String],
+    selector: &str,
+    lines: usize,
+    follow: bool,
+) -> anyhow::Result<()> {
+    let snap = collector.collect(hosts)?;
+    let row = selector::resolve(&snap.sessions, selector)?;
+    let mut stdout = std::io::stdout();
+
+    if row.host != "local" {
+        if follow {
+            anyhow::bail!(
+                "-f only works for local sessions; run `codex-ps logs -f` on {}",
+                row.host
+            );
+        }
+        collector.require_feature(&row.host, "logs")?;
+        let n = lines.to_string();
+        let out = collector.run_on_host(&row.host, &["logs", &row.thread_id, "-n", &n])?;
+        return ignore_broken_pipe(stdout.write_all(out.as_bytes()));
+    }
+
+    let path = row
+        .rollout_path
+        .as_deref()
+        .context("session has no rollout file yet")?;
+    let mut tail = RolloutTail::open(Path::new(path), LOGS_TAIL_MAX_BYTES)?;
+    let entries = tail.read_new()?;
+    let first = entries.len().saturating_sub(lines);
+    let mut page = String::new();
+    for e in &entries[first..] {
+        page.push_str(e);
+        page.push('\n');
+    }
+    ignore_broken_pipe(stdout.write_all(page.as_bytes()))?;
+    if !follow {
+        return Ok(());
+    }
+
+    loop {
+        std::thread::sleep(FOLLOW_POLL);
+        let entries = tail.read_new()?;
+        if entries.is_empty() {
+            continue;
+        }
+        let mut out = String::new();
+        for e in entries {
+            out.push_str(&e);
+            out.push('\n');
+        }
+        if let Err(e) = stdout
+            .write_all(out.as_bytes())
+            .and_then(|_| stdout.flush())
+        {
+            return ignore_broken_pipe(Err(e));
+        }
+    }
+}
+
+/// Follows a rollout file: each `read_new` returns the entries of the whole lines written
+/// since the last call (a line still being written waits for the next one).
+#[derive(Debug)]
+pub struct RolloutTail {
+    path: PathBuf,
+    file: File,
+    pos: u64,
+    pending: Vec<u8>,
+    /// Started mid-file, so the first line read is almost certainly a partial record.
+    skip_partial: bool,
+}
+
+impl RolloutTail {
+    /// Positioned `max_bytes` before the end, so the first read is the recent history.
+    pub fn open(path: &Path, max_bytes: u64) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("open rollout: {}", path.display()))?;
+        let len = file
+            .metadata()
+            .with_context(|| format!("stat rollout: {}", path.display()))?
+            .len();
+        let pos = len.saturating_sub(max_bytes);
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            pos,
+            pending: Vec::new(),
+            skip_partial: pos > 0,
+        })
+    }
+
+    pub fn read_new(&mut self) -> anyhow::Result<Vec<String>> {
+        let path = self.path.display();
+        let len = std::fs::metadata(&self.path)
+            .with_context(|| format!("stat rollout: {path}"))?
+            .len();
+        if len < self.pos {
+            // Truncated or replaced: start over from the top.
+            self.pos = 0;
+            self.pending.clear();
+            self.skip_partial = false;
+        }
+        if len == self.pos {
+            return Ok(Vec::new());
+        }
+        self.file
+            .seek(SeekFrom::Start(self.pos))
+            .with_context(|| format!("seek rollout: {path}"))?;
+        let read = Read::by_ref(&mut self.file)
+            .take(len - self.pos)
+            .read_to_end(&mut self.pending)
+            .with_context(|| format!("read rollout: {path}"))?;
+        self.pos += read as u64;
+
+        let Some(end) = self.pending.iter().rposition(|b| *b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let chunk: Vec<u8> = self.pending.drain(..=end).collect();
+        let text = String::from_utf8_lossy(&chunk);
+        let mut lines = text.lines();
+        if std::mem::take(&mut self.skip_partial) {
+            lines.next();
+        }
+        Ok(lines.filter_map(format_entry).collect())
+    }
+}
+
+/// `16:12:22 assistant: ...`, continuation lines indented under the text.
+fn format_entry(raw: &str) -> Option<String> {
+    let (ts, text) = render_rollout_line(raw)?;
+    let now = system_time_to_unix_s(std::time::SystemTime::now()).unwrap_or(0);
+    let time = ts.map_or_else(
+        || "?".to_string(),
+        |ts| format_timestamp(ts, now, TimeDisplay::Local),
+    );
+    let indent = " ".repeat(time.chars().count() + 1);
+    Some(format!(
+        "{time} {}",
+        text.trim_end().replace('\n', &format!("\n{indent}"))
+    ))
+}
+
+/// Piping into `head` closes stdout early; that is a normal way to stop.
+fn ignore_broken_pipe(r: std::io::Result<()>) -> anyhow::Result<()> {
+    match r {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_returns_whole_new_lines_only() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let path = dir.path().join("rollout.jsonl");
+        let msg = |ty: &str, text: &str| {
+            format!(
+                r#"{{"timestamp":"2025-01-01T00:00:00Z","type":"event_msg","payload":{{"type":"{ty}","message":"{text}"}}}}"#
+            )
+        };
+        let first = msg("user_message", "hello");
+        std::fs::write(&path, format!("{first}\n{}\n", msg("agent_message", "hi"))).expect("write");
+        // Starting mid-file drops the cut-off first record.
+        let mut tail = RolloutTail::open(&path, (first.len() + 10) as u64).expect("open");
+        let entries = tail.read_new().expect("read");
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].ends_with("assistant: hi"), "{entries:?}");
+
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open");
+        let next = msg("agent_message", "done");
+        f.write_all(&next.as_bytes()[..10]).expect("append");
+        assert!(tail.read_new().expect("read").is_empty());
+        f.write_all(format!("{}\n", &next[10..]).as_bytes())
+            .expect("append");
+        let entries = tail.read_new().expect("read");
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].ends_with("assistant: done"), "{entries:?}");
+    }
+}
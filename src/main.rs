@@ -1,22 +1,76 @@
+mod accessible;
+mod annotate;
+mod annotations;
 mod app;
+mod audit;
+mod baseline;
+mod budget;
+mod capabilities;
+mod changes;
+mod cli_version;
 mod codex_home;
 mod collector;
+mod config;
 mod discovery;
+mod error;
+mod eta;
+mod events;
 mod git;
+mod guard;
+mod health;
+mod history;
+mod hooks;
+mod init;
+mod jump;
+mod kill;
+mod last_seen;
+mod launch;
+mod lineage;
+mod logs;
+mod manifest;
 mod model;
+mod mounts;
 mod names;
+mod policy;
+mod raycast;
+mod receipt;
 mod rollout;
+mod schedule;
+mod selector;
+mod serve;
+mod show;
+mod statusline;
+mod suggest;
+mod swarm;
+mod sync;
+mod table;
+mod theme;
+mod timefmt;
 mod titles;
+mod todo;
+mod transport;
+mod usage;
 mod util;
+mod waits;
 
 use anyhow::Context;
 use clap::Parser;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 
+use crate::annotate::AnnotateRequest;
+use crate::app::{ColumnLayout, TuiOptions};
 use crate::codex_home::CodexHome;
 use crate::collector::Collector;
+use crate::config::Config;
+use crate::discovery::{DiscoveryBackend, ThreadIdPatterns, detect_sandbox, discovery_backend};
+use crate::hooks::HookRunner;
+use crate::launch::LaunchRequest;
+use crate::policy::PolicyEngine;
+use crate::timefmt::TimeDisplay;
+use crate::transport::{TransportKind, host_transport};
 
-const DEFAULT_REMOTE_HOSTS: &[&str] = &["home", "amirs-work-studio"];
+const DEFAULT_REFRESH_MS: u64 = 1000;
+const DEFAULT_SSH_TIMEOUT_MS: u64 = 6000;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -24,12 +78,43 @@ const DEFAULT_REMOTE_HOSTS: &[&str] = &["home", "amirs-work-studio"];
     version,
     about = "Real-time overview of active Codex CLI sessions"
 )]
+#[command(group(clap::ArgGroup::new("output").args(["json", "format"])))]
 struct Cli {
     /// Output a single JSON snapshot (no TUI).
     #[arg(long)]
     json: bool,
 
-    /// Host selector: local|home|amirs-work-studio|all, or a comma-list.
+    /// Stream NDJSON events (session_started, status_changed, session_ended, host_error, ...)
+    /// every refresh instead of running the TUI.
+    #[arg(long, conflicts_with = "json")]
+    events: bool,
+
+    /// Print one snapshot in this format instead of running the TUI (`json` is `--json`;
+    /// `lsp-json` is a compact summary for editor statuslines; `table`/`csv`/`tsv` are the
+    /// TUI's rows as text).
+    #[arg(long, value_enum, conflicts_with_all = ["json", "events"])]
+    format: Option<OutputFormat>,
+
+    /// With `--format lsp-json`: only sessions whose repo/cwd is inside this directory.
+    #[arg(long, requires = "format")]
+    repo: Option<std::path::PathBuf>,
+
+    /// With `--json`/`--format`: keep running. `lsp-json` prints a new line whenever it
+    /// changes; `json` prints one compact snapshot per line every refresh.
+    #[arg(long, requires = "output")]
+    watch: bool,
+
+    /// Print one compact JSON snapshot per line every refresh (NDJSON) instead of running the
+    /// TUI; short for `--json --watch`.
+    #[arg(long, conflicts_with_all = ["json", "format", "events"])]
+    follow: bool,
+
+    /// Print JSON snapshots on a single line (the default with `--watch`).
+    #[arg(long, requires = "output")]
+    compact: bool,
+
+    /// Host selector: `local`, a host name, `all` (local plus `hosts` in config.toml), or a
+    /// comma-list (`!HOST` drops one); `via:HOST` collects everything HOST aggregates.
     #[arg(long, default_value = "local")]
     host: String,
 
@@ -37,25 +122,274 @@ struct Cli {
     #[arg(long)]
     codex_home: Option<std::path::PathBuf>,
 
-    /// Refresh interval for the TUI.
-    #[arg(long, default_value_t = 1000)]
-    refresh_ms: u64,
+    /// Process discovery backend used for local collection.
+    #[arg(long, value_enum, default_value_t = DiscoveryBackend::Auto)]
+    discovery: DiscoveryBackend,
 
-    /// SSH binary to use for remote aggregation (Phase 2).
-    #[arg(long, default_value = "ssh")]
-    ssh_bin: String,
+    /// Procfs mount to enumerate processes from instead of /proc (e.g. the host's /proc
+    /// mounted into a Flatpak/Snap/container sandbox); implies `--discovery procfs`.
+    #[arg(long)]
+    proc_root: Option<std::path::PathBuf>,
 
-    /// Remote `codex-ps` command (must be installed on the remote host).
-    #[arg(long, default_value = "codex-ps")]
-    remote_bin: String,
+    /// Refresh interval for the TUI (default: `refresh_ms` in the config, else 1000).
+    #[arg(long)]
+    refresh_ms: Option<u64>,
+
+    /// How to reach remote hosts (Phase 2).
+    #[arg(long, value_enum, default_value_t = TransportKind::Ssh)]
+    transport: TransportKind,
+
+    /// SSH binary to use for remote aggregation (ssh transports; default: `ssh_bin` in the
+    /// config, else `ssh`).
+    #[arg(long)]
+    ssh_bin: Option<String>,
 
-    /// SSH timeout per host.
-    #[arg(long, default_value_t = 6000)]
-    ssh_timeout_ms: u64,
+    /// Remote `codex-ps` command, which must be installed on the remote host (default:
+    /// `remote_bin` in the config, else `codex-ps`).
+    #[arg(long)]
+    remote_bin: Option<String>,
+
+    /// Remote collection timeout per host, all transports (default: `ssh_timeout_ms` in the
+    /// config, else 6000).
+    #[arg(long)]
+    ssh_timeout_ms: Option<u64>,
 
-    /// Include extra diagnostic fields in JSON / status line.
+    /// Sessions table columns in order, with optional widths (`state,age,name:30,pwd`);
+    /// overrides `columns` in the config.
+    #[arg(long)]
+    columns: Option<String>,
+
+    /// Group the sessions table under a header per host (`g` toggles it in the TUI).
+    #[arg(long)]
+    group_by_host: bool,
+
+    /// How the TUI shows AGE/updated times (JSON always carries ISO 8601 UTC).
+    #[arg(long, value_enum, default_value_t = TimeDisplay::Relative)]
+    time_format: TimeDisplay,
+
+    /// Screen-reader friendly output: plain lines announcing changes instead of a redrawn grid.
+    /// Enabled automatically when stdout is not a terminal or TERM=dumb.
+    #[arg(long)]
+    accessible: bool,
+
+    /// Keep the terminal/tmux window title set to a live summary (`codex-ps ✋2 ⚒3`: waiting,
+    /// working) so it shows in the tab bar.
+    #[arg(long)]
+    set_title: bool,
+
+    /// Also ask git for each local session's current branch and show it next to the branch
+    /// the session started on when they differ.
+    #[arg(long)]
+    probe_branch: bool,
+
+    /// A session whose rollout was written this recently (and that has no pending call) is
+    /// WORKING (default: `working_max_age_secs` in the config, else 15; 45 on network mounts).
+    #[arg(long)]
+    working_max_age_secs: Option<u64>,
+
+    /// Up to this long since the last write it is UNKNOWN, after that WAITING (default:
+    /// `uncertain_max_age_secs` in the config, else 60; 180 on network mounts).
+    #[arg(long)]
+    uncertain_max_age_secs: Option<u64>,
+
+    /// Include extra diagnostic fields in JSON / status line (also `debug = true` in the
+    /// config).
     #[arg(long)]
     debug: bool,
+
+    /// Don't ask for confirmation, whatever the config's `guard` profile says.
+    #[arg(long, short = 'y', global = true)]
+    yes: bool,
+
+    /// Config file (default: ~/.config/codex-ps/config.toml).
+    #[arg(long)]
+    config: Option<std::path::PathBuf>,
+
+    /// Aggregators already relaying this request (set by an upstream `via:` collector).
+    #[arg(long, hide = true, value_delimiter = ',')]
+    relay_chain: Vec<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Name, tag or annotate an active session by selector (for hooks/launch scripts).
+    Annotate {
+        /// Session selector: thread id (prefix), name, `host:name`, `@latest` or `@blocked`.
+        #[arg(long)]
+        thread: String,
+
+        /// Session name (same as naming it in the TUI).
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Tag to add (repeatable).
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Free-form note (replaces any previous note; empty clears it).
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Launch codex (`codex-ps run -- codex <args>`) and name its session once it appears.
+    Run {
+        /// Session name; may use {cwd}, {date}, {time}, {thread}.
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Tag to add (repeatable).
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Free-form note.
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Command to run (after `--`).
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Print everything known about one session (untruncated, with its subagents).
+    Show {
+        /// Session selector: thread id (prefix), name, `host:name`, `@latest` or `@blocked`.
+        thread: String,
+
+        /// Print the session row and its subagents as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Signal a session's processes (SIGTERM by default) on whichever host runs them.
+    Kill {
+        /// Session selector: thread id (prefix), name, `host:name`, `@latest` or `@blocked`.
+        thread: String,
+
+        /// Signal name: TERM, KILL, STOP, CONT, ...
+        #[arg(long, default_value = "TERM")]
+        signal: String,
+    },
+    /// Print a session's recent messages, tool calls and outputs from its rollout.
+    Logs {
+        /// Session selector: thread id (prefix), name, `host:name`, `@latest` or `@blocked`.
+        thread: String,
+
+        /// How many events to print.
+        #[arg(short = 'n', long, default_value_t = 40)]
+        lines: usize,
+
+        /// Keep printing new events as they are written (local sessions only).
+        #[arg(short, long)]
+        follow: bool,
+    },
+    /// Print sessions waiting on you as a Markdown checklist (for daily notes).
+    Todo,
+    /// Print this build's version and remote-facing features as JSON (probed by other hosts).
+    Capabilities,
+    /// Serve the live snapshot (`GET /snapshot`) and event stream (`GET /events`, SSE) over
+    /// HTTP for dashboards; see `[serve]` in the config for the token and CORS origins.
+    Serve {
+        /// Address to listen on (default: `[serve] listen`, else 127.0.0.1:7780).
+        #[arg(long)]
+        listen: Option<String>,
+    },
+    /// First-run setup: check CODEX_HOME and discovery, pick remote hosts from
+    /// `~/.ssh/config`, verify them and write them to the config.
+    Init {
+        /// Don't ask: add every ssh host that answers and write the config.
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Merge `--json` snapshots collected elsewhere (fleet tooling) into one snapshot.
+    Merge {
+        /// `HOST=FILE` relabels that file's sessions as HOST, like remote aggregation does;
+        /// a bare FILE is used as-is.
+        #[arg(required = true)]
+        inputs: Vec<String>,
+    },
+    /// Exchange session names, tags and notes with the remote `--host`s (newest edit wins).
+    Sync {
+        /// What the remote hosts call this machine in their `--host`, so sessions running
+        /// here are synced too.
+        #[arg(long = "as")]
+        alias: Option<String>,
+    },
+    /// Read or merge this machine's names/tags stores as JSON (used by `sync`).
+    Labels {
+        #[command(subcommand)]
+        action: LabelsAction,
+    },
+    /// Record the running sessions, then later report any that are gone (for overnight batch
+    /// runs where every worker should stay alive).
+    Baseline {
+        #[command(subcommand)]
+        action: BaselineAction,
+    },
+    /// Reconcile a batch launcher's manifest (`[[session]]` name/repo/host entries) with the
+    /// running sessions: matched, missing and unexpected. Exits non-zero when any are missing.
+    Manifest {
+        file: std::path::PathBuf,
+
+        /// Print the reconciliation as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Summarize recorded usage (`capacity`: peak concurrent Working sessions per host per day).
+    Report {
+        #[arg(value_enum)]
+        kind: ReportKind,
+
+        /// How many days back to include (today counts as one).
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+    },
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum LabelsAction {
+    /// Print every name and annotation record, latest per session.
+    Export,
+    /// Apply records from another machine where they are newer; prints how many were applied.
+    Import { json: String },
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum BaselineAction {
+    /// Record the root sessions running on `--host` now.
+    Save {
+        /// Where to keep the baseline (default: baseline.json in the config directory).
+        #[arg(long)]
+        file: Option<std::path::PathBuf>,
+    },
+    /// Poll the baseline's hosts and list sessions missing, unreachable or new; exits non-zero
+    /// when any are missing.
+    Check {
+        #[arg(long)]
+        file: Option<std::path::PathBuf>,
+
+        /// Print the deviations as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    Json,
+    LspJson,
+    /// Alfred/Raycast script-filter items with resume/kill commands.
+    Raycast,
+    /// The TUI's rows and columns as aligned text.
+    Table,
+    /// Every column, untruncated, for spreadsheets.
+    Csv,
+    /// Every column, untruncated, tab-separated (for awk/cut).
+    Tsv,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum ReportKind {
+    Capacity,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -63,57 +397,389 @@ fn main() -> anyhow::Result<()> {
 
     let codex_home = CodexHome::resolve(cli.codex_home.clone())?;
 
-    let hosts = parse_hosts(&cli.host)?;
-    let mut collector = Collector::new(
-        codex_home,
-        cli.ssh_bin.clone(),
-        cli.remote_bin.clone(),
-        std::time::Duration::from_millis(cli.ssh_timeout_ms.max(100)),
+    let config = match (&cli.command, cli.config.as_deref()) {
+        // `init` creates the file `--config` names.
+        (Some(Command::Init { .. }), Some(p)) if !p.exists() => Config::default(),
+        (_, path) => Config::load(path)?,
+    };
+    // Flags win over the config, which wins over the built-in defaults.
+    let refresh_ms = cli
+        .refresh_ms
+        .or(config.refresh_ms)
+        .unwrap_or(DEFAULT_REFRESH_MS);
+    let debug = cli.debug || config.debug;
+    let remotes: Vec<String> = config.hosts.clone().unwrap_or_default();
+    let hosts = parse_hosts(&cli.host, &remotes, &config.disabled_hosts)?;
+    let discovery = discovery_backend(
+        cli.discovery,
+        std::time::Duration::from_secs(10),
+        cli.proc_root.clone(),
+    )?;
+    let transport = host_transport(
+        cli.transport,
+        cli.ssh_bin
+            .clone()
+            .or_else(|| config.ssh_bin.clone())
+            .unwrap_or_else(|| "ssh".into()),
+        std::time::Duration::from_millis(
+            cli.ssh_timeout_ms
+                .or(config.ssh_timeout_ms)
+                .unwrap_or(DEFAULT_SSH_TIMEOUT_MS)
+                .max(100),
+        ),
     )?;
+    let remote_bin = cli
+        .remote_bin
+        .clone()
+        .or_else(|| config.remote_bin.clone())
+        .unwrap_or_else(|| "codex-ps".into());
+    let mut collector = Collector::new(codex_home, discovery, transport, remote_bin)?;
+    collector.set_probe_branch(cli.probe_branch);
+    collector
+        .set_status_thresholds(
+            cli.working_max_age_secs
+                .or(config.working_max_age_secs)
+                .map(std::time::Duration::from_secs),
+            cli.uncertain_max_age_secs
+                .or(config.uncertain_max_age_secs)
+                .map(std::time::Duration::from_secs),
+        )
+        .context("status thresholds")?;
+    collector.set_host_labels(config.host_labels.clone());
+    collector.set_suggest_names(config.suggest_names);
+    collector.set_relay_chain(cli.relay_chain.clone());
+    if cli.proc_root.is_none() {
+        collector.set_sandbox(detect_sandbox());
+    }
+    collector.set_thread_id_patterns(ThreadIdPatterns::new(&config.rollout_id_patterns)?);
+    collector.set_budget(config.budget.clone());
+    if !config.policy.rules.is_empty() {
+        collector.set_policy(PolicyEngine::new(config.policy.clone()).context("[policy]")?);
+    }
+    match cli.command {
+        Some(Command::Report {
+            kind: ReportKind::Capacity,
+            days,
+        }) => return usage::run_capacity_report(days),
+        Some(Command::Show { thread, json }) => {
+            return show::run_show(collector, &hosts, &thread, json);
+        }
+        Some(Command::Logs {
+            thread,
+            lines,
+            follow,
+        }) => return logs::run_logs(collector, &hosts, &thread, lines, follow),
+        Some(Command::Todo) => return todo::run_todo(collector, &hosts),
+        Some(Command::Capabilities) => return capabilities::run_capabilities(),
+        Some(Command::Kill { thread, signal }) => {
+            return kill::run_kill(collector, &hosts, &thread, &signal, cli.yes, config.guard);
+        }
+        Some(Command::Serve { listen }) => {
+            return serve::run_serve(
+                collector,
+                hosts,
+                refresh_ms,
+                debug,
+                HookRunner::new(config.hooks),
+                config.serve,
+                listen,
+            );
+        }
+        Some(Command::Init { yes }) => {
+            return init::run_init(collector, cli.config.as_deref(), &config, yes);
+        }
+        Some(Command::Merge { inputs }) => return run_merge(&inputs),
+        Some(Command::Sync { alias }) => {
+            return sync::run_sync(collector, &hosts, alias.as_deref());
+        }
+        Some(Command::Labels { action }) => return run_labels(collector, action),
+        Some(Command::Manifest { file, json }) => {
+            return manifest::run_manifest(collector, &hosts, &file, json);
+        }
+        Some(Command::Baseline {
+            action: BaselineAction::Save { file },
+        }) => return baseline::run_save(collector, &hosts, file.as_deref()),
+        Some(Command::Baseline {
+            action: BaselineAction::Check { file, json },
+        }) => return baseline::run_check(collector, file.as_deref(), json),
+        Some(Command::Annotate {
+            thread,
+            name,
+            tags,
+            note,
+        }) => {
+            let req = AnnotateRequest {
+                selector: thread,
+                name,
+                tags,
+                note,
+                yes: cli.yes,
+            };
+            return annotate::run_annotate(collector, &hosts, req, config.guard);
+        }
+        Some(Command::Run {
+            name,
+            tags,
+            note,
+            command,
+        }) => {
+            let req = LaunchRequest {
+                argv: command,
+                name,
+                tags,
+                note,
+            };
+            let code = launch::run_launch(collector, req)?;
+            std::process::exit(code);
+        }
+        None => {}
+    }
+
+    if cli.format == Some(OutputFormat::LspJson) {
+        return statusline::run_status_line(collector, &hosts, cli.repo, cli.watch, refresh_ms);
+    }
+    if cli.format == Some(OutputFormat::Raycast) {
+        return raycast::run_script_filter(collector, &hosts);
+    }
+    if cli.json || cli.format == Some(OutputFormat::Json) || cli.follow {
+        let watch = cli.watch || cli.follow;
+        return run_json(
+            collector,
+            &hosts,
+            debug,
+            cli.compact || watch,
+            watch,
+            refresh_ms,
+        );
+    }
+
+    let columns = match (cli.columns.as_deref(), config.columns.as_deref()) {
+        (Some(flag), _) => {
+            let specs: Vec<&str> = flag.split(',').filter(|s| !s.trim().is_empty()).collect();
+            ColumnLayout::parse(&specs).context("--columns")?
+        }
+        (None, Some(specs)) => ColumnLayout::parse(specs).context("columns in config")?,
+        (None, None) => ColumnLayout::default(),
+    };
+    let hooks = HookRunner::new(config.hooks);
+    let opts = TuiOptions {
+        refresh_ms,
+        debug,
+        time_display: cli.time_format,
+        set_title: cli.set_title,
+        theme: config.theme.theme(),
+        truncate: config.theme.truncate,
+        tag_styles: config.theme.tags,
+        guard: config.guard,
+        schedule: config.schedule,
+        columns,
+        resume_command: config.resume_command,
+        group_by_host: cli.group_by_host || config.group_by_host,
+    };
+    let text_format = match cli.format {
+        Some(OutputFormat::Table) => Some(table::TextFormat::Table),
+        Some(OutputFormat::Csv) => Some(table::TextFormat::Csv),
+        Some(OutputFormat::Tsv) => Some(table::TextFormat::Tsv),
+        _ => None,
+    };
+    if let Some(format) = text_format {
+        if cli.watch {
+            anyhow::bail!("--watch is only supported with --json and --format lsp-json");
+        }
+        return table::run_table(collector, &hosts, format, &opts);
+    }
+    if cli.events {
+        return events::run_events(collector, hosts, refresh_ms, debug, hooks);
+    }
+
+    let dumb_terminal = std::env::var("TERM").is_ok_and(|t| t == "dumb");
+    if cli.accessible || dumb_terminal || !std::io::stdout().is_terminal() {
+        return accessible::run_accessible(collector, hosts, opts, hooks);
+    }
 
-    if cli.json {
-        let snapshot = collector.collect(&hosts, cli.debug)?;
-        let out = serde_json::to_string_pretty(&snapshot).context("serialize JSON snapshot")?;
-        let mut stdout = std::io::stdout();
-        if let Err(e) = writeln!(stdout, "{out}") {
+    app::run_tui(collector, hosts, opts, hooks)
+}
+
+/// `--json`: print one snapshot, or with `watch` one per refresh (NDJSON).
+fn run_json(
+    mut collector: Collector,
+    hosts: &[String],
+    debug: bool,
+    compact: bool,
+    watch: bool,
+    refresh_ms: u64,
+) -> anyhow::Result<()> {
+    let refresh = std::time::Duration::from_millis(refresh_ms.max(100));
+    let mut stdout = std::io::stdout();
+    loop {
+        let mut snapshot = collector.collect(hosts)?;
+        if !debug {
+            snapshot.strip_debug();
+        }
+        let out = if compact {
+            serde_json::to_string(&snapshot)
+        } else {
+            serde_json::to_string_pretty(&snapshot)
+        }
+        .context("serialize JSON snapshot")?;
+        if let Err(e) = writeln!(stdout, "{out}").and_then(|_| stdout.flush()) {
             // Common and harmless when piped to tools like `head`.
-            if e.kind() != std::io::ErrorKind::BrokenPipe {
-                return Err(e.into());
+            if e.kind() == std::io::ErrorKind::BrokenPipe {
+                return Ok(());
             }
+            return Err(e.into());
+        }
+        if !watch {
+            return Ok(());
+        }
+        std::thread::sleep(refresh);
+    }
+}
+
+/// `codex-ps labels`: export or import the name/tag/note set.
+fn run_labels(mut collector: Collector, action: LabelsAction) -> anyhow::Result<()> {
+    match action {
+        LabelsAction::Export => {
+            let out =
+                serde_json::to_string(&collector.export_labels()?).context("serialize labels")?;
+            println!("{out}");
+        }
+        LabelsAction::Import { json } => {
+            let set: sync::LabelSet = serde_json::from_str(&json).context("parse labels")?;
+            println!("{}", collector.import_labels(set)?);
         }
-        return Ok(());
     }
+    Ok(())
+}
 
-    app::run_tui(collector, hosts, cli.refresh_ms, cli.debug)
+/// `codex-ps merge`: read each snapshot, relabel `HOST=` inputs and print the merge.
+fn run_merge(inputs: &[String]) -> anyhow::Result<()> {
+    let mut snapshots = Vec::new();
+    for input in inputs {
+        let (host, path) = match input.split_once('=') {
+            Some((host, path)) if !host.is_empty() => (Some(host), path),
+            _ => (None, input.as_str()),
+        };
+        let text = std::fs::read_to_string(path).with_context(|| format!("read {path}"))?;
+        let mut snap: model::Snapshot =
+            serde_json::from_str(&text).with_context(|| format!("parse snapshot {path}"))?;
+        if let Some(host) = host {
+            snap.relabel_host(host);
+        }
+        snapshots.push(snap);
+    }
+    let out = serde_json::to_string_pretty(&model::Snapshot::merge(snapshots))
+        .context("serialize merged snapshot")?;
+    let mut stdout = std::io::stdout();
+    if let Err(e) = writeln!(stdout, "{out}") {
+        if e.kind() != std::io::ErrorKind::BrokenPipe {
+            return Err(e.into());
+        }
+    }
+    Ok(())
 }
 
-fn parse_hosts(s: &str) -> anyhow::Result<Vec<String>> {
+/// `--host`: `local`, `all`, or a comma-list where `!host` drops a host (`all,!home`). A list
+/// of only exclusions starts from `all`, which is `local` plus `remotes` (`hosts` in the
+/// config). `disabled` (`disabled_hosts`) is left out of `all` but can still be named
+/// explicitly.
+fn parse_hosts(s: &str, remotes: &[String], disabled: &[String]) -> anyhow::Result<Vec<String>> {
     let s = s.trim();
     if s.is_empty() {
         return Ok(vec!["local".into()]);
     }
 
-    if s.eq_ignore_ascii_case("all") {
-        let mut out = Vec::new();
-        out.push("local".into());
-        out.extend(DEFAULT_REMOTE_HOSTS.iter().map(|h| (*h).to_string()));
-        return Ok(out);
-    }
-
+    let all = || {
+        std::iter::once("local")
+            .chain(remotes.iter().map(String::as_str))
+            .filter(|h| !disabled.iter().any(|d| d == h))
+            .map(str::to_string)
+    };
     let mut out: Vec<String> = Vec::new();
+    let mut excluded: Vec<&str> = Vec::new();
     for raw in s.split(',') {
         let h = raw.trim();
         if h.is_empty() {
             continue;
         }
-        if !out.contains(&h.to_string()) {
-            out.push(h.to_string());
+        if let Some(ex) = h.strip_prefix('!') {
+            let ex = ex.trim();
+            if ex.is_empty() || ex.eq_ignore_ascii_case("all") {
+                anyhow::bail!("invalid host exclusion {h:?} in --host {s:?}");
+            }
+            excluded.push(ex);
+            continue;
+        }
+        let add: Vec<String> = if h.eq_ignore_ascii_case("all") {
+            all().collect()
+        } else {
+            vec![h.to_string()]
+        };
+        for h in add {
+            if !out.contains(&h) {
+                out.push(h);
+            }
         }
     }
 
+    if out.is_empty() && !excluded.is_empty() {
+        out = all().collect();
+    }
+    for ex in excluded {
+        let before = out.len();
+        out.retain(|h| h != ex);
+        if out.len() == before {
+            anyhow::bail!("--host {s:?} excludes {ex:?}, which isn't selected");
+        }
+    }
     if out.is_empty() {
+        if s.contains('!') {
+            anyhow::bail!("--host {s:?} excludes every host");
+        }
         out.push("local".into());
     }
 
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_lists_support_exclusions_and_disabled_hosts() {
+        let hosts = |s: &str, disabled: &[&str]| {
+            let disabled: Vec<String> = disabled.iter().map(|d| d.to_string()).collect();
+            let remotes = vec!["home".to_string(), "amirs-work-studio".to_string()];
+            parse_hosts(s, &remotes, &disabled).map_err(|e| e.to_string())
+        };
+        assert_eq!(
+            hosts("all,!home", &[]).expect("parse"),
+            vec!["local", "amirs-work-studio"]
+        );
+        assert_eq!(
+            hosts("!local", &[]).expect("parse"),
+            vec!["home", "amirs-work-studio"]
+        );
+        assert_eq!(
+            hosts("all", &["home"]).expect("parse"),
+            vec!["local", "amirs-work-studio"]
+        );
+        assert_eq!(hosts("home", &["home"]).expect("parse"), vec!["home"]);
+        assert!(
+            hosts("local,!home", &[])
+                .unwrap_err()
+                .contains("isn't selected")
+        );
+        assert!(
+            hosts("local,!", &[])
+                .unwrap_err()
+                .contains("invalid host exclusion")
+        );
+        assert!(
+            hosts("local,!local", &[])
+                .unwrap_err()
+                .contains("every host")
+        );
+    }
+}
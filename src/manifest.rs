@@ -0,0 +1,274 @@
+use std::path::Path;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{short_thread_id, shorten_home_path};
+use crate::collector::Collector;
+use crate::model::{SessionRow, Snapshot};
+
+/// The sessions a batch launcher started, as `[[session]]` entries in a TOML file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default, rename = "session")]
+    pub sessions: Vec<ExpectedSession>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedSession {
+    /// The session name (`codex-ps run --name`, `n` in the TUI).
+    pub name: String,
+    /// A path (`~/src/api`; the session's repo or cwd must be inside it) or a bare
+    /// directory name (`api`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// Any host when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Matched {
+    pub expected: ExpectedSession,
+    pub session: SessionRow,
+}
+
+/// Manifest entries against the running root sessions.
+#[derive(Debug, Default, Serialize)]
+pub struct Reconciliation {
+    pub matched: Vec<Matched>,
+    /// Expected and not running on a host that answered.
+    pub missing: Vec<ExpectedSession>,
+    /// Expected on a host that failed this time, so not known either way.
+    pub unchecked: Vec<ExpectedSession>,
+    /// Running roots no entry accounts for.
+    pub unexpected: Vec<SessionRow>,
+}
+
+impl ExpectedSession {
+    fn matches(&self, row: &SessionRow) -> bool {
+        if row.name.as_deref().map(str::trim) != Some(self.name.trim()) {
+            return false;
+        }
+        if self.host.as_deref().is_some_and(|h| h != row.host) {
+            return false;
+        }
+        let Some(repo) = self.repo.as_deref() else {
+            return true;
+        };
+        let Some(dir) = row.repo_root.as_deref().or(row.cwd.as_deref()) else {
+            return false;
+        };
+        if repo.contains('/') {
+            let want = shorten_home_path(repo);
+            let dir = shorten_home_path(dir);
+            dir == want || dir.starts_with(&format!("{}/", want.trim_end_matches('/')))
+        } else {
+            Path::new(dir).file_name().is_some_and(|n| n == repo)
+        }
+    }
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("parse {}", path.display()))
+    }
+
+    /// Each entry takes the first running root it matches; a session counts once.
+    pub fn reconcile(&self, snap: &Snapshot) -> Reconciliation {
+        let failed = |host: &str| snap.host_errors.iter().flatten().any(|e| e.host == host);
+        let mut free: Vec<&SessionRow> = snap
+            .sessions
+            .iter()
+            .filter(|r| r.subagent_parent_thread_id.is_none())
+            .collect();
+        let mut out = Reconciliation::default();
+        for e in &self.sessions {
+            match free.iter().position(|r| e.matches(r)) {
+                Some(i) => out.matched.push(Matched {
+                    expected: e.clone(),
+                    session: free.remove(i).clone(),
+                }),
+                None if e.host.as_deref().is_some_and(failed) => out.unchecked.push(e.clone()),
+                None => out.missing.push(e.clone()),
+            }
+        }
+        out.unexpected = free.into_iter().cloned().collect();
+        out
+    }
+
+    /// Hosts named by entries, for polling alongside `--host`.
+    pub fn hosts(&self) -> impl Iterator<Item = &str> {
+        self.sessions.iter().filter_map(|e| e.host.as_deref())
+    }
+}
+
+/// `codex-ps manifest FILE`: poll `hosts` plus those the manifest names and print the
+/// reconciliation. Fails when an expected session is missing.
+pub fn run_manifest(
+    mut collector: Collector,
+    hosts: &[String],
+    path: &Path,
+    json: bool,
+) -> anyhow::Result<()> {
+    let manifest = Manifest::load(path)?;
+    let mut hosts = hosts.to_vec();
+    for h in manifest.hosts() {
+        if !hosts.iter().any(|x| x == h) {
+            hosts.push(h.to_string());
+        }
+    }
+    let mut snap = collector.collect(&hosts)?;
+    snap.strip_debug();
+    let rec = manifest.reconcile(&snap);
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&rec).context("serialize reconciliation")?
+        );
+    } else {
+        print!("{}", format_reconciliation(&rec));
+    }
+    if !rec.missing.is_empty() {
+        anyhow::bail!(
+            "{} of {} expected session(s) not running",
+            rec.missing.len(),
+            manifest.sessions.len()
+        );
+    }
+    Ok(())
+}
+
+fn format_reconciliation(rec: &Reconciliation) -> String {
+    let expected = |e: &ExpectedSession| {
+        let mut s = format!("  {}  {}", e.name, e.host.as_deref().unwrap_or("any host"));
+        if let Some(repo) = e.repo.as_deref() {
+            s.push_str(&format!("  {repo}"));
+        }
+        s
+    };
+    let running = |r: &SessionRow| {
+        let mut s = format!(
+            "{}  {}",
+            r.host_display(),
+            r.name
+                .clone()
+                .unwrap_or_else(|| short_thread_id(&r.thread_id))
+        );
+        if let Some(dir) = r.repo_root.as_deref().or(r.cwd.as_deref()) {
+            s.push_str(&format!("  {}", shorten_home_path(dir)));
+        }
+        s.push_str(&format!("  {}", r.status.as_str()));
+        s
+    };
+    let mut sections: Vec<(&str, Vec<String>)> = vec![
+        (
+            "Matched",
+            rec.matched
+                .iter()
+                .map(|m| format!("  {}", running(&m.session)))
+                .collect(),
+        ),
+        ("Missing", rec.missing.iter().map(expected).collect()),
+        (
+            "Not checked (host unreachable)",
+            rec.unchecked.iter().map(expected).collect(),
+        ),
+        (
+            "Unexpected",
+            rec.unexpected
+                .iter()
+                .map(|r| format!("  {}", running(r)))
+                .collect(),
+        ),
+    ];
+    sections.retain(|(_, lines)| !lines.is_empty());
+    let mut out = String::new();
+    for (title, lines) in sections {
+        out.push_str(&format!("{title} ({}):\n", lines.len()));
+        for l in lines {
+            out.push_str(&l);
+            out.push('\n');
+        }
+    }
+    if out.is_empty() {
+        out.push_str("Nothing expected and nothing running.\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(host: &str, name: Option<&str>, repo: &str) -> SessionRow {
+        serde_json::from_value(serde_json::json!({
+            "host": host,
+            "thread_id": format!("t-{}", name.unwrap_or("none")),
+            "pids": [],
+            "status": "working",
+            "name": name,
+            "repo_root": repo,
+        }))
+        .expect("row")
+    }
+
+    #[test]
+    fn reconciles_expected_against_running() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+[[session]]
+name = "api"
+repo = "/src/api"
+
+[[session]]
+name = "web"
+repo = "web"
+host = "devbox"
+
+[[session]]
+name = "docs"
+
+[[session]]
+name = "infra"
+host = "build"
+"#,
+        )
+        .expect("parse");
+        let mut snap: Snapshot = serde_json::from_value(serde_json::json!({
+            "host": "all",
+            "generated_at_unix_s": 0,
+            "sessions": [],
+            "host_errors": [{"host": "build", "error": "timeout"}],
+        }))
+        .expect("snapshot");
+        snap.sessions = vec![
+            row("local", Some("api"), "/src/api/server"),
+            row("devbox", Some("web"), "/home/me/web"),
+            row("local", Some("docs"), "/src/docs"),
+            row("local", None, "/src/scratch"),
+        ];
+        let rec = manifest.reconcile(&snap);
+        let names: Vec<&str> = rec
+            .matched
+            .iter()
+            .map(|m| m.expected.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["api", "web", "docs"]);
+        assert!(rec.missing.is_empty());
+        assert_eq!(rec.unchecked[0].name, "infra");
+        assert_eq!(rec.unexpected.len(), 1);
+
+        let strict: Manifest =
+            toml::from_str("[[session]]\nname = \"api\"\nrepo = \"/src/web\"").expect("parse");
+        let rec = strict.reconcile(&snap);
+        assert_eq!(rec.missing.len(), 1);
+        assert!(
+            format_reconciliation(&rec).starts_with("Missing (1):\n  api  any host  /src/web\n")
+        );
+    }
+}
@@ -1,19 +1,196 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use crate::error::{ErrorInfo, ErrorKind};
+use crate::timefmt::{format_duration, to_iso8601_utc};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Snapshot {
+    /// Increments once per collection in this process (remote hosts' own counters are
+    /// discarded when aggregating).
+    #[serde(default)]
+    pub generation: u64,
     pub generated_at_unix_s: i64,
+    /// ISO 8601 UTC form of `generated_at_unix_s` (timezone-unambiguous for shared logs).
+    #[serde(default)]
+    pub generated_at: Option<String>,
     pub host: String,
+    /// When each host's rows were last collected (unix seconds); hosts still pending or
+    /// unreachable in this cycle keep their previous time or are absent.
+    #[serde(default)]
+    pub host_collected_at: BTreeMap<String, i64>,
     pub sessions: Vec<SessionRow>,
     // JSON contract: keep these keys present (possibly empty) for scripting stability.
     // We keep the type as Option for backwards-compatible deserialization when aggregating
     // across hosts (older versions may omit or null these fields).
     pub host_errors: Option<Vec<HostError>>,
-    pub warnings: Option<Vec<String>>,
+    pub warnings: Option<Vec<ErrorInfo>>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl Default for Snapshot {
+    /// An empty snapshot that keeps the `host_errors`/`warnings` keys present.
+    fn default() -> Self {
+        Self {
+            generation: 0,
+            generated_at_unix_s: 0,
+            generated_at: None,
+            host: String::new(),
+            host_collected_at: BTreeMap::new(),
+            sessions: Vec::new(),
+            host_errors: Some(Vec::new()),
+            warnings: Some(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Snapshot {
+    /// A `local` snapshot in which every host the rows are on was collected at time 0.
+    pub fn of(sessions: Vec<SessionRow>) -> Self {
+        let mut host_collected_at = BTreeMap::from([("local".to_string(), 0)]);
+        for row in &sessions {
+            host_collected_at.insert(row.host.clone(), 0);
+        }
+        Self {
+            host: "local".into(),
+            host_collected_at,
+            sessions,
+            ..Default::default()
+        }
+    }
+}
+
+impl Snapshot {
+    /// Whether this cycle heard from `row`'s host: it (or a host the row was relayed
+    /// through) was collected, and none of them failed.
+    pub fn answered_for(&self, row: &SessionRow) -> bool {
+        self.collected_at_for(row).is_some()
+    }
+
+    /// When `row`'s host last answered (unix seconds): the newest collection time of the hosts
+    /// it came through, or `None` when one of them failed or none was collected.
+    pub fn collected_at_for(&self, row: &SessionRow) -> Option<i64> {
+        let failed = |h: &str| self.host_errors.iter().flatten().any(|e| e.host == h);
+        let mut collected = None;
+        for hop in std::iter::once(&row.host).chain(&row.collected_via) {
+            let via = format!("via:{hop}");
+            if failed(hop) || failed(&via) {
+                return None;
+            }
+            let at = self
+                .host_collected_at
+                .get(hop)
+                .or_else(|| self.host_collected_at.get(&via));
+            collected = collected.max(at.copied());
+        }
+        collected
+    }
+
+    /// The snapshot as shown outside debug mode: no per-row `debug` and no verbose warnings.
+    pub fn strip_debug(&mut self) {
+        for row in &mut self.sessions {
+            row.debug = None;
+        }
+        if let Some(w) = self.warnings.as_mut() {
+            w.retain(|w| !w.verbose);
+        }
+    }
+
+    /// Treat this snapshot (a `--json` taken on `host`) as `host`'s: rows, errors and the
+    /// collection time recorded as `local` move to `host`, as remote aggregation does.
+    pub fn relabel_host(&mut self, host: &str) {
+        for row in &mut self.sessions {
+            row.relay_through(host);
+        }
+        for e in self.host_errors.iter_mut().flatten() {
+            if e.host == "local" {
+                e.host = host.to_string();
+            }
+        }
+        let at = self
+            .host_collected_at
+            .remove("local")
+            .unwrap_or(self.generated_at_unix_s);
+        self.host_collected_at.insert(host.to_string(), at);
+        self.host = host.to_string();
+    }
+
+    /// Combine per-host snapshots into one. Sessions are deduplicated as `dedupe_sessions` does
+    /// and sorted like a collected snapshot; errors and warnings are concatenated without
+    /// duplicates; timestamps keep the newest per host.
+    pub fn merge(snapshots: Vec<Snapshot>) -> Snapshot {
+        let mut hosts: Vec<String> = Vec::new();
+        let mut merged = Snapshot::default();
+        let mut sessions: Vec<SessionRow> = Vec::new();
+        for snap in snapshots {
+            merged.generation = merged.generation.max(snap.generation);
+            merged.generated_at_unix_s = merged.generated_at_unix_s.max(snap.generated_at_unix_s);
+            for h in snap.host.split(',').filter(|h| !h.is_empty()) {
+                if !hosts.iter().any(|x| x == h) {
+                    hosts.push(h.to_string());
+                }
+            }
+            for (h, at) in snap.host_collected_at {
+                let e = merged.host_collected_at.entry(h).or_insert(at);
+                *e = (*e).max(at);
+            }
+            sessions.extend(snap.sessions);
+            let errors = merged.host_errors.get_or_insert_with(Vec::new);
+            for e in snap.host_errors.into_iter().flatten() {
+                if !errors.iter().any(|x| x.same_as(&e)) {
+                    errors.push(e);
+                }
+            }
+            let warnings = merged.warnings.get_or_insert_with(Vec::new);
+            for w in snap.warnings.into_iter().flatten() {
+                if !warnings.contains(&w) {
+                    warnings.push(w);
+                }
+            }
+        }
+        merged.host = hosts.join(",");
+        merged.generated_at = to_iso8601_utc(merged.generated_at_unix_s);
+        merged.sessions = dedupe_sessions(sessions);
+        sort_sessions(&mut merged.sessions);
+        merged
+    }
+}
+
+/// One row per `SessionId`: a copy collected directly beats one relayed through other
+/// collectors, then the most recently active copy wins.
+pub fn dedupe_sessions(rows: Vec<SessionRow>) -> Vec<SessionRow> {
+    let mut by_id: BTreeMap<SessionId, SessionRow> = BTreeMap::new();
+    for row in rows {
+        match by_id.get(&row.id()) {
+            Some(old)
+                if (
+                    old.collected_via.len(),
+                    std::cmp::Reverse(old.last_activity_unix_s),
+                ) <= (
+                    row.collected_via.len(),
+                    std::cmp::Reverse(row.last_activity_unix_s),
+                ) => {}
+            _ => {
+                by_id.insert(row.id(), row);
+            }
+        }
+    }
+    by_id.into_values().collect()
+}
+
+/// Most recent activity first; ties by host then thread id for a stable table.
+pub fn sort_sessions(sessions: &mut [SessionRow]) {
+    sessions.sort_by(|a, b| {
+        let a_ts = a.last_activity_unix_s.unwrap_or(i64::MIN);
+        let b_ts = b.last_activity_unix_s.unwrap_or(i64::MIN);
+        b_ts.cmp(&a_ts)
+            .then_with(|| a.host.cmp(&b.host))
+            .then_with(|| a.thread_id.cmp(&b.thread_id))
+    });
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SessionRow {
     #[serde(default)]
     pub host: String,
@@ -21,9 +198,18 @@ pub struct SessionRow {
     pub pids: Vec<i32>,
     pub tty: Option<String>,
     pub title: Option<String>,
+    /// The title before Codex last retitled this thread (from codex-ps's title history).
+    #[serde(default)]
+    pub previous_title: Option<String>,
     // JSON contract: this is intentionally `null` when unset (do NOT add `skip_serializing_if`)
     // so `--json` keeps a stable schema for scripting.
     pub name: Option<String>,
+    /// Tags attached via `codex-ps annotate` (empty when none).
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Free-form note attached via `codex-ps annotate`.
+    #[serde(default)]
+    pub note: Option<String>,
     pub cwd: Option<String>,
     pub repo_root: Option<String>,
     pub git_branch: Option<String>,
@@ -40,39 +226,271 @@ pub struct SessionRow {
     pub subagent_depth: Option<i32>,
     pub status: SessionStatus,
     pub last_activity_unix_s: Option<i64>,
+    /// ISO 8601 UTC form of `last_activity_unix_s` (`null` when unknown).
+    #[serde(default)]
+    pub last_activity_at: Option<String>,
     pub rollout_path: Option<String>,
+    /// Set when the rollout exists but can't be read (e.g. another user's session); metadata
+    /// and tail hints are unavailable and status falls back to mtime. `null` when readable.
+    #[serde(default)]
+    pub rollout_unreadable: Option<String>,
+    /// Final assistant message (truncated) when the latest turn has completed; `null` while
+    /// a turn is in progress or the tail hasn't been read yet.
+    #[serde(default)]
+    pub final_message: Option<String>,
+    /// Newest tool call without output yet, e.g. `exec_command: cargo publish` (tail-based).
+    #[serde(default)]
+    pub pending_call: Option<String>,
+    /// When `pending_call` was made (its rollout line's timestamp).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_call_since_unix_s: Option<i64>,
+    /// The session's cwd no longer exists (worktree removed, directory renamed).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cwd_missing: bool,
+    /// Branch checked out in the cwd now (`--probe-branch`); `git_branch` is where the
+    /// session started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_branch: Option<String>,
+    /// Commits by the repo's configured author on local branches since the session started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commits_since_start: Option<u32>,
+    /// Commits on the cwd's HEAD that aren't on any remote branch (0 = everything pushed).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unpushed_commits: Option<u32>,
+    /// Short display name for `host` from `[host_labels]` in `config.toml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_label: Option<String>,
+    /// Proposed name for an unnamed session (`suggest_names` in `config.toml`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_name: Option<String>,
+    /// Flagged with `*` in the TUI (stored with tags and notes).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub watched: bool,
+    /// Last line of the assistant's newest message in the rollout tail.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_line: Option<String>,
+    /// How long `pending_call`'s command usually takes, from earlier runs seen locally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_call_eta: Option<CallEta>,
+    /// Tokens per minute over the last few minutes, from the rollout's `token_count` events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_per_min: Option<u64>,
+    /// Cumulative tokens so far, from the latest `token_count` in the rollout tail.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_tokens: Option<u64>,
+    /// Other ids this session is known by: the file name's id when it disagrees with
+    /// `session_meta.id` (a renamed or copied rollout).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thread_id_aliases: Vec<String>,
+    /// When the session started, from `session_meta`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at_unix_s: Option<i64>,
+    /// Collectors that relayed this row from `host` (the origin), nearest the origin first;
+    /// empty when collected directly (a remote that aggregates other hosts, a jump host).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collected_via: Vec<String>,
+    /// The session process's executable, when discovery resolved it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exe: Option<String>,
+    /// Codex CLI version of the running process (from its executable), else the one
+    /// recorded in `session_meta`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cli_version: Option<String>,
+    /// Which `[budget]` limits the session is past (`1.3M tokens > 1.0M`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub over_budget: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub debug: Option<SessionDebug>,
 }
 
+/// Usual duration of a running command, e.g. `cargo test` at ~4m.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallEta {
+    /// Program and subcommand the history is keyed by.
+    pub prefix: String,
+    /// Median of earlier runs (seconds).
+    pub typical_secs: i64,
+    pub started_unix_s: i64,
+}
+
+impl CallEta {
+    /// `cargo test usually ~4m, running 2m`.
+    pub fn describe(&self, now_unix_s: i64) -> String {
+        format!(
+            "{} usually ~{}, running {}",
+            self.prefix,
+            format_duration(self.typical_secs),
+            format_duration(now_unix_s.saturating_sub(self.started_unix_s)),
+        )
+    }
+}
+
+/// A session's identity across refreshes and restarts: thread ids are only unique per host.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SessionId {
+    pub host: String,
+    pub thread_id: String,
+}
+
+impl SessionId {
+    pub fn new(host: impl Into<String>, thread_id: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            thread_id: thread_id.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for SessionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.host, self.thread_id)
+    }
+}
+
+impl SessionRow {
+    /// Take this row as `host`'s codex-ps reported it: its own sessions (`local`, or no host
+    /// from older versions) become `host`'s; rows it relayed from elsewhere keep their origin
+    /// and record the hop.
+    pub fn relay_through(&mut self, host: &str) {
+        if self.host.is_empty() || self.host == "local" {
+            self.host = host.to_string();
+        } else if self.host != host {
+            self.collected_via.push(host.to_string());
+        }
+    }
+
+    pub fn id(&self) -> SessionId {
+        SessionId::new(self.host.clone(), self.thread_id.clone())
+    }
+
+    /// This session's id under each of its aliases.
+    pub fn alias_ids(&self) -> impl Iterator<Item = SessionId> + '_ {
+        self.thread_id_aliases
+            .iter()
+            .map(|a| SessionId::new(self.host.clone(), a.clone()))
+    }
+
+    /// `host_label` when configured, else `host`.
+    pub fn host_display(&self) -> &str {
+        self.host_label.as_deref().unwrap_or(&self.host)
+    }
+
+    pub fn has_id(&self, id: &SessionId) -> bool {
+        self.host == id.host && self.thread_id == id.thread_id
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HostError {
     pub host: String,
+    /// Root-cause message (older versions put the whole error string here).
     pub error: String,
+    #[serde(default)]
+    pub kind: ErrorKind,
+    /// Outermost-first "what were we doing" frames.
+    #[serde(default)]
+    pub context: Vec<String>,
+    /// Consecutive refreshes this exact error has been seen (1 = new this refresh).
+    #[serde(default = "one")]
+    pub count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen_unix_s: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_unix_s: Option<i64>,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+fn one() -> u32 {
+    1
+}
+
+impl HostError {
+    pub fn new(host: impl Into<String>, info: ErrorInfo) -> Self {
+        Self {
+            host: host.into(),
+            error: info.message,
+            kind: info.kind,
+            context: info.context,
+            count: 1,
+            first_seen_unix_s: None,
+            last_seen_unix_s: None,
+        }
+    }
+
+    /// Same failure on the same host (context frames may differ between attempts).
+    pub fn same_as(&self, other: &HostError) -> bool {
+        self.host == other.host && self.kind == other.kind && self.error == other.error
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum SessionStatus {
     Working,
     Waiting,
+    #[default]
     Unknown,
 }
 
+impl SessionStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SessionStatus::Working => "working",
+            SessionStatus::Waiting => "waiting",
+            SessionStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// Stable, machine-readable counterpart of `status_reason` (whose wording may change).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusReasonCode {
+    PendingUserInput,
+    PendingToolCall,
+    RecentHeartbeat,
+    NoMtime,
+    MtimeFuture,
+    RecentWrite,
+    Uncertain,
+    IdleTimeout,
+}
+
+impl StatusReasonCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusReasonCode::PendingUserInput => "pending_user_input",
+            StatusReasonCode::PendingToolCall => "pending_tool_call",
+            StatusReasonCode::RecentHeartbeat => "recent_heartbeat",
+            StatusReasonCode::NoMtime => "no_mtime",
+            StatusReasonCode::MtimeFuture => "mtime_future",
+            StatusReasonCode::RecentWrite => "recent_write",
+            StatusReasonCode::Uncertain => "uncertain",
+            StatusReasonCode::IdleTimeout => "idle_timeout",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SessionDebug {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_reason_code: Option<StatusReasonCode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub process_command_sample: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub proc_cwd_source: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub meta_parse_error: Option<String>,
+    pub meta_parse_error: Option<ErrorInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta_id_mismatch: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub repo_probe_error: Option<String>,
+    pub repo_probe_error: Option<ErrorInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tail_parse_error: Option<ErrorInfo>,
+    /// What `last_activity_unix_s` came from: `mtime` or `tail_timestamp` (network mounts).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity_source: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title_source: Option<String>,
 }
@@ -87,6 +505,9 @@ pub struct SessionMeta {
     pub session_source: Option<String>,
     pub subagent_parent_thread_id: Option<String>,
     pub subagent_depth: Option<i32>,
+    pub started_at_unix_s: Option<i64>,
+    /// The CLI version that wrote the rollout.
+    pub cli_version: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -97,4 +518,104 @@ pub struct SessionBuilder {
     pub proc_cwd: Option<PathBuf>,
     pub rollout_path: Option<PathBuf>,
     pub proc_command_sample: Option<String>,
+    /// File name ids that differ from `thread_id` (taken from `session_meta`).
+    pub aliases: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(json: serde_json::Value) -> Snapshot {
+        serde_json::from_value(json).expect("snapshot")
+    }
+
+    #[test]
+    fn merge_relabels_dedupes_and_sorts() {
+        let row = |tid: &str, at: i64| {
+            serde_json::json!({"host": "local", "thread_id": tid, "pids": [], "tty": null,
+                "title": null, "cwd": null, "repo_root": null, "git_branch": null,
+                "git_commit": null, "session_source": null, "forked_from_id": null,
+                "subagent_parent_thread_id": null, "subagent_depth": null, "status": "waiting",
+                "last_activity_unix_s": at, "rollout_path": null})
+        };
+        let down = serde_json::json!({"host": "local", "error": "timeout", "kind": "transport"});
+        let mut a = snap(
+            serde_json::json!({"generated_at_unix_s": 100, "host": "local",
+            "sessions": [row("t1", 50)], "host_errors": [down], "warnings": []}),
+        );
+        let mut b = snap(
+            serde_json::json!({"generated_at_unix_s": 120, "host": "local",
+            "sessions": [row("t2", 90)], "host_errors": [], "warnings": []}),
+        );
+        a.relabel_host("home");
+        b.relabel_host("work");
+        let mut newer = snap(
+            serde_json::json!({"generated_at_unix_s": 130, "host": "local",
+            "sessions": [row("t1", 60)], "host_errors": [], "warnings": []}),
+        );
+        newer.relabel_host("home");
+
+        let merged = Snapshot::merge(vec![a.clone(), b, newer, a]);
+        assert_eq!(merged.host, "home,work");
+        assert_eq!(merged.generated_at_unix_s, 130);
+        assert_eq!(merged.host_collected_at.get("home"), Some(&130));
+        let ids: Vec<String> = merged.sessions.iter().map(|r| r.id().to_string()).collect();
+        assert_eq!(ids, vec!["work:t2", "home:t1"]);
+        assert_eq!(merged.sessions[1].last_activity_unix_s, Some(60));
+        let errors = merged.host_errors.expect("host_errors");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].host, "home");
+    }
+
+    #[test]
+    fn relayed_rows_keep_their_origin_and_direct_copies_win() {
+        let row = |host: &str, tid: &str, at: i64| {
+            serde_json::json!({"host": host, "thread_id": tid, "pids": [], "status": "waiting",
+                "last_activity_unix_s": at})
+        };
+        // `jump` ran with `--host all` and relays `home`'s session; `home` is also asked
+        // directly, with a slightly older view of the same session.
+        let mut jump = snap(
+            serde_json::json!({"generated_at_unix_s": 100, "host": "local,home",
+            "sessions": [row("local", "j1", 50), row("home", "h1", 90)]}),
+        );
+        let mut home = snap(
+            serde_json::json!({"generated_at_unix_s": 100, "host": "local",
+            "sessions": [row("local", "h1", 80)]}),
+        );
+        jump.relabel_host("jump");
+        home.relabel_host("home");
+        assert_eq!(jump.sessions[1].id().to_string(), "home:h1");
+        assert_eq!(jump.sessions[1].collected_via, vec!["jump"]);
+
+        let merged = Snapshot::merge(vec![jump.clone(), home]);
+        let ids: Vec<String> = merged.sessions.iter().map(|r| r.id().to_string()).collect();
+        assert_eq!(ids, vec!["home:h1", "jump:j1"]);
+        assert!(merged.sessions[0].collected_via.is_empty());
+        assert_eq!(merged.sessions[0].last_activity_unix_s, Some(80));
+
+        let merged = Snapshot::merge(vec![jump]);
+        assert_eq!(merged.sessions[0].collected_via, vec!["jump"]);
+    }
+
+    #[test]
+    fn strip_debug_drops_reasons_and_verbose_warnings() {
+        let mut s = snap(serde_json::json!({
+            "host": "local", "generated_at_unix_s": 0,
+            "sessions": [{"host": "local", "thread_id": "t", "pids": [], "status": "waiting",
+                          "debug": {"status_reason": "pending tool call"}}],
+            "host_errors": [],
+            "warnings": [{"kind": "discovery", "message": "sandboxed"},
+                         {"kind": "io", "message": "usage log", "verbose": true}]}));
+        s.strip_debug();
+        assert!(s.sessions[0].debug.is_none());
+        let kept: Vec<&str> = s
+            .warnings
+            .iter()
+            .flatten()
+            .map(|w| w.message.as_str())
+            .collect();
+        assert_eq!(kept, vec!["sandboxed"]);
+    }
 }
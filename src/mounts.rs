@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::util::run_cmd_with_timeout;
+
+/// Filesystem types whose mtimes/attributes are cached or coarse enough to break
+/// "recent write == working" heuristics.
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smbfs",
+    "smb3",
+    "afpfs",
+    "webdav",
+    "9p",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "sshfs",
+];
+
+/// Best-effort: the filesystem type of `path` when it lives on a network mount, else `None`.
+///
+/// Linux reads `/proc/self/mounts`; elsewhere we parse `mount` output. Any failure means
+/// "assume local" (the pre-existing behavior).
+pub fn detect_network_fs(path: &Path) -> Option<String> {
+    let mounts = if Path::new("/proc/self/mounts").exists() {
+        let text = std::fs::read_to_string("/proc/self/mounts").ok()?;
+        parse_proc_mounts(&text)
+    } else {
+        let out = run_cmd_with_timeout(Command::new("mount"), Duration::from_secs(2)).ok()?;
+        if !out.status.success() {
+            return None;
+        }
+        parse_bsd_mount_output(&String::from_utf8_lossy(&out.stdout))
+    };
+
+    let (_, fs_type) = mounts
+        .into_iter()
+        .filter(|(mp, _)| path.starts_with(mp))
+        .max_by_key(|(mp, _)| mp.as_os_str().len())?;
+
+    is_network_fs_type(&fs_type).then_some(fs_type)
+}
+
+fn is_network_fs_type(fs_type: &str) -> bool {
+    let t = fs_type.strip_prefix("fuse.").unwrap_or(fs_type);
+    NETWORK_FS_TYPES.contains(&t)
+}
+
+/// `/proc/self/mounts`: `<dev> <mountpoint> <type> <opts> 0 0` (spaces escaped as `\040`).
+fn parse_proc_mounts(text: &str) -> Vec<(PathBuf, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let _dev = parts.next()?;
+            let mp = parts.next()?.replace("\\040", " ");
+            let fs_type = parts.next()?;
+            Some((PathBuf::from(mp), fs_type.to_string()))
+        })
+        .collect()
+}
+
+/// macOS/BSD `mount`: `<dev> on <mountpoint> (<type>, <opts>...)`.
+fn parse_bsd_mount_output(text: &str) -> Vec<(PathBuf, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (mp, opts) = rest.rsplit_once(" (")?;
+            let fs_type = opts.split([',', ')']).next()?.trim();
+            Some((PathBuf::from(mp), fs_type.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_mounts_and_flags_network_types() {
+        let text = "/dev/sda1 / ext4 rw 0 0\nserver:/export /home/u/.codex nfs4 rw 0 0\nremote: /mnt/my\\040share fuse.sshfs rw 0 0\n";
+        let mounts = parse_proc_mounts(text);
+        assert_eq!(mounts[1], (PathBuf::from("/home/u/.codex"), "nfs4".into()));
+        assert_eq!(mounts[2].0, PathBuf::from("/mnt/my share"));
+        assert!(is_network_fs_type(&mounts[1].1));
+        assert!(is_network_fs_type(&mounts[2].1));
+        assert!(!is_network_fs_type(&mounts[0].1));
+    }
+
+    #[test]
+    fn parses_bsd_mount_output() {
+        let text = "/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)\n//u@nas/share on /Volumes/share (smbfs, nodev, nosuid, mounted by u)\n";
+        let mounts = parse_bsd_mount_output(text);
+        assert_eq!(mounts[0], (PathBuf::from("/"), "apfs".into()));
+        assert_eq!(mounts[1], (PathBuf::from("/Volumes/share"), "smbfs".into()));
+    }
+}
@@ -7,17 +7,15 @@ use std::time::SystemTime;
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
-pub struct SessionNameKey {
-    pub host: String,
-    pub thread_id: String,
-}
+use crate::model::SessionId;
 
 #[derive(Clone, Debug)]
 pub struct NamesStore {
     path: PathBuf,
     last_mtime: Option<SystemTime>,
-    names: HashMap<SessionNameKey, String>,
+    names: HashMap<SessionId, String>,
+    /// When each session's latest record was written (ms), for last-writer-wins sync.
+    stamps: HashMap<SessionId, i64>,
 }
 
 impl NamesStore {
@@ -30,6 +28,7 @@ impl NamesStore {
             path,
             last_mtime: None,
             names: HashMap::new(),
+            stamps: HashMap::new(),
         }
     }
 
@@ -43,6 +42,7 @@ impl NamesStore {
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
                 self.last_mtime = None;
                 self.names.clear();
+                self.stamps.clear();
                 return Ok(());
             }
             Err(e) => {
@@ -55,12 +55,14 @@ impl NamesStore {
             return Ok(());
         }
 
-        let parsed: anyhow::Result<HashMap<SessionNameKey, String>> = (|| {
+        type Parsed = (HashMap<SessionId, String>, HashMap<SessionId, i64>);
+        let parsed: anyhow::Result<Parsed> = (|| {
             let f = fs::File::open(&self.path)
                 .with_context(|| format!("open {}", self.path.display()))?;
             let mut r = BufReader::new(f);
 
-            let mut names: HashMap<SessionNameKey, String> = HashMap::new();
+            let mut names: HashMap<SessionId, String> = HashMap::new();
+            let mut stamps: HashMap<SessionId, i64> = HashMap::new();
 
             let mut line = String::new();
             let mut line_no: usize = 0;
@@ -73,13 +75,14 @@ impl NamesStore {
                     continue;
                 }
 
-                let rec: NamesLine = serde_json::from_str(&raw)
+                let rec: NameRecord = serde_json::from_str(&raw)
                     .with_context(|| format!("parse session_names.jsonl line {line_no}"))?;
 
-                let key = SessionNameKey {
+                let key = SessionId {
                     host: rec.host,
                     thread_id: rec.thread_id,
                 };
+                stamps.insert(key.clone(), rec.at_unix_ms.unwrap_or(0));
 
                 match normalize_name_opt(rec.name) {
                     Some(name) => {
@@ -91,54 +94,98 @@ impl NamesStore {
                 }
             }
 
-            Ok(names)
+            Ok((names, stamps))
         })();
 
         match parsed {
-            Ok(names) => {
+            Ok((names, stamps)) => {
                 self.names = names;
+                self.stamps = stamps;
                 self.last_mtime = mtime;
                 Ok(())
             }
             Err(e) => {
                 self.names.clear();
+                self.stamps.clear();
                 self.last_mtime = mtime;
                 Err(e)
             }
         }
     }
 
-    pub fn get_cached(&self, key: &SessionNameKey) -> Option<&str> {
+    pub fn get_cached(&self, key: &SessionId) -> Option<&str> {
         self.names.get(key).map(|s| s.as_str())
     }
 
-    pub fn set(&mut self, key: SessionNameKey, name: String) -> anyhow::Result<Option<String>> {
+    pub fn set(&mut self, key: SessionId, name: String) -> anyhow::Result<Option<String>> {
         let Some(normalized) = normalize_name_opt(Some(name)) else {
             self.clear(key)?;
             return Ok(None);
         };
 
-        self.append_record(&key, Some(&normalized))?;
-        self.names.insert(key, normalized.clone());
+        self.write(&key, Some(normalized.clone()), unix_ms_now())?;
         Ok(Some(normalized))
     }
 
-    pub fn clear(&mut self, key: SessionNameKey) -> anyhow::Result<()> {
-        self.append_record(&key, None)?;
-        self.names.remove(&key);
+    pub fn clear(&mut self, key: SessionId) -> anyhow::Result<()> {
+        self.write(&key, None, unix_ms_now())
+    }
+
+    /// The latest record per session, clears included (what `labels export` prints).
+    pub fn records(&self) -> Vec<NameRecord> {
+        let mut out: Vec<NameRecord> = self
+            .stamps
+            .iter()
+            .map(|(key, at)| NameRecord {
+                host: key.host.clone(),
+                thread_id: key.thread_id.clone(),
+                name: self.names.get(key).cloned(),
+                at_unix_ms: Some(*at),
+            })
+            .collect();
+        out.sort_by(|a, b| (&a.host, &a.thread_id).cmp(&(&b.host, &b.thread_id)));
+        out
+    }
+
+    /// Last-writer-wins: keep `rec` (with its original timestamp) only if it is newer than
+    /// what we have for that session. Returns whether it was applied.
+    pub fn merge_record(&mut self, rec: NameRecord) -> anyhow::Result<bool> {
+        self.refresh_if_changed()?;
+        let key = SessionId::new(rec.host, rec.thread_id);
+        let at = rec.at_unix_ms.unwrap_or(0);
+        if self.stamps.get(&key).is_some_and(|have| *have >= at) {
+            return Ok(false);
+        }
+        self.write(&key, normalize_name_opt(rec.name), at)?;
+        Ok(true)
+    }
+
+    fn write(&mut self, key: &SessionId, name: Option<String>, at: i64) -> anyhow::Result<()> {
+        self.append_record(key, name.as_deref(), at)?;
+        self.stamps.insert(key.clone(), at);
+        match name {
+            Some(name) => self.names.insert(key.clone(), name),
+            None => self.names.remove(key),
+        };
         Ok(())
     }
 
-    fn append_record(&mut self, key: &SessionNameKey, name: Option<&str>) -> anyhow::Result<()> {
+    fn append_record(
+        &mut self,
+        key: &SessionId,
+        name: Option<&str>,
+        at: i64,
+    ) -> anyhow::Result<()> {
         if let Some(parent) = self.path.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("create dir {}", parent.display()))?;
         }
 
-        let rec = NamesLine {
+        let rec = NameRecord {
             host: key.host.clone(),
             thread_id: key.thread_id.clone(),
             name: name.map(|s| s.to_string()),
+            at_unix_ms: Some(at),
         };
         let line = serde_json::to_string(&rec).with_context(|| "serialize session name record")?;
 
@@ -158,14 +205,26 @@ impl NamesStore {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct NamesLine {
-    host: String,
-    thread_id: String,
-    name: Option<String>,
+/// One `session_names.jsonl` line; `name: null` clears. Records written before sync existed
+/// have no timestamp and lose to any that do.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NameRecord {
+    pub host: String,
+    pub thread_id: String,
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at_unix_ms: Option<i64>,
+}
+
+pub fn unix_ms_now() -> i64 {
+    SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .and_then(|d| i64::try_from(d.as_millis()).ok())
+        .unwrap_or(0)
 }
 
-fn normalize_name_opt(name: Option<String>) -> Option<String> {
+pub fn normalize_name_opt(name: Option<String>) -> Option<String> {
     let name = name?;
     let trimmed = name.trim();
     if trimmed.is_empty() {
@@ -175,17 +234,20 @@ fn normalize_name_opt(name: Option<String>) -> Option<String> {
 }
 
 fn default_names_path() -> anyhow::Result<PathBuf> {
+    Ok(config_dir()?.join("session_names.jsonl"))
+}
+
+/// `$XDG_CONFIG_HOME/codex-ps` (or `~/.config/codex-ps`): where codex-ps keeps its own state.
+pub fn config_dir() -> anyhow::Result<PathBuf> {
     if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
         let xdg = xdg.trim();
         if !xdg.is_empty() {
-            return Ok(PathBuf::from(xdg)
-                .join("codex-ps")
-                .join("session_names.jsonl"));
+            return Ok(PathBuf::from(xdg).join("codex-ps"));
         }
     }
 
     let home = dirs::home_dir().context("resolve home dir (needed for ~/.config)")?;
-    Ok(home.join(".config/codex-ps/session_names.jsonl"))
+    Ok(home.join(".config/codex-ps"))
 }
 
 #[cfg(test)]
@@ -211,16 +273,37 @@ mod tests {
         let mut store = NamesStore::new_at(p);
         store.refresh_if_changed().expect("refresh");
 
-        let k1 = SessionNameKey {
+        let k1 = SessionId::new("local", "t1");
+        let k2 = SessionId::new("local", "t2");
+        assert_eq!(store.get_cached(&k1), Some("final"));
+        assert_eq!(store.get_cached(&k2), Some("x"));
+    }
+
+    #[test]
+    fn merge_record_keeps_the_newest_write() {
+        let dir = TempDir::new().expect("tempdir");
+        let p = dir.path().join("session_names.jsonl");
+        fs::write(
+            &p,
+            "{\"host\":\"local\",\"thread_id\":\"t1\",\"name\":\"legacy\"}\n",
+        )
+        .expect("write");
+
+        let mut store = NamesStore::new_at(p.clone());
+        let rec = |name: &str, at| NameRecord {
             host: "local".into(),
             thread_id: "t1".into(),
+            name: Some(name.into()),
+            at_unix_ms: Some(at),
         };
-        let k2 = SessionNameKey {
-            host: "local".into(),
-            thread_id: "t2".into(),
-        };
-        assert_eq!(store.get_cached(&k1), Some("final"));
-        assert_eq!(store.get_cached(&k2), Some("x"));
+        assert!(store.merge_record(rec("synced", 200)).expect("merge"));
+        assert!(!store.merge_record(rec("stale", 100)).expect("merge"));
+        let key = SessionId::new("local", "t1");
+        assert_eq!(store.get_cached(&key), Some("synced"));
+
+        let mut reread = NamesStore::new_at(p);
+        reread.refresh_if_changed().expect("refresh");
+        assert_eq!(reread.records(), vec![rec("synced", 200)]);
     }
 
     #[test]
@@ -231,10 +314,7 @@ mod tests {
 
         let mut store = NamesStore::new_at(p.clone());
         store.refresh_if_changed().expect("refresh");
-        let k1 = SessionNameKey {
-            host: "local".into(),
-            thread_id: "t1".into(),
-        };
+        let k1 = SessionId::new("local", "t1");
         assert_eq!(store.get_cached(&k1), Some("x"));
 
         fs::remove_file(&p).expect("remove");
@@ -248,10 +328,7 @@ mod tests {
         let p = dir.path().join("session_names.jsonl");
 
         let mut store = NamesStore::new_at(p.clone());
-        let key = SessionNameKey {
-            host: "local".into(),
-            thread_id: "t1".into(),
-        };
+        let key = SessionId::new("local", "t1");
         assert_eq!(
             store
                 .set(key.clone(), "  hello world  ".into())
@@ -270,10 +347,7 @@ mod tests {
         let p = dir.path().join("session_names.jsonl");
 
         let mut store = NamesStore::new_at(p.clone());
-        let key = SessionNameKey {
-            host: "local".into(),
-            thread_id: "t1".into(),
-        };
+        let key = SessionId::new("local", "t1");
         store.set(key.clone(), "x".into()).expect("set x");
         assert_eq!(store.get_cached(&key), Some("x"));
 
@@ -0,0 +1,310 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use crate::audit::{AuditLog, AuditRecord};
+use crate::budget::de_duration;
+use crate::model::{SessionId, SessionRow, SessionStatus};
+use crate::timefmt::format_duration;
+
+/// `[policy]`: rules that stop or kill sessions nobody is looking after. Matches are only
+/// written to the audit log until `enforce = true`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyConfig {
+    #[serde(default)]
+    pub enforce: bool,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+/// One `[[policy.rules]]` entry; every condition given must hold.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRule {
+    pub name: String,
+    pub action: PolicyAction,
+    /// No rollout activity for this long (seconds or `"24h"`).
+    #[serde(default, deserialize_with = "de_duration")]
+    pub idle_over: Option<u64>,
+    /// `true`: only sessions without a name; `false`: only named ones.
+    pub unnamed: Option<bool>,
+    pub status: Option<SessionStatus>,
+    /// Past a `[budget]` limit.
+    pub over_budget: Option<bool>,
+    /// Hosts the rule applies to; empty means all.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    /// SIGSTOP: frozen, and resumable with `kill -CONT`.
+    Stop,
+    Terminate,
+    Kill,
+}
+
+impl PolicyAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PolicyAction::Stop => "stop",
+            PolicyAction::Terminate => "terminate",
+            PolicyAction::Kill => "kill",
+        }
+    }
+
+    pub fn signal(self) -> &'static str {
+        match self {
+            PolicyAction::Stop => "STOP",
+            PolicyAction::Terminate => "TERM",
+            PolicyAction::Kill => "KILL",
+        }
+    }
+}
+
+impl PolicyRule {
+    /// Why `row` matches at `now_unix_s`, or `None`.
+    fn matches(&self, row: &SessionRow, now_unix_s: i64) -> Option<String> {
+        if !self.hosts.is_empty() && !self.hosts.contains(&row.host) {
+            return None;
+        }
+        let mut why = Vec::new();
+        if let Some(max) = self.idle_over {
+            let idle = now_unix_s.saturating_sub(row.last_activity_unix_s?);
+            if idle < i64::try_from(max).unwrap_or(i64::MAX) {
+                return None;
+            }
+            why.push(format!("idle {}", format_duration(idle)));
+        }
+        if let Some(want) = self.unnamed {
+            let unnamed = row.name.as_deref().is_none_or(|n| n.trim().is_empty());
+            if unnamed != want {
+                return None;
+            }
+            why.push(if want { "unnamed" } else { "named" }.to_string());
+        }
+        if let Some(status) = self.status {
+            if row.status != status {
+                return None;
+            }
+            why.push(status.as_str().to_string());
+        }
+        if let Some(want) = self.over_budget {
+            if row.over_budget.is_some() != want {
+                return None;
+            }
+            why.push(match row.over_budget.as_deref() {
+                Some(over) => format!("over budget ({over})"),
+                None => "within budget".to_string(),
+            });
+        }
+        Some(why.join(", "))
+    }
+
+    fn has_conditions(&self) -> bool {
+        self.idle_over.is_some()
+            || self.unnamed.is_some()
+            || self.status.is_some()
+            || self.over_budget.is_some()
+    }
+}
+
+/// Applies `[policy]` to each snapshot, acting on a session at most once while it runs.
+/// Watched sessions (`*` in the TUI) are never touched.
+#[derive(Debug)]
+pub struct PolicyEngine {
+    cfg: PolicyConfig,
+    audit: AuditLog,
+    handled: HashSet<SessionId>,
+}
+
+impl PolicyEngine {
+    pub fn new(cfg: PolicyConfig) -> anyhow::Result<Self> {
+        Self::new_at(cfg, AuditLog::new()?)
+    }
+
+    pub fn new_at(cfg: PolicyConfig, audit: AuditLog) -> anyhow::Result<Self> {
+        for rule in &cfg.rules {
+            if !rule.has_conditions() {
+                anyhow::bail!(
+                    "policy rule {:?} has no conditions; it would match every session",
+                    rule.name
+                );
+            }
+        }
+        Ok(Self {
+            cfg,
+            audit,
+            handled: HashSet::new(),
+        })
+    }
+
+    /// Act on (or, without `enforce`, just log) sessions a rule newly matches; `signal`
+    /// delivers the action. Rows that aren't `fresh` (stale data) are skipped for now.
+    /// Returns what was recorded in the audit log.
+    pub fn apply(
+        &mut self,
+        rows: &[SessionRow],
+        now_unix_s: i64,
+        fresh: impl Fn(&SessionRow) -> bool,
+        mut signal: impl FnMut(&SessionRow, &str) -> anyhow::Result<()>,
+    ) -> anyhow::Result<Vec<AuditRecord>> {
+        self.handled
+            .retain(|key| rows.iter().any(|r| r.has_id(key)));
+        let mut out = Vec::new();
+        for row in rows {
+            if row.watched || self.handled.contains(&row.id()) || !fresh(row) {
+                continue;
+            }
+            let Some((rule, reason)) = self
+                .cfg
+                .rules
+                .iter()
+                .find_map(|rule| Some((rule, rule.matches(row, now_unix_s)?)))
+            else {
+                continue;
+            };
+            let error = if self.cfg.enforce {
+                signal(row, rule.action.signal())
+                    .err()
+                    .map(|e| format!("{e:#}"))
+            } else {
+                None
+            };
+            let rec = AuditRecord {
+                at_unix_s: now_unix_s,
+                host: row.host.clone(),
+                thread_id: row.thread_id.clone(),
+                name: row.name.clone(),
+                action: rule.action.as_str().to_string(),
+                rule: rule.name.clone(),
+                reason,
+                dry_run: !self.cfg.enforce,
+                error,
+            };
+            self.handled.insert(row.id());
+            self.audit.append(&rec)?;
+            out.push(rec);
+        }
+        Ok(out)
+    }
+}
+
+impl AuditRecord {
+    /// `policy "stale": stop local/019a… (idle 26h00m, unnamed)`.
+    pub fn summary(&self) -> String {
+        let verb = if self.dry_run {
+            format!("would {}", self.action)
+        } else {
+            self.action.clone()
+        };
+        let who = self.name.clone().unwrap_or_else(|| {
+            format!(
+                "{}/{}",
+                self.host,
+                crate::app::short_thread_id(&self.thread_id)
+            )
+        });
+        let mut s = format!("policy {:?}: {verb} {who} ({})", self.rule, self.reason);
+        if let Some(e) = self.error.as_deref() {
+            s.push_str(&format!(" failed: {e}"));
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(thread_id: &str, name: Option<&str>, last: i64) -> SessionRow {
+        serde_json::from_value(serde_json::json!({
+            "host": "local",
+            "thread_id": thread_id,
+            "pids": [42],
+            "status": "waiting",
+            "name": name,
+            "last_activity_unix_s": last,
+        }))
+        .expect("row")
+    }
+
+    #[test]
+    fn acts_once_per_session_and_audits() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let audit = AuditLog::new_at(dir.path().join("audit.jsonl"));
+        let cfg: PolicyConfig = toml::from_str(
+            r#"
+enforce = true
+[[rules]]
+name = "stale"
+action = "stop"
+idle_over = "24h"
+unnamed = true
+"#,
+        )
+        .expect("parse");
+        let mut engine = PolicyEngine::new_at(cfg.clone(), audit.clone()).expect("engine");
+
+        let day = 24 * 3600;
+        let mut watched = row("w", None, 0);
+        watched.watched = true;
+        let rows = vec![
+            row("old", None, 0),
+            row("named", Some("keep"), 0),
+            row("fresh", None, day),
+            watched,
+        ];
+        let mut sent = Vec::new();
+        let recs = engine
+            .apply(
+                &rows,
+                day + 60,
+                |_| true,
+                |r, sig| {
+                    sent.push((r.thread_id.clone(), sig.to_string()));
+                    Ok(())
+                },
+            )
+            .expect("apply");
+        assert_eq!(sent, vec![("old".to_string(), "STOP".to_string())]);
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].reason, "idle 24h01m, unnamed");
+        assert!(!recs[0].dry_run);
+
+        // Still matching next cycle, but already handled.
+        let again = engine
+            .apply(
+                &rows,
+                day + 120,
+                |_| true,
+                |_, _| anyhow::bail!("signalled twice"),
+            )
+            .expect("apply");
+        assert!(again.is_empty());
+        let logged = std::fs::read_to_string(dir.path().join("audit.jsonl")).expect("read");
+        assert_eq!(logged.lines().count(), 1);
+
+        let mut dry = PolicyConfig {
+            enforce: false,
+            ..cfg
+        };
+        let mut engine = PolicyEngine::new_at(dry.clone(), audit.clone()).expect("engine");
+        let recs = engine
+            .apply(
+                &rows,
+                day + 60,
+                |_| true,
+                |_, _| anyhow::bail!("dry run signalled"),
+            )
+            .expect("apply");
+        assert!(recs[0].dry_run);
+        assert!(recs[0].summary().contains("would stop"));
+
+        dry.rules[0].idle_over = None;
+        dry.rules[0].unnamed = None;
+        assert!(PolicyEngine::new_at(dry, audit).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::app::short_thread_id;
+use crate::collector::Collector;
+use crate::jump::{on_host, resume_shell_command};
+use crate::model::{SessionRow, SessionStatus, Snapshot};
+use crate::timefmt::{TimeDisplay, format_timestamp};
+
+/// Script-filter JSON (`{"items": [...]}`) as read by Alfred and Raycast script-filter
+/// extensions.
+#[derive(Debug, Serialize)]
+pub struct ScriptFilter {
+    pub items: Vec<ScriptFilterItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScriptFilterItem {
+    pub uid: String,
+    pub title: String,
+    pub subtitle: String,
+    /// Default action: a shell command that resumes the session (`codex resume <id>`).
+    pub arg: String,
+    pub valid: bool,
+    /// Alternate actions keyed by modifier (`cmd` kills the session's processes).
+    pub mods: BTreeMap<&'static str, ScriptFilterMod>,
+    pub text: ScriptFilterText,
+    /// Everything a custom extension needs without parsing titles.
+    pub variables: BTreeMap<&'static str, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScriptFilterMod {
+    pub arg: String,
+    pub subtitle: String,
+    pub valid: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScriptFilterText {
+    pub copy: String,
+}
+
+/// One item per root session, waiting sessions first.
+pub fn build_script_filter(snap: &Snapshot, now_unix_s: i64) -> ScriptFilter {
+    let mut rows: Vec<&SessionRow> = snap
+        .sessions
+        .iter()
+        .filter(|r| r.subagent_parent_thread_id.is_none())
+        .collect();
+    rows.sort_by_key(|r| {
+        (
+            r.status != SessionStatus::Waiting,
+            std::cmp::Reverse(r.last_activity_unix_s),
+        )
+    });
+    ScriptFilter {
+        items: rows.into_iter().map(|r| item(r, now_unix_s)).collect(),
+    }
+}
+
+fn item(r: &SessionRow, now_unix_s: i64) -> ScriptFilterItem {
+    let label = r
+        .name
+        .clone()
+        .or_else(|| r.title.clone())
+        .unwrap_or_else(|| short_thread_id(&r.thread_id));
+    let status = match r.status {
+        SessionStatus::Working => "working",
+        SessionStatus::Waiting => "waiting",
+        SessionStatus::Unknown => "unknown",
+    };
+    let age = r
+        .last_activity_unix_s
+        .map(|ts| format_timestamp(ts, now_unix_s, TimeDisplay::Relative))
+        .unwrap_or_else(|| "?".into());
+    let mut subtitle = vec![format!("{status} {age}"), r.host_display().to_string()];
+    subtitle.extend(r.git_branch.clone());
+    subtitle.extend(r.cwd.clone());
+
+    let resume = resume_shell_command(r);
+    let mut mods = BTreeMap::new();
+    if !r.pids.is_empty() {
+        let pids: Vec<String> = r.pids.iter().map(|p| p.to_string()).collect();
+        mods.insert(
+            "cmd",
+            ScriptFilterMod {
+                arg: on_host(&r.host, &format!("kill {}", pids.join(" "))),
+                subtitle: format!("Kill pid {}", pids.join(", ")),
+                valid: true,
+            },
+        );
+    }
+
+    let mut variables = BTreeMap::new();
+    variables.insert("host", r.host.clone());
+    variables.insert("thread_id", r.thread_id.clone());
+    variables.insert("status", status.to_string());
+    variables.insert("resume_command", resume.clone());
+
+    ScriptFilterItem {
+        uid: format!("{}:{}", r.host, r.thread_id),
+        title: label,
+        subtitle: subtitle.join(" · "),
+        arg: resume,
+        valid: true,
+        mods,
+        text: ScriptFilterText {
+            copy: r.thread_id.clone(),
+        },
+        variables,
+    }
+}
+
+/// `--format raycast`: print the script-filter JSON for one snapshot.
+pub fn run_script_filter(mut collector: Collector, hosts: &[String]) -> anyhow::Result<()> {
+    let snap = collector.collect(hosts)?;
+    let now = crate::util::system_time_to_unix_s(std::time::SystemTime::now()).unwrap_or(0);
+    let out = serde_json::to_string(&build_script_filter(&snap, now))
+        .context("serialize script filter")?;
+    let mut stdout = std::io::stdout();
+    if let Err(e) = writeln!(stdout, "{out}") {
+        if e.kind() != std::io::ErrorKind::BrokenPipe {
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_carry_resume_and_kill_commands() {
+        let row = SessionRow {
+            host: "home".into(),
+            thread_id: "019c2590-aaaa".into(),
+            pids: vec![4242],
+            title: Some("Fix flaky test".into()),
+            cwd: Some("/src/my app".into()),
+            git_branch: Some("main".into()),
+            status: SessionStatus::Waiting,
+            last_activity_unix_s: Some(940),
+            ..Default::default()
+        };
+        let snap = Snapshot {
+            generation: 1,
+            generated_at_unix_s: 1000,
+            host: "home".into(),
+            sessions: vec![row],
+            ..Default::default()
+        };
+
+        let out = build_script_filter(&snap, 1000);
+        let item = &out.items[0];
+        assert_eq!(item.title, "Fix flaky test");
+        assert_eq!(item.subtitle, "waiting 1m · home · main · /src/my app");
+        assert_eq!(
+            item.arg,
+            "ssh -t home 'cd '\\''/src/my app'\\'' && codex resume 019c2590-aaaa'"
+        );
+        assert_eq!(item.mods["cmd"].arg, "ssh -t home 'kill 4242'");
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::model::SessionRow;
+use crate::rollout::read_rollout_totals;
+use crate::timefmt::format_duration;
+
+/// What an ended session did, assembled from its last row and (for local sessions) a full
+/// read of its rollout.
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+pub struct SessionReceipt {
+    pub host: String,
+    pub thread_id: String,
+    pub duration_secs: Option<i64>,
+    pub turns: Option<u32>,
+    pub total_tokens: Option<u64>,
+    pub repo_root: Option<String>,
+    pub branch: Option<String>,
+    pub commits: Option<u32>,
+    pub unpushed_commits: Option<u32>,
+}
+
+impl SessionReceipt {
+    pub fn for_row(row: &SessionRow) -> Self {
+        // Remote rollout paths name files on the other machine.
+        let totals = row
+            .rollout_path
+            .as_deref()
+            .filter(|_| row.host == "local")
+            .and_then(|p| read_rollout_totals(Path::new(p)).ok());
+        let duration_secs = totals.as_ref().and_then(|t| {
+            let end = t.last_timestamp_unix_s.or(row.last_activity_unix_s)?;
+            Some(end - t.started_at_unix_s?).filter(|d| *d >= 0)
+        });
+        Self {
+            host: row.host.clone(),
+            thread_id: row.thread_id.clone(),
+            duration_secs,
+            turns: totals.as_ref().map(|t| t.turns),
+            total_tokens: totals.and_then(|t| t.total_tokens),
+            repo_root: row.repo_root.clone(),
+            branch: row
+                .current_branch
+                .clone()
+                .or_else(|| row.git_branch.clone()),
+            commits: row.commits_since_start,
+            unpushed_commits: row.unpushed_commits,
+        }
+    }
+
+    /// `42m, 7 turns, 120.3k tokens, on main, 3 commits (2 unpushed)`; unknown parts are
+    /// left out.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(d) = self.duration_secs {
+            parts.push(format_duration(d));
+        }
+        if let Some(t) = self.turns {
+            parts.push(format!("{t} turn{}", if t == 1 { "" } else { "s" }));
+        }
+        if let Some(n) = self.total_tokens {
+            parts.push(if n >= 1000 {
+                format!("{:.1}k tokens", n as f64 / 1000.0)
+            } else {
+                format!("{n} tokens")
+            });
+        }
+        if let Some(b) = self.branch.as_deref() {
+            parts.push(format!("on {b}"));
+        }
+        match (self.commits, self.unpushed_commits) {
+            (Some(c), Some(u)) if u > 0 => parts.push(format!("{c} commits ({u} unpushed)")),
+            (Some(c), Some(_)) if c > 0 => parts.push(format!("{c} commits, all pushed")),
+            (Some(c), _) => parts.push(format!("{c} commits")),
+            (None, Some(u)) if u > 0 => parts.push(format!("{u} unpushed")),
+            _ => {}
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_rollout_and_git_state() {
+        let mut f = tempfile::NamedTempFile::new().expect("tempfile");
+        std::io::Write::write_all(
+            &mut f,
+            br#"{"timestamp":"2026-02-03T16:00:00Z","type":"session_meta","payload":{"id":"t"}}
+{"timestamp":"2026-02-03T16:00:05Z","type":"event_msg","payload":{"type":"task_started"}}
+{"timestamp":"2026-02-03T16:10:00Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"total_tokens":120345}}}}
+{"timestamp":"2026-02-03T16:20:00Z","type":"event_msg","payload":{"type":"task_started"}}
+{"timestamp":"2026-02-03T16:42:00Z","type":"event_msg","payload":{"type":"task_complete"}}
+"#,
+        )
+        .expect("write");
+
+        let row: SessionRow = serde_json::from_value(serde_json::json!({
+            "host": "local",
+            "thread_id": "t",
+            "pids": [],
+            "status": "waiting",
+            "git_branch": "main",
+            "rollout_path": f.path(),
+            "commits_since_start": 3,
+            "unpushed_commits": 2,
+        }))
+        .expect("row");
+        let receipt = SessionReceipt::for_row(&row);
+        assert_eq!(receipt.duration_secs, Some(42 * 60));
+        assert_eq!(
+            receipt.summary(),
+            "42m, 2 turns, 120.3k tokens, on main, 3 commits (2 unpushed)"
+        );
+    }
+}
@@ -1,14 +1,18 @@
+use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 
 use anyhow::Context;
 use serde::Deserialize;
 
 use crate::model::SessionMeta;
+use crate::util::{parse_rfc3339_utc_to_unix_s, truncate_end};
 
 #[derive(Debug, Deserialize)]
 struct RolloutLine<T> {
+    #[serde(default)]
+    timestamp: Option<String>,
     #[serde(rename = "type")]
     ty: String,
     payload: T,
@@ -17,10 +21,12 @@ struct RolloutLine<T> {
 #[derive(Debug, Deserialize)]
 struct SessionMetaPayload {
     id: Option<String>,
+    timestamp: Option<String>,
     forked_from_id: Option<String>,
     cwd: Option<String>,
     source: Option<serde_json::Value>,
     git: Option<GitInfo>,
+    cli_version: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,6 +35,88 @@ struct GitInfo {
     branch: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct TailLine {
+    timestamp: Option<String>,
+    #[serde(rename = "type")]
+    ty: String,
+    payload: Option<TailPayload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TailPayload {
+    #[serde(rename = "type")]
+    ty: Option<String>,
+    name: Option<String>,
+    call_id: Option<String>,
+    // `Value` rather than `String`: other payload types reuse these keys with other shapes.
+    message: Option<serde_json::Value>,
+    last_agent_message: Option<serde_json::Value>,
+    /// JSON-encoded string for `function_call`.
+    arguments: Option<serde_json::Value>,
+    /// Free-form input for `custom_tool_call`.
+    input: Option<serde_json::Value>,
+    /// Usage totals for `token_count`.
+    info: Option<serde_json::Value>,
+    /// Result of a tool call (`function_call_output`), often JSON-encoded.
+    output: Option<serde_json::Value>,
+}
+
+/// Longest final-message preview kept per session.
+const FINAL_MESSAGE_MAX_CHARS: usize = 500;
+
+/// A tool call that has been persisted to the rollout but has no matching output yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingFunctionCall {
+    pub call_id: String,
+    pub name: String,
+    /// Human-readable gist of the arguments (the command, question or path), when known.
+    pub summary: Option<String>,
+    /// The call line's `timestamp` (unix seconds).
+    pub started_unix_s: Option<i64>,
+}
+
+/// A tool call whose start and output are both in the tail, with how long it took
+/// (user-input requests excluded).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompletedCall {
+    pub call_id: String,
+    pub summary: String,
+    pub secs: i64,
+}
+
+impl PendingFunctionCall {
+    /// `exec_command: cargo publish`, or just the tool name without a summary.
+    pub fn describe(&self) -> String {
+        match self.summary.as_deref() {
+            Some(s) => format!("{}: {s}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Longest pending-call summary kept.
+const CALL_SUMMARY_MAX_CHARS: usize = 200;
+
+/// Signals recovered from a bounded read of the end of a rollout.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TailHints {
+    pub pending_call: Option<PendingFunctionCall>,
+    /// Newest line `timestamp` (unix seconds). Unlike mtime, this survives network-FS
+    /// attribute caching and coarse mtime granularity.
+    pub last_timestamp_unix_s: Option<i64>,
+    /// The assistant's final message when the newest turn in the tail has completed
+    /// (`task_complete` with no later turn start). Truncated to `FINAL_MESSAGE_MAX_CHARS`.
+    pub final_message: Option<String>,
+    /// Last non-empty line of the newest assistant message, truncated to
+    /// `CALL_SUMMARY_MAX_CHARS`. Unlike `final_message`, kept while a turn is running.
+    pub latest_line: Option<String>,
+    /// Calls with a summary that finished inside the tail, oldest first.
+    pub completed_calls: Vec<CompletedCall>,
+    /// `(timestamp, cumulative total)` of each timestamped `token_count`, oldest first.
+    pub token_readings: Vec<(i64, u64)>,
+}
+
 pub fn read_session_meta(path: &Path) -> anyhow::Result<SessionMeta> {
     let f = File::open(path).with_context(|| format!("open rollout: {}", path.display()))?;
     let mut r = BufReader::new(f);
@@ -62,9 +150,338 @@ pub fn read_session_meta(path: &Path) -> anyhow::Result<SessionMeta> {
         session_source,
         subagent_parent_thread_id,
         subagent_depth,
+        started_at_unix_s: line
+            .payload
+            .timestamp
+            .or(line.timestamp)
+            .as_deref()
+            .and_then(parse_rfc3339_utc_to_unix_s),
+        cli_version: line.payload.cli_version,
     })
 }
 
+/// Whole-rollout counters for an ended session's receipt.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RolloutTotals {
+    pub started_at_unix_s: Option<i64>,
+    pub last_timestamp_unix_s: Option<i64>,
+    pub turns: u32,
+    /// Latest cumulative `token_count` total, when Codex recorded one.
+    pub total_tokens: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TotalsLine {
+    timestamp: Option<String>,
+    #[serde(rename = "type")]
+    ty: String,
+    payload: Option<TotalsPayload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TotalsPayload {
+    #[serde(rename = "type")]
+    ty: Option<String>,
+    info: Option<serde_json::Value>,
+}
+
+/// Full scan of a rollout: first/last timestamps, turns (`task_started`, or user messages
+/// for older rollouts) and the final token total. Only used once, when a session ends.
+pub fn read_rollout_totals(path: &Path) -> anyhow::Result<RolloutTotals> {
+    let f = File::open(path).with_context(|| format!("open rollout: {}", path.display()))?;
+    let mut totals = RolloutTotals::default();
+    let mut user_messages = 0;
+    for raw in BufReader::new(f).lines() {
+        let raw = raw.with_context(|| format!("read rollout: {}", path.display()))?;
+        let Ok(line) = serde_json::from_str::<TotalsLine>(raw.trim()) else {
+            continue;
+        };
+        if let Some(ts) = line
+            .timestamp
+            .as_deref()
+            .and_then(parse_rfc3339_utc_to_unix_s)
+        {
+            totals.started_at_unix_s.get_or_insert(ts);
+            totals.last_timestamp_unix_s = Some(ts);
+        }
+        let Some(payload) = line.payload.filter(|_| line.ty == "event_msg") else {
+            continue;
+        };
+        match payload.ty.as_deref() {
+            Some("task_started") => totals.turns += 1,
+            Some("user_message") => user_messages += 1,
+            Some("token_count") => {
+                if let Some(n) = payload
+                    .info
+                    .as_ref()
+                    .and_then(|i| i.pointer("/total_token_usage/total_tokens"))
+                    .and_then(|v| v.as_u64())
+                {
+                    totals.total_tokens = Some(n);
+                }
+            }
+            _ => {}
+        }
+    }
+    if totals.turns == 0 {
+        totals.turns = user_messages;
+    }
+    Ok(totals)
+}
+
+/// The text of the first `user_message`, reading at most `max_bytes` from the start.
+pub fn read_first_user_message(path: &Path, max_bytes: u64) -> anyhow::Result<Option<String>> {
+    let f = File::open(path).with_context(|| format!("open rollout: {}", path.display()))?;
+    for raw in BufReader::new(f.take(max_bytes)).lines() {
+        // The byte limit can cut the last line (even a character) in half.
+        let Ok(raw) = raw else {
+            break;
+        };
+        let Ok(line) = serde_json::from_str::<TailLine>(raw.trim()) else {
+            continue;
+        };
+        let Some(payload) = line.payload.filter(|_| line.ty == "event_msg") else {
+            continue;
+        };
+        if payload.ty.as_deref() == Some("user_message") {
+            if let Some(text) = payload.message.as_ref().and_then(|m| m.as_str()) {
+                return Ok(Some(text.to_string()));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Bounded tail scan (last `max_bytes`): the most recent `function_call` whose
+/// `function_call_output` has not been written yet, the newest line timestamp, and the
+/// final message of a just-completed turn.
+pub fn read_tail_hints(path: &Path, max_bytes: u64) -> anyhow::Result<TailHints> {
+    let mut f = File::open(path).with_context(|| format!("open rollout: {}", path.display()))?;
+    let len = f
+        .metadata()
+        .with_context(|| format!("stat rollout: {}", path.display()))?
+        .len();
+    let start = len.saturating_sub(max_bytes);
+    f.seek(SeekFrom::Start(start))
+        .with_context(|| format!("seek rollout tail: {}", path.display()))?;
+
+    let mut buf = Vec::new();
+    f.take(max_bytes)
+        .read_to_end(&mut buf)
+        .with_context(|| format!("read rollout tail: {}", path.display()))?;
+    let text = String::from_utf8_lossy(&buf);
+
+    let mut lines = text.lines();
+    if start > 0 {
+        // The first line is almost certainly a partial record.
+        lines.next();
+    }
+
+    let mut calls: Vec<PendingFunctionCall> = Vec::new();
+    let mut completed: HashSet<String> = HashSet::new();
+    let mut last_timestamp_unix_s: Option<i64> = None;
+    let mut last_agent_message: Option<String> = None;
+    let mut final_message: Option<String> = None;
+    let mut latest_line: Option<String> = None;
+    let mut completed_calls: Vec<CompletedCall> = Vec::new();
+    let mut token_readings: Vec<(i64, u64)> = Vec::new();
+    for raw in lines {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+        // Partial/unknown lines are expected at the edges of a live rollout; skip them.
+        let Ok(line) = serde_json::from_str::<TailLine>(raw) else {
+            continue;
+        };
+        let line_ts = line
+            .timestamp
+            .as_deref()
+            .and_then(parse_rfc3339_utc_to_unix_s);
+        if let Some(ts) = line_ts {
+            last_timestamp_unix_s = Some(last_timestamp_unix_s.map_or(ts, |prev| prev.max(ts)));
+        }
+        let Some(payload) = line.payload else {
+            continue;
+        };
+        if line.ty == "event_msg" {
+            let text =
+                |v: Option<serde_json::Value>| v.and_then(|v| v.as_str().map(str::to_string));
+            match payload.ty.as_deref() {
+                Some("agent_message") => {
+                    last_agent_message = text(payload.message);
+                    if let Some(line) = last_agent_message
+                        .as_deref()
+                        .and_then(|m| m.lines().map(str::trim).rfind(|l| !l.is_empty()))
+                    {
+                        latest_line = Some(truncate_end(line, CALL_SUMMARY_MAX_CHARS));
+                    }
+                }
+                Some("task_complete") => {
+                    let msg = text(payload.last_agent_message)
+                        .or_else(|| last_agent_message.clone())
+                        .unwrap_or_default();
+                    final_message = Some(truncate_end(msg.trim(), FINAL_MESSAGE_MAX_CHARS));
+                }
+                Some("task_started") | Some("user_message") => {
+                    last_agent_message = None;
+                    final_message = None;
+                }
+                Some("token_count") => {
+                    if let (Some(ts), Some(total)) = (
+                        line_ts,
+                        payload
+                            .info
+                            .as_ref()
+                            .and_then(|i| i.pointer("/total_token_usage/total_tokens"))
+                            .and_then(|v| v.as_u64()),
+                    ) {
+                        token_readings.push((ts, total));
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if line.ty != "response_item" {
+            continue;
+        }
+        let Some(call_id) = payload.call_id else {
+            continue;
+        };
+        match payload.ty.as_deref() {
+            Some("function_call") | Some("custom_tool_call") => {
+                let raw = payload.arguments.or(payload.input);
+                calls.push(PendingFunctionCall {
+                    call_id,
+                    name: payload.name.unwrap_or_else(|| "unknown".into()),
+                    summary: raw.as_ref().and_then(summarize_call_arguments),
+                    started_unix_s: line_ts,
+                });
+            }
+            Some("function_call_output") | Some("custom_tool_call_output") => {
+                if let Some((call, (start, end))) = calls
+                    .iter()
+                    .find(|c| c.call_id == call_id)
+                    .and_then(|c| Some((c, (c.started_unix_s?, line_ts?))))
+                {
+                    // Waiting on the user says nothing about how long the next one takes.
+                    if let (Some(summary), false) =
+                        (call.summary.clone(), call.name == "request_user_input")
+                    {
+                        completed_calls.push(CompletedCall {
+                            call_id: call_id.clone(),
+                            summary,
+                            secs: end.saturating_sub(start).max(0),
+                        });
+                    }
+                }
+                completed.insert(call_id);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(TailHints {
+        pending_call: calls
+            .into_iter()
+            .rev()
+            .find(|c| !completed.contains(&c.call_id)),
+        last_timestamp_unix_s,
+        final_message,
+        latest_line,
+        completed_calls,
+        token_readings,
+    })
+}
+
+/// One rollout line as a readable entry for `codex-ps logs`: user and assistant messages, tool
+/// calls and their output, turn boundaries; with the line's timestamp. `None` for everything
+/// else (reasoning, token counts, metadata, partial lines).
+pub fn render_rollout_line(raw: &str) -> Option<(Option<i64>, String)> {
+    let line = serde_json::from_str::<TailLine>(raw.trim()).ok()?;
+    let ts = line
+        .timestamp
+        .as_deref()
+        .and_then(parse_rfc3339_utc_to_unix_s);
+    let payload = line.payload?;
+    let text = |v: Option<&serde_json::Value>| v.and_then(|v| v.as_str()).map(str::to_string);
+    let rendered = match (line.ty.as_str(), payload.ty.as_deref()?) {
+        ("event_msg", "user_message") => format!("user: {}", text(payload.message.as_ref())?),
+        ("event_msg", "agent_message") => {
+            format!("assistant: {}", text(payload.message.as_ref())?)
+        }
+        ("event_msg", "task_started") => "--- turn started ---".to_string(),
+        ("event_msg", "task_complete") => "--- turn complete ---".to_string(),
+        ("response_item", "function_call" | "custom_tool_call") => {
+            let call = PendingFunctionCall {
+                call_id: payload.call_id.unwrap_or_default(),
+                name: payload.name.unwrap_or_else(|| "unknown".into()),
+                summary: payload
+                    .arguments
+                    .or(payload.input)
+                    .as_ref()
+                    .and_then(summarize_call_arguments),
+                started_unix_s: ts,
+            };
+            format!("-> {}", call.describe())
+        }
+        ("response_item", "function_call_output" | "custom_tool_call_output") => {
+            let raw = text(payload.output.as_ref()).unwrap_or_default();
+            // Shell outputs are `{"output": "...", "metadata": {...}}` encoded as a string.
+            let out = serde_json::from_str::<serde_json::Value>(&raw)
+                .ok()
+                .and_then(|v| text(v.get("output")))
+                .unwrap_or(raw);
+            let mut lines = out.lines().filter(|l| !l.trim().is_empty());
+            let first = lines.next().unwrap_or("(no output)");
+            let more = lines.count();
+            let mut s = format!("<- {}", truncate_end(first.trim(), CALL_SUMMARY_MAX_CHARS));
+            if more > 0 {
+                s.push_str(&format!(" (+{more} lines)"));
+            }
+            s
+        }
+        _ => return None,
+    };
+    Some((ts, rendered))
+}
+
+/// Pull the interesting part out of tool-call arguments: `cmd`/`command` for shell tools,
+/// the question for user-input requests, the path for file tools; otherwise the raw text.
+fn summarize_call_arguments(raw: &serde_json::Value) -> Option<String> {
+    let parsed = match raw {
+        serde_json::Value::String(s) => {
+            serde_json::from_str::<serde_json::Value>(s).unwrap_or_else(|_| raw.clone())
+        }
+        v => v.clone(),
+    };
+    let text = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(items) => {
+            let parts: Vec<&str> = items.iter().filter_map(|i| i.as_str()).collect();
+            (!parts.is_empty()).then(|| parts.join(" "))
+        }
+        _ => None,
+    };
+
+    let summary = match &parsed {
+        serde_json::Value::Object(map) => ["cmd", "command", "question", "prompt", "path"]
+            .iter()
+            .find_map(|k| map.get(*k).and_then(text))
+            .or_else(|| {
+                map.get("questions")
+                    .and_then(|q| q.get(0))
+                    .and_then(|q| q.get("question"))
+                    .and_then(text)
+            }),
+        serde_json::Value::String(s) => Some(s.clone()),
+        _ => None,
+    }?;
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!summary.is_empty()).then(|| truncate_end(&summary, CALL_SUMMARY_MAX_CHARS))
+}
+
 fn parse_session_source(
     source: Option<&serde_json::Value>,
 ) -> (Option<String>, Option<String>, Option<i32>) {
@@ -114,12 +531,13 @@ mod tests {
         let mut f = NamedTempFile::new().expect("tempfile");
         std::io::Write::write_all(
             &mut f,
-            br#"{"type":"session_meta","payload":{"id":"019c2590-5605-7cd1-81b8-8a488af219a3","cwd":"/tmp/example","git":{"commit_hash":"abc123","branch":"main"}}}
+            br#"{"timestamp":"2026-02-03T16:12:22.500Z","type":"session_meta","payload":{"id":"019c2590-5605-7cd1-81b8-8a488af219a3","timestamp":"2026-02-03T16:12:22.123Z","cwd":"/tmp/example","git":{"commit_hash":"abc123","branch":"main"}}}
 "#,
         )
         .expect("write");
 
         let meta = read_session_meta(f.path()).expect("read_session_meta");
+        assert_eq!(meta.started_at_unix_s, Some(1_770_135_142));
         assert_eq!(
             meta.id.as_deref(),
             Some("019c2590-5605-7cd1-81b8-8a488af219a3")
@@ -152,6 +570,97 @@ mod tests {
         assert_eq!(meta.subagent_depth, Some(1));
     }
 
+    #[test]
+    fn pending_function_call_ignores_completed_calls() {
+        let mut f = NamedTempFile::new().expect("tempfile");
+        std::io::Write::write_all(
+            &mut f,
+            br#"{"type":"session_meta","payload":{"id":"x"}}
+{"timestamp":"2026-02-03T16:08:00Z","type":"response_item","payload":{"type":"function_call","name":"exec_command","call_id":"c1","arguments":"{\"cmd\":\"cargo test\"}"}}
+{"timestamp":"2026-02-03T16:12:10Z","type":"response_item","payload":{"type":"function_call_output","call_id":"c1"}}
+{"timestamp":"2026-02-03T16:12:22.123Z","type":"response_item","payload":{"type":"function_call","name":"request_user_input","call_id":"c2","arguments":"{\"questions\":[{\"question\":\"Publish now?\"}]}"}}
+"#,
+        )
+        .expect("write");
+
+        let hints = read_tail_hints(f.path(), 64 * 1024).expect("read tail");
+        let pending = hints.pending_call.expect("pending call");
+        assert_eq!(pending.call_id, "c2");
+        assert_eq!(pending.name, "request_user_input");
+        assert_eq!(pending.describe(), "request_user_input: Publish now?");
+        assert_eq!(hints.last_timestamp_unix_s, Some(1_770_135_142));
+        assert_eq!(
+            hints.completed_calls,
+            vec![CompletedCall {
+                call_id: "c1".into(),
+                summary: "cargo test".into(),
+                secs: 250,
+            }]
+        );
+    }
+
+    #[test]
+    fn final_message_only_after_turn_completes() {
+        let mut f = NamedTempFile::new().expect("tempfile");
+        std::io::Write::write_all(
+            &mut f,
+            br#"{"type":"session_meta","payload":{"id":"x"}}
+{"type":"event_msg","payload":{"type":"task_started"}}
+{"type":"event_msg","payload":{"type":"agent_message","message":"All tests pass."}}
+{"type":"event_msg","payload":{"type":"task_complete","last_agent_message":null}}
+"#,
+        )
+        .expect("write");
+        let hints = read_tail_hints(f.path(), 64 * 1024).expect("read tail");
+        assert_eq!(hints.final_message.as_deref(), Some("All tests pass."));
+
+        std::io::Write::write_all(
+            &mut f,
+            br#"{"type":"event_msg","payload":{"type":"user_message","message":"next"}}
+"#,
+        )
+        .expect("write");
+        let hints = read_tail_hints(f.path(), 64 * 1024).expect("read tail");
+        assert_eq!(hints.final_message, None);
+        assert_eq!(hints.latest_line.as_deref(), Some("All tests pass."));
+    }
+
+    #[test]
+    fn renders_messages_calls_and_outputs() {
+        let render = |raw: &str| render_rollout_line(raw).map(|(_, text)| text);
+        assert_eq!(
+            render(
+                r#"{"timestamp":"2026-02-03T16:12:22Z","type":"event_msg","payload":{"type":"user_message","message":"fix it"}}"#
+            )
+            .as_deref(),
+            Some("user: fix it")
+        );
+        assert_eq!(
+            render(
+                r#"{"type":"response_item","payload":{"type":"function_call","name":"exec_command","call_id":"c1","arguments":"{\"cmd\":\"cargo test\"}"}}"#
+            )
+            .as_deref(),
+            Some("-> exec_command: cargo test")
+        );
+        assert_eq!(
+            render(
+                r#"{"type":"response_item","payload":{"type":"function_call_output","call_id":"c1","output":"{\"output\":\"running 3 tests\\ntest a ... ok\\n\",\"metadata\":{}}"}}"#
+            )
+            .as_deref(),
+            Some("<- running 3 tests (+1 lines)")
+        );
+        assert_eq!(
+            render(r#"{"type":"event_msg","payload":{"type":"token_count","info":null}}"#),
+            None
+        );
+        assert_eq!(
+            render_rollout_line(
+                r#"{"timestamp":"2026-02-03T16:12:22Z","type":"event_msg","payload":{"type":"task_started"}}"#
+            ),
+            Some((Some(1_770_135_142), "--- turn started ---".to_string()))
+        );
+    }
+
     #[test]
     fn read_session_meta_requires_first_line_type() {
         let mut f = NamedTempFile::new().expect("tempfile");
@@ -0,0 +1,100 @@
+use chrono::{Datelike, Local, NaiveDateTime, Timelike, Weekday};
+use serde::{Deserialize, Deserializer};
+
+/// `[schedule]` in `config.toml`: when the TUI stops collecting, e.g. so a laptop left open
+/// doesn't SSH into a work machine at night.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// Local-time window such as `22:00-07:00` (may wrap past midnight).
+    #[serde(default, deserialize_with = "hours")]
+    pub quiet_hours: Option<(u32, u32)>,
+    /// Whole days off, e.g. `["sat", "sun"]`.
+    #[serde(default, deserialize_with = "weekdays")]
+    pub quiet_days: Vec<Weekday>,
+    #[serde(default)]
+    pub pause: PauseScope,
+}
+
+/// What stops during quiet hours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PauseScope {
+    /// Remote hosts keep their last result; local sessions still refresh.
+    #[default]
+    Remote,
+    /// No collection at all; the last snapshot stays on screen.
+    All,
+}
+
+impl ScheduleConfig {
+    /// What is paused at local time `now`, if anything.
+    pub fn paused_at(&self, now: NaiveDateTime) -> Option<PauseScope> {
+        let minute = now.hour() * 60 + now.minute();
+        let in_hours = self.quiet_hours.is_some_and(|(start, end)| {
+            if start <= end {
+                (start..end).contains(&minute)
+            } else {
+                minute >= start || minute < end
+            }
+        });
+        (in_hours || self.quiet_days.contains(&now.weekday())).then_some(self.pause)
+    }
+
+    pub fn paused_now(&self) -> Option<PauseScope> {
+        self.paused_at(Local::now().naive_local())
+    }
+}
+
+fn hours<'de, D: Deserializer<'de>>(d: D) -> Result<Option<(u32, u32)>, D::Error> {
+    let s = String::deserialize(d)?;
+    let minutes = |t: &str| {
+        let (h, m) = t.trim().split_once(':')?;
+        let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+        (h < 24 && m < 60).then_some(h * 60 + m)
+    };
+    s.split_once('-')
+        .and_then(|(start, end)| Some((minutes(start)?, minutes(end)?)))
+        .map(Some)
+        .ok_or_else(|| serde::de::Error::custom(format!("expected HH:MM-HH:MM, got {s:?}")))
+}
+
+fn weekdays<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Weekday>, D::Error> {
+    Vec::<String>::deserialize(d)?
+        .iter()
+        .map(|s| {
+            s.parse::<Weekday>()
+                .map_err(|_| serde::de::Error::custom(format!("unknown day {s:?}")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_hours_wrap_midnight_and_days_pause_all_day() {
+        let cfg: ScheduleConfig = toml::from_str(
+            r#"
+quiet_hours = "22:30-07:00"
+quiet_days = ["sat", "Sunday"]
+pause = "all"
+"#,
+        )
+        .expect("parse");
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").expect("datetime");
+        // 2026-10-14 is a Wednesday.
+        assert_eq!(cfg.paused_at(at("2026-10-14 03:00")), Some(PauseScope::All));
+        assert_eq!(cfg.paused_at(at("2026-10-14 22:30")), Some(PauseScope::All));
+        assert_eq!(cfg.paused_at(at("2026-10-14 07:00")), None);
+        assert_eq!(cfg.paused_at(at("2026-10-14 22:29")), None);
+        assert_eq!(cfg.paused_at(at("2026-10-17 12:00")), Some(PauseScope::All));
+
+        assert!(toml::from_str::<ScheduleConfig>(r#"quiet_hours = "25:00-07:00""#).is_err());
+        assert_eq!(
+            ScheduleConfig::default().paused_at(at("2026-10-14 03:00")),
+            None
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::app::short_thread_id;
+use crate::model::{SessionRow, SessionStatus};
+
+/// Resolve a session selector against the active sessions (shared by every subcommand that
+/// targets one session).
+///
+/// Accepted forms, optionally qualified as `host:<selector>` (a host or its label):
+/// - `@latest`: most recent activity
+/// - `@blocked`: the session that has been waiting on the user the longest
+/// - an exact thread id or session name
+/// - a unique, case-insensitive thread id prefix
+///
+/// Ambiguous matches are errors that list the candidates.
+pub fn resolve<'a>(sessions: &'a [SessionRow], selector: &str) -> anyhow::Result<&'a SessionRow> {
+    let selector = selector.trim();
+    if selector.is_empty() {
+        anyhow::bail!("empty session selector");
+    }
+
+    // Only a known host counts as a qualifier, so names containing ':' still work.
+    let (scope, term): (Vec<&SessionRow>, &str) = match selector.split_once(':') {
+        Some((host, rest)) if sessions.iter().any(|s| on_host(s, host)) => {
+            (sessions.iter().filter(|s| on_host(s, host)).collect(), rest)
+        }
+        _ => (sessions.iter().collect(), selector),
+    };
+    if term.is_empty() {
+        anyhow::bail!("empty session selector after host qualifier in {selector:?}");
+    }
+
+    match term {
+        "@latest" => {
+            return scope
+                .into_iter()
+                .max_by_key(|s| s.last_activity_unix_s.unwrap_or(i64::MIN))
+                .ok_or_else(|| anyhow::anyhow!("no active sessions for {selector:?}"));
+        }
+        "@blocked" => {
+            return scope
+                .into_iter()
+                .filter(|s| s.status == SessionStatus::Waiting)
+                .min_by_key(|s| s.last_activity_unix_s.unwrap_or(i64::MAX))
+                .ok_or_else(|| anyhow::anyhow!("no session is waiting on you ({selector:?})"));
+        }
+        t if t.starts_with('@') => {
+            anyhow::bail!("unknown selector alias {t:?} (expected @latest or @blocked)")
+        }
+        _ => {}
+    }
+
+    if let Some(row) = scope.iter().copied().find(|s| s.thread_id == term) {
+        return Ok(row);
+    }
+    let named = |exact: bool| -> Vec<&'a SessionRow> {
+        scope
+            .iter()
+            .copied()
+            .filter(|s| {
+                s.name.as_deref().is_some_and(|n| {
+                    if exact {
+                        n == term
+                    } else {
+                        n.eq_ignore_ascii_case(term)
+                    }
+                })
+            })
+            .collect()
+    };
+    for exact in [true, false] {
+        let hits = named(exact);
+        if !hits.is_empty() {
+            return unique(hits, &format!("name {term:?}"));
+        }
+    }
+
+    let prefix = term.to_ascii_lowercase();
+    let hits: Vec<&SessionRow> = scope
+        .into_iter()
+        .filter(|s| {
+            std::iter::once(&s.thread_id)
+                .chain(&s.thread_id_aliases)
+                .any(|id| id.to_ascii_lowercase().starts_with(&prefix))
+        })
+        .collect();
+    if hits.is_empty() {
+        anyhow::bail!("no active session matches {selector:?}");
+    }
+    unique(hits, &format!("thread id prefix {prefix:?}"))
+}
+
+fn unique<'a>(hits: Vec<&'a SessionRow>, what: &str) -> anyhow::Result<&'a SessionRow> {
+    if let [one] = hits.as_slice() {
+        return Ok(one);
+    }
+    let list = hits
+        .iter()
+        .map(|s| match s.name.as_deref() {
+            Some(n) => format!("{}:{} {n:?}", s.host, short_thread_id(&s.thread_id)),
+            None => format!("{}:{}", s.host, short_thread_id(&s.thread_id)),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    anyhow::bail!("{what} is ambiguous ({} matches: {list})", hits.len())
+}
+
+fn on_host(s: &SessionRow, host: &str) -> bool {
+    s.host == host || s.host_label.as_deref() == Some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(host: &str, thread_id: &str, name: Option<&str>, last: i64) -> SessionRow {
+        SessionRow {
+            host: host.into(),
+            thread_id: thread_id.into(),
+            name: name.map(str::to_string),
+            status: SessionStatus::Working,
+            last_activity_unix_s: Some(last),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolves_prefixes_names_hosts_and_aliases() {
+        let mut blocked = row("home", "019d0000-cccc", Some("triage"), 50);
+        blocked.status = SessionStatus::Waiting;
+        blocked.host_label = Some("hm".into());
+        let sessions = vec![
+            row("local", "019c2590-aaaa", Some("triage"), 300),
+            row("local", "019c2590-bbbb", None, 200),
+            blocked,
+        ];
+        let tid = |sel: &str| resolve(&sessions, sel).map(|s| s.thread_id.clone());
+
+        assert_eq!(tid("019C2590-B").expect("resolve"), "019c2590-bbbb");
+        assert_eq!(tid("home:triage").expect("resolve"), "019d0000-cccc");
+        assert_eq!(tid("@latest").expect("resolve"), "019c2590-aaaa");
+        assert_eq!(tid("home:@latest").expect("resolve"), "019d0000-cccc");
+        assert_eq!(tid("hm:triage").expect("resolve"), "019d0000-cccc");
+        assert_eq!(tid("@blocked").expect("resolve"), "019d0000-cccc");
+
+        let err = tid("triage").unwrap_err().to_string();
+        assert!(err.contains("ambiguous (2 matches"), "{err}");
+        let err = tid("019").unwrap_err().to_string();
+        assert!(err.contains("ambiguous (3 matches"), "{err}");
+        assert!(tid("ffff").is_err());
+        assert!(tid("local:@blocked").is_err());
+    }
+}
@@ -0,0 +1,614 @@
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::changes::ChangeTracker;
+use crate::collector::Collector;
+use crate::events::{EventMeta, SessionEvent, events_from_changes, policy_events};
+use crate::health::Health;
+use crate::hooks::HookRunner;
+use crate::model::Snapshot;
+use crate::statusline::{build_status_line, change_key};
+use crate::timefmt::to_iso8601_utc;
+use crate::util::system_time_to_unix_s;
+
+/// Events kept for `/events` clients that fall a little behind.
+const EVENT_BACKLOG: usize = 256;
+/// SSE comment sent while nothing happens, so proxies keep the stream open.
+const KEEPALIVE: Duration = Duration::from_secs(15);
+const HEALTH_EVERY: Duration = Duration::from_secs(60);
+/// Longest a `/statusline?since=` request is held before answering `304 Not Modified`.
+const LONG_POLL: Duration = Duration::from_secs(30);
+/// Longest request head read before giving up on a client.
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `[serve]` in `config.toml`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServeConfig {
+    /// Address to listen on (default `127.0.0.1:7780`; `--listen` overrides).
+    #[serde(default)]
+    pub listen: Option<String>,
+    /// Bearer token clients must send (`CODEX_PS_SERVE_TOKEN` overrides). Required unless
+    /// listening on loopback.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Origins allowed to read the feed from a browser (`*` for any).
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+}
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:7780";
+
+/// `codex-ps serve`: collect every `refresh_ms` and serve the latest snapshot at
+/// `GET /snapshot`, the `--events` stream as server-sent events at `GET /events`, the
+/// collector's health at `GET /healthz` and a long-polled `lsp-json` line at `GET /statusline`.
+pub fn run_serve(
+    mut collector: Collector,
+    hosts: Vec<String>,
+    refresh_ms: u64,
+    debug: bool,
+    mut hooks: HookRunner,
+    config: ServeConfig,
+    listen: Option<String>,
+) -> anyhow::Result<()> {
+    let addr = listen
+        .or(config.listen.clone())
+        .unwrap_or_else(|| DEFAULT_LISTEN.to_string());
+    let token = std::env::var("CODEX_PS_SERVE_TOKEN")
+        .ok()
+        .or(config.token.clone())
+        .filter(|t| !t.is_empty());
+    let listener = TcpListener::bind(&addr).with_context(|| format!("listen on {addr}"))?;
+    let local = listener.local_addr().context("listener address")?;
+    if token.is_none() && !local.ip().is_loopback() {
+        anyhow::bail!(
+            "refusing to serve on {local} without a token; set [serve] token or CODEX_PS_SERVE_TOKEN"
+        );
+    }
+    eprintln!("codex-ps: serving on http://{local} (/snapshot, /events, /healthz, /statusline)");
+
+    let feed = Arc::new(Feed::default());
+    let access = Arc::new(Access {
+        token,
+        cors_origins: config.cors_origins,
+    });
+    {
+        let feed = Arc::clone(&feed);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let feed = Arc::clone(&feed);
+                let access = Arc::clone(&access);
+                std::thread::spawn(move || {
+                    let _ = handle_connection(stream, &feed, &access);
+                });
+            }
+        });
+    }
+
+    let refresh = Duration::from_millis(refresh_ms.max(100));
+    let mut tracker = ChangeTracker::default();
+    let mut last_health: Option<Instant> = None;
+    loop {
+        let collected = collector.collect(&hosts);
+        feed.set_health(collector.health());
+        if let Ok(mut snap) = collected {
+            if !debug {
+                snap.strip_debug();
+            }
+            let changes = tracker.update(&snap);
+            let now = system_time_to_unix_s(SystemTime::now()).unwrap_or(0);
+            hooks.on_snapshot(&snap, &changes, now);
+
+            let mut events = events_from_changes(&snap, &changes);
+            events.extend(policy_events(&mut collector, &snap, &changes)?);
+            if last_health.is_none_or(|t| t.elapsed() >= HEALTH_EVERY) {
+                last_health = Some(Instant::now());
+                events.push(SessionEvent::Health {
+                    meta: EventMeta {
+                        generation: changes.generation,
+                        at: to_iso8601_utc(now),
+                    },
+                    health: collector.health(),
+                });
+            }
+            feed.publish(snap, &events)?;
+        }
+        std::thread::sleep(refresh);
+    }
+}
+
+/// Who may read the feed.
+struct Access {
+    token: Option<String>,
+    cors_origins: Vec<String>,
+}
+
+impl Access {
+    fn authorized(&self, req: &Request) -> bool {
+        let Some(token) = self.token.as_deref() else {
+            return true;
+        };
+        // Browsers' EventSource can't set headers, so the token may come as `?access_token=`.
+        req.header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim)
+            .or_else(|| req.query_param("access_token"))
+            .is_some_and(|t| constant_time_eq(t.as_bytes(), token.as_bytes()))
+    }
+
+    /// The `Access-Control-Allow-Origin` value for this request, if its origin is allowed.
+    fn cors_origin<'a>(&self, req: &'a Request) -> Option<&'a str> {
+        let origin = req.header("origin")?;
+        self.cors_origins
+            .iter()
+            .any(|o| o == "*" || o == origin)
+            .then_some(origin)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Latest snapshot plus a bounded backlog of SSE frames, numbered in publish order.
+#[derive(Default)]
+struct Feed {
+    state: Mutex<FeedState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct FeedState {
+    snapshot: Option<String>,
+    /// The same snapshot, for `/statusline` to summarize per request.
+    latest: Option<Snapshot>,
+    /// The collector's last health report and when it was taken.
+    health: Option<(Health, Instant)>,
+    /// Sequence number of the newest frame in `backlog`.
+    seq: u64,
+    backlog: VecDeque<(u64, String)>,
+}
+
+impl Feed {
+    fn publish(&self, snap: Snapshot, events: &[SessionEvent]) -> anyhow::Result<()> {
+        let json = serde_json::to_string(&snap).context("serialize snapshot")?;
+        let mut state = self.state.lock().expect("feed lock");
+        for ev in events {
+            let value = serde_json::to_value(ev).context("serialize event")?;
+            let name = value
+                .get("event")
+                .and_then(|v| v.as_str())
+                .unwrap_or("message")
+                .to_string();
+            state.seq += 1;
+            let seq = state.seq;
+            state
+                .backlog
+                .push_back((seq, sse_frame(&name, seq, &value.to_string())));
+            if state.backlog.len() > EVENT_BACKLOG {
+                state.backlog.pop_front();
+            }
+        }
+        state.snapshot = Some(json);
+        state.latest = Some(snap);
+        self.changed.notify_all();
+        Ok(())
+    }
+
+    fn set_health(&self, health: Health) {
+        self.state.lock().expect("feed lock").health = Some((health, Instant::now()));
+    }
+
+    /// The last health report, aged to now so a wedged collection loop still shows.
+    fn health(&self) -> Option<Health> {
+        let state = self.state.lock().expect("feed lock");
+        let (health, at) = state.health.as_ref()?;
+        let age = at.elapsed();
+        let mut health = health.clone();
+        health.uptime_secs += age.as_secs();
+        health.since_last_cycle_ms = health
+            .since_last_cycle_ms
+            .map(|ms| ms.saturating_add(age.as_millis() as u64));
+        Some(health)
+    }
+
+    /// The status line for `repo` and its tag (the ETag, unquoted), once it differs from `since` (or right away
+    /// without one). `None` if it didn't change, or no collection finished, within `timeout`.
+    fn next_status_line(
+        &self,
+        repo: Option<&Path>,
+        since: Option<&str>,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<(String, String)>> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock().expect("feed lock");
+        loop {
+            if let Some(snap) = state.latest.as_ref() {
+                let line = build_status_line(snap, repo);
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                change_key(&line)?.hash(&mut hasher);
+                let tag = format!("{:016x}", hasher.finish());
+                if since != Some(tag.as_str()) {
+                    let body = serde_json::to_string(&line).context("serialize status line")?;
+                    return Ok(Some((tag, body)));
+                }
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(None);
+            }
+            state = self.changed.wait_timeout(state, left).expect("feed lock").0;
+        }
+    }
+}
+
+fn sse_frame(event: &str, id: u64, data: &str) -> String {
+    format!("event: {event}\nid: {id}\ndata: {data}\n\n")
+}
+
+/// The parts of an HTTP/1.1 request head the server looks at.
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    /// Lowercased names.
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    fn parse(head: &str) -> Option<Self> {
+        let mut lines = head.lines();
+        let mut parts = lines.next()?.split_whitespace();
+        let method = parts.next()?.to_string();
+        let target = parts.next()?;
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let headers = lines
+            .filter_map(|l| l.split_once(':'))
+            .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+            .collect();
+        Some(Self {
+            method,
+            path: path.to_string(),
+            query: query.to_string(),
+            headers,
+        })
+    }
+
+    /// A query parameter with `%XX` escapes and `+` decoded (`None` if the escapes aren't UTF-8).
+    fn query_param_decoded(&self, name: &str) -> Option<String> {
+        let raw = self.query_param(name)?.as_bytes();
+        let mut out = Vec::with_capacity(raw.len());
+        let mut i = 0;
+        while i < raw.len() {
+            let hex = raw
+                .get(i + 1..i + 3)
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok());
+            match (raw[i], hex) {
+                (b'%', Some(b)) => {
+                    out.push(b);
+                    i += 3;
+                }
+                (b'+', _) => {
+                    out.push(b' ');
+                    i += 1;
+                }
+                (c, _) => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8(out).ok()
+    }
+
+    fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .split('&')
+            .filter_map(|kv| kv.split_once('='))
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v)
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn read_head(stream: &TcpStream) -> anyhow::Result<String> {
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    loop {
+        let n = reader.read_line(&mut head).context("read request")?;
+        if n == 0 || head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            return Ok(head);
+        }
+        if head.len() > MAX_REQUEST_HEAD {
+            anyhow::bail!("request head too large");
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, feed: &Feed, access: &Access) -> anyhow::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT)).ok();
+    let peer: Option<SocketAddr> = stream.peer_addr().ok();
+    let head = read_head(&stream)?;
+    let Some(req) = Request::parse(&head) else {
+        return respond(&mut stream, "400 Bad Request", &[], "bad request\n");
+    };
+
+    let mut headers: Vec<(&str, String)> = Vec::new();
+    if let Some(origin) = access.cors_origin(&req) {
+        headers.push(("Access-Control-Allow-Origin", origin.to_string()));
+        headers.push(("Vary", "Origin".to_string()));
+    }
+    if req.method == "OPTIONS" {
+        headers.push(("Access-Control-Allow-Methods", "GET, OPTIONS".to_string()));
+        headers.push((
+            "Access-Control-Allow-Headers",
+            "Authorization, Last-Event-ID".to_string(),
+        ));
+        headers.push(("Access-Control-Max-Age", "600".to_string()));
+        return respond(&mut stream, "204 No Content", &headers, "");
+    }
+    if req.method != "GET" {
+        return respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            &headers,
+            "only GET\n",
+        );
+    }
+    if !access.authorized(&req) {
+        if let Some(peer) = peer {
+            eprintln!("codex-ps: rejected {peer}: missing or wrong bearer token");
+        }
+        headers.push(("WWW-Authenticate", "Bearer".to_string()));
+        return respond(&mut stream, "401 Unauthorized", &headers, "unauthorized\n");
+    }
+
+    match req.path.as_str() {
+        "/snapshot" => {
+            let snapshot = feed.state.lock().expect("feed lock").snapshot.clone();
+            match snapshot {
+                Some(json) => {
+                    headers.push(("Content-Type", "application/json".to_string()));
+                    respond(&mut stream, "200 OK", &headers, &json)
+                }
+                None => respond(
+                    &mut stream,
+                    "503 Service Unavailable",
+                    &headers,
+                    "first collection still running\n",
+                ),
+            }
+        }
+        "/events" => {
+            let resume_after = req
+                .header("last-event-id")
+                .and_then(|v| v.parse::<u64>().ok());
+            stream_events(stream, feed, &headers, resume_after)
+        }
+        "/healthz" => match feed.health() {
+            Some(health) => {
+                let json = serde_json::to_string(&health).context("serialize health")?;
+                headers.push(("Content-Type", "application/json".to_string()));
+                respond(&mut stream, "200 OK", &headers, &json)
+            }
+            None => respond(
+                &mut stream,
+                "503 Service Unavailable",
+                &headers,
+                "first collection still running\n",
+            ),
+        },
+        "/statusline" => {
+            // Sessions report canonical cwds, as with `--format lsp-json --repo`.
+            let repo = req
+                .query_param_decoded("repo")
+                .filter(|r| !r.is_empty())
+                .map(|r| {
+                    let p = PathBuf::from(r);
+                    p.canonicalize().unwrap_or(p)
+                });
+            let since = req
+                .query_param_decoded("since")
+                .map(|s| s.trim_matches('"').to_string());
+            let wait = if since.is_some() {
+                LONG_POLL
+            } else {
+                Duration::ZERO
+            };
+            headers.push(("Cache-Control", "no-store".to_string()));
+            match feed.next_status_line(repo.as_deref(), since.as_deref(), wait)? {
+                Some((tag, json)) => {
+                    headers.push(("ETag", format!("\"{tag}\"")));
+                    headers.push(("Content-Type", "application/json".to_string()));
+                    respond(&mut stream, "200 OK", &headers, &json)
+                }
+                None if since.is_some() => respond(&mut stream, "304 Not Modified", &headers, ""),
+                None => respond(
+                    &mut stream,
+                    "503 Service Unavailable",
+                    &headers,
+                    "first collection still running\n",
+                ),
+            }
+        }
+        _ => respond(&mut stream, "404 Not Found", &headers, "not found\n"),
+    }
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    headers: &[(&str, String)],
+    body: &str,
+) -> anyhow::Result<()> {
+    let mut out = format!(
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (k, v) in headers {
+        out.push_str(&format!("{k}: {v}\r\n"));
+    }
+    out.push_str("\r\n");
+    out.push_str(body);
+    stream.write_all(out.as_bytes()).context("write response")?;
+    Ok(())
+}
+
+/// SSE: a `snapshot` frame first (unless resuming), then every event as it is published.
+fn stream_events(
+    mut stream: TcpStream,
+    feed: &Feed,
+    headers: &[(&str, String)],
+    resume_after: Option<u64>,
+) -> anyhow::Result<()> {
+    let mut out = String::from(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n",
+    );
+    for (k, v) in headers {
+        out.push_str(&format!("{k}: {v}\r\n"));
+    }
+    out.push_str("\r\n");
+
+    let mut state = feed.state.lock().expect("feed lock");
+    let mut sent = match resume_after {
+        Some(id) => id,
+        None => {
+            if let Some(snap) = state.snapshot.as_deref() {
+                out.push_str(&sse_frame("snapshot", state.seq, snap));
+            }
+            state.seq
+        }
+    };
+    loop {
+        for (_, frame) in state.backlog.iter().filter(|(seq, _)| *seq > sent) {
+            out.push_str(frame);
+        }
+        sent = state.seq;
+        drop(state);
+        if out.is_empty() {
+            out.push_str(": keepalive\n\n");
+        }
+        stream
+            .write_all(out.as_bytes())
+            .and_then(|()| stream.flush())
+            .context("client went away")?;
+        out.clear();
+
+        state = feed.state.lock().expect("feed lock");
+        while state.seq == sent {
+            let (next, timeout) = feed
+                .changed
+                .wait_timeout(state, KEEPALIVE)
+                .expect("feed lock");
+            state = next;
+            if timeout.timed_out() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_need_the_token_and_an_allowed_origin_for_cors() {
+        let req = Request::parse(
+            "GET /events?x=1 HTTP/1.1\r\nHost: ci\r\nAuthorization: Bearer s3cret\r\nOrigin: https://ci.example\r\n\r\n",
+        )
+        .expect("parse");
+        assert_eq!(req.method, "GET");
+        assert_eq!(req.path, "/events");
+
+        let access = Access {
+            token: Some("s3cret".into()),
+            cors_origins: vec!["https://ci.example".into()],
+        };
+        assert!(access.authorized(&req));
+        assert_eq!(access.cors_origin(&req), Some("https://ci.example"));
+
+        let other = Request::parse(
+            "GET /snapshot HTTP/1.1\r\nauthorization: Bearer nope\r\norigin: https://evil.example\r\n\r\n",
+        )
+        .expect("parse");
+        assert!(!access.authorized(&other));
+        assert_eq!(access.cors_origin(&other), None);
+
+        let open = Access {
+            token: None,
+            cors_origins: vec!["*".into()],
+        };
+        assert!(open.authorized(&other));
+        let query =
+            Request::parse("GET /events?access_token=s3cret HTTP/1.1\r\n\r\n").expect("parse");
+        assert!(access.authorized(&query));
+        assert_eq!(open.cors_origin(&other), Some("https://evil.example"));
+        assert_eq!(
+            sse_frame("status_changed", 7, "{}"),
+            "event: status_changed\nid: 7\ndata: {}\n\n"
+        );
+    }
+
+    #[test]
+    fn status_line_long_poll_waits_for_a_change() {
+        use crate::model::{SessionRow, SessionStatus};
+
+        let feed = Feed::default();
+        let none = Duration::from_millis(10);
+        assert_eq!(feed.next_status_line(None, None, none).expect("poll"), None);
+
+        let mut row = SessionRow {
+            host: "local".into(),
+            thread_id: "t".into(),
+            cwd: Some("/src/app".into()),
+            status: SessionStatus::Working,
+            ..Default::default()
+        };
+        feed.publish(Snapshot::of(vec![row.clone()]), &[])
+            .expect("publish");
+        let (tag, body) = feed
+            .next_status_line(None, None, none)
+            .expect("poll")
+            .expect("line");
+        assert!(body.contains("\"working\":1"));
+        assert_eq!(
+            feed.next_status_line(None, Some(&tag), none).expect("poll"),
+            None
+        );
+
+        row.status = SessionStatus::Waiting;
+        feed.publish(Snapshot::of(vec![row]), &[]).expect("publish");
+        let (next, _) = feed
+            .next_status_line(Some(Path::new("/src/app")), Some(&tag), none)
+            .expect("poll")
+            .expect("changed");
+        assert_ne!(next, tag);
+
+        let req =
+            Request::parse("GET /statusline?repo=%2Fsrc%2Fmy+app&since=%22ab%22 HTTP/1.1\r\n\r\n")
+                .expect("parse");
+        assert_eq!(
+            req.query_param_decoded("repo").as_deref(),
+            Some("/src/my app")
+        );
+        assert_eq!(req.query_param_decoded("since").as_deref(), Some("\"ab\""));
+    }
+}
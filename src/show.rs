@@ -0,0 +1,263 @@
+use std::io::Write;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::cli_version::{install_source, version_key};
+use crate::collector::Collector;
+use crate::model::{SessionRow, SessionStatus};
+use crate::selector;
+use crate::timefmt::format_age;
+
+/// `codex-ps show --json`: the session's full row plus the rows of its subagents.
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionDetail {
+    #[serde(flatten)]
+    pub session: SessionRow,
+    pub subagents: Vec<SessionRow>,
+    /// Newest Codex CLI version among the host's sessions, when this one runs an older one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newer_cli_version: Option<String>,
+}
+
+impl SessionDetail {
+    /// `session` plus the rows in `sessions` that are its subagents.
+    pub fn from_sessions(sessions: &[SessionRow], session: &SessionRow) -> Self {
+        let subagents = sessions
+            .iter()
+            .filter(|r| {
+                r.host == session.host
+                    && r.subagent_parent_thread_id.as_ref() == Some(&session.thread_id)
+            })
+            .cloned()
+            .collect();
+        let newest = sessions
+            .iter()
+            .filter(|r| r.host == session.host)
+            .filter_map(|r| r.cli_version.as_deref())
+            .max_by_key(|v| version_key(v));
+        let newer_cli_version = match (session.cli_version.as_deref(), newest) {
+            (Some(mine), Some(newest)) if version_key(newest) > version_key(mine) => {
+                Some(newest.to_string())
+            }
+            _ => None,
+        };
+        Self {
+            session: session.clone(),
+            subagents,
+            newer_cli_version,
+        }
+    }
+}
+
+/// One `field: value` line per known field, untruncated; unknown fields are left out.
+pub fn format_detail(detail: &SessionDetail, now_unix_s: i64) -> String {
+    let s = &detail.session;
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    let mut add = |k: &'static str, v: Option<String>| {
+        if let Some(v) = v.filter(|v| !v.is_empty()) {
+            fields.push((k, v));
+        }
+    };
+    let status = |r: &SessionRow| match r.status {
+        SessionStatus::Working => "working",
+        SessionStatus::Waiting => "waiting",
+        SessionStatus::Unknown => "unknown",
+    };
+    let join = |v: &[String]| v.join(", ");
+
+    add("thread", Some(s.thread_id.clone()));
+    add(
+        "host",
+        Some(match s.collected_via.as_slice() {
+            [] => s.host_display().to_string(),
+            via => format!("{} (via {})", s.host_display(), via.join(" → ")),
+        }),
+    );
+    add("name", s.name.clone());
+    add(
+        "exe",
+        s.exe
+            .as_ref()
+            .map(|exe| format!("{exe} ({})", install_source(exe))),
+    );
+    add(
+        "codex",
+        s.cli_version
+            .as_ref()
+            .map(|v| match detail.newer_cli_version.as_deref() {
+                Some(newer) => format!("{v} (outdated: {newer} runs on this host)"),
+                None => v.clone(),
+            }),
+    );
+    add("suggested name", s.suggested_name.clone());
+    add("title", s.title.clone());
+    add("previous title", s.previous_title.clone());
+    add("tags", Some(join(&s.tags)));
+    add("note", s.note.clone());
+    add("watched", s.watched.then(|| "yes".to_string()));
+    add(
+        "status",
+        Some(
+            match s.debug.as_ref().and_then(|d| d.status_reason.as_deref()) {
+                Some(reason) => format!("{} ({reason})", status(s)),
+                None => status(s).to_string(),
+            },
+        ),
+    );
+    add(
+        "last activity",
+        s.last_activity_unix_s.map(|ts| {
+            let at = s.last_activity_at.clone().unwrap_or_default();
+            format!("{} ago {at}", format_age(ts, now_unix_s))
+                .trim_end()
+                .to_string()
+        }),
+    );
+    add("pending call", s.pending_call.clone());
+    add(
+        "eta",
+        s.pending_call_eta.as_ref().map(|e| e.describe(now_unix_s)),
+    );
+    add("tokens/min", s.tokens_per_min.map(|n| n.to_string()));
+    add("tokens", s.total_tokens.map(|n| n.to_string()));
+    add("over budget", s.over_budget.clone());
+    add(
+        "pids",
+        Some(join(
+            &s.pids.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+        )),
+    );
+    add("tty", s.tty.clone());
+    add(
+        "cwd",
+        s.cwd.clone().map(|c| {
+            if s.cwd_missing {
+                format!("{c} (missing)")
+            } else {
+                c
+            }
+        }),
+    );
+    add("repo", s.repo_root.clone());
+    add("branch", s.git_branch.clone());
+    add("current branch", s.current_branch.clone());
+    add("commit", s.git_commit.clone());
+    add(
+        "commits since start",
+        s.commits_since_start.map(|n| n.to_string()),
+    );
+    add(
+        "unpushed commits",
+        s.unpushed_commits.map(|n| n.to_string()),
+    );
+    add("source", s.session_source.clone());
+    add("forked from", s.forked_from_id.clone());
+    add("parent", s.subagent_parent_thread_id.clone());
+    add("depth", s.subagent_depth.map(|d| d.to_string()));
+    add("rollout", s.rollout_path.clone());
+    add("rollout unreadable", s.rollout_unreadable.clone());
+    add("final message", s.final_message.clone());
+
+    let width = fields.iter().map(|(k, _)| k.len()).max().unwrap_or(0) + 1;
+    let mut out = String::new();
+    for (k, v) in &fields {
+        // Continuation lines of multi-line values line up under the first.
+        let v = v.replace('\n', &format!("\n{:width$} ", ""));
+        out.push_str(&format!("{:width$} {v}\n", format!("{k}:")));
+    }
+    if !detail.subagents.is_empty() {
+        out.push_str(&format!("subagents ({}):\n", detail.subagents.len()));
+        for sub in &detail.subagents {
+            let label = sub.title.as_deref().unwrap_or("");
+            out.push_str(&format!("  {} {} {label}\n", sub.thread_id, status(sub)));
+        }
+    }
+    out
+}
+
+/// `codex-ps show <selector>`: everything known about one session.
+pub fn run_show(
+    mut collector: Collector,
+    hosts: &[String],
+    selector: &str,
+    json: bool,
+) -> anyhow::Result<()> {
+    let snap = collector.collect(hosts)?;
+    let row = selector::resolve(&snap.sessions, selector)?;
+    let detail = SessionDetail::from_sessions(&snap.sessions, row);
+    let text = if json {
+        let mut t = serde_json::to_string_pretty(&detail).context("serialize session")?;
+        t.push('\n');
+        t
+    } else {
+        format_detail(&detail, snap.generated_at_unix_s)
+    };
+    if let Err(e) = std::io::stdout().write_all(text.as_bytes()) {
+        if e.kind() != std::io::ErrorKind::BrokenPipe {
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_known_fields_and_subagents() {
+        let row = |v: serde_json::Value| -> SessionRow { serde_json::from_value(v).expect("row") };
+        let sessions = vec![
+            row(serde_json::json!({
+                "host": "local",
+                "thread_id": "019c2590-aaaa",
+                "pids": [42, 43],
+                "status": "working",
+                "name": "release",
+                "pending_call": "exec_command: cargo publish",
+                "final_message": "line one\nline two",
+                "last_activity_unix_s": 940,
+                "cli_version": "0.45.0",
+                "exe": "/opt/homebrew/Cellar/codex/0.45.0/bin/codex",
+            })),
+            row(serde_json::json!({
+                "host": "local",
+                "thread_id": "019c2590-bbbb",
+                "pids": [],
+                "status": "waiting",
+                "title": "docs",
+                "cli_version": "0.46.0",
+                "subagent_parent_thread_id": "019c2590-aaaa",
+            })),
+            row(serde_json::json!({
+                "host": "devbox",
+                "thread_id": "019c2590-cccc",
+                "pids": [],
+                "status": "waiting",
+                "subagent_parent_thread_id": "019c2590-aaaa",
+            })),
+        ];
+        let detail = SessionDetail::from_sessions(&sessions, &sessions[0]);
+        let text = format_detail(&detail, 1000);
+        assert!(text.contains("name:          release\n"), "{text}");
+        assert!(text.contains("pids:          42, 43\n"), "{text}");
+        assert!(text.contains("last activity: 1m ago\n"), "{text}");
+        assert!(
+            text.contains("final message: line one\n               line two\n"),
+            "{text}"
+        );
+        assert!(!text.contains("tty:"), "{text}");
+        assert!(
+            text.contains(
+                "exe:           /opt/homebrew/Cellar/codex/0.45.0/bin/codex (homebrew)\n"
+            ),
+            "{text}"
+        );
+        assert!(
+            text.contains("codex:         0.45.0 (outdated: 0.46.0 runs on this host)\n"),
+            "{text}"
+        );
+        assert!(text.ends_with("subagents (1):\n  019c2590-bbbb waiting docs\n"));
+    }
+}
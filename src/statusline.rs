@@ -0,0 +1,196 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::collector::Collector;
+use crate::model::{SessionRow, SessionStatus, Snapshot};
+
+/// Bumped only on breaking changes to `StatusLine`; fields may be added within a version.
+const SCHEMA_VERSION: u32 = 1;
+
+/// `--format lsp-json`: a compact, stable summary for editor statusline plugins.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct StatusLine {
+    pub v: u32,
+    /// Workspace root the sessions were filtered to (`--repo`), if any.
+    pub repo: Option<String>,
+    pub generated_at_unix_s: i64,
+    pub working: usize,
+    pub waiting: usize,
+    pub unknown: usize,
+    /// Ready-made statusline text, e.g. `✋1 ⚒2` (empty when there are no sessions).
+    pub text: String,
+    pub sessions: Vec<StatusLineSession>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct StatusLineSession {
+    pub host: String,
+    pub thread_id: String,
+    pub label: String,
+    pub status: SessionStatus,
+    pub last_activity_unix_s: Option<i64>,
+    pub branch: Option<String>,
+    pub pending_call: Option<String>,
+}
+
+/// Summarize root sessions, keeping only those whose repo (or cwd) is inside `repo`.
+/// Waiting sessions come first so plugins can show `sessions[0]` as "blocked here".
+pub fn build_status_line(snap: &Snapshot, repo: Option<&Path>) -> StatusLine {
+    let mut rows: Vec<&SessionRow> = snap
+        .sessions
+        .iter()
+        .filter(|r| r.subagent_parent_thread_id.is_none())
+        .filter(|r| repo.is_none_or(|repo| in_repo(r, repo)))
+        .collect();
+    rows.sort_by_key(|r| {
+        (
+            r.status != SessionStatus::Waiting,
+            std::cmp::Reverse(r.last_activity_unix_s),
+        )
+    });
+
+    let count = |s: SessionStatus| rows.iter().filter(|r| r.status == s).count();
+    let (working, waiting, unknown) = (
+        count(SessionStatus::Working),
+        count(SessionStatus::Waiting),
+        count(SessionStatus::Unknown),
+    );
+    let mut text = Vec::new();
+    if waiting > 0 {
+        text.push(format!("✋{waiting}"));
+    }
+    if working > 0 {
+        text.push(format!("⚒{working}"));
+    }
+
+    StatusLine {
+        v: SCHEMA_VERSION,
+        repo: repo.map(|p| p.display().to_string()),
+        generated_at_unix_s: snap.generated_at_unix_s,
+        working,
+        waiting,
+        unknown,
+        text: text.join(" "),
+        sessions: rows
+            .into_iter()
+            .map(|r| StatusLineSession {
+                host: r.host.clone(),
+                thread_id: r.thread_id.clone(),
+                label: r
+                    .name
+                    .clone()
+                    .or_else(|| r.title.clone())
+                    .unwrap_or_else(|| crate::app::short_thread_id(&r.thread_id)),
+                status: r.status,
+                last_activity_unix_s: r.last_activity_unix_s,
+                branch: r.git_branch.clone(),
+                pending_call: r.pending_call.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// What `--watch` and `serve`'s `/statusline` compare to tell whether the line changed: the
+/// line without its timestamp, which moves every cycle.
+pub fn change_key(line: &StatusLine) -> anyhow::Result<String> {
+    serde_json::to_string(&StatusLine {
+        generated_at_unix_s: 0,
+        ..line.clone()
+    })
+    .context("serialize status line")
+}
+
+fn in_repo(row: &SessionRow, repo: &Path) -> bool {
+    [row.repo_root.as_ref(), row.cwd.as_ref()]
+        .into_iter()
+        .flatten()
+        .any(|p| Path::new(p).starts_with(repo))
+}
+
+/// Print one status line, or with `watch` keep polling and print a line whenever it changes
+/// (editor plugins read this like a long poll: each line means "something changed").
+pub fn run_status_line(
+    mut collector: Collector,
+    hosts: &[String],
+    repo: Option<PathBuf>,
+    watch: bool,
+    refresh_ms: u64,
+) -> anyhow::Result<()> {
+    // Codex reports canonical cwds, so resolve symlinks/relative paths the editor passes.
+    let repo = repo.map(|p| p.canonicalize().unwrap_or(p));
+    let refresh = Duration::from_millis(refresh_ms.max(100));
+    let mut stdout = std::io::stdout();
+    let mut last: Option<String> = None;
+
+    loop {
+        let snap = match collector.collect(hosts) {
+            Ok(snap) => snap,
+            Err(e) if watch => {
+                // Keep the stream alive; the next cycle usually recovers.
+                eprintln!("codex-ps: {e:#}");
+                std::thread::sleep(refresh);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let line = build_status_line(&snap, repo.as_deref());
+        let key = change_key(&line)?;
+        if last.as_deref() != Some(key.as_str()) {
+            let out = serde_json::to_string(&line).context("serialize status line")?;
+            if let Err(e) = writeln!(stdout, "{out}").and_then(|_| stdout.flush()) {
+                if e.kind() == std::io::ErrorKind::BrokenPipe {
+                    return Ok(());
+                }
+                return Err(e.into());
+            }
+            last = Some(key);
+        }
+        if !watch {
+            return Ok(());
+        }
+        std::thread::sleep(refresh);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(thread_id: &str, cwd: &str, status: SessionStatus) -> SessionRow {
+        SessionRow {
+            host: "local".into(),
+            thread_id: thread_id.into(),
+            cwd: Some(cwd.into()),
+            status,
+            last_activity_unix_s: Some(100),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn filters_to_repo_and_puts_blocked_first() {
+        let mut blocked = row("b", "/src/app/crates/core", SessionStatus::Waiting);
+        blocked.name = Some("fix tests".into());
+        let snap = Snapshot {
+            generation: 1,
+            generated_at_unix_s: 200,
+            host: "local".into(),
+            sessions: vec![
+                row("a", "/src/app", SessionStatus::Working),
+                blocked,
+                row("c", "/src/application", SessionStatus::Waiting),
+            ],
+            ..Default::default()
+        };
+
+        let line = build_status_line(&snap, Some(Path::new("/src/app")));
+        assert_eq!((line.working, line.waiting), (1, 1));
+        assert_eq!(line.text, "✋1 ⚒1");
+        assert_eq!(line.sessions[0].label, "fix tests");
+        assert_eq!(build_status_line(&snap, None).sessions.len(), 3);
+    }
+}
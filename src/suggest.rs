@@ -0,0 +1,70 @@
+use std::path::Path;
+
+/// Most keywords taken from the prompt.
+const MAX_KEYWORDS: usize = 4;
+
+/// Filler that says nothing about the task.
+const STOPWORDS: &[&str] = &[
+    "about", "add", "all", "also", "and", "any", "are", "based", "but", "can", "could", "does",
+    "doing", "for", "from", "get", "have", "hello", "help", "here", "hey", "how", "into", "its",
+    "just", "let", "lets", "look", "make", "me", "need", "not", "now", "our", "please", "should",
+    "some", "sure", "that", "the", "then", "there", "this", "want", "what", "when", "where",
+    "which", "why", "will", "with", "would", "you", "your",
+];
+
+/// Propose a name from a session's first prompt and repo: `codex-ps: flaky login test`.
+/// `None` when the prompt has nothing usable.
+pub fn suggest_name(prompt: &str, repo_root: Option<&str>) -> Option<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for raw in prompt.split_whitespace() {
+        // Paths, URLs and flags are too specific to read well in a name.
+        if raw.contains('/') || raw.contains('=') || raw.starts_with('-') {
+            continue;
+        }
+        let word = raw
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if word.chars().count() < 3
+            || STOPWORDS.contains(&word.as_str())
+            || keywords.contains(&word)
+        {
+            continue;
+        }
+        keywords.push(word);
+        if keywords.len() == MAX_KEYWORDS {
+            break;
+        }
+    }
+    if keywords.is_empty() {
+        return None;
+    }
+    let repo = repo_root
+        .and_then(|r| Path::new(r).file_name())
+        .map(|n| n.to_string_lossy().to_string());
+    Some(match repo {
+        Some(repo) => format!("{repo}: {}", keywords.join(" ")),
+        None => keywords.join(" "),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_leading_keywords_and_prefixes_repo() {
+        assert_eq!(
+            suggest_name(
+                "Can you fix the flaky Login test in src/auth.rs? It fails on CI --verbose",
+                Some("/src/codex-ps"),
+            )
+            .as_deref(),
+            Some("codex-ps: fix flaky login test")
+        );
+        assert_eq!(
+            suggest_name("Refactor the parser", None).as_deref(),
+            Some("refactor parser")
+        );
+        assert_eq!(suggest_name("hi, can you help?", Some("/src/x")), None);
+    }
+}
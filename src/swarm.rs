@@ -0,0 +1,207 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::changes::SnapshotChanges;
+use crate::model::SessionId;
+use crate::model::{SessionRow, SessionStatus, Snapshot};
+
+/// Spawn rate is reported over this trailing window.
+pub const SPAWN_RATE_WINDOW_SECS: i64 = 5 * 60;
+
+/// One root session's subagent fleet.
+#[derive(Clone, Debug)]
+pub struct SwarmRow {
+    pub root: SessionRow,
+    /// Live subagents at any depth, oldest first.
+    pub subagents: Vec<SessionRow>,
+    pub working: usize,
+    pub waiting: usize,
+    pub unknown: usize,
+    /// Subagents first seen within `SPAWN_RATE_WINDOW_SECS`.
+    pub recent_spawns: usize,
+    /// Subagents that have ended since codex-ps started watching.
+    pub completed: usize,
+    pub avg_depth: Option<f64>,
+    /// Longest-running subagent that is still working (or unknown): `(thread_id, since)`.
+    pub oldest_pending: Option<(String, i64)>,
+}
+
+/// Remembers when subagents appeared and how many finished, per root.
+#[derive(Clone, Debug, Default)]
+pub struct SwarmTracker {
+    first_seen: HashMap<SessionId, i64>,
+    roots: HashMap<SessionId, SessionId>,
+    spawns: HashMap<SessionId, VecDeque<i64>>,
+    completed: HashMap<SessionId, usize>,
+}
+
+impl SwarmTracker {
+    pub fn update(&mut self, snap: &Snapshot, changes: &SnapshotChanges, now_unix_s: i64) {
+        let by_id = index(&snap.sessions);
+        for row in snap.sessions.iter().filter(|r| is_subagent(r)) {
+            let key = row.id();
+            let root = root_of(row, &by_id);
+            self.roots.insert(key.clone(), root.id());
+            if !self.first_seen.contains_key(&key) {
+                self.first_seen.insert(key.clone(), now_unix_s);
+                if !changes.initial {
+                    self.spawns
+                        .entry(root.id())
+                        .or_default()
+                        .push_back(now_unix_s);
+                }
+            }
+        }
+
+        for row in changes.removed.iter().filter(|r| is_subagent(r)) {
+            let key = row.id();
+            self.first_seen.remove(&key);
+            if let Some(root) = self.roots.remove(&key) {
+                *self.completed.entry(root).or_default() += 1;
+            }
+        }
+
+        for spawns in self.spawns.values_mut() {
+            while spawns
+                .front()
+                .is_some_and(|t| now_unix_s - t > SPAWN_RATE_WINDOW_SECS)
+            {
+                spawns.pop_front();
+            }
+        }
+    }
+
+    /// Roots that have (or had) subagents, busiest first.
+    pub fn rows(&self, sessions: &[SessionRow]) -> Vec<SwarmRow> {
+        let by_id = index(sessions);
+        let mut fleets: HashMap<SessionId, Vec<&SessionRow>> = HashMap::new();
+        for row in sessions {
+            if is_subagent(row) {
+                let root = root_of(row, &by_id);
+                fleets.entry(root.id()).or_default().push(row);
+            } else {
+                fleets.entry(row.id()).or_default();
+            }
+        }
+
+        let mut out = Vec::new();
+        for (root_key, subs) in fleets {
+            let completed = self.completed.get(&root_key).copied().unwrap_or(0);
+            if subs.is_empty() && completed == 0 {
+                continue;
+            }
+            let Some(root) = by_id.get(&root_key) else {
+                continue;
+            };
+
+            let since = |r: &SessionRow| {
+                self.first_seen
+                    .get(&r.id())
+                    .copied()
+                    .or(r.last_activity_unix_s)
+                    .unwrap_or(i64::MAX)
+            };
+            let mut subagents: Vec<SessionRow> = subs.into_iter().cloned().collect();
+            subagents.sort_by_key(|r| (since(r), r.thread_id.clone()));
+
+            let count = |s: SessionStatus| subagents.iter().filter(|r| r.status == s).count();
+            let depths: Vec<i32> = subagents.iter().filter_map(|r| r.subagent_depth).collect();
+            let avg_depth = (!depths.is_empty())
+                .then(|| depths.iter().map(|d| f64::from(*d)).sum::<f64>() / depths.len() as f64);
+            let oldest_pending = subagents
+                .iter()
+                .find(|r| r.status != SessionStatus::Waiting)
+                .map(|r| (r.thread_id.clone(), since(r)));
+
+            out.push(SwarmRow {
+                root: (*root).clone(),
+                working: count(SessionStatus::Working),
+                waiting: count(SessionStatus::Waiting),
+                unknown: count(SessionStatus::Unknown),
+                recent_spawns: self.spawns.get(&root_key).map_or(0, |s| s.len()),
+                completed,
+                avg_depth,
+                oldest_pending,
+                subagents,
+            });
+        }
+
+        out.sort_by(|a, b| {
+            b.subagents
+                .len()
+                .cmp(&a.subagents.len())
+                .then_with(|| a.root.host.cmp(&b.root.host))
+                .then_with(|| a.root.thread_id.cmp(&b.root.thread_id))
+        });
+        out
+    }
+}
+
+fn is_subagent(row: &SessionRow) -> bool {
+    row.subagent_parent_thread_id.is_some()
+}
+
+fn index(sessions: &[SessionRow]) -> HashMap<SessionId, &SessionRow> {
+    sessions.iter().map(|r| (r.id(), r)).collect()
+}
+
+/// Follow parent links (nested subagents included) to the top-most session we can see.
+fn root_of<'a>(row: &'a SessionRow, by_id: &HashMap<SessionId, &'a SessionRow>) -> &'a SessionRow {
+    let mut cur = row;
+    for _ in 0..by_id.len() {
+        let Some(parent) = cur.subagent_parent_thread_id.as_ref() else {
+            break;
+        };
+        let key = SessionId::new(cur.host.clone(), parent.clone());
+        match by_id.get(&key) {
+            Some(p) => cur = p,
+            None => break,
+        }
+    }
+    cur
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::changes::ChangeTracker;
+
+    fn row(thread_id: &str, parent: Option<&str>, depth: i32, status: SessionStatus) -> SessionRow {
+        SessionRow {
+            host: "local".into(),
+            thread_id: thread_id.into(),
+            subagent_parent_thread_id: parent.map(str::to_string),
+            subagent_depth: Some(depth),
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn summarizes_nested_fleet_spawns_and_completions() {
+        let mut changes = ChangeTracker::default();
+        let mut swarm = SwarmTracker::default();
+
+        let s1 = Snapshot::of(vec![
+            row("root", None, 0, SessionStatus::Working),
+            row("s1", Some("root"), 1, SessionStatus::Working),
+        ]);
+        swarm.update(&s1, &changes.update(&s1), 100);
+
+        let s2 = Snapshot::of(vec![
+            row("root", None, 0, SessionStatus::Working),
+            row("s2", Some("root"), 1, SessionStatus::Waiting),
+            row("s3", Some("s2"), 2, SessionStatus::Working),
+        ]);
+        swarm.update(&s2, &changes.update(&s2), 160);
+
+        let rows = swarm.rows(&s2.sessions);
+        assert_eq!(rows.len(), 1);
+        let r = &rows[0];
+        assert_eq!(r.root.thread_id, "root");
+        assert_eq!(r.subagents.len(), 2);
+        assert_eq!(r.recent_spawns, 2);
+        assert_eq!(r.completed, 1);
+        assert_eq!(r.avg_depth, Some(1.5));
+        assert_eq!(r.oldest_pending, Some(("s3".to_string(), 160)));
+    }
+}
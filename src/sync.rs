@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::annotations::AnnotationRecord;
+use crate::collector::Collector;
+use crate::names::NameRecord;
+
+/// Everything in the names and annotations stores, latest record per session
+/// (`codex-ps labels export`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LabelSet {
+    #[serde(default)]
+    pub names: Vec<NameRecord>,
+    #[serde(default)]
+    pub annotations: Vec<AnnotationRecord>,
+}
+
+impl LabelSet {
+    /// Rewrite host keys through `map` (`(from, to)` pairs), dropping records for any other
+    /// host: each machine keys its own sessions as `local` and everyone else's by alias.
+    pub fn rekey(self, map: &[(&str, &str)]) -> Self {
+        let to = |host: &str| {
+            map.iter()
+                .find(|(from, _)| *from == host)
+                .map(|(_, to)| to.to_string())
+        };
+        Self {
+            names: self
+                .names
+                .into_iter()
+                .filter_map(|r| {
+                    Some(NameRecord {
+                        host: to(&r.host)?,
+                        ..r
+                    })
+                })
+                .collect(),
+            annotations: self
+                .annotations
+                .into_iter()
+                .filter_map(|r| {
+                    Some(AnnotationRecord {
+                        host: to(&r.host)?,
+                        ..r
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Records that are newer than `other`'s for the same session (what is worth sending).
+    pub fn newer_than(self, other: &LabelSet) -> Self {
+        let stamp = |host: &str, thread_id: &str, at: Option<i64>| {
+            ((host.to_string(), thread_id.to_string()), at.unwrap_or(0))
+        };
+        let names: HashMap<_, _> = other
+            .names
+            .iter()
+            .map(|r| stamp(&r.host, &r.thread_id, r.at_unix_ms))
+            .collect();
+        let annotations: HashMap<_, _> = other
+            .annotations
+            .iter()
+            .map(|r| stamp(&r.host, &r.thread_id, r.at_unix_ms))
+            .collect();
+        let newer = |seen: &HashMap<(String, String), i64>, host: &str, thread_id: &str, at| {
+            let (key, at) = stamp(host, thread_id, at);
+            seen.get(&key).is_none_or(|have| *have < at)
+        };
+        Self {
+            names: self
+                .names
+                .into_iter()
+                .filter(|r| newer(&names, &r.host, &r.thread_id, r.at_unix_ms))
+                .collect(),
+            annotations: self
+                .annotations
+                .into_iter()
+                .filter(|r| newer(&annotations, &r.host, &r.thread_id, r.at_unix_ms))
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len() + self.annotations.len()
+    }
+}
+
+/// `codex-ps sync`: exchange names, tags and notes with each remote host, newest record per
+/// session winning on both sides. Only sessions running on that host are exchanged, plus this
+/// machine's own sessions when `alias` says what the remote calls it.
+pub fn run_sync(
+    mut collector: Collector,
+    hosts: &[String],
+    alias: Option<&str>,
+) -> anyhow::Result<()> {
+    let remotes: Vec<&String> = hosts.iter().filter(|h| *h != "local").collect();
+    if remotes.is_empty() {
+        anyhow::bail!("sync needs at least one remote --host");
+    }
+    let mut failed = 0;
+    for host in remotes {
+        match sync_host(&mut collector, host, alias) {
+            Ok((pulled, pushed)) => println!("{host}: pulled {pulled}, pushed {pushed}"),
+            Err(e) => {
+                failed += 1;
+                eprintln!("codex-ps: sync {host}: {e:#}");
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("sync failed for {failed} host(s)");
+    }
+    Ok(())
+}
+
+fn sync_host(
+    collector: &mut Collector,
+    host: &str,
+    alias: Option<&str>,
+) -> anyhow::Result<(usize, usize)> {
+    collector.require_feature(host, "labels")?;
+    let out = collector.run_on_host(host, &["labels", "export"])?;
+    let remote: LabelSet =
+        serde_json::from_str(out.trim()).with_context(|| format!("parse labels from {host}"))?;
+
+    // Their `local` is our `host`; our `local` is their `alias`.
+    let mut pull_map = vec![("local", host)];
+    let mut push_map = vec![(host, "local")];
+    if let Some(alias) = alias {
+        pull_map.push((alias, "local"));
+        push_map.push(("local", alias));
+    }
+    let remote = remote.rekey(&pull_map);
+    let pulled = collector.import_labels(remote.clone())?;
+
+    let push = collector
+        .export_labels()?
+        .newer_than(&remote)
+        .rekey(&push_map);
+    let pushed = push.len();
+    if pushed > 0 {
+        let json = serde_json::to_string(&push).context("serialize labels")?;
+        collector.run_on_host(host, &["labels", "import", &json])?;
+    }
+    Ok((pulled, pushed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::annotations::Annotation;
+
+    fn name(host: &str, thread_id: &str, name: Option<&str>, at: i64) -> NameRecord {
+        NameRecord {
+            host: host.into(),
+            thread_id: thread_id.into(),
+            name: name.map(str::to_string),
+            at_unix_ms: Some(at),
+        }
+    }
+
+    #[test]
+    fn rekeys_between_viewpoints_and_keeps_only_newer_records() {
+        let remote = LabelSet {
+            names: vec![
+                name("local", "t1", Some("from studio"), 200),
+                name("laptop", "t2", None, 300),
+                name("ci", "t3", Some("someone else's"), 300),
+            ],
+            annotations: vec![AnnotationRecord {
+                host: "local".into(),
+                thread_id: "t1".into(),
+                annotation: Annotation {
+                    tags: vec!["infra".into()],
+                    note: None,
+                    watched: false,
+                },
+                at_unix_ms: Some(50),
+            }],
+        };
+        let pulled = remote.rekey(&[("local", "studio"), ("laptop", "local")]);
+        assert_eq!(
+            pulled.names,
+            vec![
+                name("studio", "t1", Some("from studio"), 200),
+                name("local", "t2", None, 300),
+            ]
+        );
+        assert_eq!(pulled.annotations[0].host, "studio");
+
+        let ours = LabelSet {
+            names: vec![
+                name("studio", "t1", Some("older"), 100),
+                name("local", "t2", Some("newer"), 400),
+            ],
+            annotations: Vec::new(),
+        };
+        let push = ours
+            .newer_than(&pulled)
+            .rekey(&[("studio", "local"), ("local", "laptop")]);
+        assert_eq!(push.names, vec![name("laptop", "t2", Some("newer"), 400)]);
+    }
+}
@@ -0,0 +1,185 @@
+use std::io::{IsTerminal, Write};
+
+use crate::app::{
+    RowFormat, TuiOptions, fit_session_columns, group_sessions_for_display, session_cells,
+};
+use crate::collector::Collector;
+use crate::model::Snapshot;
+
+/// `--format table|csv|tsv`: one snapshot as plain text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextFormat {
+    /// The TUI's columns and truncation, space-aligned.
+    Table,
+    /// Every column, untruncated, RFC 4180 quoting.
+    Csv,
+    /// Every column, untruncated; tabs and newlines inside cells become spaces.
+    Tsv,
+}
+
+/// Root sessions as the TUI lists them. `width` picks the table's columns like the TUI does;
+/// CSV/TSV always carry every column.
+pub fn format_snapshot(
+    snap: &Snapshot,
+    format: TextFormat,
+    opts: &TuiOptions,
+    width: u16,
+) -> String {
+    let rows = group_sessions_for_display(&snap.sessions, opts.debug);
+    let fmt = RowFormat {
+        theme: opts.theme,
+        debug: opts.debug,
+        time_display: opts.time_display,
+        truncate: opts.truncate,
+        tag_styles: &opts.tag_styles,
+    };
+    let table = format == TextFormat::Table;
+    let columns = fit_session_columns(
+        if table { width } else { u16::MAX },
+        opts.debug,
+        opts.time_display,
+        &opts.columns,
+    );
+
+    let mut lines: Vec<Vec<String>> = vec![columns.iter().map(|c| c.label().to_string()).collect()];
+    for row in &rows {
+        lines.push(
+            session_cells(row, &columns, fmt, false, table)
+                .into_iter()
+                .map(|(text, _)| text)
+                .collect(),
+        );
+    }
+
+    let mut out = String::new();
+    match format {
+        TextFormat::Table => {
+            let widths: Vec<usize> = (0..columns.len())
+                .map(|i| {
+                    lines
+                        .iter()
+                        .map(|l| l[i].chars().count())
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            for line in &lines {
+                let cells: Vec<String> = line
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, w)| format!("{cell:<w$}"))
+                    .collect();
+                out.push_str(cells.join(" ").trim_end());
+                out.push('\n');
+            }
+        }
+        TextFormat::Csv | TextFormat::Tsv => {
+            let sep = if format == TextFormat::Csv { "," } else { "\t" };
+            for line in &lines {
+                let cells: Vec<String> = line
+                    .iter()
+                    .map(|cell| match format {
+                        TextFormat::Csv => csv_field(cell),
+                        _ => cell.replace(['\t', '\n', '\r'], " "),
+                    })
+                    .collect();
+                out.push_str(&cells.join(sep));
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Print one snapshot as `format`. On a terminal the table fits its width; piped, it keeps
+/// every column.
+pub fn run_table(
+    mut collector: Collector,
+    hosts: &[String],
+    format: TextFormat,
+    opts: &TuiOptions,
+) -> anyhow::Result<()> {
+    let snap = collector.collect(hosts)?;
+    let mut stdout = std::io::stdout();
+    let width = if stdout.is_terminal() {
+        crossterm::terminal::size().map_or(u16::MAX, |(w, _)| w)
+    } else {
+        u16::MAX
+    };
+    if let Err(e) = stdout.write_all(format_snapshot(&snap, format, opts, width).as_bytes()) {
+        if e.kind() != std::io::ErrorKind::BrokenPipe {
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SessionRow;
+    use crate::timefmt::TimeDisplay;
+
+    #[test]
+    fn csv_quotes_cells_and_table_aligns_columns() {
+        let row: SessionRow = serde_json::from_value(serde_json::json!({
+            "host": "local",
+            "thread_id": "019c2590-aaaa-bbbb",
+            "pids": [42],
+            "status": "waiting",
+            "name": "fix \"auth\", again",
+            "cwd": "/src/app",
+        }))
+        .expect("row");
+        let snap = Snapshot {
+            generation: 1,
+            host: "local".into(),
+            sessions: vec![row],
+            ..Default::default()
+        };
+        let opts = TuiOptions {
+            refresh_ms: 1000,
+            debug: false,
+            time_display: TimeDisplay::Relative,
+            set_title: false,
+            theme: Default::default(),
+            truncate: Default::default(),
+            tag_styles: Default::default(),
+            guard: Default::default(),
+            schedule: Default::default(),
+            columns: Default::default(),
+            resume_command: None,
+            group_by_host: false,
+        };
+
+        let csv = format_snapshot(&snap, TextFormat::Csv, &opts, 0);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("HOST,PID,TID,SUB,CMT,STATE,AGE,RATE,NAME,TITLE,BRANCH,PWD")
+        );
+        assert!(
+            lines
+                .next()
+                .expect("row")
+                .contains(",IDLE,?,-,\"fix \"\"auth\"\", again\",unknown,unknown,/src/app")
+        );
+
+        let table = format_snapshot(&snap, TextFormat::Table, &opts, 200);
+        let lines: Vec<&str> = table.lines().collect();
+        let column = |line: &str, text: &str| line.find(text).map(|i| line[..i].chars().count());
+        assert_eq!(
+            column(lines[0], "STATE"),
+            column(lines[1], "IDLE"),
+            "columns line up:\n{table}"
+        );
+    }
+}
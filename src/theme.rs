@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Deserializer};
+
+use crate::model::SessionStatus;
+use crate::util::Truncation;
+
+/// `[theme]` in `config.toml`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub palette: Palette,
+    #[serde(default)]
+    pub truncate: TruncateConfig,
+    /// `[theme.tags]`: whole-row styles for tagged sessions (`prod = { bg = "red" }`).
+    #[serde(default)]
+    pub tags: BTreeMap<String, TagStyle>,
+    /// `[theme.styles]`: overrides for single elements of the palette.
+    #[serde(default)]
+    pub styles: ThemeStyles,
+}
+
+impl ThemeConfig {
+    pub fn theme(&self) -> Theme {
+        Theme {
+            palette: self.palette,
+            styles: self.styles,
+        }
+    }
+}
+
+/// `[theme.styles]`: each entry replaces the palette's style for that element.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeStyles {
+    #[serde(default)]
+    pub working: Option<TagStyle>,
+    #[serde(default)]
+    pub waiting: Option<TagStyle>,
+    #[serde(default)]
+    pub unknown: Option<TagStyle>,
+    #[serde(default)]
+    pub done: Option<TagStyle>,
+    /// Table headers and the `codex-ps` title.
+    #[serde(default)]
+    pub header: Option<TagStyle>,
+    /// The selected row.
+    #[serde(default)]
+    pub highlight: Option<TagStyle>,
+}
+
+/// How the TUI is drawn: a named palette plus `[theme.styles]` overrides.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    pub palette: Palette,
+    pub styles: ThemeStyles,
+}
+
+impl Theme {
+    /// STATE cell text and style (see `Palette::state`).
+    pub fn state(self, status: SessionStatus, finished: bool) -> (&'static str, Style) {
+        let (label, style) = self.palette.state(status, finished);
+        let custom = match status {
+            _ if finished => self.styles.done,
+            SessionStatus::Working => self.styles.working,
+            SessionStatus::Waiting => self.styles.waiting,
+            SessionStatus::Unknown => self.styles.unknown,
+        };
+        (label, custom.map_or(style, TagStyle::style))
+    }
+
+    pub fn header(self) -> Style {
+        self.styles.header.map_or_else(
+            || match self.palette {
+                Palette::Monochrome => Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::UNDERLINED),
+                _ => Style::default().add_modifier(Modifier::BOLD),
+            },
+            TagStyle::style,
+        )
+    }
+
+    pub fn highlight(self) -> Style {
+        self.styles.highlight.map_or_else(
+            || Style::default().add_modifier(Modifier::REVERSED),
+            TagStyle::style,
+        )
+    }
+
+    /// A text accent written for a dark background (`Color::Yellow` for warnings,
+    /// `Color::DarkGray` for hints), as this palette draws it.
+    pub fn fg(self, color: Color) -> Style {
+        match self.palette {
+            Palette::Default | Palette::ColorBlind => Style::default().fg(color),
+            Palette::Light => Style::default().fg(match color {
+                Color::Yellow => LIGHT_AMBER,
+                Color::Green => LIGHT_GREEN,
+                Color::Red => LIGHT_RED,
+                Color::Cyan => LIGHT_BLUE,
+                Color::White => Color::Black,
+                c => c,
+            }),
+            Palette::Monochrome => match color {
+                Color::DarkGray | Color::Gray => Style::default().add_modifier(Modifier::DIM),
+                Color::Red | Color::Yellow => Style::default().add_modifier(Modifier::BOLD),
+                _ => Style::default(),
+            },
+        }
+    }
+}
+
+/// Row style for one tag. Colors are names (`red`, `light-blue`), `#rrggbb` or 0-255.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TagStyle {
+    #[serde(default, deserialize_with = "color")]
+    pub fg: Option<Color>,
+    #[serde(default, deserialize_with = "color")]
+    pub bg: Option<Color>,
+    #[serde(default)]
+    pub bold: bool,
+}
+
+impl TagStyle {
+    pub fn style(self) -> Style {
+        let mut style = Style::default();
+        if let Some(fg) = self.fg {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = self.bg {
+            style = style.bg(bg);
+        }
+        if self.bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        style
+    }
+}
+
+fn color<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Color>, D::Error> {
+    let s = String::deserialize(d)?;
+    Color::from_str(&s)
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("unknown color {s:?}")))
+}
+
+/// Style of the first of `tags` that has one configured (keys may be written with `#`).
+pub fn tag_style(styles: &BTreeMap<String, TagStyle>, tags: &[String]) -> Option<Style> {
+    tags.iter().find_map(|tag| {
+        styles
+            .iter()
+            .find(|(key, _)| key.trim_start_matches('#') == tag)
+            .map(|(_, s)| s.style())
+    })
+}
+
+/// `[theme.truncate]`: how each long session column is shortened (default `middle`, `path`
+/// for PWD).
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TruncateConfig {
+    #[serde(default)]
+    pub name: Truncation,
+    #[serde(default)]
+    pub title: Truncation,
+    #[serde(default)]
+    pub branch: Truncation,
+    #[serde(default = "path_truncation")]
+    pub pwd: Truncation,
+    #[serde(default)]
+    pub why: Truncation,
+}
+
+/// How session states are drawn in the TUI.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    /// Green/yellow/red text labels.
+    #[default]
+    Default,
+    /// A distinct glyph per state plus blue/orange hues (Okabe–Ito), so states never rely
+    /// on telling red from green.
+    ColorBlind,
+    /// Darker hues that stay readable on light terminal backgrounds.
+    Light,
+    /// No colors: states and accents use bold, dim and underline only.
+    Monochrome,
+}
+
+const CB_BLUE: Color = Color::Rgb(0, 114, 178);
+const CB_ORANGE: Color = Color::Rgb(230, 159, 0);
+const CB_SKY: Color = Color::Rgb(86, 180, 233);
+const LIGHT_GREEN: Color = Color::Rgb(0, 110, 0);
+const LIGHT_AMBER: Color = Color::Rgb(150, 90, 0);
+const LIGHT_RED: Color = Color::Rgb(170, 0, 0);
+const LIGHT_BLUE: Color = Color::Rgb(0, 80, 170);
+
+impl Default for TruncateConfig {
+    fn default() -> Self {
+        Self {
+            name: Truncation::Middle,
+            title: Truncation::Middle,
+            branch: Truncation::Middle,
+            pwd: Truncation::Path,
+            why: Truncation::Middle,
+        }
+    }
+}
+
+fn path_truncation() -> Truncation {
+    Truncation::Path
+}
+
+impl Palette {
+    /// STATE cell text and style; `finished` is the transient DONE state. Labels fit the
+    /// 5-column STATE width.
+    pub fn state(self, status: SessionStatus, finished: bool) -> (&'static str, Style) {
+        match self {
+            Palette::Default => match status {
+                _ if finished => (
+                    "DONE",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                SessionStatus::Working => ("WORK", Style::default().fg(Color::Green)),
+                SessionStatus::Waiting => ("IDLE", Style::default().fg(Color::Yellow)),
+                SessionStatus::Unknown => ("UNK", Style::default().fg(Color::Red)),
+            },
+            Palette::ColorBlind => match status {
+                _ if finished => (
+                    "✓DONE",
+                    Style::default().fg(CB_SKY).add_modifier(Modifier::BOLD),
+                ),
+                SessionStatus::Working => ("▶WORK", Style::default().fg(CB_BLUE)),
+                SessionStatus::Waiting => (
+                    "■IDLE",
+                    Style::default().fg(CB_ORANGE).add_modifier(Modifier::BOLD),
+                ),
+                SessionStatus::Unknown => ("?UNK", Style::default().add_modifier(Modifier::DIM)),
+            },
+            Palette::Light => match status {
+                _ if finished => (
+                    "DONE",
+                    Style::default().fg(LIGHT_BLUE).add_modifier(Modifier::BOLD),
+                ),
+                SessionStatus::Working => ("WORK", Style::default().fg(LIGHT_GREEN)),
+                SessionStatus::Waiting => (
+                    "IDLE",
+                    Style::default()
+                        .fg(LIGHT_AMBER)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                SessionStatus::Unknown => ("UNK", Style::default().fg(LIGHT_RED)),
+            },
+            Palette::Monochrome => match status {
+                _ if finished => ("DONE", Style::default().add_modifier(Modifier::BOLD)),
+                SessionStatus::Working => ("WORK", Style::default()),
+                SessionStatus::Waiting => (
+                    "IDLE",
+                    Style::default()
+                        .add_modifier(Modifier::BOLD)
+                        .add_modifier(Modifier::UNDERLINED),
+                ),
+                SessionStatus::Unknown => ("UNK", Style::default().add_modifier(Modifier::DIM)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_blind_states_differ_by_glyph() {
+        let cfg: ThemeConfig = toml::from_str("palette = \"color-blind\"").expect("parse");
+        assert_eq!(cfg.palette, Palette::ColorBlind);
+
+        let glyphs: Vec<char> = [
+            SessionStatus::Working,
+            SessionStatus::Waiting,
+            SessionStatus::Unknown,
+        ]
+        .into_iter()
+        .map(|s| cfg.palette.state(s, false).0.chars().next().expect("label"))
+        .collect();
+        assert_eq!(glyphs, vec!['▶', '■', '?']);
+        assert_eq!(cfg.palette.state(SessionStatus::Working, true).0, "✓DONE");
+    }
+
+    #[test]
+    fn tag_styles_parse_and_match_first_configured_tag() {
+        let cfg: ThemeConfig = toml::from_str(
+            r##"
+[tags]
+"#prod" = { bg = "red", fg = "white", bold = true }
+wip = { fg = "#808080" }
+"##,
+        )
+        .expect("parse");
+        let tags = |t: &[&str]| t.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            tag_style(&cfg.tags, &tags(&["infra", "prod", "wip"])),
+            Some(
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD)
+            )
+        );
+        assert_eq!(
+            tag_style(&cfg.tags, &tags(&["wip"])),
+            Some(Style::default().fg(Color::Rgb(128, 128, 128)))
+        );
+        assert_eq!(tag_style(&cfg.tags, &tags(&["infra"])), None);
+        assert!(toml::from_str::<ThemeConfig>("[tags]\nx = { fg = \"reddish\" }").is_err());
+    }
+
+    #[test]
+    fn monochrome_and_style_overrides() {
+        let cfg: ThemeConfig = toml::from_str(
+            r##"
+palette = "monochrome"
+[styles]
+working = { fg = "blue" }
+highlight = { bg = "#303030" }
+"##,
+        )
+        .expect("parse");
+        let theme = cfg.theme();
+        assert_eq!(
+            theme.state(SessionStatus::Working, false),
+            ("WORK", Style::default().fg(Color::Blue))
+        );
+        let (_, idle) = theme.state(SessionStatus::Waiting, false);
+        assert_eq!(idle.fg, None);
+        assert_eq!(theme.fg(Color::Yellow).fg, None);
+        assert_eq!(
+            theme.highlight(),
+            Style::default().bg(Color::Rgb(48, 48, 48))
+        );
+        assert!(theme.header().add_modifier.contains(Modifier::UNDERLINED));
+
+        let light = Theme {
+            palette: Palette::Light,
+            ..Theme::default()
+        };
+        assert_eq!(light.fg(Color::White), Style::default().fg(Color::Black));
+        assert!(toml::from_str::<ThemeConfig>("[styles]\nselected = { bold = true }").is_err());
+    }
+}
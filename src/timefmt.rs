@@ -0,0 +1,95 @@
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+
+/// How AGE/"updated" timestamps are rendered in the TUI.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimeDisplay {
+    /// `12s`, `5m`, `3h` ago.
+    #[default]
+    Relative,
+    /// Absolute local wall-clock time (24h).
+    Local,
+    /// Absolute UTC time (24h, `Z` suffix) — unambiguous in shared screenshots/logs.
+    Utc,
+}
+
+impl TimeDisplay {
+    /// Column width needed for `format_timestamp` output in this mode.
+    pub fn column_width(self) -> u16 {
+        match self {
+            TimeDisplay::Relative => 6,
+            TimeDisplay::Local => 11,
+            TimeDisplay::Utc => 12,
+        }
+    }
+}
+
+pub fn format_age(ts: i64, now: i64) -> String {
+    let delta = now.saturating_sub(ts);
+    if delta < 60 {
+        format!("{delta}s")
+    } else if delta < 3600 {
+        format!("{}m", delta / 60)
+    } else {
+        format!("{}h", delta / 3600)
+    }
+}
+
+/// Cumulative durations: `45s`, `12m`, `1h05m`.
+pub fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Same-day times show `HH:MM:SS`; older ones `MM-DD HH:MM` (both 24h).
+pub fn format_timestamp(ts: i64, now: i64, mode: TimeDisplay) -> String {
+    let Some(utc) = DateTime::<Utc>::from_timestamp(ts, 0) else {
+        return "?".into();
+    };
+    let now_utc = DateTime::<Utc>::from_timestamp(now, 0).unwrap_or(utc);
+    match mode {
+        TimeDisplay::Relative => format_age(ts, now),
+        TimeDisplay::Local => {
+            let t = utc.with_timezone(&Local);
+            if t.date_naive() == now_utc.with_timezone(&Local).date_naive() {
+                t.format("%H:%M:%S").to_string()
+            } else {
+                t.format("%m-%d %H:%M").to_string()
+            }
+        }
+        TimeDisplay::Utc => {
+            if utc.date_naive() == now_utc.date_naive() {
+                utc.format("%H:%M:%SZ").to_string()
+            } else {
+                utc.format("%m-%d %H:%MZ").to_string()
+            }
+        }
+    }
+}
+
+/// RFC 3339 / ISO 8601 UTC (`2026-02-03T16:12:22Z`) for JSON exports.
+pub fn to_iso8601_utc(ts: i64) -> Option<String> {
+    DateTime::<Utc>::from_timestamp(ts, 0).map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_relative_utc_and_iso() {
+        let ts = 1_770_135_142; // 2026-02-03T16:12:22Z
+        assert_eq!(format_timestamp(ts, ts + 90, TimeDisplay::Relative), "1m");
+        assert_eq!(format_timestamp(ts, ts + 90, TimeDisplay::Utc), "16:12:22Z");
+        assert_eq!(
+            format_timestamp(ts, ts + 2 * 86_400, TimeDisplay::Utc),
+            "02-03 16:12Z"
+        );
+        assert_eq!(to_iso8601_utc(ts).as_deref(), Some("2026-02-03T16:12:22Z"));
+    }
+}
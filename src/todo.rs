@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use crate::app::short_thread_id;
+use crate::collector::Collector;
+use crate::model::{SessionRow, SessionStatus};
+use crate::util::message_preview;
+
+/// Render sessions waiting on the user as a Markdown checklist, e.g.
+/// `- [ ] work-studio: release triage — waiting: Ready to run cargo publish?`.
+///
+/// Subagents are left out: their parent is what needs attention.
+pub fn render_todo(sessions: &[SessionRow]) -> String {
+    let mut rows: Vec<&SessionRow> = sessions
+        .iter()
+        .filter(|r| r.status == SessionStatus::Waiting && r.subagent_parent_thread_id.is_none())
+        .collect();
+    rows.sort_by(|a, b| {
+        (&a.host, a.name.is_none(), &a.name, &a.thread_id).cmp(&(
+            &b.host,
+            b.name.is_none(),
+            &b.name,
+            &b.thread_id,
+        ))
+    });
+
+    let mut out = String::new();
+    for r in rows {
+        let label = r
+            .name
+            .clone()
+            .or_else(|| r.title.clone())
+            .unwrap_or_else(|| short_thread_id(&r.thread_id));
+        let reason = match (
+            r.pending_call.as_deref(),
+            r.final_message.as_deref().map(|m| message_preview(m, 120)),
+        ) {
+            (Some(call), _) => format!("waiting on `{call}`"),
+            (None, Some(m)) if !m.is_empty() => format!("waiting: {m}"),
+            _ => "waiting for input".to_string(),
+        };
+        let mut line = format!("- [ ] {}: {label} — {reason}", r.host_display());
+        if let Some(branch) = r.git_branch.as_deref() {
+            line.push_str(&format!(" (`{branch}`)"));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// `codex-ps todo`: print the checklist for one fresh snapshot.
+pub fn run_todo(mut collector: Collector, hosts: &[String]) -> anyhow::Result<()> {
+    let snap = collector.collect(hosts)?;
+    let text = render_todo(&snap.sessions);
+    let mut stdout = std::io::stdout();
+    if let Err(e) = stdout.write_all(text.as_bytes()) {
+        if e.kind() != std::io::ErrorKind::BrokenPipe {
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(thread_id: &str, status: SessionStatus) -> SessionRow {
+        SessionRow {
+            host: "work-studio".into(),
+            thread_id: thread_id.into(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_waiting_sessions_as_checklist() {
+        let mut named = row("a", SessionStatus::Waiting);
+        named.name = Some("release triage".into());
+        named.final_message = Some("Ready to run `cargo publish`?\n\nDetails...".into());
+        named.git_branch = Some("release".into());
+        let mut sub = row("c", SessionStatus::Waiting);
+        sub.subagent_parent_thread_id = Some("a".into());
+
+        let md = render_todo(&[row("b", SessionStatus::Working), named, sub]);
+        assert_eq!(
+            md,
+            "- [ ] work-studio: release triage — waiting: Ready to run `cargo publish`? (`release`)\n"
+        );
+    }
+}
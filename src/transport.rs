@@ -0,0 +1,232 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::util::{CancelToken, run_cmd_cancellable, shell_quote, truncate_middle};
+
+/// How we reach a remote host to run `codex-ps --json` there.
+///
+/// Transports only move bytes; snapshot parsing and host relabeling stay in the collector so
+/// every transport aggregates identically.
+pub trait HostTransport: Send {
+    /// Short, stable label (matches the `--transport` value).
+    fn name(&self) -> &'static str;
+
+    /// Run `argv` on `host` and return its raw output (non-zero exits are the caller's call).
+    /// Implementations should give up promptly once `cancel` fires.
+    fn run(&self, host: &str, argv: &[String], cancel: &CancelToken) -> anyhow::Result<Output>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TransportKind {
+    /// The system `ssh` binary (BatchMode, one connection per refresh).
+    Ssh,
+    /// `ssh` with a persistent ControlMaster socket (reuses connections across refreshes).
+    SshControlMaster,
+    /// `docker exec <container>` (host = container name/id).
+    Docker,
+    /// `kubectl exec <pod>` (host = `pod` or `namespace/pod`).
+    Kubectl,
+}
+
+pub fn host_transport(
+    kind: TransportKind,
+    ssh_bin: String,
+    timeout: Duration,
+) -> anyhow::Result<Box<dyn HostTransport>> {
+    Ok(match kind {
+        TransportKind::Ssh => Box::new(OpenSshTransport {
+            ssh_bin,
+            timeout,
+            control_dir: None,
+        }),
+        TransportKind::SshControlMaster => {
+            let dir = std::env::temp_dir().join("codex-ps-ssh");
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("create ssh control dir {}", dir.display()))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))
+                    .with_context(|| format!("chmod 700 {}", dir.display()))?;
+            }
+            Box::new(OpenSshTransport {
+                ssh_bin,
+                timeout,
+                control_dir: Some(dir),
+            })
+        }
+        TransportKind::Docker => Box::new(DockerExecTransport {
+            docker_bin: "docker".into(),
+            timeout,
+        }),
+        TransportKind::Kubectl => Box::new(KubectlExecTransport {
+            kubectl_bin: "kubectl".into(),
+            timeout,
+        }),
+    })
+}
+
+pub struct OpenSshTransport {
+    pub ssh_bin: String,
+    pub timeout: Duration,
+    /// When set, multiplex over a ControlMaster socket in this directory.
+    pub control_dir: Option<PathBuf>,
+}
+
+impl HostTransport for OpenSshTransport {
+    fn name(&self) -> &'static str {
+        if self.control_dir.is_some() {
+            "ssh-control-master"
+        } else {
+            "ssh"
+        }
+    }
+
+    fn run(&self, host: &str, argv: &[String], cancel: &CancelToken) -> anyhow::Result<Output> {
+        let mut cmd = Command::new(&self.ssh_bin);
+        cmd.args(["-o", "BatchMode=yes"]);
+        cmd.args(["-o", "ConnectTimeout=3"]);
+        if let Some(dir) = self.control_dir.as_ref() {
+            cmd.args(["-o", "ControlMaster=auto"]);
+            cmd.arg("-o")
+                .arg(format!("ControlPath={}", dir.join("%C").display()));
+            cmd.args(["-o", "ControlPersist=60s"]);
+        }
+        cmd.arg(host);
+        // ssh hands the remote shell one joined string, so quote arguments (names may have
+        // spaces); the program itself stays bare so `~/bin/codex-ps` still expands.
+        if let Some((program, args)) = argv.split_first() {
+            cmd.arg(program);
+            cmd.args(args.iter().map(|a| shell_quote(a)));
+        }
+        run_checked(cmd, self.timeout, &format!("ssh {host}"), cancel)
+    }
+}
+
+pub struct DockerExecTransport {
+    pub docker_bin: String,
+    pub timeout: Duration,
+}
+
+impl HostTransport for DockerExecTransport {
+    fn name(&self) -> &'static str {
+        "docker"
+    }
+
+    fn run(&self, host: &str, argv: &[String], cancel: &CancelToken) -> anyhow::Result<Output> {
+        let mut cmd = Command::new(&self.docker_bin);
+        cmd.arg("exec").arg(host);
+        cmd.args(argv);
+        run_checked(cmd, self.timeout, &format!("docker exec {host}"), cancel)
+    }
+}
+
+pub struct KubectlExecTransport {
+    pub kubectl_bin: String,
+    pub timeout: Duration,
+}
+
+impl HostTransport for KubectlExecTransport {
+    fn name(&self) -> &'static str {
+        "kubectl"
+    }
+
+    fn run(&self, host: &str, argv: &[String], cancel: &CancelToken) -> anyhow::Result<Output> {
+        let mut cmd = Command::new(&self.kubectl_bin);
+        cmd.arg("exec");
+        match host.split_once('/') {
+            Some((ns, pod)) => cmd.args(["-n", ns, pod]),
+            None => cmd.arg(host),
+        };
+        cmd.arg("--");
+        cmd.args(argv);
+        run_checked(cmd, self.timeout, &format!("kubectl exec {host}"), cancel)
+    }
+}
+
+/// The remote command ran but exited non-zero (distinct from "couldn't connect/spawn").
+#[derive(Debug)]
+pub struct RemoteCommandFailed {
+    pub label: String,
+    pub status: std::process::ExitStatus,
+    pub stderr: String,
+}
+
+impl std::fmt::Display for RemoteCommandFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} failed (status {}): {}",
+            self.label, self.status, self.stderr
+        )
+    }
+}
+
+impl std::error::Error for RemoteCommandFailed {}
+
+fn run_checked(
+    cmd: Command,
+    timeout: Duration,
+    label: &str,
+    cancel: &CancelToken,
+) -> anyhow::Result<Output> {
+    let out = run_cmd_cancellable(cmd, timeout, cancel).with_context(|| label.to_string())?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(RemoteCommandFailed {
+            label: label.to_string(),
+            status: out.status,
+            stderr: truncate_middle(stderr.trim(), 200),
+        }
+        .into());
+    }
+    Ok(out)
+}
+
+/// A real exit status with `code`, for tests (`ExitStatus` can't be built portably).
+#[cfg(test)]
+pub fn exit_status(code: i32) -> std::process::ExitStatus {
+    let mut cmd = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.args(["/C", &format!("exit {code}")]);
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.args(["-c", &format!("exit {code}")]);
+        c
+    };
+    cmd.status().expect("run shell")
+}
+
+/// Scripted transport for tests: per-host canned stdout or error. Every command run is
+/// recorded in `calls` as `host: argv`.
+#[cfg(test)]
+pub struct MockTransport {
+    pub responses: std::collections::HashMap<String, Result<String, String>>,
+    pub calls: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+#[cfg(test)]
+impl HostTransport for MockTransport {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn run(&self, host: &str, argv: &[String], _cancel: &CancelToken) -> anyhow::Result<Output> {
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(format!("{host}: {}", argv.join(" ")));
+        }
+        match self.responses.get(host) {
+            Some(Ok(stdout)) => Ok(Output {
+                status: exit_status(0),
+                stdout: stdout.clone().into_bytes(),
+                stderr: Vec::new(),
+            }),
+            Some(Err(e)) => Err(anyhow::anyhow!("{e}")),
+            None => anyhow::bail!("mock: unknown host {host}"),
+        }
+    }
+}
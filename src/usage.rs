@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::model::{HostError, SessionRow, SessionStatus};
+use crate::names::config_dir;
+
+/// Re-record unchanged counts this often so the report can tell "idle" from "not watching".
+const HEARTBEAT_SECS: i64 = 5 * 60;
+/// Gaps longer than this (codex-ps wasn't running, host unreachable) count as unobserved.
+const MAX_GAP_SECS: i64 = 2 * HEARTBEAT_SECS;
+
+/// One line of `usage.jsonl`: per-host session counts at a point in time.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UsageRecord {
+    pub host: String,
+    pub at_unix_s: i64,
+    pub working: usize,
+    pub sessions: usize,
+}
+
+/// Append-only log of concurrency per host (`usage.jsonl`), written when a host's counts
+/// change (plus a heartbeat), for `codex-ps report capacity`.
+#[derive(Clone, Debug)]
+pub struct UsageLog {
+    path: PathBuf,
+    last: HashMap<String, UsageRecord>,
+}
+
+impl UsageLog {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self::new_at(config_dir()?.join("usage.jsonl")))
+    }
+
+    pub fn new_at(path: PathBuf) -> Self {
+        Self {
+            path,
+            last: HashMap::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record counts for every host in `hosts` that collected cleanly this cycle.
+    pub fn record(
+        &mut self,
+        hosts: &[String],
+        sessions: &[SessionRow],
+        host_errors: &[HostError],
+        now_unix_s: i64,
+    ) -> anyhow::Result<()> {
+        let mut out = Vec::new();
+        for host in hosts {
+            if host_errors.iter().any(|e| &e.host == host) {
+                continue;
+            }
+            let rows = sessions.iter().filter(|r| &r.host == host);
+            let rec = UsageRecord {
+                host: host.clone(),
+                at_unix_s: now_unix_s,
+                working: rows
+                    .clone()
+                    .filter(|r| r.status == SessionStatus::Working)
+                    .count(),
+                sessions: rows.count(),
+            };
+            let unchanged = self.last.get(host).is_some_and(|prev| {
+                prev.working == rec.working
+                    && prev.sessions == rec.sessions
+                    && now_unix_s - prev.at_unix_s < HEARTBEAT_SECS
+            });
+            if !unchanged {
+                self.last.insert(host.clone(), rec.clone());
+                out.push(rec);
+            }
+        }
+        if out.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create dir {}", parent.display()))?;
+        }
+        let mut f = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("open for append {}", self.path.display()))?;
+        for rec in out {
+            let line = serde_json::to_string(&rec).context("serialize usage record")?;
+            writeln!(f, "{line}").context("append usage record")?;
+        }
+        Ok(())
+    }
+
+    pub fn load(&self) -> anyhow::Result<Vec<UsageRecord>> {
+        let f = match fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("open {}", self.path.display())),
+        };
+        let mut out = Vec::new();
+        for (i, line) in BufReader::new(f).lines().enumerate() {
+            let line = line.context("read line")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let rec: UsageRecord = serde_json::from_str(&line)
+                .with_context(|| format!("parse usage.jsonl line {}", i + 1))?;
+            out.push(rec);
+        }
+        Ok(out)
+    }
+}
+
+/// Peak concurrency for one host on one local day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapacityDay {
+    pub day: NaiveDate,
+    pub host: String,
+    pub peak_working: usize,
+    pub peak_at_unix_s: i64,
+    /// Sum of working sessions × time ("agent-hours").
+    pub working_hours: f64,
+    /// Hours codex-ps was actually watching this host.
+    pub observed_hours: f64,
+}
+
+/// Fold records into per-(day, host) rows. Each record's counts hold until the host's next
+/// record, capped at `MAX_GAP_SECS`.
+pub fn capacity_by_day(records: &[UsageRecord], since_unix_s: i64) -> Vec<CapacityDay> {
+    let mut by_host: HashMap<&str, Vec<&UsageRecord>> = HashMap::new();
+    for r in records.iter().filter(|r| r.at_unix_s >= since_unix_s) {
+        by_host.entry(r.host.as_str()).or_default().push(r);
+    }
+
+    let mut days: BTreeMap<(NaiveDate, String), CapacityDay> = BTreeMap::new();
+    for (host, mut recs) in by_host {
+        recs.sort_by_key(|r| r.at_unix_s);
+        for (i, r) in recs.iter().enumerate() {
+            let Some(day) = DateTime::from_timestamp(r.at_unix_s, 0)
+                .map(|t| t.with_timezone(&Local).date_naive())
+            else {
+                continue;
+            };
+            let held = recs
+                .get(i + 1)
+                .map_or(0, |next| (next.at_unix_s - r.at_unix_s).min(MAX_GAP_SECS));
+            let entry = days
+                .entry((day, host.to_string()))
+                .or_insert_with(|| CapacityDay {
+                    day,
+                    host: host.to_string(),
+                    peak_working: 0,
+                    peak_at_unix_s: r.at_unix_s,
+                    working_hours: 0.0,
+                    observed_hours: 0.0,
+                });
+            if r.working > entry.peak_working {
+                entry.peak_working = r.working;
+                entry.peak_at_unix_s = r.at_unix_s;
+            }
+            entry.working_hours += (r.working as f64) * (held as f64) / 3600.0;
+            entry.observed_hours += (held as f64) / 3600.0;
+        }
+    }
+    days.into_values().collect()
+}
+
+/// `codex-ps report capacity`: a plain table of peak concurrency per host per day.
+pub fn run_capacity_report(days: u32) -> anyhow::Result<()> {
+    let log = UsageLog::new()?;
+    let records = log.load()?;
+    let since = (Local::now() - chrono::Duration::days(i64::from(days.max(1)) - 1))
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .map_or(0, |t| t.timestamp());
+    let rows = capacity_by_day(&records, since);
+
+    if rows.is_empty() {
+        println!(
+            "No usage recorded in the last {days} day(s) ({}). Usage is logged each time \
+             codex-ps collects.",
+            log.path().display()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{:<10}  {:<18}  {:>4}  {:>5}  {:>9}  {:>8}",
+        "DATE", "HOST", "PEAK", "AT", "AGENT-H", "WATCHED"
+    );
+    for r in rows {
+        let at = DateTime::from_timestamp(r.peak_at_unix_s, 0)
+            .map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+            .unwrap_or_else(|| "?".into());
+        println!(
+            "{:<10}  {:<18}  {:>4}  {:>5}  {:>9.1}  {:>7.1}h",
+            r.day.format("%Y-%m-%d"),
+            r.host,
+            r.peak_working,
+            at,
+            r.working_hours,
+            r.observed_hours
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rec(host: &str, at: i64, working: usize) -> UsageRecord {
+        UsageRecord {
+            host: host.into(),
+            at_unix_s: at,
+            working,
+            sessions: working + 1,
+        }
+    }
+
+    #[test]
+    fn records_changes_and_folds_peaks_per_host() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut log = UsageLog::new_at(dir.path().join("usage.jsonl"));
+        let hosts = vec!["local".to_string()];
+        log.record(&hosts, &[], &[], 1000).expect("record");
+        log.record(&hosts, &[], &[], 1010).expect("record");
+        assert_eq!(log.load().expect("load").len(), 1);
+
+        let t = 1_770_120_000; // 2026-02-03, mid-day in most timezones
+        let records = vec![
+            rec("local", t, 1),
+            rec("local", t + 600, 3),
+            rec("local", t + 900, 2),
+            rec("local", t + 1200, 0),
+            rec("home", t, 2),
+        ];
+        let days = capacity_by_day(&records, 0);
+        let local = days.iter().find(|d| d.host == "local").expect("local");
+        assert_eq!(local.peak_working, 3);
+        assert_eq!(local.peak_at_unix_s, t + 600);
+        // 1×600s + 3×300s + 2×300s = 2100 agent-seconds.
+        assert!((local.working_hours - 2100.0 / 3600.0).abs() < 1e-9);
+        assert!((local.observed_hours - 1200.0 / 3600.0).abs() < 1e-9);
+        assert_eq!(days.iter().filter(|d| d.host == "home").count(), 1);
+    }
+}
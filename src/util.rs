@@ -1,12 +1,40 @@
 use std::process::Stdio;
 use std::process::{Command, Output};
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 use anyhow::Context;
 use tempfile::NamedTempFile;
 use wait_timeout::ChildExt;
 
-pub fn run_cmd_with_timeout(mut cmd: Command, timeout: Duration) -> anyhow::Result<Output> {
+/// Shared "stop what you're doing" flag (e.g. the user quit while a remote host is slow).
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// How often a running child is checked for cancellation.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub fn run_cmd_with_timeout(cmd: Command, timeout: Duration) -> anyhow::Result<Output> {
+    run_cmd_cancellable(cmd, timeout, &CancelToken::default())
+}
+
+/// Like `run_cmd_with_timeout`, but kills the child early once `cancel` fires.
+pub fn run_cmd_cancellable(
+    mut cmd: Command,
+    timeout: Duration,
+    cancel: &CancelToken,
+) -> anyhow::Result<Output> {
     // Avoid deadlocks when commands emit a lot of output (pipes can fill and block the child).
     // We redirect stdout/stderr to temp files, then read them after completion.
     let stdout_file = NamedTempFile::new().context("create temp stdout file")?;
@@ -23,17 +51,29 @@ pub fn run_cmd_with_timeout(mut cmd: Command, timeout: Duration) -> anyhow::Resu
         .spawn()
         .with_context(|| format!("spawn command: {cmd:?}"))?;
 
-    let status = child
-        .wait_timeout(timeout)
-        .with_context(|| format!("wait_timeout for {cmd:?}"))?;
-
-    let status = match status {
-        Some(s) => s,
-        None => {
-            let _ = child.kill();
-            let _ = child.wait();
-            anyhow::bail!("command timed out after {timeout:?}: {cmd:?}");
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let status = child
+            .wait_timeout(remaining.min(CANCEL_POLL_INTERVAL))
+            .with_context(|| format!("wait_timeout for {cmd:?}"))?;
+        if let Some(s) = status {
+            break s;
         }
+
+        let (kind, what) = if cancel.is_cancelled() {
+            (std::io::ErrorKind::Interrupted, "cancelled".to_string())
+        } else if remaining <= CANCEL_POLL_INTERVAL {
+            (
+                std::io::ErrorKind::TimedOut,
+                format!("timed out after {timeout:?}"),
+            )
+        } else {
+            continue;
+        };
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(std::io::Error::new(kind, format!("command {what}: {cmd:?}")).into());
     };
 
     let stdout = std::fs::read(stdout_file.path())
@@ -54,8 +94,17 @@ pub fn system_time_to_unix_s(t: std::time::SystemTime) -> Option<i64> {
         .and_then(|d| i64::try_from(d.as_secs()).ok())
 }
 
+/// Parse the UTC timestamps Codex writes into rollouts (`2026-02-03T16:12:22.123Z`).
+/// Offsets other than `Z`/`+00:00` are rejected rather than guessed.
+pub fn parse_rfc3339_utc_to_unix_s(s: &str) -> Option<i64> {
+    let t = chrono::DateTime::parse_from_rfc3339(s.trim()).ok()?;
+    (t.offset().local_minus_utc() == 0).then(|| t.timestamp())
+}
+
+/// Keep both ends of `s` within `max` characters (not bytes), marking the cut with "…".
 pub fn truncate_middle(s: &str, max: usize) -> String {
-    if s.len() <= max {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() <= max {
         return s.to_string();
     }
     if max <= 1 {
@@ -64,7 +113,194 @@ pub fn truncate_middle(s: &str, max: usize) -> String {
 
     let keep_left = (max - 1) / 2;
     let keep_right = max - 1 - keep_left;
-    let left = &s[..keep_left.min(s.len())];
-    let right = &s[s.len().saturating_sub(keep_right)..];
+    let left: String = chars[..keep_left].iter().collect();
+    let right: String = chars[chars.len() - keep_right..].iter().collect();
     format!("{left}…{right}")
 }
+
+/// How a table cell that doesn't fit is shortened (`[theme.truncate]` in `config.toml`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Truncation {
+    /// Keep both ends: `/src/mon…/core`.
+    #[default]
+    Middle,
+    /// Keep the start: `/src/monorepo/cr…`.
+    End,
+    /// Drop whole middle path segments, keeping the start, the repo directory and the
+    /// last two segments: `~/code/…/api/crates/core`.
+    Path,
+}
+
+impl Truncation {
+    pub fn apply(self, s: &str, max: usize) -> String {
+        match self {
+            Truncation::Middle => truncate_middle(s, max),
+            Truncation::End if s.chars().count() <= max => s.to_string(),
+            Truncation::End => truncate_end(s, max.saturating_sub(1)),
+            Truncation::Path => truncate_path(s, None, max),
+        }
+    }
+
+    /// Like `apply`, but `Path` also keeps the segment naming `repo_root`.
+    pub fn apply_path(self, s: &str, repo_root: Option<&str>, max: usize) -> String {
+        match self {
+            Truncation::Path => truncate_path(s, repo_root, max),
+            _ => self.apply(s, max),
+        }
+    }
+}
+
+/// Shorten a path by eliding whole middle segments: the last two segments and the repo
+/// directory (`repo_root`, when the path is inside it) always survive, then as many
+/// leading segments as fit, e.g. `~/code/client-x/…/service-api/worktrees/fix-auth`.
+/// Falls back to middle truncation when even the kept segments don't fit.
+pub fn truncate_path(s: &str, repo_root: Option<&str>, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let segs: Vec<&str> = s.trim_end_matches('/').split('/').collect();
+    let n = segs.len();
+    if n < 3 {
+        return truncate_middle(s, max);
+    }
+    let repo_idx = repo_root
+        .map(|r| r.trim_end_matches('/'))
+        .filter(|r| s.starts_with(r) && s[r.len()..].starts_with('/'))
+        .map(|r| r.split('/').count() - 1);
+
+    let render = |keep: &[bool]| {
+        let mut out: Vec<&str> = Vec::new();
+        for (i, seg) in segs.iter().enumerate() {
+            if keep[i] {
+                out.push(seg);
+            } else if out.last() != Some(&"…") {
+                out.push("…");
+            }
+        }
+        out.join("/")
+    };
+    let fits = |keep: &[bool]| render(keep).chars().count() <= max;
+
+    for repo in [repo_idx, None] {
+        let mut keep = vec![false; n];
+        keep[n - 1] = true;
+        keep[n - 2] = true;
+        if let Some(i) = repo {
+            keep[i] = true;
+        }
+        if !fits(&keep) {
+            continue;
+        }
+        for i in 0..n - 2 {
+            if keep[i] {
+                continue;
+            }
+            keep[i] = true;
+            if !fits(&keep) {
+                keep[i] = false;
+                break;
+            }
+        }
+        return render(&keep);
+    }
+    truncate_middle(s, max)
+}
+
+/// Split `s` into lines of at most `width` characters (for expanded table rows).
+pub fn wrap_chars(s: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    if width == 0 || chars.is_empty() {
+        return vec![s.to_string()];
+    }
+    chars.chunks(width).map(|c| c.iter().collect()).collect()
+}
+
+/// Keep the first `max` characters (not bytes), marking the cut with "…".
+pub fn truncate_end(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((i, _)) => format!("{}…", &s[..i]),
+        None => s.to_string(),
+    }
+}
+
+/// First non-blank line of a (possibly multi-line, markdown) message, for one-line display.
+pub fn message_preview(msg: &str, max: usize) -> String {
+    let line = msg
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    truncate_end(line, max)
+}
+
+/// Quote `s` for a POSIX shell command line (single quotes unless it's plainly safe).
+pub fn shell_quote(s: &str) -> String {
+    let safe = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c));
+    if safe {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncation_strategies() {
+        let p = "/src/monorepo/services/billing/crates/core";
+        assert_eq!(Truncation::Path.apply(p, 20), "/src/…/crates/core");
+        assert_eq!(
+            truncate_path(
+                "~/code/client-x/monorepo/service-api/worktrees/fix-auth",
+                Some("~/code/client-x/monorepo/service-api"),
+                50
+            ),
+            "~/code/client-x/…/service-api/worktrees/fix-auth"
+        );
+        assert_eq!(Truncation::End.apply(p, 10), "/src/mono…");
+        assert_eq!(Truncation::Middle.apply(p, 9), "/src…core");
+        assert_eq!(Truncation::Path.apply("short", 20), "short");
+        assert_eq!(wrap_chars("abcdef", 4), vec!["abcd", "ef"]);
+
+        let accented = "é".repeat(40);
+        let cut = truncate_middle(&accented, 24);
+        assert_eq!(cut.chars().count(), 24);
+        assert_eq!(cut, format!("{}…{}", "é".repeat(11), "é".repeat(12)));
+        assert_eq!(
+            Truncation::Path.apply("/données/projets/büro/très-long-nom", 12),
+            "/donn…ng-nom"
+        );
+    }
+
+    #[test]
+    fn parses_codex_rollout_timestamps() {
+        assert_eq!(parse_rfc3339_utc_to_unix_s("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_rfc3339_utc_to_unix_s("2026-02-03T16:12:22.123Z"),
+            Some(1_770_135_142)
+        );
+        assert_eq!(
+            parse_rfc3339_utc_to_unix_s("2026-02-03T16:12:22+02:00"),
+            None
+        );
+        assert_eq!(parse_rfc3339_utc_to_unix_s("not a time"), None);
+    }
+
+    #[test]
+    fn cancelled_command_is_killed_early() {
+        let cancel = CancelToken::default();
+        cancel.cancel();
+        let mut cmd = Command::new("sleep");
+        cmd.arg("5");
+        let started = Instant::now();
+        let err = run_cmd_cancellable(cmd, Duration::from_secs(10), &cancel).unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        let io = err.downcast_ref::<std::io::Error>().expect("io error");
+        assert_eq!(io.kind(), std::io::ErrorKind::Interrupted);
+    }
+}
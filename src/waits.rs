@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::model::SessionId;
+use crate::model::{SessionStatus, Snapshot};
+
+/// Cumulative time each session has spent waiting on the user today (local date).
+///
+/// Each interval between snapshots is attributed to the status seen at its start, so the
+/// totals are as precise as the refresh rate. Counts reset at local midnight and are only
+/// kept while codex-ps runs.
+#[derive(Clone, Debug, Default)]
+pub struct WaitTracker {
+    day: Option<NaiveDate>,
+    waited: HashMap<SessionId, i64>,
+    last: HashMap<SessionId, (SessionStatus, i64)>,
+}
+
+impl WaitTracker {
+    pub fn update(&mut self, snap: &Snapshot, now: DateTime<Local>) {
+        let today = now.date_naive();
+        let day_start = today
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(Local).earliest())
+            .map_or(i64::MIN, |t| t.timestamp());
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.waited.clear();
+        }
+
+        let at = snap.generated_at_unix_s;
+        let mut last = HashMap::new();
+        for row in &snap.sessions {
+            let key = row.id();
+            if let Some((SessionStatus::Waiting, since)) = self.last.get(&key) {
+                let secs = at.saturating_sub((*since).max(day_start));
+                if secs > 0 {
+                    *self.waited.entry(key.clone()).or_default() += secs;
+                }
+            }
+            last.insert(key, (row.status, at));
+        }
+        self.last = last;
+    }
+
+    /// Seconds `key` has waited for input today (0 when never seen waiting).
+    pub fn waited_today(&self, key: &SessionId) -> i64 {
+        self.waited.get(key).copied().unwrap_or(0)
+    }
+
+    /// Sum over every session seen today, including ones that have since ended.
+    pub fn total_today(&self) -> i64 {
+        self.waited.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SessionRow;
+    use chrono::TimeZone;
+
+    fn snap(at: i64, status: SessionStatus) -> Snapshot {
+        let row = SessionRow {
+            host: "local".into(),
+            thread_id: "a".into(),
+            status,
+            ..Default::default()
+        };
+        Snapshot {
+            generated_at_unix_s: at,
+            host: "local".into(),
+            sessions: vec![row],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accumulates_waiting_time_and_resets_at_midnight() {
+        let noon = Local
+            .with_ymd_and_hms(2026, 2, 3, 12, 0, 0)
+            .single()
+            .expect("time");
+        let t = noon.timestamp();
+        let key = SessionId::new("local", "a");
+        let mut w = WaitTracker::default();
+
+        w.update(&snap(t, SessionStatus::Waiting), noon);
+        w.update(&snap(t + 60, SessionStatus::Working), noon);
+        w.update(&snap(t + 120, SessionStatus::Waiting), noon);
+        w.update(&snap(t + 150, SessionStatus::Waiting), noon);
+        assert_eq!(w.waited_today(&key), 90);
+        assert_eq!(w.total_today(), 90);
+
+        let tomorrow = noon + chrono::Duration::days(1);
+        w.update(
+            &snap(tomorrow.timestamp(), SessionStatus::Waiting),
+            tomorrow,
+        );
+        assert_eq!(w.waited_today(&key), 12 * 3600);
+    }
+}